@@ -0,0 +1,11 @@
+use pbtb_rust::config::dynamodb::DynamoDBConfig;
+use pbtb_rust::config::s3::S3Config;
+use pbtb_rust::config::telegram::TelegramConfig;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct DailyDigestConfig {
+    pub dynamodb: DynamoDBConfig,
+    pub s3: S3Config,
+    pub telegram: Option<TelegramConfig>,
+}
@@ -0,0 +1,20 @@
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use std::sync::Arc;
+
+use crate::AppState;
+use lambda_runtime::{Error, LambdaEvent, tracing};
+
+/// The payload is only a tick; whose digest is due is decided from the
+/// stored preferences and the wall clock.
+pub(crate) async fn function_handler(
+    _event: LambdaEvent<EventBridgeEvent>,
+    state: Arc<AppState>,
+) -> Result<(), Error> {
+    let run = state.send_due.execute().await.map_err(Error::from)?;
+    tracing::info!(
+        "daily digest tick: {} sent, {} failed",
+        run.sent,
+        run.failed
+    );
+    Ok(())
+}
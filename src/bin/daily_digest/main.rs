@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
+
+use crate::config::DailyDigestConfig;
+use pbtb_rust::config::configs::load_config;
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::domain::account::ExchangeAccount;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::preferences::UserPreferencesRepository;
+use pbtb_rust::infra::bybit::BYBIT_MAINNET_URL;
+use pbtb_rust::infra::client::{create_dynamodb_client, create_s3_client};
+use pbtb_rust::infra::telegram::{TelegramNotifier, resolve_token};
+use pbtb_rust::infra::{BybitTradingGateway, DynamoBotRepository, S3BotConfigRepository};
+use pbtb_rust::telemetry::init_tracing;
+use pbtb_rust::usecase::{BuildDailyDigestUseCase, SendDueDigestsUseCase};
+
+mod config;
+mod event_handler;
+
+#[derive(Clone)]
+pub struct AppState {
+    send_due: Arc<SendDueDigestsUseCase>,
+}
+
+/// Invoked at the top of every hour by an EventBridge schedule rule; sends
+/// each user whose digest hour it is a summary of their bots over Telegram.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    init_tracing();
+
+    // Cold start only: DynamoDB for bot, runtime, history and preference rows,
+    // S3 for the config objects drift is read from, and the telebot's token.
+    let configs: DailyDigestConfig =
+        load_config().map_err(|e| Error::from(format!("Failed to load configs: {e:#}")))?;
+    let token = resolve_token(configs.telegram.as_ref())
+        .await
+        .map_err(|e| Error::from(format!("{e:#}")))?;
+
+    let dynamodb_client = create_dynamodb_client(&configs.dynamodb).await;
+    let s3_client = create_s3_client(&configs.s3).await;
+
+    // The same repo satisfies every DynamoDB-backed domain trait.
+    let repo = Arc::new(DynamoBotRepository::new(
+        dynamodb_client,
+        configs.dynamodb.table_name.clone(),
+    ));
+    let prefs: Arc<dyn UserPreferencesRepository> = repo.clone();
+    let bot_configs = Arc::new(S3BotConfigRepository::new(
+        s3_client,
+        configs.s3.bucket_name.clone(),
+    ));
+    let account: Arc<dyn ExchangeAccount> = Arc::new(BybitTradingGateway::new(BYBIT_MAINNET_URL));
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+
+    let build = Arc::new(BuildDailyDigestUseCase::new(
+        repo.clone(),
+        repo.clone(),
+        repo,
+        bot_configs,
+        account,
+        prefs.clone(),
+        clock.clone(),
+    ));
+    let state = Arc::new(AppState {
+        send_due: Arc::new(SendDueDigestsUseCase::new(
+            prefs,
+            build,
+            Arc::new(TelegramNotifier::new(token)),
+            clock,
+        )),
+    });
+
+    run(service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let state = state.clone();
+        async move { event_handler::function_handler(event, state).await }
+    }))
+    .await
+}
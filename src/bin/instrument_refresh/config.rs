@@ -0,0 +1,7 @@
+use pbtb_rust::config::s3::S3Config;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct InstrumentRefreshConfig {
+    pub s3: S3Config,
+}
@@ -0,0 +1,15 @@
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use std::sync::Arc;
+
+use crate::AppState;
+use lambda_runtime::{Error, LambdaEvent, tracing};
+
+/// The payload is only a tick; the catalog is read from the exchange anew.
+pub(crate) async fn function_handler(
+    _event: LambdaEvent<EventBridgeEvent>,
+    state: Arc<AppState>,
+) -> Result<(), Error> {
+    let count = state.refresh.execute().await.map_err(Error::from)?;
+    tracing::info!("instrument refresh: {count} instruments");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
+
+use crate::config::InstrumentRefreshConfig;
+use pbtb_rust::config::configs::load_config;
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::instrument::{InstrumentCatalogRepository, InstrumentSource};
+use pbtb_rust::infra::BybitTradingGateway;
+use pbtb_rust::infra::bybit::BYBIT_MAINNET_URL;
+use pbtb_rust::infra::client::create_s3_client;
+use pbtb_rust::infra::instrumentrepository::S3InstrumentCatalogRepository;
+use pbtb_rust::telemetry::init_tracing;
+use pbtb_rust::usecase::RefreshInstrumentCatalogUseCase;
+
+mod config;
+mod event_handler;
+
+#[derive(Clone)]
+pub struct AppState {
+    refresh: Arc<RefreshInstrumentCatalogUseCase>,
+}
+
+/// Invoked every few hours by an EventBridge schedule rule; caches the
+/// exchange's instrument list in the config bucket for the coin and leverage
+/// checks of the telebot and the other Lambdas.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    init_tracing();
+
+    // Cold start only: the instruments endpoint is public, so S3 is all the
+    // configuration there is.
+    let configs: InstrumentRefreshConfig =
+        load_config().map_err(|e| Error::from(format!("Failed to load configs: {e:#}")))?;
+
+    let s3_client = create_s3_client(&configs.s3).await;
+    let source: Arc<dyn InstrumentSource> = Arc::new(BybitTradingGateway::new(BYBIT_MAINNET_URL));
+    let catalogs: Arc<dyn InstrumentCatalogRepository> = Arc::new(
+        S3InstrumentCatalogRepository::new(s3_client, configs.s3.bucket_name),
+    );
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+
+    let state = Arc::new(AppState {
+        refresh: Arc::new(RefreshInstrumentCatalogUseCase::new(
+            source, catalogs, clock,
+        )),
+    });
+
+    run(service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let state = state.clone();
+        async move { event_handler::function_handler(event, state).await }
+    }))
+    .await
+}
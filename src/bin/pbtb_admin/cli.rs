@@ -0,0 +1,151 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Operator tool for inspecting and repairing bots, over the same tables,
+/// buckets and use cases as the telebot. Configured by the same `APP__*`
+/// environment.
+#[derive(Debug, Parser)]
+#[command(name = "pbtb-admin", version)]
+pub struct Cli {
+    /// How results are printed.
+    #[arg(long, short, value_enum, global = true, default_value_t = Format::Table)]
+    pub output: Format,
+    /// Show what a mutating command would do, without doing it.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Bots of one user.
+    #[command(subcommand)]
+    Bots(BotsCommand),
+    /// A bot's runtime row (observed task state and the start lock).
+    #[command(subcommand)]
+    Runtime(RuntimeCommand),
+    /// Start or stop a bot, exactly as the Run/Stop buttons do.
+    #[command(subcommand)]
+    Bot(BotCommand),
+    /// A bot's passivbot config in S3.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Predefined config templates in S3.
+    #[command(subcommand)]
+    Template(TemplateCommand),
+}
+
+/// A user and one of their bots.
+#[derive(Debug, Args)]
+pub struct BotRef {
+    /// Telegram user id.
+    #[arg(long)]
+    pub user: String,
+    #[arg(long)]
+    pub bot: String,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BotsCommand {
+    /// List a user's bots with their desired and observed state.
+    List {
+        /// Telegram user id.
+        #[arg(long)]
+        user: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RuntimeCommand {
+    Show(BotRef),
+    /// Release a stuck `starting` lock now instead of waiting for it to go
+    /// stale. Refused while the lock's task is alive.
+    ReleaseLock(BotRef),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BotCommand {
+    Start(BotRef),
+    Stop(BotRef),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the config JSON.
+    Get(BotRef),
+    /// Replace the config with a JSON file (`-` for stdin).
+    Put {
+        #[command(flatten)]
+        bot: BotRef,
+        #[arg(long)]
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TemplateCommand {
+    List,
+    /// Create or replace a template from a passivbot config JSON file (`-`
+    /// for stdin).
+    Import {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        file: PathBuf,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_flags_go_anywhere() {
+        let cli = Cli::try_parse_from([
+            "pbtb-admin",
+            "bot",
+            "start",
+            "--user",
+            "1",
+            "--bot",
+            "b",
+            "--dry-run",
+            "-o",
+            "json",
+        ])
+        .unwrap();
+        assert!(cli.dry_run);
+        assert_eq!(cli.output, Format::Json);
+        assert!(matches!(cli.command, Command::Bot(BotCommand::Start(ref b)) if b.bot == "b"));
+
+        let cli = Cli::try_parse_from([
+            "pbtb-admin",
+            "runtime",
+            "release-lock",
+            "--user",
+            "1",
+            "--bot",
+            "b",
+        ])
+        .unwrap();
+        assert!(!cli.dry_run);
+        assert!(matches!(
+            cli.command,
+            Command::Runtime(RuntimeCommand::ReleaseLock(_))
+        ));
+        assert!(Cli::try_parse_from(["pbtb-admin", "bots", "list"]).is_err());
+    }
+}
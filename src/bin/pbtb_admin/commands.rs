@@ -0,0 +1,666 @@
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+
+use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::botconfig::BotConfigRepository;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::configtemplate::ConfigTemplateRepository;
+use pbtb_rust::domain::history::BotHistoryRepository;
+use pbtb_rust::domain::instrument::InstrumentCatalogRepository;
+use pbtb_rust::domain::metrics::NoopMetrics;
+use pbtb_rust::domain::riskguard::RiskGuardRepository;
+use pbtb_rust::domain::runtime::{
+    BotRuntime, BotRuntimeRepository, RuntimePhase, StartLockRepository,
+};
+use pbtb_rust::usecase::{
+    GetBotConfigUseCase, GetBotRuntimeUseCase, ImportTemplateUseCase, ListBotsUseCase,
+    ListBotsWithRuntimeUseCase, ListTemplatesUseCase, ReleaseLockOutcome, ReleaseStartLockUseCase,
+    StartBotUseCase, StartOutcome, StopBotUseCase, StopOutcome, TaskController, TaskRunner,
+    UpdateBotConfigUseCase,
+};
+
+use crate::cli::{
+    BotCommand, BotRef, BotsCommand, Command, ConfigCommand, RuntimeCommand, TemplateCommand,
+};
+use crate::output::Output;
+
+/// Bots read per query when listing all of a user's bots.
+const LIST_PAGE_SIZE: usize = 100;
+
+/// The adapters the commands run against: DynamoDB and S3 in `main`,
+/// in-memory twins in tests.
+pub struct Ports {
+    pub bots: Arc<dyn BotRepository>,
+    pub runtimes: Arc<dyn BotRuntimeRepository>,
+    pub locks: Arc<dyn StartLockRepository>,
+    pub history: Arc<dyn BotHistoryRepository>,
+    /// Cleared of a pending guard restart when a bot is stopped.
+    pub guards: Arc<dyn RiskGuardRepository>,
+    pub configs: Arc<dyn BotConfigRepository>,
+    pub templates: Arc<dyn ConfigTemplateRepository>,
+    /// Read before a start, to refuse configs the exchange cannot trade.
+    pub instruments: Arc<dyn InstrumentCatalogRepository>,
+    pub clock: Arc<dyn Clock>,
+    /// ECS, when `APP__ECS__*` is set; the commands that launch, stop or
+    /// check tasks need it.
+    pub compute: Option<Compute>,
+}
+
+pub struct Compute {
+    pub runner: Arc<dyn TaskRunner>,
+    pub controller: Arc<dyn TaskController>,
+    pub cluster_arn: String,
+    pub td_arn: String,
+    pub container_name: String,
+}
+
+/// The use cases that need a compute backend.
+struct TaskUseCases {
+    start: StartBotUseCase,
+    stop: StopBotUseCase,
+    release_lock: ReleaseStartLockUseCase,
+}
+
+pub struct Admin {
+    bots: ListBotsUseCase,
+    list: ListBotsWithRuntimeUseCase,
+    runtime: GetBotRuntimeUseCase,
+    get_config: GetBotConfigUseCase,
+    update_config: UpdateBotConfigUseCase,
+    list_templates: ListTemplatesUseCase,
+    import_template: ImportTemplateUseCase,
+    tasks: Option<TaskUseCases>,
+}
+
+impl Admin {
+    pub fn new(ports: Ports) -> Self {
+        // Operator starts and stops are not counted: stdout is the command's
+        // output, not a place for EMF lines.
+        let tasks = ports.compute.map(|compute| TaskUseCases {
+            start: StartBotUseCase::new(
+                ports.bots.clone(),
+                ports.runtimes.clone(),
+                ports.locks.clone(),
+                compute.runner,
+                compute.controller.clone(),
+                ports.clock.clone(),
+                ports.history.clone(),
+                Arc::new(NoopMetrics),
+                ports.configs.clone(),
+                ports.instruments.clone(),
+                compute.cluster_arn.clone(),
+                compute.td_arn,
+                compute.container_name,
+            ),
+            stop: StopBotUseCase::new(
+                ports.bots.clone(),
+                ports.runtimes.clone(),
+                ports.guards.clone(),
+                compute.controller.clone(),
+                ports.clock.clone(),
+                ports.history.clone(),
+                Arc::new(NoopMetrics),
+                compute.cluster_arn.clone(),
+            ),
+            release_lock: ReleaseStartLockUseCase::new(
+                ports.runtimes.clone(),
+                ports.locks.clone(),
+                compute.controller,
+                ports.clock.clone(),
+                compute.cluster_arn,
+            ),
+        });
+        Self {
+            bots: ListBotsUseCase::new(ports.bots.clone()),
+            list: ListBotsWithRuntimeUseCase::new(ports.bots, ports.runtimes.clone()),
+            runtime: GetBotRuntimeUseCase::new(ports.runtimes),
+            get_config: GetBotConfigUseCase::new(ports.configs.clone()),
+            update_config: UpdateBotConfigUseCase::new(ports.configs, ports.clock),
+            list_templates: ListTemplatesUseCase::new(ports.templates.clone()),
+            import_template: ImportTemplateUseCase::new(ports.templates),
+            tasks,
+        }
+    }
+
+    pub async fn run(&self, command: Command, dry_run: bool) -> Result<Output> {
+        match command {
+            Command::Bots(BotsCommand::List { user }) => self.list_bots(&user).await,
+            Command::Runtime(RuntimeCommand::Show(bot)) => self.show_runtime(&bot).await,
+            Command::Runtime(RuntimeCommand::ReleaseLock(bot)) => {
+                self.release_lock(&bot, dry_run).await
+            }
+            Command::Bot(BotCommand::Start(bot)) => self.start(&bot, dry_run).await,
+            Command::Bot(BotCommand::Stop(bot)) => self.stop(&bot, dry_run).await,
+            Command::Config(ConfigCommand::Get(bot)) => self.get_config(&bot).await,
+            Command::Config(ConfigCommand::Put { bot, file }) => {
+                self.put_config(&bot, read_json(&file)?, dry_run).await
+            }
+            Command::Template(TemplateCommand::List) => self.templates().await,
+            Command::Template(TemplateCommand::Import { name, file }) => {
+                self.import(&name, read_json(&file)?, dry_run).await
+            }
+        }
+    }
+
+    fn tasks(&self) -> Result<&TaskUseCases> {
+        self.tasks
+            .as_ref()
+            .context("This command needs ECS: set APP__ECS__*")
+    }
+
+    async fn list_bots(&self, user_id: &str) -> Result<Output> {
+        let mut rows = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let page = self
+                .list
+                .page(user_id, after.as_deref(), LIST_PAGE_SIZE)
+                .await
+                .map_err(anyhow::Error::msg)?;
+            for (bot, runtime) in page.bots {
+                rows.push(vec![
+                    json!(bot.id),
+                    json!(bot.exchange.as_str()),
+                    json!(bot.enabled),
+                    json!(runtime.as_ref().map(|r| r.phase.as_str())),
+                    json!(runtime.as_ref().and_then(|r| r.task_id.clone())),
+                    json!(runtime.as_ref().map(|r| r.restart_attempts)),
+                ]);
+            }
+            match page.next_cursor {
+                Some(cursor) => after = Some(cursor),
+                None => break,
+            }
+        }
+        Ok(Output::Rows {
+            columns: &[
+                "id",
+                "exchange",
+                "enabled",
+                "phase",
+                "task_id",
+                "restart_attempts",
+            ],
+            rows,
+        })
+    }
+
+    async fn find_runtime(&self, bot: &BotRef) -> Result<Option<BotRuntime>> {
+        self.runtime
+            .execute(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// The bot, or an error naming it: mutating commands refuse unknown bots.
+    async fn require_bot(&self, bot: &BotRef) -> Result<pbtb_rust::domain::bot::Bot> {
+        self.bots
+            .find(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?
+            .with_context(|| format!("User {} has no bot {}", bot.user, bot.bot))
+    }
+
+    async fn show_runtime(&self, bot: &BotRef) -> Result<Output> {
+        let enabled = self
+            .bots
+            .find(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?
+            .map(|b| b.enabled);
+        let runtime = self.find_runtime(bot).await?;
+        if enabled.is_none() && runtime.is_none() {
+            bail!("User {} has no bot {}", bot.user, bot.bot);
+        }
+        Ok(Output::Record(vec![
+            ("user", json!(bot.user)),
+            ("bot", json!(bot.bot)),
+            ("enabled", json!(enabled)),
+            ("phase", json!(runtime.as_ref().map(|r| r.phase.as_str()))),
+            (
+                "task_id",
+                json!(runtime.as_ref().and_then(|r| r.task_id.clone())),
+            ),
+            ("version", json!(runtime.as_ref().map(|r| r.version))),
+            (
+                "observed_at",
+                json!(runtime.as_ref().map(|r| r.observed_at)),
+            ),
+            (
+                "restart_attempts",
+                json!(runtime.as_ref().map(|r| r.restart_attempts)),
+            ),
+        ]))
+    }
+
+    async fn release_lock(&self, bot: &BotRef, dry_run: bool) -> Result<Output> {
+        let usecase = &self.tasks()?.release_lock;
+        let outcome = if dry_run {
+            usecase.preview(&bot.user, &bot.bot).await
+        } else {
+            usecase.execute(&bot.user, &bot.bot).await
+        }
+        .map_err(anyhow::Error::msg)?;
+        let outcome = match outcome {
+            ReleaseLockOutcome::Released => "released",
+            ReleaseLockOutcome::Releasable => "would release",
+            ReleaseLockOutcome::NotLocked(phase) => bail!(
+                "No start lock is held: the runtime row is {}",
+                phase.map_or("absent", |p| p.as_str())
+            ),
+            ReleaseLockOutcome::TaskAlive { task_id } => bail!(
+                "Refusing: task {task_id} is alive and will settle the lock when it reports RUNNING"
+            ),
+        };
+        Ok(action("release-lock", bot, dry_run, outcome, None))
+    }
+
+    async fn start(&self, bot: &BotRef, dry_run: bool) -> Result<Output> {
+        let usecase = &self.tasks()?.start;
+        if dry_run {
+            self.require_bot(bot).await?;
+            let phase = self.find_runtime(bot).await?.map(|r| r.phase);
+            let outcome = match phase {
+                Some(RuntimePhase::Running) => "already running",
+                Some(RuntimePhase::Starting) => "already starting",
+                Some(RuntimePhase::Stopping) => "stopping; retry once stopped",
+                Some(RuntimePhase::Stopped) | None => "would start",
+            };
+            return Ok(action("start", bot, true, outcome, None));
+        }
+        let (outcome, task_id) = match usecase
+            .execute(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?
+        {
+            StartOutcome::Started { task_id } => ("started", Some(task_id)),
+            StartOutcome::AlreadyRunning => ("already running", None),
+            StartOutcome::AlreadyStarting => ("already starting", None),
+            StartOutcome::Stopping => {
+                bail!("The previous task is still stopping; retry once stopped")
+            }
+            StartOutcome::BotNotFound => bail!("User {} has no bot {}", bot.user, bot.bot),
+            StartOutcome::Rejected(problems) => bail!("Not started: {problems}"),
+        };
+        Ok(action("start", bot, false, outcome, task_id))
+    }
+
+    async fn stop(&self, bot: &BotRef, dry_run: bool) -> Result<Output> {
+        let usecase = &self.tasks()?.stop;
+        if dry_run {
+            self.require_bot(bot).await?;
+            let runtime = self.find_runtime(bot).await?;
+            let outcome = match runtime.as_ref().map(|r| &r.phase) {
+                Some(RuntimePhase::Running | RuntimePhase::Starting) => "would stop",
+                Some(RuntimePhase::Stopping) => "already stopping",
+                Some(RuntimePhase::Stopped) | None => "not running; would turn off",
+            };
+            let task_id = runtime.and_then(|r| r.task_id);
+            return Ok(action("stop", bot, true, outcome, task_id));
+        }
+        let (outcome, task_id) = match usecase
+            .execute(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?
+        {
+            StopOutcome::Stopped { task_id } => ("stopped", Some(task_id)),
+            StopOutcome::NotRunning => ("not running", None),
+            StopOutcome::AlreadyStopping => ("already stopping", None),
+            StopOutcome::StartInProgress => {
+                bail!("The bot is still starting and is now turned off; stop again once it runs")
+            }
+            StopOutcome::BotNotFound => bail!("User {} has no bot {}", bot.user, bot.bot),
+        };
+        Ok(action("stop", bot, false, outcome, task_id))
+    }
+
+    async fn get_config(&self, bot: &BotRef) -> Result<Output> {
+        let config = self
+            .get_config
+            .execute(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?;
+        Ok(Output::Document(config.config_data))
+    }
+
+    async fn put_config(&self, bot: &BotRef, config: Value, dry_run: bool) -> Result<Output> {
+        if !config.is_object() {
+            bail!("The config must be a JSON object");
+        }
+        // The task reports under `live.user`; a config copied from another
+        // bot would report as that bot.
+        match config.pointer("/live/user").and_then(Value::as_str) {
+            Some(user) if user == bot.bot => {}
+            other => bail!(
+                "live.user is {}, expected {:?}",
+                other.map_or("missing".to_string(), |u| format!("{u:?}")),
+                bot.bot
+            ),
+        }
+        self.require_bot(bot).await?;
+        let current = self
+            .get_config
+            .execute(&bot.user, &bot.bot)
+            .await
+            .map_err(anyhow::Error::msg)?;
+        let mut changed = Vec::new();
+        changed_paths("", &current.config_data, &config, &mut changed);
+        if !dry_run {
+            self.update_config
+                .execute(&bot.user, &bot.bot, config)
+                .await
+                .map_err(anyhow::Error::msg)?;
+        }
+        let outcome = match (dry_run, changed.is_empty()) {
+            (_, true) => "unchanged",
+            (true, false) => "would replace",
+            (false, false) => "replaced",
+        };
+        let Output::Record(mut fields) = action("config-put", bot, dry_run, outcome, None) else {
+            unreachable!()
+        };
+        fields.push(("changed", json!(changed)));
+        Ok(Output::Record(fields))
+    }
+
+    async fn templates(&self) -> Result<Output> {
+        let mut names = self
+            .list_templates
+            .execute()
+            .await
+            .map_err(anyhow::Error::msg)?;
+        names.sort();
+        Ok(Output::Rows {
+            columns: &["name"],
+            rows: names.into_iter().map(|n| vec![json!(n)]).collect(),
+        })
+    }
+
+    async fn import(&self, name: &str, config: Value, dry_run: bool) -> Result<Output> {
+        let replaced = if dry_run {
+            self.import_template.preview(name, &config).await
+        } else {
+            self.import_template.execute(name, config).await
+        }
+        .map_err(anyhow::Error::msg)?;
+        let outcome = match (dry_run, replaced) {
+            (true, false) => "would create",
+            (true, true) => "would replace",
+            (false, false) => "created",
+            (false, true) => "replaced",
+        };
+        Ok(Output::Record(vec![
+            ("action", json!("template-import")),
+            ("template", json!(name)),
+            ("dry_run", json!(dry_run)),
+            ("outcome", json!(outcome)),
+        ]))
+    }
+}
+
+/// The result of a mutating command on one bot.
+fn action(
+    name: &str,
+    bot: &BotRef,
+    dry_run: bool,
+    outcome: &str,
+    task_id: Option<String>,
+) -> Output {
+    Output::Record(vec![
+        ("action", json!(name)),
+        ("user", json!(bot.user)),
+        ("bot", json!(bot.bot)),
+        ("dry_run", json!(dry_run)),
+        ("outcome", json!(outcome)),
+        ("task_id", json!(task_id)),
+    ])
+}
+
+/// Dotted paths of the leaves that differ between two JSON documents.
+fn changed_paths(prefix: &str, old: &Value, new: &Value, out: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                let null = Value::Null;
+                changed_paths(
+                    &path,
+                    a.get(key).unwrap_or(&null),
+                    b.get(key).unwrap_or(&null),
+                    out,
+                );
+            }
+        }
+        (a, b) if a != b => out.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
+/// A JSON file, or stdin for `-`.
+fn read_json(path: &Path) -> Result<Value> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    };
+    serde_json::from_str(&text).with_context(|| format!("{} is not valid JSON", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pbtb_rust::domain::bot::Bot;
+    use pbtb_rust::domain::botconfig::BotConfig;
+    use pbtb_rust::domain::clock::MockClock;
+    use pbtb_rust::domain::configtemplate::ConfigTemplate;
+    use pbtb_rust::domain::exchange::Exchange;
+    use pbtb_rust::infra::memory::{
+        FakeTaskBackend, InMemoryBotConfigRepository, InMemoryBotRepository,
+        InMemoryInstrumentCatalog, InMemoryTemplateRepository,
+    };
+
+    struct World {
+        repo: Arc<InMemoryBotRepository>,
+        configs: Arc<InMemoryBotConfigRepository>,
+        backend: Arc<FakeTaskBackend>,
+        admin: Admin,
+    }
+
+    fn bot_ref() -> BotRef {
+        BotRef {
+            user: "1001".into(),
+            bot: "grid".into(),
+        }
+    }
+
+    fn config() -> Value {
+        json!({
+            "live": { "user": "grid" },
+            "bot": {
+                "long": { "total_wallet_exposure_limit": 1.0 },
+                "short": { "total_wallet_exposure_limit": 0.5 }
+            }
+        })
+    }
+
+    async fn world() -> World {
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let configs = Arc::new(InMemoryBotConfigRepository::new());
+        let backend = Arc::new(FakeTaskBackend::new());
+        repo.save(&Bot::new(
+            "grid".into(),
+            "1001".into(),
+            Exchange::Bybit,
+            "grid".into(),
+            "key".into(),
+            "secret".into(),
+            false,
+            0,
+            0,
+        ))
+        .await
+        .unwrap();
+        let template = ConfigTemplate {
+            name: "grid".into(),
+            description: None,
+            config_data: config(),
+            version: None,
+        };
+        configs
+            .save(&BotConfig::from_template("1001".into(), "grid".into(), &template, 0).unwrap())
+            .await
+            .unwrap();
+        let admin = Admin::new(Ports {
+            bots: repo.clone(),
+            runtimes: repo.clone(),
+            locks: repo.clone(),
+            history: repo.clone(),
+            guards: repo.clone(),
+            configs: configs.clone(),
+            templates: Arc::new(InMemoryTemplateRepository::new(vec![])),
+            instruments: Arc::new(InMemoryInstrumentCatalog::new()),
+            clock,
+            compute: Some(Compute {
+                runner: backend.clone(),
+                controller: backend.clone(),
+                cluster_arn: "cluster".into(),
+                td_arn: "td".into(),
+                container_name: "passivbot".into(),
+            }),
+        });
+        World {
+            repo,
+            configs,
+            backend,
+            admin,
+        }
+    }
+
+    fn field<'a>(output: &'a Output, name: &str) -> &'a Value {
+        let Output::Record(fields) = output else {
+            panic!("not a record: {output:?}");
+        };
+        &fields.iter().find(|(k, _)| *k == name).unwrap().1
+    }
+
+    #[tokio::test]
+    async fn dry_run_start_launches_nothing() {
+        let w = world().await;
+        let out = w
+            .admin
+            .run(Command::Bot(BotCommand::Start(bot_ref())), true)
+            .await
+            .unwrap();
+        assert_eq!(field(&out, "outcome"), "would start");
+        assert!(w.backend.tasks().is_empty());
+        assert!(
+            !BotRepository::find(&*w.repo, "1001", "grid")
+                .await
+                .unwrap()
+                .unwrap()
+                .enabled
+        );
+
+        let out = w
+            .admin
+            .run(Command::Bot(BotCommand::Start(bot_ref())), false)
+            .await
+            .unwrap();
+        assert_eq!(field(&out, "outcome"), "started");
+        assert_eq!(w.backend.tasks().len(), 1);
+
+        let out = w
+            .admin
+            .run(Command::Bot(BotCommand::Start(bot_ref())), true)
+            .await
+            .unwrap();
+        assert_eq!(field(&out, "outcome"), "already starting");
+
+        let unknown = BotRef {
+            user: "1001".into(),
+            bot: "nope".into(),
+        };
+        assert!(
+            w.admin
+                .run(Command::Bot(BotCommand::Stop(unknown)), true)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn release_lock_refuses_a_live_task() {
+        let w = world().await;
+        let release = || Command::Runtime(RuntimeCommand::ReleaseLock(bot_ref()));
+        assert!(w.admin.run(release(), true).await.is_err());
+
+        w.admin
+            .run(Command::Bot(BotCommand::Start(bot_ref())), false)
+            .await
+            .unwrap();
+        let err = w.admin.run(release(), false).await.unwrap_err();
+        assert!(err.to_string().contains("alive"), "{err}");
+        let phase = BotRuntimeRepository::find_consistent(&*w.repo, "1001", "grid")
+            .await
+            .unwrap();
+        assert_eq!(phase.unwrap().phase, RuntimePhase::Starting);
+    }
+
+    #[tokio::test]
+    async fn config_put_dry_run_lists_changes_only() {
+        let w = world().await;
+        let mut new = config();
+        new["bot"]["long"]["total_wallet_exposure_limit"] = json!(2.0);
+        new["bot"]["long"]["n_positions"] = json!(3);
+
+        let out = w
+            .admin
+            .put_config(&bot_ref(), new.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(field(&out, "outcome"), "would replace");
+        assert_eq!(
+            field(&out, "changed"),
+            &json!([
+                "bot.long.n_positions",
+                "bot.long.total_wallet_exposure_limit"
+            ])
+        );
+        assert_eq!(
+            w.configs.get("1001", "grid").await.unwrap().config_data,
+            config()
+        );
+
+        let out = w
+            .admin
+            .put_config(&bot_ref(), new.clone(), false)
+            .await
+            .unwrap();
+        assert_eq!(field(&out, "outcome"), "replaced");
+        assert_eq!(
+            w.configs.get("1001", "grid").await.unwrap().config_data,
+            new
+        );
+
+        // A config copied from another bot would report as that bot.
+        new["live"]["user"] = json!("other");
+        assert!(w.admin.put_config(&bot_ref(), new, false).await.is_err());
+    }
+}
@@ -0,0 +1,13 @@
+use pbtb_rust::config::dynamodb::DynamoDBConfig;
+use pbtb_rust::config::ecs::EcsConfig;
+use pbtb_rust::config::s3::S3Config;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AdminConfig {
+    pub dynamodb: DynamoDBConfig,
+    pub s3: S3Config,
+    /// Only `bot start/stop` and `runtime release-lock` need ECS; the read and
+    /// config commands run without it.
+    pub ecs: Option<EcsConfig>,
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use clap::Parser;
+
+use crate::cli::Cli;
+use crate::commands::{Admin, Compute, Ports};
+use crate::config::AdminConfig;
+use pbtb_rust::config::configs::load_config;
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::infra::client::{create_dynamodb_client, create_ecs_client, create_s3_client};
+use pbtb_rust::infra::instrumentrepository::S3InstrumentCatalogRepository;
+use pbtb_rust::infra::{DynamoBotRepository, S3BotConfigRepository, S3TemplateRepository};
+use pbtb_rust::telemetry::{CorrelationId, init_tracing_with_writer, with_correlation_id};
+use pbtb_rust::usecase::{EcsTaskController, RunTaskUseCase};
+
+mod cli;
+mod commands;
+mod config;
+mod output;
+
+/// Operator CLI: one command per invocation against the live tables and
+/// buckets, through the same use cases the telebot runs.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    // stdout is the command's output; log lines go to stderr.
+    init_tracing_with_writer(std::io::stderr);
+    let configs: AdminConfig = load_config().context("Failed to load APP__* config")?;
+
+    let dynamodb_client = create_dynamodb_client(&configs.dynamodb).await;
+    let s3_client = create_s3_client(&configs.s3).await;
+    let bucket_name = configs.s3.bucket_name.clone();
+
+    // The same repo satisfies every DynamoDB-backed domain trait.
+    let repo = Arc::new(DynamoBotRepository::new(
+        dynamodb_client,
+        configs.dynamodb.table_name.clone(),
+    ));
+    let compute = match &configs.ecs {
+        Some(ecs) => {
+            let ecs_client = create_ecs_client(ecs).await;
+            Some(Compute {
+                runner: Arc::new(RunTaskUseCase::new(ecs_client.clone())),
+                controller: Arc::new(EcsTaskController::new(ecs_client)),
+                cluster_arn: ecs.cluster_arn.clone(),
+                td_arn: ecs.td_passivbot_arn.clone(),
+                container_name: ecs.td_passivbot_container_name.clone(),
+            })
+        }
+        None => None,
+    };
+    let admin = Admin::new(Ports {
+        bots: repo.clone(),
+        runtimes: repo.clone(),
+        locks: repo.clone(),
+        history: repo.clone(),
+        guards: repo,
+        configs: Arc::new(S3BotConfigRepository::new(
+            s3_client.clone(),
+            bucket_name.clone(),
+        )),
+        instruments: Arc::new(S3InstrumentCatalogRepository::new(
+            s3_client.clone(),
+            bucket_name.clone(),
+        )),
+        templates: Arc::new(S3TemplateRepository::new(s3_client, bucket_name)),
+        clock: Arc::new(SystemClock),
+        compute,
+    });
+
+    // A command is one action; a bot it starts carries its correlation id.
+    let output =
+        with_correlation_id(CorrelationId::new(), admin.run(cli.command, cli.dry_run)).await?;
+    println!("{}", output.render(cli.output));
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use serde_json::{Map, Value};
+
+use crate::cli::Format;
+
+/// What a command prints.
+#[derive(Debug)]
+pub enum Output {
+    /// Records of one kind: a table, or a JSON array of objects.
+    Rows {
+        columns: &'static [&'static str],
+        rows: Vec<Vec<Value>>,
+    },
+    /// One record: `field  value` lines, or a JSON object.
+    Record(Vec<(&'static str, Value)>),
+    /// A document (a config) printed as JSON in either format.
+    Document(Value),
+}
+
+impl Output {
+    pub fn render(&self, format: Format) -> String {
+        match (self, format) {
+            (Output::Document(doc), _) => pretty(doc),
+            (Output::Rows { columns, rows }, Format::Json) => pretty(&Value::Array(
+                rows.iter()
+                    .map(|row| object(columns.iter().copied().zip(row.iter().cloned())))
+                    .collect(),
+            )),
+            (Output::Record(fields), Format::Json) => {
+                pretty(&object(fields.iter().map(|(k, v)| (*k, v.clone()))))
+            }
+            (Output::Rows { columns, rows }, Format::Table) => {
+                let cells: Vec<Vec<String>> = rows
+                    .iter()
+                    .map(|row| row.iter().map(cell).collect())
+                    .collect();
+                let header: Vec<String> = columns.iter().map(|c| c.to_uppercase()).collect();
+                table(&header, &cells)
+            }
+            (Output::Record(fields), Format::Table) => {
+                let cells: Vec<Vec<String>> = fields
+                    .iter()
+                    .map(|(k, v)| vec![k.to_string(), cell(v)])
+                    .collect();
+                table(&[], &cells)
+            }
+        }
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).expect("a Value always serializes")
+}
+
+fn object<'a>(fields: impl Iterator<Item = (&'a str, Value)>) -> Value {
+    Value::Object(
+        fields
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/// A value as a table cell: strings unquoted, null as `-`.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Left-aligned columns separated by two spaces; `header` may be empty.
+fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let lines: Vec<&[String]> = std::iter::once(header)
+        .filter(|h| !h.is_empty())
+        .chain(rows.iter().map(Vec::as_slice))
+        .collect();
+    let columns = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            lines
+                .iter()
+                .filter_map(|l| l.get(i))
+                .map(|c| c.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    lines
+        .iter()
+        .map(|line| {
+            let padded: Vec<String> = line
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{c:<w$}"))
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rows_render_as_aligned_table_or_json_array() {
+        let out = Output::Rows {
+            columns: &["id", "phase"],
+            rows: vec![
+                vec![json!("grid-long"), json!("running")],
+                vec![json!("b"), Value::Null],
+            ],
+        };
+        assert_eq!(
+            out.render(Format::Table),
+            "ID         PHASE\ngrid-long  running\nb          -"
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&out.render(Format::Json)).unwrap(),
+            json!([{ "id": "grid-long", "phase": "running" }, { "id": "b", "phase": null }])
+        );
+    }
+
+    #[test]
+    fn records_render_as_fields() {
+        let out = Output::Record(vec![("bot", json!("b")), ("restart_attempts", json!(2))]);
+        assert_eq!(
+            out.render(Format::Table),
+            "bot               b\nrestart_attempts  2"
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&out.render(Format::Json)).unwrap(),
+            json!({ "bot": "b", "restart_attempts": 2 })
+        );
+    }
+}
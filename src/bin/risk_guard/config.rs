@@ -0,0 +1,13 @@
+use pbtb_rust::config::dynamodb::DynamoDBConfig;
+use pbtb_rust::config::ecs::EcsConfig;
+use pbtb_rust::config::s3::S3Config;
+use pbtb_rust::config::telegram::TelegramConfig;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct RiskGuardConfig {
+    pub dynamodb: DynamoDBConfig,
+    pub s3: S3Config,
+    pub ecs: EcsConfig,
+    pub telegram: Option<TelegramConfig>,
+}
@@ -0,0 +1,22 @@
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use std::sync::Arc;
+
+use crate::AppState;
+use lambda_runtime::{Error, LambdaEvent, tracing};
+
+/// The payload is only a tick; every guard is checked against the equity
+/// read now.
+pub(crate) async fn function_handler(
+    _event: LambdaEvent<EventBridgeEvent>,
+    state: Arc<AppState>,
+) -> Result<(), Error> {
+    let run = state.evaluate.execute().await.map_err(Error::from)?;
+    tracing::info!(
+        "risk guard tick: {} evaluated, {} tripped, {} restarted, {} failed",
+        run.evaluated,
+        run.tripped,
+        run.restarted,
+        run.failed
+    );
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
+
+use crate::config::RiskGuardConfig;
+use pbtb_rust::config::configs::load_config;
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::domain::account::ExchangeAccount;
+use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::botconfig::BotConfigRepository;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::history::BotHistoryRepository;
+use pbtb_rust::domain::instrument::InstrumentCatalogRepository;
+use pbtb_rust::domain::metrics::Metrics;
+use pbtb_rust::domain::runtime::{BotRuntimeRepository, StartLockRepository};
+use pbtb_rust::infra::bybit::BYBIT_MAINNET_URL;
+use pbtb_rust::infra::client::{create_dynamodb_client, create_s3_client, setup_ecs_with_configs};
+use pbtb_rust::infra::instrumentrepository::S3InstrumentCatalogRepository;
+use pbtb_rust::infra::telegram::{TelegramNotifier, resolve_token};
+use pbtb_rust::infra::{
+    BybitTradingGateway, DynamoBotRepository, EmfMetrics, S3BotConfigRepository,
+};
+use pbtb_rust::telemetry::init_tracing;
+use pbtb_rust::usecase::{
+    EcsTaskController, EvaluateRiskGuardsUseCase, RunTaskUseCase, ScheduleActionRunner,
+    SetStrategySideUseCase, StartBotUseCase, StopBotUseCase, TaskController, TaskRunner,
+    UpdateRiskLevelUseCase, UseCaseActionRunner,
+};
+
+mod config;
+mod event_handler;
+
+#[derive(Clone)]
+pub struct AppState {
+    evaluate: Arc<EvaluateRiskGuardsUseCase>,
+}
+
+/// Invoked every minute by an EventBridge schedule rule; checks each armed
+/// risk guard against its bot's equity and winds the bot down on a breach,
+/// through the same use cases as the telebot.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    init_tracing();
+
+    // Cold start only: DynamoDB for bot, runtime, guard and history rows, S3
+    // for the configs the sides are switched in, ECS for the restart, and the
+    // telebot's token to notify the user.
+    let configs: RiskGuardConfig =
+        load_config().map_err(|e| Error::from(format!("Failed to load configs: {e:#}")))?;
+    let token = resolve_token(configs.telegram.as_ref())
+        .await
+        .map_err(|e| Error::from(format!("{e:#}")))?;
+
+    let dynamodb_client = create_dynamodb_client(&configs.dynamodb).await;
+    let s3_client = create_s3_client(&configs.s3).await;
+    let (ecs_client, cluster_arn, td_arn) = setup_ecs_with_configs(&configs.ecs).await;
+    let container_name = configs.ecs.td_passivbot_container_name.clone();
+
+    // The same repo satisfies every DynamoDB-backed domain trait.
+    let repo = Arc::new(DynamoBotRepository::new(
+        dynamodb_client,
+        configs.dynamodb.table_name.clone(),
+    ));
+    let bots: Arc<dyn BotRepository> = repo.clone();
+    let runtimes: Arc<dyn BotRuntimeRepository> = repo.clone();
+    let start_locks: Arc<dyn StartLockRepository> = repo.clone();
+    let history: Arc<dyn BotHistoryRepository> = repo.clone();
+    let bot_configs: Arc<dyn BotConfigRepository> = Arc::new(S3BotConfigRepository::new(
+        s3_client.clone(),
+        configs.s3.bucket_name.clone(),
+    ));
+    let instruments: Arc<dyn InstrumentCatalogRepository> = Arc::new(
+        S3InstrumentCatalogRepository::new(s3_client, configs.s3.bucket_name.clone()),
+    );
+    let account: Arc<dyn ExchangeAccount> = Arc::new(BybitTradingGateway::new(BYBIT_MAINNET_URL));
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let metrics: Arc<dyn Metrics> = Arc::new(EmfMetrics::stdout());
+
+    let task_runner: Arc<dyn TaskRunner> = Arc::new(RunTaskUseCase::new(ecs_client.clone()));
+    let task_controller: Arc<dyn TaskController> = Arc::new(EcsTaskController::new(ecs_client));
+    let start_bot = Arc::new(StartBotUseCase::new(
+        bots.clone(),
+        runtimes.clone(),
+        start_locks,
+        task_runner,
+        task_controller.clone(),
+        clock.clone(),
+        history.clone(),
+        metrics.clone(),
+        bot_configs.clone(),
+        instruments.clone(),
+        cluster_arn.clone(),
+        td_arn,
+        container_name,
+    ));
+    let stop_bot = Arc::new(StopBotUseCase::new(
+        bots.clone(),
+        runtimes.clone(),
+        repo.clone(),
+        task_controller,
+        clock.clone(),
+        history.clone(),
+        metrics,
+        cluster_arn,
+    ));
+    let update_risk_level = Arc::new(UpdateRiskLevelUseCase::new(
+        bots.clone(),
+        bot_configs.clone(),
+        instruments,
+        clock.clone(),
+    ));
+    let set_strategy_side = Arc::new(SetStrategySideUseCase::new(bot_configs, clock.clone()));
+    let runner: Arc<dyn ScheduleActionRunner> = Arc::new(UseCaseActionRunner::new(
+        start_bot,
+        stop_bot,
+        update_risk_level,
+        set_strategy_side,
+    ));
+
+    let state = Arc::new(AppState {
+        evaluate: Arc::new(EvaluateRiskGuardsUseCase::new(
+            repo,
+            bots,
+            runtimes,
+            account,
+            runner,
+            history,
+            Arc::new(TelegramNotifier::new(token)),
+            clock,
+        )),
+    });
+
+    run(service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let state = state.clone();
+        async move { event_handler::function_handler(event, state).await }
+    }))
+    .await
+}
@@ -0,0 +1,41 @@
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use std::sync::Arc;
+
+use crate::AppState;
+use lambda_runtime::tracing::Instrument;
+use lambda_runtime::{Error, LambdaEvent, tracing};
+use pbtb_rust::telemetry::{CorrelationId, with_correlation_id};
+use pbtb_rust::usecase::SweepAction;
+
+/// The payload is only a tick; the sweep compares the stored runtime rows
+/// with what ECS reports right now.
+pub(crate) async fn function_handler(
+    _event: LambdaEvent<EventBridgeEvent>,
+    state: Arc<AppState>,
+) -> Result<(), Error> {
+    // One correlation id per pass, carried by every task it relaunches.
+    let correlation_id = CorrelationId::new();
+    let span = tracing::info_span!("runtime_sweep", correlation_id = %correlation_id);
+    with_correlation_id(correlation_id, sweep(&state).instrument(span)).await
+}
+
+async fn sweep(state: &AppState) -> Result<(), Error> {
+    let summary = state.sweep.execute().await.map_err(Error::from)?;
+
+    for action in &summary.actions {
+        match action {
+            SweepAction::Failed { .. } | SweepAction::Conflict { .. } => {
+                tracing::warn!("runtime sweep: {action}")
+            }
+            _ => tracing::info!("runtime sweep: {action}"),
+        }
+    }
+    tracing::info!(
+        rows = summary.rows_checked,
+        live_tasks = summary.live_tasks,
+        actions = summary.actions.len(),
+        failed = summary.failed(),
+        "runtime sweep done"
+    );
+    Ok(())
+}
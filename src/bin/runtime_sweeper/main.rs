@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
+
+use crate::config::RuntimeSweeperConfig;
+use pbtb_rust::config::configs::load_config;
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::runtime::{
+    BotRuntimeRepository, RuntimeScanRepository, StartLockRepository,
+};
+use pbtb_rust::infra::DynamoBotRepository;
+use pbtb_rust::infra::client::{create_dynamodb_client, create_ecs_client};
+use pbtb_rust::telemetry::init_tracing;
+use pbtb_rust::usecase::{
+    EcsTaskController, EcsTaskInventory, RunTaskUseCase, SweepRuntimesUseCase, TaskController,
+    TaskInventory, TaskRunner,
+};
+
+mod config;
+mod event_handler;
+
+#[derive(Clone)]
+pub struct AppState {
+    sweep: Arc<SweepRuntimesUseCase>,
+}
+
+/// Invoked every few minutes by an EventBridge schedule rule; repairs runtime
+/// rows left wrong by ECS events that never reached task_state_change_handler.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    init_tracing();
+
+    // Cold start only: same config shape as task_state_change_handler.
+    let configs: RuntimeSweeperConfig =
+        load_config().map_err(|e| Error::from(format!("Failed to load configs: {e:#}")))?;
+
+    let ecs_client = create_ecs_client(&configs.ecs).await;
+    let dynamodb_client = create_dynamodb_client(&configs.dynamodb).await;
+    let table_name = configs.dynamodb.table_name.clone();
+
+    // The same repo satisfies every DynamoDB-backed domain trait.
+    let repo = Arc::new(DynamoBotRepository::new(dynamodb_client, table_name));
+    let scan: Arc<dyn RuntimeScanRepository> = repo.clone();
+    let bots: Arc<dyn BotRepository> = repo.clone();
+    let runtimes: Arc<dyn BotRuntimeRepository> = repo.clone();
+    let start_locks: Arc<dyn StartLockRepository> = repo;
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+
+    let inventory: Arc<dyn TaskInventory> = Arc::new(EcsTaskInventory::new(ecs_client.clone()));
+    let task_runner: Arc<dyn TaskRunner> = Arc::new(RunTaskUseCase::new(ecs_client.clone()));
+    let task_controller: Arc<dyn TaskController> = Arc::new(EcsTaskController::new(ecs_client));
+
+    let ecs = configs.ecs;
+    let state = Arc::new(AppState {
+        sweep: Arc::new(SweepRuntimesUseCase::new(
+            scan,
+            bots,
+            runtimes,
+            start_locks,
+            inventory,
+            task_runner,
+            task_controller,
+            clock,
+            ecs.cluster_arn,
+            ecs.td_passivbot_arn,
+            ecs.td_passivbot_container_name,
+        )),
+    });
+
+    run(service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let state = state.clone();
+        async move { event_handler::function_handler(event, state).await }
+    }))
+    .await
+}
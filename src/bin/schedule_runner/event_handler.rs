@@ -0,0 +1,36 @@
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use std::sync::Arc;
+
+use crate::AppState;
+use lambda_runtime::{Error, LambdaEvent, tracing};
+
+/// The payload is only a tick; what is due is decided from the stored
+/// schedules and the wall clock.
+pub(crate) async fn function_handler(
+    _event: LambdaEvent<EventBridgeEvent>,
+    state: Arc<AppState>,
+) -> Result<(), Error> {
+    let runs = state.run_due.execute().await.map_err(Error::from)?;
+
+    for run in &runs {
+        let s = &run.schedule;
+        match &run.result {
+            Ok(summary) => tracing::info!(
+                schedule_id = %s.id,
+                user_id = %s.user_id,
+                bot_id = %s.bot_id,
+                action = %s.action,
+                "schedule fired: {summary}"
+            ),
+            Err(e) => tracing::warn!(
+                schedule_id = %s.id,
+                user_id = %s.user_id,
+                bot_id = %s.bot_id,
+                action = %s.action,
+                "schedule action failed: {e}"
+            ),
+        }
+    }
+    tracing::info!("schedule runner tick: {} schedule(s) fired", runs.len());
+    Ok(())
+}
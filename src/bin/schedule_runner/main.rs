@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
+
+use pbtb_rust::config::configs::{Configs, load_config};
+use pbtb_rust::domain::SystemClock;
+use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::botconfig::BotConfigRepository;
+use pbtb_rust::domain::clock::Clock;
+use pbtb_rust::domain::history::BotHistoryRepository;
+use pbtb_rust::domain::instrument::InstrumentCatalogRepository;
+use pbtb_rust::domain::metrics::Metrics;
+use pbtb_rust::domain::riskguard::RiskGuardRepository;
+use pbtb_rust::domain::runtime::{BotRuntimeRepository, StartLockRepository};
+use pbtb_rust::domain::schedule::ScheduleRepository;
+use pbtb_rust::infra::client::{
+    setup_dynamodb_with_configs, setup_ecs_with_configs, setup_s3_with_configs,
+};
+use pbtb_rust::infra::instrumentrepository::S3InstrumentCatalogRepository;
+use pbtb_rust::infra::{DynamoBotRepository, EmfMetrics, S3BotConfigRepository};
+use pbtb_rust::telemetry::init_tracing;
+use pbtb_rust::usecase::{
+    EcsTaskController, RunDueSchedulesUseCase, RunTaskUseCase, ScheduleActionRunner,
+    SetStrategySideUseCase, StartBotUseCase, StopBotUseCase, TaskController, TaskRunner,
+    UpdateRiskLevelUseCase, UseCaseActionRunner,
+};
+
+mod event_handler;
+
+#[derive(Clone)]
+pub struct AppState {
+    run_due: Arc<RunDueSchedulesUseCase>,
+}
+
+/// Invoked every minute by an EventBridge schedule rule; fires the schedules
+/// that are due through the same use cases as the telebot.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    init_tracing();
+
+    // Cold start only: the telebot's full config (DynamoDB, S3, ECS), since the
+    // actions span bot rows, config objects and ECS tasks.
+    let configs: Configs =
+        load_config().map_err(|e| Error::from(format!("Failed to load configs: {e:#}")))?;
+
+    let (dynamodb_client, table_name) = setup_dynamodb_with_configs(&configs).await;
+    let (s3_client, bucket_name) = setup_s3_with_configs(&configs).await;
+    let ecs = configs
+        .ecs
+        .as_ref()
+        .ok_or_else(|| Error::from("ECS is not configured (APP__ECS__*)"))?;
+    let (ecs_client, cluster_arn, td_arn) = setup_ecs_with_configs(ecs).await;
+    let container_name = ecs.td_passivbot_container_name.clone();
+
+    // The same repo satisfies every DynamoDB-backed domain trait.
+    let repo = Arc::new(DynamoBotRepository::new(dynamodb_client, table_name));
+    let bots: Arc<dyn BotRepository> = repo.clone();
+    let runtimes: Arc<dyn BotRuntimeRepository> = repo.clone();
+    let start_locks: Arc<dyn StartLockRepository> = repo.clone();
+    let history: Arc<dyn BotHistoryRepository> = repo.clone();
+    let guards: Arc<dyn RiskGuardRepository> = repo.clone();
+    let schedules: Arc<dyn ScheduleRepository> = repo;
+    let bot_configs: Arc<dyn BotConfigRepository> = Arc::new(S3BotConfigRepository::new(
+        s3_client.clone(),
+        bucket_name.clone(),
+    ));
+    let instruments: Arc<dyn InstrumentCatalogRepository> =
+        Arc::new(S3InstrumentCatalogRepository::new(s3_client, bucket_name));
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let metrics: Arc<dyn Metrics> = Arc::new(EmfMetrics::stdout());
+
+    let task_runner: Arc<dyn TaskRunner> = Arc::new(RunTaskUseCase::new(ecs_client.clone()));
+    let task_controller: Arc<dyn TaskController> = Arc::new(EcsTaskController::new(ecs_client));
+    let start_bot = Arc::new(StartBotUseCase::new(
+        bots.clone(),
+        runtimes.clone(),
+        start_locks,
+        task_runner,
+        task_controller.clone(),
+        clock.clone(),
+        history.clone(),
+        metrics.clone(),
+        bot_configs.clone(),
+        instruments.clone(),
+        cluster_arn.clone(),
+        td_arn,
+        container_name,
+    ));
+    let stop_bot = Arc::new(StopBotUseCase::new(
+        bots.clone(),
+        runtimes,
+        guards,
+        task_controller,
+        clock.clone(),
+        history,
+        metrics,
+        cluster_arn,
+    ));
+    let update_risk_level = Arc::new(UpdateRiskLevelUseCase::new(
+        bots.clone(),
+        bot_configs.clone(),
+        instruments,
+        clock.clone(),
+    ));
+    let set_strategy_side = Arc::new(SetStrategySideUseCase::new(bot_configs, clock.clone()));
+
+    let runner: Arc<dyn ScheduleActionRunner> = Arc::new(UseCaseActionRunner::new(
+        start_bot,
+        stop_bot,
+        update_risk_level,
+        set_strategy_side,
+    ));
+    let state = Arc::new(AppState {
+        run_due: Arc::new(RunDueSchedulesUseCase::new(schedules, bots, runner, clock)),
+    });
+
+    run(service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let state = state.clone();
+        async move { event_handler::function_handler(event, state).await }
+    }))
+    .await
+}
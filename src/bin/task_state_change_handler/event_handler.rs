@@ -2,8 +2,11 @@ use aws_lambda_events::event::eventbridge::EventBridgeEvent;
 use std::sync::Arc;
 
 use crate::AppState;
+use lambda_runtime::tracing::Instrument;
 use lambda_runtime::{Error, LambdaEvent, tracing};
-use pbtb_rust::usecase::{ReconcileOutcome, RecordRunningOutcome, StopInfo};
+use pbtb_rust::domain::restart::{ContainerExit, StopReport};
+use pbtb_rust::telemetry::{CORRELATION_ID_ENV, CorrelationId, with_correlation_id};
+use pbtb_rust::usecase::{ReconcileOutcome, RecordRunningOutcome};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -47,14 +50,26 @@ struct EcsContainer {
     name: Option<String>,
     #[serde(rename = "exitCode")]
     exit_code: Option<i32>,
+    reason: Option<String>,
 }
 
-/// Extract (user_id, bot_id) from the first container override's environment.
-/// These are the overrides `RunTaskUseCase` injects, present for the whole task
+/// The bot a task runs and the action that launched it, as `RunTaskUseCase`
+/// injected them into the container overrides.
+#[derive(Debug, PartialEq)]
+struct TaskIdentity {
+    user_id: Option<String>,
+    bot_id: Option<String>,
+    /// Absent on tasks launched before correlation ids, or outside the telebot.
+    correlation_id: Option<CorrelationId>,
+}
+
+/// Extract the identity from the container overrides' environment. These are
+/// the overrides `RunTaskUseCase` injects, present for the whole task
 /// lifecycle, so they are available on both RUNNING and STOPPED events.
-fn extract_user_bot(detail: &EcsTaskStateChangeDetail) -> (Option<String>, Option<String>) {
+fn extract_identity(detail: &EcsTaskStateChangeDetail) -> TaskIdentity {
     let mut user_id: Option<String> = None;
     let mut bot_id: Option<String> = None;
+    let mut correlation_id: Option<CorrelationId> = None;
 
     // Scan every container override for the env-bearing one: a name-only sidecar
     // override (GuardDuty agent, Service Connect, etc.) can sort ahead of the
@@ -72,6 +87,7 @@ fn extract_user_bot(detail: &EcsTaskStateChangeDetail) -> (Option<String>, Optio
                     match k {
                         "USER_ID" | "user_id" => user_id = Some(v),
                         "BOT_ID" | "bot_id" => bot_id = Some(v),
+                        CORRELATION_ID_ENV => correlation_id = CorrelationId::parse(&v),
                         _ => {}
                     }
                 }
@@ -80,12 +96,38 @@ fn extract_user_bot(detail: &EcsTaskStateChangeDetail) -> (Option<String>, Optio
     }
 
     tracing::info!(
-        "Extracted from overrides: user_id={:?}, bot_id={:?}",
+        "Extracted from overrides: user_id={:?}, bot_id={:?}, correlation_id={:?}",
         user_id,
-        bot_id
+        bot_id,
+        correlation_id.as_ref().map(CorrelationId::as_str)
     );
 
-    (user_id, bot_id)
+    TaskIdentity {
+        user_id,
+        bot_id,
+        correlation_id,
+    }
+}
+
+/// Collect the task-level stop code and reason and every container's exit into
+/// a report. `main_container` is the configured passivbot container name, so
+/// the report can tell its exit apart from a sidecar's wherever it is listed.
+fn stop_report(detail: &EcsTaskStateChangeDetail, main_container: &str) -> StopReport {
+    StopReport {
+        stop_code: detail.stop_code.clone(),
+        stopped_reason: detail.stopped_reason.clone(),
+        main_container: main_container.to_string(),
+        containers: detail
+            .containers
+            .iter()
+            .flatten()
+            .map(|c| ContainerExit {
+                name: c.name.clone().unwrap_or_default(),
+                exit_code: c.exit_code,
+                reason: c.reason.clone(),
+            })
+            .collect(),
+    }
 }
 
 fn task_id_from_arn(task_arn: &str) -> &str {
@@ -131,14 +173,11 @@ pub(crate) async fn function_handler(
 
     let last_status = detail.last_status.as_deref().unwrap_or("");
     let task_arn = detail.task_arn.as_deref().unwrap_or("<unknown-task-arn>");
-    let cluster_arn = detail
-        .cluster_arn
-        .as_deref()
-        .unwrap_or("<unknown-cluster-arn>");
 
     // user_id/bot_id identify the bot for every state we act on. Skip if absent.
-    let (user_id, bot_id) = extract_user_bot(&detail);
-    let (Some(user_id), Some(bot_id)) = (user_id.as_deref(), bot_id.as_deref()) else {
+    let identity = extract_identity(&detail);
+    let (Some(user_id), Some(bot_id)) = (identity.user_id.as_deref(), identity.bot_id.as_deref())
+    else {
         tracing::warn!(
             "Missing USER_ID/BOT_ID in overrides; skip. taskArn={}, lastStatus={}",
             task_arn,
@@ -147,9 +186,43 @@ pub(crate) async fn function_handler(
         return Ok(());
     };
 
+    // Continue the launching action's correlation id, so these lines and a
+    // replacement task launched below join the ones that started the task.
+    let correlation_id = identity.correlation_id.clone().unwrap_or_default();
+    let span = tracing::info_span!(
+        "task_state_change",
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        bot_id = %bot_id,
+        task_id = %task_id_from_arn(task_arn),
+        last_status = %last_status,
+    );
+    with_correlation_id(
+        correlation_id,
+        handle_transition(&state, &detail, user_id, bot_id, observed_at).instrument(span),
+    )
+    .await
+}
+
+/// Act on a RUNNING or STOPPED transition of the bot's task.
+async fn handle_transition(
+    state: &AppState,
+    detail: &EcsTaskStateChangeDetail,
+    user_id: &str,
+    bot_id: &str,
+    observed_at: i64,
+) -> Result<(), Error> {
+    let last_status = detail.last_status.as_deref().unwrap_or("");
+    let task_arn = detail.task_arn.as_deref().unwrap_or("<unknown-task-arn>");
+    let cluster_arn = detail
+        .cluster_arn
+        .as_deref()
+        .unwrap_or("<unknown-cluster-arn>");
+
     match last_status {
         // Task reached RUNNING -> record observed-running. This fills the runtime
-        // row for any task, including ones started outside this Lambda.
+        // row for any task, including ones started outside this Lambda, unless a
+        // different live task already owns the row (then the newcomer is stopped).
         "RUNNING" => {
             let task_id = task_id_from_arn(task_arn);
             tracing::info!(
@@ -162,7 +235,7 @@ pub(crate) async fn function_handler(
 
             let outcome = state
                 .record_running
-                .execute(user_id, bot_id, task_id, observed_at)
+                .execute(user_id, bot_id, task_id, cluster_arn, observed_at)
                 .await
                 .map_err(|e| Error::from(format!("Failed to record running task: {e:#}")))?;
 
@@ -177,13 +250,38 @@ pub(crate) async fn function_handler(
                 RecordRunningOutcome::SkippedStale => {
                     tracing::warn!("Stale RUNNING event ignored. taskArn={}", task_arn);
                 }
+                RecordRunningOutcome::Conflict {
+                    owner_task_id,
+                    newcomer_stopped: true,
+                } => {
+                    tracing::warn!(
+                        "Duplicate task refused and stopped: taskArn={}, owner task_id={}, user_id={}, bot_id={}",
+                        task_arn,
+                        owner_task_id,
+                        user_id,
+                        bot_id
+                    );
+                }
+                RecordRunningOutcome::Conflict {
+                    owner_task_id,
+                    newcomer_stopped: false,
+                } => {
+                    tracing::error!(
+                        "Duplicate task refused but could NOT be stopped; two tasks are trading: taskArn={}, owner task_id={}, user_id={}, bot_id={}",
+                        task_arn,
+                        owner_task_id,
+                        user_id,
+                        bot_id
+                    );
+                }
             }
             Ok(())
         }
 
         // Task STOPPED -> delegate the restart-or-skip decision to the reconcile
-        // use case. It checks desired state (Bot.enabled), the memory-related rule,
-        // records observed runtime, and (re)starts the task when appropriate.
+        // use case. It checks desired state (Bot.enabled) and the bot's restart
+        // policy against the classified stop cause, records observed runtime, and
+        // (re)starts the task when appropriate.
         "STOPPED" => {
             tracing::info!(
                 "ECS task stopped: taskArn={}, clusterArn={}, stopCode={:?}, stoppedReason={:?}",
@@ -193,32 +291,31 @@ pub(crate) async fn function_handler(
                 detail.stopped_reason
             );
 
-            let container = match detail.containers.as_ref().and_then(|v| v.first()) {
-                Some(c) => c,
-                None => {
-                    tracing::info!(
-                        "No containers found in task detail; skip container-based handling. taskArn={}",
-                        task_arn
-                    );
-                    return Ok(());
-                }
-            };
-
-            let name = container.name.as_deref().unwrap_or("<unknown-container>");
-            let exit = container.exit_code.unwrap_or(-1);
-            let stop_code = detail.stop_code.as_deref().unwrap_or("<unknown-stop-code>");
-            tracing::info!(
-                "Container exit (single-container task): name={}, exitCode={}, reason={:?}",
-                name,
-                exit,
-                stop_code
-            );
-
             let cfg = &state.configs;
-            let stop = StopInfo {
-                exit_code: exit,
-                stop_code: stop_code.to_string(),
-            };
+            let report = stop_report(detail, &cfg.ecs.td_passivbot_container_name);
+            for c in &report.containers {
+                let role = if c.name == report.main_container {
+                    "passivbot"
+                } else {
+                    "sidecar"
+                };
+                tracing::info!(
+                    "Container exit ({}): name={}, exitCode={:?}, reason={:?}",
+                    role,
+                    c.name,
+                    c.exit_code,
+                    c.reason
+                );
+            }
+            if report.main().is_none() {
+                tracing::warn!(
+                    "Passivbot container {:?} not in STOPPED event; classifying from the other containers. taskArn={}",
+                    report.main_container,
+                    task_arn
+                );
+            }
+            tracing::info!("Classified stop cause: {}", report.cause().describe());
+
             let stopped_task_id = task_id_from_arn(task_arn);
 
             let outcome = state
@@ -230,7 +327,7 @@ pub(crate) async fn function_handler(
                     &cfg.ecs.cluster_arn,
                     &cfg.ecs.td_passivbot_arn,
                     &cfg.ecs.td_passivbot_container_name,
-                    stop,
+                    &report,
                     observed_at,
                     now_epoch(),
                 )
@@ -238,8 +335,12 @@ pub(crate) async fn function_handler(
                 .map_err(|e| Error::from(format!("Failed to reconcile stopped task: {e:#}")))?;
 
             match outcome {
-                ReconcileOutcome::Restarted { task_id } => {
-                    tracing::info!("Started replacement task_id={}", task_id);
+                ReconcileOutcome::Restarted { task_id, attempt } => {
+                    tracing::info!(
+                        "Started replacement task_id={} (consecutive restart {})",
+                        task_id,
+                        attempt
+                    );
                 }
                 ReconcileOutcome::SkippedNotEnabled => {
                     tracing::warn!(
@@ -247,9 +348,18 @@ pub(crate) async fn function_handler(
                         task_arn
                     );
                 }
-                ReconcileOutcome::SkippedNotMemoryRelated => {
+                ReconcileOutcome::SkippedByPolicy => {
                     tracing::warn!(
-                        "Task did not exit due to a memory-related reason; skipping restart. taskArn={}",
+                        "Restart policy does not cover this stop cause; not restarting. taskArn={}",
+                        task_arn
+                    );
+                }
+                ReconcileOutcome::SkippedMaxAttempts { attempts } => {
+                    tracing::error!(
+                        "Restart budget spent after {} consecutive restarts; leaving bot stopped. user_id={}, bot_id={}, taskArn={}",
+                        attempts,
+                        user_id,
+                        bot_id,
                         task_arn
                     );
                 }
@@ -277,3 +387,118 @@ pub(crate) async fn function_handler(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pbtb_rust::domain::restart::StopCause;
+
+    /// Captured EventBridge events, reduced to the fields the handler reads.
+    fn detail_of(event_json: &str) -> EcsTaskStateChangeDetail {
+        let event: serde_json::Value = serde_json::from_str(event_json).unwrap();
+        serde_json::from_value(event["detail"].clone()).unwrap()
+    }
+
+    #[test]
+    fn classifies_captured_stopped_events() {
+        let cases = [
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_oom.json"),
+                StopCause::OutOfMemory,
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_python_exception.json"),
+                StopCause::ProcessFailed { exit_code: 1 },
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_clean_exit.json"),
+                StopCause::Exited,
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_user_initiated.json"),
+                StopCause::UserInitiated,
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_spot_interruption.json"),
+                StopCause::Infrastructure {
+                    stop_code: "SpotInterruption".to_string(),
+                },
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_image_pull_failed.json"),
+                StopCause::Infrastructure {
+                    stop_code: "TaskFailedToStart".to_string(),
+                },
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_sidecar_first_oom.json"),
+                StopCause::OutOfMemory,
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_sidecar_first_failure.json"),
+                StopCause::ProcessFailed { exit_code: 1 },
+            ),
+            (
+                include_str!("../../../tests/fixtures/ecs/stopped_scheduler_drain.json"),
+                StopCause::Infrastructure {
+                    stop_code: "ServiceSchedulerInitiated".to_string(),
+                },
+            ),
+        ];
+        for (fixture, expected) in cases {
+            let detail = detail_of(fixture);
+            assert_eq!(
+                stop_report(&detail, "passivbot").cause(),
+                expected,
+                "{:?}",
+                detail.stopped_reason
+            );
+        }
+    }
+
+    #[test]
+    fn reads_identity_from_captured_event_overrides() {
+        let detail = detail_of(include_str!("../../../tests/fixtures/ecs/stopped_oom.json"));
+        assert_eq!(
+            extract_identity(&detail),
+            TaskIdentity {
+                user_id: Some("user-1".to_string()),
+                bot_id: Some("bot-1".to_string()),
+                correlation_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reads_the_correlation_id_a_launch_injected() {
+        let mut event: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/ecs/stopped_sidecar_first_oom.json"
+        ))
+        .unwrap();
+        let id = CorrelationId::new();
+        for co in event["detail"]["overrides"]["containerOverrides"]
+            .as_array_mut()
+            .unwrap()
+        {
+            if let Some(env) = co["environment"].as_array_mut() {
+                env.push(serde_json::json!({ "name": "CORRELATION_ID", "value": id.as_str() }));
+            }
+        }
+        let identity = extract_identity(&detail_of(&event.to_string()));
+        assert_eq!(identity.bot_id.as_deref(), Some("bot-1"));
+        assert_eq!(identity.correlation_id, Some(id));
+    }
+
+    #[test]
+    fn locates_the_passivbot_container_behind_a_sidecar() {
+        let detail = detail_of(include_str!(
+            "../../../tests/fixtures/ecs/stopped_sidecar_first_oom.json"
+        ));
+        let report = stop_report(&detail, "passivbot");
+        assert_eq!(report.containers.len(), 2);
+        assert_eq!(report.containers[0].name, "log-router");
+        let main = report.main().unwrap();
+        assert_eq!(main.exit_code, Some(137));
+        assert!(main.reason.as_deref().unwrap().contains("OutOfMemory"));
+    }
+}
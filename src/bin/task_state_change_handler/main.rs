@@ -1,14 +1,17 @@
 use std::sync::Arc;
 
 use aws_lambda_events::event::eventbridge::EventBridgeEvent;
-use lambda_runtime::{Error, LambdaEvent, run, service_fn, tracing};
+use lambda_runtime::{Error, LambdaEvent, run, service_fn};
 
 use crate::config::TaskStateChangeConfig;
 use pbtb_rust::config::configs::load_config;
 use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::history::BotHistoryRepository;
+use pbtb_rust::domain::metrics::Metrics;
 use pbtb_rust::domain::runtime::{BotRuntimeRepository, StartLockRepository};
-use pbtb_rust::infra::DynamoBotRepository;
 use pbtb_rust::infra::client::{create_dynamodb_client, create_ecs_client};
+use pbtb_rust::infra::{DynamoBotRepository, EmfMetrics};
+use pbtb_rust::telemetry::init_tracing;
 use pbtb_rust::usecase::{
     EcsTaskController, ReconcileStoppedTaskUseCase, RecordRunningTaskUseCase, RunTaskUseCase,
     TaskController, TaskRunner,
@@ -26,7 +29,7 @@ pub struct AppState {
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    tracing::init_default_subscriber();
+    init_tracing();
 
     // 冷启动初始化：只执行一次
     let configs: TaskStateChangeConfig =
@@ -46,9 +49,13 @@ async fn main() -> Result<(), Error> {
     let runtimes: Arc<dyn BotRuntimeRepository> = repo.clone();
     let runtimes_for_record: Arc<dyn BotRuntimeRepository> = repo.clone();
     let start_locks: Arc<dyn StartLockRepository> = repo.clone();
+    let history: Arc<dyn BotHistoryRepository> = repo.clone();
+    // Outcome counts and time-to-running, as EMF lines in the function's logs.
+    let metrics: Arc<dyn Metrics> = Arc::new(EmfMetrics::stdout());
 
     let run_task: Arc<dyn TaskRunner> = Arc::new(RunTaskUseCase::new(ecs_client.clone()));
-    // Stops a just-restarted task if the bot was disabled mid-launch.
+    // Stops a just-restarted task if the bot was disabled mid-launch, and a
+    // duplicate task that reports RUNNING while the tracked one is alive.
     let stopper: Arc<dyn TaskController> = Arc::new(EcsTaskController::new(ecs_client));
     // The auto-restart goes through the same exclusive start lock as the telebot,
     // so a duplicate STOPPED event cannot launch a second live-trading task.
@@ -57,10 +64,17 @@ async fn main() -> Result<(), Error> {
         runtimes,
         start_locks,
         run_task,
-        stopper,
+        stopper.clone(),
+        history.clone(),
+        metrics.clone(),
     ));
     // Observed-running recorder for the RUNNING branch.
-    let record_running = Arc::new(RecordRunningTaskUseCase::new(runtimes_for_record));
+    let record_running = Arc::new(RecordRunningTaskUseCase::new(
+        runtimes_for_record,
+        stopper,
+        history,
+        metrics,
+    ));
 
     let state = AppState {
         configs: Arc::new(configs),
@@ -1,4 +1,8 @@
 pub mod configs;
+pub mod docker;
 pub mod dynamodb;
 pub mod ecs;
+pub mod http;
+pub mod metrics;
 pub mod s3;
+pub mod telegram;
@@ -1,5 +1,8 @@
+use super::docker::DockerConfig;
 use super::dynamodb::DynamoDBConfig;
 use super::ecs::EcsConfig;
+use super::http::HttpConfig;
+use super::metrics::MetricsConfig;
 use super::s3::S3Config;
 use anyhow::Context;
 use serde::Deserialize;
@@ -9,7 +12,14 @@ use serde::de::DeserializeOwned;
 pub struct Configs {
     pub dynamodb: DynamoDBConfig,
     pub s3: S3Config,
-    pub ecs: EcsConfig,
+    /// Compute backend: ECS, or the local Docker engine when `docker` is set.
+    /// At least one must be configured; `docker` wins if both are.
+    pub ecs: Option<EcsConfig>,
+    pub docker: Option<DockerConfig>,
+    /// The HTTP management API; not served when unset.
+    pub http: Option<HttpConfig>,
+    /// The Prometheus endpoint; not served when unset.
+    pub metrics: Option<MetricsConfig>,
 }
 
 /// Build the config from `APP__*` environment variables, the single config
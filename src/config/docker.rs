@@ -0,0 +1,55 @@
+// src/config/docker.rs
+use serde::Deserialize;
+
+/// The local Docker compute backend. When set (`APP__DOCKER__IMAGE`), the
+/// telebot launches bots as containers on the local engine instead of ECS
+/// tasks and supervises them in-process.
+#[derive(Debug, Deserialize)]
+pub struct DockerConfig {
+    /// The passivbot image; plays the role of the ECS task definition.
+    pub image: String,
+    /// Label value grouping this deployment's containers, standing in for the
+    /// ECS cluster. Env: APP__DOCKER__CLUSTER.
+    #[serde(default = "default_cluster")]
+    pub cluster: String,
+    /// Name the passivbot container is reported under in stop reports and
+    /// history, like `td_passivbot_container_name` on ECS.
+    #[serde(default = "default_container_name")]
+    pub container_name: String,
+    /// Docker network to attach containers to (e.g. the one a local S3 runs
+    /// on). Unset uses the engine's default bridge.
+    pub network: Option<String>,
+    /// Comma-separated names of host environment variables copied into every
+    /// container, e.g. `AWS_ACCESS_KEY_ID,AWS_SECRET_ACCESS_KEY` for the
+    /// entrypoint's S3 download.
+    #[serde(default)]
+    pub forward_env: String,
+    /// How often the in-process sweeper repairs state missed while the event
+    /// stream was down.
+    #[serde(default = "default_sweep_interval_secs")]
+    pub sweep_interval_secs: u64,
+}
+
+fn default_cluster() -> String {
+    "pbtb-local".to_string()
+}
+
+fn default_container_name() -> String {
+    "passivbot".to_string()
+}
+
+fn default_sweep_interval_secs() -> u64 {
+    300
+}
+
+impl DockerConfig {
+    /// The forwarded variables as `NAME=value`, skipping any unset on the host.
+    pub fn forwarded_env(&self) -> Vec<String> {
+        self.forward_env
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| std::env::var(name).ok().map(|v| format!("{name}={v}")))
+            .collect()
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// The HTTP management API. When set (`APP__HTTP__BIND_ADDR`), the telebot
+/// also serves `interface::http` on this address. It speaks plain HTTP, so
+/// anything but loopback belongs behind a TLS-terminating proxy.
+#[derive(Debug, Deserialize)]
+pub struct HttpConfig {
+    /// e.g. `127.0.0.1:8080`.
+    pub bind_addr: String,
+}
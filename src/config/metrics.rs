@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// The Prometheus endpoint. When set (`APP__METRICS__BIND_ADDR`), the telebot
+/// serves its counters and histograms as `GET /metrics` on this address.
+#[derive(Debug, Deserialize)]
+pub struct MetricsConfig {
+    /// e.g. `127.0.0.1:9464`. Unauthenticated: bind it where only the scraper
+    /// can reach it.
+    pub bind_addr: String,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+/// How a Lambda finds the telebot's Bot API token to message users.
+#[derive(Debug, Deserialize)]
+pub struct TelegramConfig {
+    /// SSM SecureString holding the telebot's token.
+    pub token_param: String,
+}
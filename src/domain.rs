@@ -1,13 +1,34 @@
+pub mod account;
+pub mod apitoken;
 pub mod bot;
 pub mod botconfig;
 pub mod clock;
 pub mod configtemplate;
 pub mod error;
 pub mod exchange;
+pub mod history;
+pub mod instrument;
+pub mod leverage;
+pub mod metrics;
+pub mod notify;
+pub mod preferences;
+pub mod resources;
+pub mod restart;
+pub mod riskguard;
+pub mod riskpreset;
 pub mod runtime;
+pub mod schedule;
+pub mod trading;
 
 pub use bot::{ApiKeyRepository, Bot, BotRepository};
 pub use botconfig::RiskLevel;
 pub use clock::SystemClock;
 pub use configtemplate::ConfigTemplate;
-pub use runtime::{BotRuntimeRepository, RuntimePhase, StartLockRepository};
+pub use history::{BotEvent, BotHistoryRepository};
+pub use leverage::{LeveragePolicy, LeveragePolicyKind};
+pub use metrics::{Metrics, NoopMetrics};
+pub use resources::ResourceProfile;
+pub use restart::{RestartPolicy, StopCause, StopReport};
+pub use runtime::{BotRuntimeRepository, RuntimePhase, RuntimeScanRepository, StartLockRepository};
+pub use schedule::ScheduleRepository;
+pub use trading::ExchangeTradingGateway;
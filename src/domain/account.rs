@@ -0,0 +1,77 @@
+use crate::domain::bot::Bot;
+use crate::domain::error::DomainError;
+use crate::domain::trading::{Position, PositionSide};
+use async_trait::async_trait;
+
+/// Direction of an order or fill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+}
+
+/// A closed (or partly closed) position and what it realised, fees included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedPnl {
+    pub symbol: String,
+    /// The side of the position that was closed.
+    pub side: PositionSide,
+    pub qty: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub pnl: f64,
+    /// Unix seconds.
+    pub closed_at: i64,
+}
+
+/// One execution of one of the bot's orders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub qty: f64,
+    /// In the settle coin; negative for a maker rebate.
+    pub fee: f64,
+    pub is_maker: bool,
+    /// Unix seconds.
+    pub executed_at: i64,
+}
+
+/// Port for reading how a bot's exchange account is doing. Credentials come
+/// from the bot row; every read is the bot's own sub-account.
+#[async_trait]
+pub trait ExchangeAccount: Send + Sync {
+    /// Positions closed between `since` and `until` (unix seconds), newest
+    /// first.
+    async fn closed_pnl(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<ClosedPnl>, DomainError>;
+
+    /// Up to `limit` trade executions between `since` and `until` (unix
+    /// seconds), newest first.
+    async fn fills(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+        limit: usize,
+    ) -> Result<Vec<Fill>, DomainError>;
+
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError>;
+
+    /// The account's equity (wallet balance plus unrealized PnL) in USD.
+    async fn equity(&self, bot: &Bot) -> Result<f64, DomainError>;
+}
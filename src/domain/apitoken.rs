@@ -0,0 +1,63 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+/// Prefix of every issued token, so a leaked one is recognisable in logs and
+/// secret scanners.
+pub const API_TOKEN_PREFIX: &str = "pbtb_";
+
+/// A user's bearer token for the HTTP API. Only the SHA-256 of the secret is
+/// stored; the secret itself is shown once, when issued. A user has at most
+/// one token: issuing a new one replaces the old.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    /// Hex SHA-256 of the secret; the lookup key.
+    pub token_hash: String,
+    pub user_id: String,
+    pub created_at: i64,
+}
+
+impl ApiToken {
+    /// Mint a token for `user_id`. Returns the secret to hand to the user and
+    /// the record to store.
+    pub fn issue(user_id: &str, now: i64) -> (String, Self) {
+        let secret = format!("{}{}", API_TOKEN_PREFIX, uuid::Uuid::new_v4().simple());
+        let token = Self {
+            token_hash: Self::hash(&secret),
+            user_id: user_id.to_string(),
+            created_at: now,
+        };
+        (secret, token)
+    }
+
+    pub fn hash(secret: &str) -> String {
+        hex::encode(Sha256::digest(secret.as_bytes()))
+    }
+}
+
+/// Storage for API tokens: looked up by hash on every request, and by user
+/// when a token is rotated or revoked.
+#[async_trait]
+pub trait ApiTokenRepository: Send + Sync {
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, DomainError>;
+    async fn find_by_user(&self, user_id: &str) -> Result<Option<ApiToken>, DomainError>;
+    async fn save(&self, token: &ApiToken) -> Result<(), DomainError>;
+    async fn delete(&self, token: &ApiToken) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_secret_hashes_to_the_stored_hash() {
+        let (secret, token) = ApiToken::issue("u", 7);
+        assert!(secret.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(token.token_hash, ApiToken::hash(&secret));
+        assert_eq!(token.user_id, "u");
+        assert_eq!(token.created_at, 7);
+
+        let (other, _) = ApiToken::issue("u", 7);
+        assert_ne!(secret, other);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::domain::error::DomainError;
 use crate::domain::exchange::Exchange;
+use crate::domain::leverage::LeveragePolicyKind;
+use crate::domain::resources::ResourceProfile;
+use crate::domain::restart::RestartPolicy;
 use async_trait::async_trait;
 
 #[derive(Debug, Clone)]
@@ -11,6 +14,15 @@ pub struct Bot {
     pub api_key: String,
     pub secret_key: String,
     pub enabled: bool,
+    /// How leverage is derived from the risk level for this bot.
+    pub leverage_policy: LeveragePolicyKind,
+    /// Ceiling on leveraged exposure (`RiskLevel::leveraged_exposure`) above
+    /// which the risk dialogue warns before saving. `None` disables the warning.
+    pub exposure_ceiling: Option<f64>,
+    /// Which stops the ECS Lambda restarts this bot's task after.
+    pub restart_policy: RestartPolicy,
+    /// CPU/memory the bot's task is launched with.
+    pub resource_profile: ResourceProfile,
     pub created_at: i64, // Unix timestamp in seconds
     pub updated_at: i64, // Unix timestamp in seconds
 }
@@ -36,11 +48,40 @@ impl Bot {
             api_key,
             secret_key,
             enabled,
+            leverage_policy: LeveragePolicyKind::default(),
+            exposure_ceiling: None,
+            restart_policy: RestartPolicy::default(),
+            resource_profile: ResourceProfile::default(),
             created_at,
             updated_at,
         }
     }
 
+    /// Longest name (in bytes) a new bot may have; `select_bot:<id>` has to
+    /// fit Telegram's 64-byte callback data.
+    pub const MAX_NAME_LEN: usize = 32;
+
+    /// Check a name for a new bot. The name becomes the id, which is a
+    /// segment of DynamoDB sort keys (`#`-separated) and S3 keys
+    /// (`/`-separated), so neither separator may appear in it.
+    pub fn validate_name(name: &str) -> Result<(), DomainError> {
+        let problem = if name.trim().is_empty() {
+            "it must not be empty".to_string()
+        } else if name.trim() != name {
+            "it must not start or end with whitespace".to_string()
+        } else if name.len() > Self::MAX_NAME_LEN {
+            format!("it must be at most {} characters", Self::MAX_NAME_LEN)
+        } else if let Some(c) = name
+            .chars()
+            .find(|c| matches!(c, '#' | '/') || c.is_control())
+        {
+            format!("it must not contain {c:?}")
+        } else {
+            return Ok(());
+        };
+        Err(DomainError::InvalidBotName(problem))
+    }
+
     /// Factory encapsulating the construction policy for a newly added bot.
     /// id is derived from the name, exchange defaults to Bybit, and the bot
     /// starts disabled (desired state off).
@@ -59,6 +100,10 @@ impl Bot {
             api_key,
             secret_key,
             enabled: false,
+            leverage_policy: LeveragePolicyKind::default(),
+            exposure_ceiling: None,
+            restart_policy: RestartPolicy::default(),
+            resource_profile: ResourceProfile::default(),
             created_at: now,
             updated_at: now,
         }
@@ -75,6 +120,44 @@ impl Bot {
         self.enabled = false;
         self.updated_at = now;
     }
+
+    /// Select how leverage is derived from this bot's risk level. Takes effect
+    /// the next time a risk level is applied.
+    pub fn set_leverage_policy(&mut self, policy: LeveragePolicyKind, now: i64) {
+        self.leverage_policy = policy;
+        self.updated_at = now;
+    }
+
+    /// Select which stops the task is automatically restarted after. Takes
+    /// effect at the next STOPPED event.
+    pub fn set_restart_policy(&mut self, policy: RestartPolicy, now: i64) {
+        self.restart_policy = policy;
+        self.updated_at = now;
+    }
+
+    /// Select the task size. Takes effect at the next launch; a running task
+    /// keeps the size it was started with.
+    pub fn set_resource_profile(&mut self, profile: ResourceProfile, now: i64) {
+        self.resource_profile = profile;
+        self.updated_at = now;
+    }
+
+    /// Set (or clear with `None`) the leveraged-exposure warning ceiling. A
+    /// ceiling must be a positive finite number.
+    pub fn set_exposure_ceiling(
+        &mut self,
+        ceiling: Option<f64>,
+        now: i64,
+    ) -> Result<(), DomainError> {
+        if let Some(value) = ceiling.filter(|v| !v.is_finite() || *v <= 0.0) {
+            return Err(DomainError::InvalidConfig(format!(
+                "exposure ceiling must be a positive number, got {value}"
+            )));
+        }
+        self.exposure_ceiling = ceiling;
+        self.updated_at = now;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -95,10 +178,75 @@ pub trait BotRepository: Send + Sync {
         self.find(user_id, bot_id).await
     }
     async fn save(&self, bot: &Bot) -> Result<(), DomainError>;
+    /// Move the stored resource profile from `from` to `to` after the
+    /// out-of-memory stop of `stopped_task_id`, touching nothing else so a
+    /// concurrent desired-state change is not overwritten by a stale
+    /// whole-bot save. `Ok(false)` means nothing was written: the bot is
+    /// gone, its profile is no longer `from`, or this task's stop already
+    /// promoted it (a redelivered STOPPED event). Defaults to a
+    /// read-modify-save that checks only the profile; the DynamoDB
+    /// implementation overrides it with a conditional update that also
+    /// records the task.
+    async fn promote_resource_profile(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        from: ResourceProfile,
+        to: ResourceProfile,
+        stopped_task_id: &str,
+        now: i64,
+    ) -> Result<bool, DomainError> {
+        let _ = stopped_task_id;
+        match self.find(user_id, bot_id).await? {
+            Some(mut bot) if bot.resource_profile == from => {
+                bot.set_resource_profile(to, now);
+                self.save(&bot).await.map(|()| true)
+            }
+            _ => Ok(false),
+        }
+    }
     async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError>;
+    /// Up to `limit` of the user's bots in id order, starting after the bot id
+    /// `after` (the previous page's `next_cursor`). Defaults to paging over
+    /// `find_by_user_id`; the DynamoDB implementation overrides it with a
+    /// Query that resumes from the cursor.
+    async fn find_page_by_user_id(
+        &self,
+        user_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<BotPage, DomainError> {
+        let mut bots = self.find_by_user_id(user_id).await?;
+        bots.sort_by(|a, b| a.id.cmp(&b.id));
+        bots.retain(|b| after.is_none_or(|cursor| b.id.as_str() > cursor));
+        Ok(BotPage::from_sorted(bots, limit))
+    }
     async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String>;
 }
 
+/// One page of a user's bots, in id order. `next_cursor` is the id to resume
+/// after for the next page; `None` on the last page.
+#[derive(Debug, Clone)]
+pub struct BotPage {
+    pub bots: Vec<Bot>,
+    pub next_cursor: Option<String>,
+}
+
+impl BotPage {
+    /// Cut `bots` (already in id order, starting at the cursor) to a page of
+    /// `limit`. A cursor is set only when at least one more bot follows, so
+    /// a full last page does not lead to an empty one.
+    pub fn from_sorted(mut bots: Vec<Bot>, limit: usize) -> Self {
+        let next_cursor = if bots.len() > limit {
+            bots.truncate(limit);
+            bots.last().map(|b| b.id.clone())
+        } else {
+            None
+        };
+        Self { bots, next_cursor }
+    }
+}
+
 /// Domain port for persisting a bot's exchange API keys (e.g. to object
 /// storage). Use cases depend on this abstraction, not the concrete infra impl.
 #[async_trait]
@@ -125,10 +273,59 @@ mod tests {
         assert_eq!(bot.user_id, "user-1");
         assert_eq!(bot.exchange, Exchange::Bybit);
         assert!(!bot.enabled);
+        assert_eq!(bot.leverage_policy, LeveragePolicyKind::default());
+        assert_eq!(bot.restart_policy, RestartPolicy::default());
+        assert_eq!(bot.resource_profile, ResourceProfile::Small);
+        assert_eq!(bot.exposure_ceiling, None);
         assert_eq!(bot.created_at, 42);
         assert_eq!(bot.updated_at, 42);
     }
 
+    #[test]
+    fn names_that_cannot_be_keys_are_rejected() {
+        for name in [
+            "grid-1",
+            "PaperTrader",
+            "BTC grid 2",
+            &"x".repeat(Bot::MAX_NAME_LEN),
+        ] {
+            assert!(Bot::validate_name(name).is_ok(), "{name}");
+        }
+        for name in [
+            "",
+            "   ",
+            " grid",
+            "grid#1",
+            "a/b",
+            "tab\there",
+            &"x".repeat(Bot::MAX_NAME_LEN + 1),
+        ] {
+            assert!(
+                matches!(
+                    Bot::validate_name(name),
+                    Err(DomainError::InvalidBotName(_))
+                ),
+                "{name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn page_sets_a_cursor_only_when_more_bots_follow() {
+        let bots: Vec<Bot> = ["a", "b", "c"]
+            .iter()
+            .map(|id| Bot::create("u".into(), id.to_string(), "ak".into(), "sk".into(), 0))
+            .collect();
+
+        let page = BotPage::from_sorted(bots.clone(), 2);
+        assert_eq!(page.bots.len(), 2);
+        assert_eq!(page.next_cursor.as_deref(), Some("b"));
+
+        let page = BotPage::from_sorted(bots, 3);
+        assert_eq!(page.bots.len(), 3);
+        assert_eq!(page.next_cursor, None);
+    }
+
     #[test]
     fn enable_disable_transitions() {
         let mut bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 1);
@@ -139,4 +336,18 @@ mod tests {
         assert!(!bot.enabled);
         assert_eq!(bot.updated_at, 200);
     }
+
+    #[test]
+    fn exposure_ceiling_must_be_positive() {
+        let mut bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 0);
+        assert!(bot.set_exposure_ceiling(Some(0.0), 1).is_err());
+        assert!(bot.set_exposure_ceiling(Some(f64::NAN), 1).is_err());
+        assert_eq!(bot.updated_at, 0);
+
+        bot.set_exposure_ceiling(Some(40.0), 2).unwrap();
+        assert_eq!(bot.exposure_ceiling, Some(40.0));
+        bot.set_exposure_ceiling(None, 3).unwrap();
+        assert_eq!(bot.exposure_ceiling, None);
+        assert_eq!(bot.updated_at, 3);
+    }
 }
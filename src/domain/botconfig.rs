@@ -1,5 +1,7 @@
 use crate::domain::ConfigTemplate;
 use crate::domain::error::DomainError;
+use crate::domain::instrument::InstrumentCatalog;
+use crate::domain::leverage::LeveragePolicy;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -33,6 +35,13 @@ impl RiskLevel {
         Ok(Self { long, short })
     }
 
+    /// Wallet exposure scaled by leverage, summed over both sides
+    /// (`long * leverage.long + short * leverage.short`). This is the figure the
+    /// risk dialogue compares against the bot's exposure ceiling.
+    pub fn leveraged_exposure(&self, leverage: &Leverage) -> f64 {
+        self.long * leverage.long + self.short * leverage.short
+    }
+
     fn check(value: f64) -> Result<(), DomainError> {
         if value < 0.0 || value > 10.0 {
             return Err(DomainError::RiskOutOfRange {
@@ -247,6 +256,61 @@ impl BotConfig {
         Ok(Coins::new(long_coins, short_coins))
     }
 
+    /// Replace one side's (`"long"`/`"short"`) approved coins and bump
+    /// `updated_at`. Errors if the side is invalid or `live.approved_coins` is
+    /// missing or not an object.
+    pub fn set_coins(&mut self, side: &str, coins: &[String], now: i64) -> Result<(), DomainError> {
+        if side != "long" && side != "short" {
+            return Err(DomainError::InvalidConfig(format!(
+                "invalid side: {}",
+                side
+            )));
+        }
+        let approved = self
+            .config_data
+            .get_mut("live")
+            .and_then(|live| live.get_mut("approved_coins"))
+            .ok_or(DomainError::MissingConfigPath("live.approved_coins"))?
+            .as_object_mut()
+            .ok_or_else(|| {
+                DomainError::InvalidConfig("live.approved_coins is not an object".to_string())
+            })?;
+        approved.insert(side.to_string(), serde_json::json!(coins));
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Check the approved coins and the leverage against the exchange's
+    /// instruments: every coin must be listed and trading, and the leverage
+    /// must not exceed any coin's max leverage. All problems are reported in
+    /// one `InvalidConfig`. Coins not in the per-side form (e.g. a passivbot
+    /// coins file) cannot be checked and pass.
+    pub fn check_instruments(&self, catalog: &InstrumentCatalog) -> Result<(), DomainError> {
+        let Ok(coins) = self.coins() else {
+            return Ok(());
+        };
+        let coins = coins.all_coins();
+        let mut problems = catalog.coin_problems(&coins);
+        // Either side's leverage applies to every approved coin.
+        let too_high = self
+            .leverage()
+            .ok()
+            .map(|leverage| leverage.long.max(leverage.short))
+            .zip(catalog.tightest_leverage(&coins))
+            .filter(|(leverage, tightest)| *leverage > tightest.max_leverage);
+        if let Some((leverage, tightest)) = too_high {
+            problems.push(format!(
+                "leverage {}x exceeds the {}x maximum of {}",
+                leverage, tightest.max_leverage, tightest.symbol
+            ));
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::InvalidConfig(problems.join("; ")))
+        }
+    }
+
     /// Update risk level in config data
     pub fn set_risk_level(&mut self, risk_level: &RiskLevel) -> Result<(), DomainError> {
         let bot = self
@@ -285,20 +349,21 @@ impl BotConfig {
 
     /// Apply a risk level and derive the leverage from it.
     ///
-    /// This is the single place the "leverage = max(long, short) + 1" policy
-    /// lives. It sets the risk level, derives a uniform leverage, sets it, and
-    /// bumps `updated_at`.
-    pub fn apply_risk_level(&mut self, risk: &RiskLevel, now: i64) -> Result<(), DomainError> {
+    /// The derivation rule is the bot's `LeveragePolicy`; this is the single
+    /// place it is applied. Sets the risk level, derives a uniform leverage via
+    /// the policy, sets it, and bumps `updated_at`. Nothing is changed when the
+    /// policy cannot produce an in-range leverage for this risk.
+    pub fn apply_risk_level(
+        &mut self,
+        risk: &RiskLevel,
+        policy: &dyn LeveragePolicy,
+        now: i64,
+    ) -> Result<Leverage, DomainError> {
+        let leverage = policy.derive(risk)?;
         self.set_risk_level(risk)?;
-        // Derived leverage = max(long, short) + 1.0. For any valid RiskLevel
-        // (both in [0.0, 10.0]) this lands in [1.0, 11.0], which is inside
-        // Leverage's allowed [1.0, 125.0] range, so the `?` below cannot fail
-        // today. This couples the two value objects' ranges implicitly: if the
-        // RiskLevel range is widened, revisit whether this can overflow Leverage.
-        let leverage = Leverage::uniform(risk.long.max(risk.short) + 1.0)?;
         self.set_leverage(&leverage)?;
         self.updated_at = now;
-        Ok(())
+        Ok(leverage)
     }
 
     /// Strategy name the config derives from (the `predefined/` template stem),
@@ -377,6 +442,20 @@ impl BotConfig {
         enabled: bool,
         now: i64,
     ) -> Result<(), DomainError> {
+        let mode = if enabled { "" } else { "graceful_stop" };
+        self.set_forced_mode(side, mode, now)
+    }
+
+    /// Put both sides into passivbot's `"panic"` mode (cancel orders and close
+    /// positions at market). Used by the kill switch so that any task that does
+    /// get (re)started runs flat instead of trading. Same errors as
+    /// `set_side_enabled`.
+    pub fn set_panic(&mut self, now: i64) -> Result<(), DomainError> {
+        self.set_forced_mode("long", "panic", now)?;
+        self.set_forced_mode("short", "panic", now)
+    }
+
+    fn set_forced_mode(&mut self, side: &str, mode: &str, now: i64) -> Result<(), DomainError> {
         if side != "long" && side != "short" {
             return Err(DomainError::InvalidConfig(format!(
                 "invalid side: {}",
@@ -393,10 +472,9 @@ impl BotConfig {
             DomainError::InvalidConfig("config.live is not an object".to_string())
         })?;
 
-        let value = if enabled { "" } else { "graceful_stop" };
         obj.insert(
             format!("forced_mode_{}", side),
-            Value::String(value.to_string()),
+            Value::String(mode.to_string()),
         );
         self.updated_at = now;
         Ok(())
@@ -458,6 +536,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn leveraged_exposure_sums_both_sides() {
+        let risk = RiskLevel::new(2.0, 1.0).unwrap();
+        let lev = Leverage::new(3.0, 4.0).unwrap();
+        assert_eq!(risk.leveraged_exposure(&lev), 10.0);
+    }
+
     #[test]
     fn leverage_ok_out_of_range_and_uniform() {
         assert!(Leverage::new(5.0, 10.0).is_ok());
@@ -501,7 +586,9 @@ mod tests {
     fn apply_risk_level_derives_leverage() {
         let mut config = sample_config(0);
         let risk = RiskLevel::new(3.0, 5.0).unwrap();
-        config.apply_risk_level(&risk, 999).unwrap();
+        let policy = crate::domain::leverage::FixedOffset { offset: 1.0 };
+        let derived = config.apply_risk_level(&risk, &policy, 999).unwrap();
+        assert_eq!(derived, Leverage::uniform(6.0).unwrap());
 
         let stored_risk = config.risk_level().unwrap();
         assert_eq!(stored_risk.long, 3.0);
@@ -515,6 +602,17 @@ mod tests {
         assert_eq!(config.updated_at, 999);
     }
 
+    #[test]
+    fn apply_risk_level_leaves_config_untouched_when_policy_fails() {
+        let mut config = sample_config(0);
+        let risk = RiskLevel::new(10.0, 0.0).unwrap();
+        let policy = crate::domain::leverage::Multiplier { factor: 20.0 };
+        assert!(config.apply_risk_level(&risk, &policy, 999).is_err());
+        assert_eq!(config.risk_level().unwrap().long, 1.0);
+        assert_eq!(config.leverage().unwrap().long, 2.0);
+        assert_eq!(config.updated_at, 0);
+    }
+
     #[test]
     fn set_live_user_sets_and_validates() {
         let mut config = sample_config(0);
@@ -567,4 +665,58 @@ mod tests {
         config.config_data["description"] = json!("   ");
         assert_eq!(config.description(), None); // blank → None
     }
+
+    #[test]
+    fn coins_are_set_per_side_and_checked_against_the_instruments() {
+        use crate::domain::exchange::Exchange;
+        use crate::domain::instrument::Instrument;
+
+        let mut config = sample_config(0);
+        config.config_data["live"]["approved_coins"] = json!({ "long": [], "short": [] });
+        config.config_data["live"]["leverage"] = json!(30.0);
+        config
+            .set_coins("long", &["BTC".to_string(), "XRP".to_string()], 5)
+            .unwrap();
+        assert_eq!(config.coins().unwrap().long, vec!["BTC", "XRP"]);
+        assert_eq!(config.updated_at, 5);
+        assert!(config.set_coins("both", &[], 6).is_err());
+
+        let instrument = |symbol: &str, base: &str, max_leverage: f64| Instrument {
+            symbol: symbol.into(),
+            base_coin: base.into(),
+            status: "Trading".into(),
+            min_qty: 0.1,
+            tick_size: 0.0001,
+            max_leverage,
+        };
+        let mut catalog = InstrumentCatalog {
+            exchange: Exchange::Bybit,
+            fetched_at: 0,
+            instruments: vec![instrument("BTCUSDT", "BTC", 100.0)],
+        };
+        let err = config.check_instruments(&catalog).unwrap_err().to_string();
+        assert_eq!(err, "invalid config: XRP is not listed on bybit");
+
+        catalog.instruments.push(instrument("XRPUSDT", "XRP", 25.0));
+        let err = config.check_instruments(&catalog).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "invalid config: leverage 30x exceeds the 25x maximum of XRPUSDT"
+        );
+        config.config_data["live"]["leverage"] = json!(25.0);
+        assert!(config.check_instruments(&catalog).is_ok());
+    }
+
+    #[test]
+    fn set_panic_forces_both_sides_off() {
+        let mut config = sample_config(0);
+        config.set_panic(7).unwrap();
+        assert_eq!(config.config_data["live"]["forced_mode_long"], "panic");
+        assert_eq!(config.config_data["live"]["forced_mode_short"], "panic");
+        assert!(!config.side_enabled("long") && !config.side_enabled("short"));
+        assert_eq!(config.updated_at, 7);
+
+        config.config_data = json!({ "bot": {} });
+        assert!(config.set_panic(8).is_err());
+    }
 }
@@ -18,14 +18,35 @@ impl Clock for SystemClock {
     }
 }
 
-#[cfg(test)]
+/// Settable clock for tests; `advance` moves time forward between steps of a
+/// scenario (e.g. before and after a schedule's slot).
+#[cfg(any(test, feature = "testing"))]
 pub struct MockClock {
-    pub timestamp: i64,
+    timestamp: std::sync::atomic::AtomicI64,
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "testing"))]
+impl MockClock {
+    pub fn new(timestamp: i64) -> Self {
+        Self {
+            timestamp: std::sync::atomic::AtomicI64::new(timestamp),
+        }
+    }
+
+    pub fn set(&self, timestamp: i64) {
+        self.timestamp
+            .store(timestamp, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, secs: i64) {
+        self.timestamp
+            .fetch_add(secs, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
 impl Clock for MockClock {
     fn now(&self) -> i64 {
-        self.timestamp
+        self.timestamp.load(std::sync::atomic::Ordering::SeqCst)
     }
 }
@@ -21,4 +21,7 @@ pub trait ConfigTemplateRepository: Send + Sync {
 
     /// Check if template exists
     async fn exists(&self, template_name: &str) -> Result<bool, String>;
+
+    /// Create or replace the template named `template.name`
+    async fn save(&self, template: &ConfigTemplate) -> Result<(), String>;
 }
@@ -8,6 +8,12 @@ pub enum DomainError {
     MissingConfigPath(&'static str),
     #[error("invalid config: {0}")]
     InvalidConfig(String),
+    #[error("invalid bot name: {0}")]
+    InvalidBotName(String),
     #[error("repository error: {0}")]
     Repository(String),
+    #[error("exchange error: {0}")]
+    Exchange(String),
+    #[error("delivery error: {0}")]
+    Delivery(String),
 }
@@ -0,0 +1,124 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+
+/// How long a lifecycle event is kept before DynamoDB's TTL deletes it. Keeps
+/// the per-bot history bounded without a cleanup job.
+pub const BOT_EVENT_RETENTION_SECS: i64 = 30 * 24 * 3600;
+
+/// What happened to a bot's task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotEventKind {
+    /// A user (or a schedule / bulk action) launched a task.
+    Started,
+    /// A user (or a schedule / bulk action) asked the bot to stop.
+    StopRequested,
+    /// ECS reported a task RUNNING and it was recorded as the bot's task.
+    Running,
+    /// ECS reported a task RUNNING while another live task owned the bot; the
+    /// newcomer was refused.
+    DuplicateRefused,
+    /// ECS reported the task STOPPED.
+    Stopped,
+    /// The reconcile Lambda launched a replacement after a stop.
+    Restarted,
+    /// The bot's resource profile was promoted after an out-of-memory kill.
+    Resized,
+    /// The bot's risk guard tripped; both sides were set to `graceful_stop`.
+    GuardTripped,
+}
+
+impl BotEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::StopRequested => "stop_requested",
+            Self::Running => "running",
+            Self::DuplicateRefused => "duplicate_refused",
+            Self::Stopped => "stopped",
+            Self::Restarted => "restarted",
+            Self::Resized => "resized",
+            Self::GuardTripped => "guard_tripped",
+        }
+    }
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "started" => Some(Self::Started),
+            "stop_requested" => Some(Self::StopRequested),
+            "running" => Some(Self::Running),
+            "duplicate_refused" => Some(Self::DuplicateRefused),
+            "stopped" => Some(Self::Stopped),
+            "restarted" => Some(Self::Restarted),
+            "resized" => Some(Self::Resized),
+            "guard_tripped" => Some(Self::GuardTripped),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of a bot's lifecycle history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotEvent {
+    pub user_id: String,
+    pub bot_id: String,
+    /// Epoch seconds the event happened (the ECS event time for observations).
+    pub at: i64,
+    pub kind: BotEventKind,
+    pub task_id: Option<String>,
+    /// Human-readable cause or outcome, e.g. "out of memory (exit 137)".
+    pub detail: Option<String>,
+    /// Consecutive automatic restart number, on `Restarted` events.
+    pub restart_attempt: Option<u32>,
+}
+
+impl BotEvent {
+    pub fn new(user_id: &str, bot_id: &str, kind: BotEventKind, at: i64) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            bot_id: bot_id.to_string(),
+            at,
+            kind,
+            task_id: None,
+            detail: None,
+            restart_attempt: None,
+        }
+    }
+}
+
+/// Append-only, bounded per-bot lifecycle history. Writers treat an append
+/// failure as non-fatal: losing an entry must never fail a start, stop or
+/// restart.
+#[async_trait]
+pub trait BotHistoryRepository: Send + Sync {
+    /// Store `event`; an identical event (same bot, second and kind, e.g. a
+    /// redelivered ECS event) overwrites rather than duplicates.
+    async fn append(&self, event: &BotEvent) -> Result<(), DomainError>;
+    /// The bot's most recent events, newest first, at most `limit`.
+    async fn recent(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        limit: usize,
+    ) -> Result<Vec<BotEvent>, DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_kind_round_trip() {
+        for kind in [
+            BotEventKind::Started,
+            BotEventKind::StopRequested,
+            BotEventKind::Running,
+            BotEventKind::DuplicateRefused,
+            BotEventKind::Stopped,
+            BotEventKind::Restarted,
+            BotEventKind::Resized,
+            BotEventKind::GuardTripped,
+        ] {
+            assert_eq!(BotEventKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(BotEventKind::parse("bogus"), None);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::domain::error::DomainError;
+use crate::domain::exchange::Exchange;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Trading rules of one USDT-settled perpetual, as the exchange's public
+/// instruments endpoint reports them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instrument {
+    /// Exchange symbol, e.g. `BTCUSDT` or `1000PEPEUSDT`.
+    pub symbol: String,
+    /// Base coin as the exchange names it, e.g. `BTC` or `1000PEPE`.
+    pub base_coin: String,
+    /// Exchange status; only `Trading` accepts orders.
+    pub status: String,
+    pub min_qty: f64,
+    pub tick_size: f64,
+    pub max_leverage: f64,
+}
+
+impl Instrument {
+    pub fn is_trading(&self) -> bool {
+        self.status == "Trading"
+    }
+}
+
+/// Every perpetual a bot can trade on one exchange, fetched by the refresh
+/// job and cached for the checks below. Coins are looked up the way
+/// passivbot names them in `live.approved_coins`: the symbol itself, the base
+/// coin, or the base coin without a `1000`-style multiplier (`PEPE` trades as
+/// `1000PEPEUSDT`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentCatalog {
+    pub exchange: Exchange,
+    /// When the exchange was read (unix seconds).
+    pub fetched_at: i64,
+    pub instruments: Vec<Instrument>,
+}
+
+impl InstrumentCatalog {
+    pub fn resolve(&self, coin: &str) -> Option<&Instrument> {
+        let coin = coin.trim().to_uppercase();
+        let find = |matches: fn(&Instrument, &str) -> bool| {
+            self.instruments.iter().find(|i| matches(i, &coin))
+        };
+        find(|i, c| i.symbol == c)
+            .or_else(|| find(|i, c| i.base_coin == c))
+            .or_else(|| find(|i, c| without_multiplier(&i.base_coin) == c))
+    }
+
+    /// Why each of `coins` cannot be traded (not listed, or listed but not
+    /// trading); empty when all of them can.
+    pub fn coin_problems(&self, coins: &[String]) -> Vec<String> {
+        let exchange = self.exchange.as_str();
+        coins
+            .iter()
+            .filter_map(|coin| match self.resolve(coin) {
+                None => Some(format!("{coin} is not listed on {exchange}")),
+                Some(i) if !i.is_trading() => Some(format!(
+                    "{} is not trading on {exchange} (status {})",
+                    i.symbol, i.status
+                )),
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    /// The instrument with the lowest max leverage among `coins`. Passivbot
+    /// applies one leverage to every coin, so this one bounds it. `None` when
+    /// none of the coins is listed.
+    pub fn tightest_leverage(&self, coins: &[String]) -> Option<&Instrument> {
+        coins
+            .iter()
+            .filter_map(|c| self.resolve(c))
+            .min_by(|a, b| a.max_leverage.total_cmp(&b.max_leverage))
+    }
+}
+
+/// `1000PEPE` → `PEPE`; a base coin without a power-of-ten prefix (including
+/// `1INCH`) is returned as is.
+fn without_multiplier(base_coin: &str) -> &str {
+    let digits = base_coin.len()
+        - base_coin
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    let prefix = &base_coin[..digits];
+    if digits >= 4 && prefix.starts_with('1') && prefix[1..].bytes().all(|b| b == b'0') {
+        &base_coin[digits..]
+    } else {
+        base_coin
+    }
+}
+
+/// Port for reading the instrument list from the exchange (public data, no
+/// credentials).
+#[async_trait]
+pub trait InstrumentSource: Send + Sync {
+    async fn instruments(&self) -> Result<Vec<Instrument>, DomainError>;
+}
+
+/// Where the refresh job caches the catalog for everything else to read.
+#[async_trait]
+pub trait InstrumentCatalogRepository: Send + Sync {
+    /// `None` until the refresh job has stored a catalog.
+    async fn load(&self) -> Result<Option<InstrumentCatalog>, DomainError>;
+    async fn save(&self, catalog: &InstrumentCatalog) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instrument(symbol: &str, base: &str, status: &str, max_leverage: f64) -> Instrument {
+        Instrument {
+            symbol: symbol.into(),
+            base_coin: base.into(),
+            status: status.into(),
+            min_qty: 0.001,
+            tick_size: 0.1,
+            max_leverage,
+        }
+    }
+
+    fn catalog() -> InstrumentCatalog {
+        InstrumentCatalog {
+            exchange: Exchange::Bybit,
+            fetched_at: 0,
+            instruments: vec![
+                instrument("BTCUSDT", "BTC", "Trading", 100.0),
+                instrument("1000PEPEUSDT", "1000PEPE", "Trading", 25.0),
+                instrument("1INCHUSDT", "1INCH", "Trading", 50.0),
+                instrument("LUNAUSDT", "LUNA", "Closed", 10.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn coins_resolve_by_symbol_base_coin_or_without_multiplier() {
+        let c = catalog();
+        assert_eq!(c.resolve("BTCUSDT").unwrap().symbol, "BTCUSDT");
+        assert_eq!(c.resolve("btc").unwrap().symbol, "BTCUSDT");
+        assert_eq!(c.resolve("PEPE").unwrap().symbol, "1000PEPEUSDT");
+        assert_eq!(c.resolve("1INCH").unwrap().symbol, "1INCHUSDT");
+        assert!(c.resolve("INCH").is_none());
+    }
+
+    #[test]
+    fn problems_name_unlisted_and_halted_coins_and_leverage_follows_the_tightest() {
+        let c = catalog();
+        let coins: Vec<String> = ["BTC", "PEPE", "LUNA", "NOPE"].map(String::from).into();
+        assert_eq!(
+            c.coin_problems(&coins),
+            vec![
+                "LUNAUSDT is not trading on bybit (status Closed)".to_string(),
+                "NOPE is not listed on bybit".to_string(),
+            ]
+        );
+        assert_eq!(
+            c.tightest_leverage(&coins[..2]).unwrap().symbol,
+            "1000PEPEUSDT"
+        );
+        assert!(c.tightest_leverage(&coins[3..]).is_none());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::domain::botconfig::{Leverage, RiskLevel};
+use crate::domain::error::DomainError;
+use std::fmt;
+
+/// Rule that derives a bot's leverage from its risk level (per-side wallet
+/// exposure). Passivbot needs enough margin headroom for the configured
+/// exposure, and how much headroom is right differs per account, so the rule is
+/// selectable per bot rather than hard-wired into `BotConfig`.
+pub trait LeveragePolicy: Send + Sync {
+    fn derive(&self, risk: &RiskLevel) -> Result<Leverage, DomainError>;
+}
+
+/// `leverage = max(long, short) + offset`. An offset of 1.0 is the historical
+/// default and keeps a full unit of headroom above the larger exposure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedOffset {
+    pub offset: f64,
+}
+
+impl LeveragePolicy for FixedOffset {
+    fn derive(&self, risk: &RiskLevel) -> Result<Leverage, DomainError> {
+        Leverage::uniform(risk.long.max(risk.short) + self.offset)
+    }
+}
+
+/// `leverage = max(long, short) * factor`, floored at 1x so a zero-exposure
+/// risk level still yields a valid leverage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multiplier {
+    pub factor: f64,
+}
+
+impl LeveragePolicy for Multiplier {
+    fn derive(&self, risk: &RiskLevel) -> Result<Leverage, DomainError> {
+        Leverage::uniform((risk.long.max(risk.short) * self.factor).max(1.0))
+    }
+}
+
+/// A constant leverage regardless of the risk level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedValue {
+    pub value: f64,
+}
+
+impl LeveragePolicy for FixedValue {
+    fn derive(&self, _risk: &RiskLevel) -> Result<Leverage, DomainError> {
+        Leverage::uniform(self.value)
+    }
+}
+
+/// Wraps another policy and clamps its result to the instrument's max leverage
+/// on the exchange, so a derived value the exchange would reject never reaches
+/// the config.
+pub struct InstrumentCap {
+    pub base: Box<dyn LeveragePolicy>,
+    pub max_leverage: f64,
+}
+
+impl LeveragePolicy for InstrumentCap {
+    fn derive(&self, risk: &RiskLevel) -> Result<Leverage, DomainError> {
+        let base = self.base.derive(risk)?;
+        Leverage::new(
+            base.long.min(self.max_leverage),
+            base.short.min(self.max_leverage),
+        )
+    }
+}
+
+/// The per-bot policy selection stored on the bot row (`leverage_policy`).
+///
+/// Persisted and entered by the user in a compact text form that `parse` and
+/// `Display` round-trip: `offset:<n>`, `multiplier:<n>`, `fixed:<n>`, and
+/// `cap:<max>:<base>` where `<max>` is in [1, 125] and `<base>` is any non-cap
+/// form (e.g. `cap:25:offset:1`). The catalog's per-coin maximum is applied on
+/// top of a typed cap when a risk level is saved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeveragePolicyKind {
+    FixedOffset {
+        offset: f64,
+    },
+    Multiplier {
+        factor: f64,
+    },
+    FixedValue {
+        value: f64,
+    },
+    InstrumentCap {
+        base: Box<LeveragePolicyKind>,
+        max_leverage: f64,
+    },
+}
+
+impl Default for LeveragePolicyKind {
+    fn default() -> Self {
+        LeveragePolicyKind::FixedOffset { offset: 1.0 }
+    }
+}
+
+impl LeveragePolicyKind {
+    /// Parse the compact text form, validating every parameter so a stored or
+    /// user-entered selection can never produce an out-of-range leverage by
+    /// construction (offset in [0, 115], factor in (0, 125], fixed value and cap
+    /// within `Leverage`'s range). Accepts `:` or whitespace as separators.
+    pub fn parse(s: &str) -> Result<Self, DomainError> {
+        let normalized = s.trim().to_lowercase().replace(char::is_whitespace, ":");
+        let parts: Vec<&str> = normalized.split(':').filter(|p| !p.is_empty()).collect();
+        Self::parse_parts(&parts)
+    }
+
+    fn parse_parts(parts: &[&str]) -> Result<Self, DomainError> {
+        let invalid = || {
+            DomainError::InvalidConfig(format!(
+                "invalid leverage policy '{}': expected offset:<n>, multiplier:<n>, fixed:<n> or cap:<max>:<policy>",
+                parts.join(":")
+            ))
+        };
+        let number = |p: Option<&&str>| -> Result<f64, DomainError> {
+            p.and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| v.is_finite())
+                .ok_or_else(invalid)
+        };
+
+        match parts.first().copied() {
+            Some("offset") if parts.len() == 2 => {
+                let offset = number(parts.get(1))?;
+                if !(0.0..=115.0).contains(&offset) {
+                    return Err(DomainError::InvalidConfig(format!(
+                        "leverage offset {offset} out of range [0, 115]"
+                    )));
+                }
+                Ok(Self::FixedOffset { offset })
+            }
+            Some("multiplier") if parts.len() == 2 => {
+                let factor = number(parts.get(1))?;
+                if factor <= 0.0 || factor > 125.0 {
+                    return Err(DomainError::InvalidConfig(format!(
+                        "leverage multiplier {factor} out of range (0, 125]"
+                    )));
+                }
+                Ok(Self::Multiplier { factor })
+            }
+            Some("fixed") if parts.len() == 2 => {
+                let value = number(parts.get(1))?;
+                Leverage::uniform(value)?;
+                Ok(Self::FixedValue { value })
+            }
+            Some("cap") if parts.len() >= 3 => {
+                let max_leverage = number(parts.get(1))?;
+                if !(1.0..=125.0).contains(&max_leverage) {
+                    return Err(DomainError::InvalidConfig(format!(
+                        "leverage cap {max_leverage} out of range [1, 125]"
+                    )));
+                }
+                let base = Self::parse_parts(&parts[2..])?;
+                if matches!(base, Self::InstrumentCap { .. }) {
+                    return Err(invalid());
+                }
+                Ok(Self::InstrumentCap {
+                    base: Box::new(base),
+                    max_leverage,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Build the policy this selection describes.
+    pub fn policy(&self) -> Box<dyn LeveragePolicy> {
+        match self {
+            Self::FixedOffset { offset } => Box::new(FixedOffset { offset: *offset }),
+            Self::Multiplier { factor } => Box::new(Multiplier { factor: *factor }),
+            Self::FixedValue { value } => Box::new(FixedValue { value: *value }),
+            Self::InstrumentCap { base, max_leverage } => Box::new(InstrumentCap {
+                base: base.policy(),
+                max_leverage: *max_leverage,
+            }),
+        }
+    }
+
+    /// Short human-readable rule for display (e.g. "max risk + 1").
+    pub fn describe(&self) -> String {
+        match self {
+            Self::FixedOffset { offset } => format!("max risk + {}", offset),
+            Self::Multiplier { factor } => format!("max risk × {}", factor),
+            Self::FixedValue { value } => format!("fixed {}x", value),
+            Self::InstrumentCap { base, max_leverage } => {
+                format!("{}, capped at {}x", base.describe(), max_leverage)
+            }
+        }
+    }
+}
+
+impl fmt::Display for LeveragePolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FixedOffset { offset } => write!(f, "offset:{}", offset),
+            Self::Multiplier { factor } => write!(f, "multiplier:{}", factor),
+            Self::FixedValue { value } => write!(f, "fixed:{}", value),
+            Self::InstrumentCap { base, max_leverage } => {
+                write!(f, "cap:{}:{}", max_leverage, base)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn risk(long: f64, short: f64) -> RiskLevel {
+        RiskLevel::new(long, short).unwrap()
+    }
+
+    #[test]
+    fn policies_derive_expected_leverage() {
+        let offset = FixedOffset { offset: 1.0 }.derive(&risk(3.0, 5.0)).unwrap();
+        assert_eq!(offset, Leverage::uniform(6.0).unwrap());
+
+        let mult = Multiplier { factor: 1.5 }.derive(&risk(2.0, 4.0)).unwrap();
+        assert_eq!(mult, Leverage::uniform(6.0).unwrap());
+
+        // zero exposure is floored at 1x rather than rejected
+        let floored = Multiplier { factor: 2.0 }.derive(&risk(0.0, 0.0)).unwrap();
+        assert_eq!(floored.long, 1.0);
+
+        let fixed = FixedValue { value: 10.0 }.derive(&risk(9.0, 9.0)).unwrap();
+        assert_eq!(fixed, Leverage::uniform(10.0).unwrap());
+    }
+
+    #[test]
+    fn instrument_cap_clamps_base() {
+        let capped = InstrumentCap {
+            base: Box::new(Multiplier { factor: 10.0 }),
+            max_leverage: 25.0,
+        };
+        assert_eq!(capped.derive(&risk(5.0, 1.0)).unwrap().long, 25.0);
+        assert_eq!(capped.derive(&risk(1.0, 1.0)).unwrap().long, 10.0);
+    }
+
+    #[test]
+    fn multiplier_overflowing_leverage_range_is_rejected() {
+        let err = Multiplier { factor: 20.0 }.derive(&risk(10.0, 0.0));
+        assert!(matches!(err, Err(DomainError::LeverageOutOfRange { .. })));
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for s in ["offset:1", "multiplier:1.5", "fixed:5", "cap:25:offset:2"] {
+            let kind = LeveragePolicyKind::parse(s).unwrap();
+            assert_eq!(kind.to_string(), s);
+        }
+        assert_eq!(
+            LeveragePolicyKind::parse(" Multiplier 2 ").unwrap(),
+            LeveragePolicyKind::Multiplier { factor: 2.0 }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_input() {
+        for s in [
+            "",
+            "offset",
+            "offset:x",
+            "offset:-1",
+            "multiplier:0",
+            "fixed:200",
+            "cap:25",
+            "cap:0:offset:1",
+            "cap:126:offset:1",
+            "cap:200:fixed:5",
+            "cap:25:cap:10:offset:1",
+            "bogus:1",
+        ] {
+            assert!(LeveragePolicyKind::parse(s).is_err(), "accepted {s:?}");
+        }
+    }
+
+    #[test]
+    fn default_is_offset_one() {
+        let kind = LeveragePolicyKind::default();
+        let lev = kind.policy().derive(&risk(3.0, 5.0)).unwrap();
+        assert_eq!(lev.long, 6.0);
+    }
+}
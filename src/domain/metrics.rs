@@ -0,0 +1,107 @@
+//! Counters and histograms the control plane emits about bot lifecycles.
+//!
+//! Use cases record through the [`Metrics`] port; the Lambdas sink it as
+//! CloudWatch Embedded Metric Format lines and the telebot as a Prometheus
+//! endpoint (`infra::metrics`). Labels are low-cardinality only — an outcome
+//! or a stop cause, never a user, bot or task id.
+
+/// Starts requested, by `outcome`.
+pub const BOT_STARTS: &str = "bot_starts_total";
+/// Stops requested, by `outcome`.
+pub const BOT_STOPS: &str = "bot_stops_total";
+/// RUNNING events handled, by `outcome`.
+pub const TASK_RUNNING_EVENTS: &str = "task_running_events_total";
+/// STOPPED events handled, by `outcome` and the stop's `cause`.
+pub const TASK_STOPPED_EVENTS: &str = "task_stopped_events_total";
+/// Automatic restarts launched, by the `cause` of the stop they replace.
+pub const BOT_RESTARTS: &str = "bot_restarts_total";
+/// Seconds from the start lock being claimed to the task reporting RUNNING.
+pub const TIME_TO_RUNNING_SECONDS: &str = "bot_time_to_running_seconds";
+
+/// `(label, value)` pairs attached to one measurement.
+pub type Labels<'a> = &'a [(&'static str, &'a str)];
+
+/// Where measurements go. Recording never fails and never blocks on I/O a
+/// caller would notice; a sink that cannot deliver drops the value.
+pub trait Metrics: Send + Sync {
+    /// Add one to the counter `name`.
+    fn increment(&self, name: &'static str, labels: Labels<'_>);
+    /// Record `value` in the histogram `name`.
+    fn observe(&self, name: &'static str, labels: Labels<'_>, value: f64);
+}
+
+/// Discards everything; for tests and tools that do not report metrics.
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {
+    fn increment(&self, _name: &'static str, _labels: Labels<'_>) {}
+    fn observe(&self, _name: &'static str, _labels: Labels<'_>, _value: f64) {}
+}
+
+/// A metric name with its labels, as [`RecordingMetrics`] keeps them.
+#[cfg(any(test, feature = "testing"))]
+type Recorded = (String, Vec<(String, String)>);
+
+/// Keeps every measurement so a test can assert on what a use case emitted.
+#[cfg(any(test, feature = "testing"))]
+#[derive(Default)]
+pub struct RecordingMetrics {
+    counters: std::sync::Mutex<Vec<Recorded>>,
+    observations: std::sync::Mutex<Vec<(Recorded, f64)>>,
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl RecordingMetrics {
+    /// How often `name` was incremented with at least the given labels.
+    pub fn count(&self, name: &str, labels: Labels<'_>) -> usize {
+        self.counters
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(n, l)| n == name && has_labels(l, labels))
+            .count()
+    }
+
+    /// Values recorded in the histogram `name`, in order.
+    pub fn observations(&self, name: &str) -> Vec<f64> {
+        self.observations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((n, _), _)| n == name)
+            .map(|(_, v)| *v)
+            .collect()
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+fn owned(labels: Labels<'_>) -> Vec<(String, String)> {
+    labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[cfg(any(test, feature = "testing"))]
+fn has_labels(recorded: &[(String, String)], wanted: Labels<'_>) -> bool {
+    wanted
+        .iter()
+        .all(|(k, v)| recorded.iter().any(|(rk, rv)| rk == k && rv == v))
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl Metrics for RecordingMetrics {
+    fn increment(&self, name: &'static str, labels: Labels<'_>) {
+        self.counters
+            .lock()
+            .unwrap()
+            .push((name.to_string(), owned(labels)));
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels<'_>, value: f64) {
+        self.observations
+            .lock()
+            .unwrap()
+            .push(((name.to_string(), owned(labels)), value));
+    }
+}
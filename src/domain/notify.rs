@@ -0,0 +1,9 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+
+/// Port for pushing a message to a user outside a conversation (the daily
+/// digest). Users are addressed by their id, which is their Telegram user id.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, user_id: &str, text: &str) -> Result<(), DomainError>;
+}
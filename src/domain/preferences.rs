@@ -0,0 +1,123 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+const DAY: i64 = 86_400;
+
+/// Per-user settings that are not tied to one bot. Today that is the daily
+/// digest: the UTC hour it is sent at and the account equity each bot had at
+/// the last one, which the next digest reports the change against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserPreferences {
+    pub user_id: String,
+    /// UTC hour (0-23) of the daily digest; `None` when it is off.
+    pub digest_hour: Option<u32>,
+    /// When the next digest is due (unix seconds); `None` when it is off.
+    pub next_digest_at: Option<i64>,
+    /// Equity per bot id at the last digest.
+    pub digest_equity: BTreeMap<String, f64>,
+    pub updated_at: i64,
+}
+
+impl UserPreferences {
+    /// Defaults for a user who never set anything: no digest.
+    pub fn new(user_id: String, now: i64) -> Self {
+        Self {
+            user_id,
+            digest_hour: None,
+            next_digest_at: None,
+            digest_equity: BTreeMap::new(),
+            updated_at: now,
+        }
+    }
+
+    /// Send the digest every day at `hour` UTC, or stop sending it (`None`).
+    /// The first one goes out at the next such hour after `now`.
+    pub fn set_digest_hour(&mut self, hour: Option<u32>, now: i64) -> Result<(), DomainError> {
+        if let Some(h) = hour.filter(|h| *h > 23) {
+            return Err(DomainError::InvalidConfig(format!(
+                "digest hour {h} must be between 0 and 23 (UTC)"
+            )));
+        }
+        self.digest_hour = hour;
+        self.next_digest_at = hour.map(|h| next_digest_after(h, now));
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn is_digest_due(&self, now: i64) -> bool {
+        self.next_digest_at.is_some_and(|at| at <= now)
+    }
+
+    /// Move the next digest to the first slot after `now`. A digest that was
+    /// missed for days is sent once, not once per missed day.
+    pub fn advance_digest(&mut self, now: i64) {
+        self.next_digest_at = self.digest_hour.map(|h| next_digest_after(h, now));
+    }
+}
+
+/// The first `hour`:00 UTC strictly after `ts`.
+fn next_digest_after(hour: u32, ts: i64) -> i64 {
+    let slot = ts.div_euclid(DAY) * DAY + i64::from(hour) * 3_600;
+    if slot > ts { slot } else { slot + DAY }
+}
+
+#[async_trait]
+pub trait UserPreferencesRepository: Send + Sync {
+    async fn find(&self, user_id: &str) -> Result<Option<UserPreferences>, DomainError>;
+    async fn save(&self, prefs: &UserPreferences) -> Result<(), DomainError>;
+    /// Every user whose digest is due at `now` (`next_digest_at <= now`).
+    async fn find_due_digests(&self, now: i64) -> Result<Vec<UserPreferences>, DomainError>;
+    /// Move the digest slot forward to `prefs.next_digest_at`, but only if the
+    /// stored value is still `expected_next_digest_at`. `false` when another
+    /// run claimed the slot (or the digest was changed) in between.
+    async fn claim_digest(
+        &self,
+        prefs: &UserPreferences,
+        expected_next_digest_at: i64,
+    ) -> Result<bool, DomainError>;
+    /// Store the equity snapshot taken by the digest just sent.
+    async fn record_digest_equity(
+        &self,
+        user_id: &str,
+        equity: &BTreeMap<String, f64>,
+    ) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-05 00:00:00 UTC.
+    const MIDNIGHT: i64 = 1_704_412_800;
+
+    #[test]
+    fn digest_slot_is_the_next_hour_strictly_after_now() {
+        let mut prefs = UserPreferences::new("u".into(), MIDNIGHT);
+        prefs.set_digest_hour(Some(7), MIDNIGHT).unwrap();
+        assert_eq!(prefs.next_digest_at, Some(MIDNIGHT + 7 * 3_600));
+
+        // Exactly at the slot -> tomorrow.
+        prefs
+            .set_digest_hour(Some(7), MIDNIGHT + 7 * 3_600)
+            .unwrap();
+        assert_eq!(prefs.next_digest_at, Some(MIDNIGHT + DAY + 7 * 3_600));
+
+        prefs.set_digest_hour(None, MIDNIGHT).unwrap();
+        assert_eq!(prefs.next_digest_at, None);
+        assert!(!prefs.is_digest_due(MIDNIGHT + 10 * DAY));
+
+        assert!(prefs.set_digest_hour(Some(24), MIDNIGHT).is_err());
+    }
+
+    #[test]
+    fn a_late_digest_advances_past_missed_days() {
+        let mut prefs = UserPreferences::new("u".into(), MIDNIGHT);
+        prefs.set_digest_hour(Some(7), MIDNIGHT).unwrap();
+        let late = MIDNIGHT + 3 * DAY + 9 * 3_600;
+        assert!(prefs.is_digest_due(late));
+
+        prefs.advance_digest(late);
+        assert_eq!(prefs.next_digest_at, Some(MIDNIGHT + 4 * DAY + 7 * 3_600));
+    }
+}
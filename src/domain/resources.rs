@@ -0,0 +1,203 @@
+use crate::domain::error::DomainError;
+use std::fmt;
+
+/// Bounds for an explicit profile. The upper limits keep a single task
+/// placeable on one cluster host (a t4g.medium registers 2048 CPU units and
+/// ~3835 MiB); ECS rejects a task-level size below 128 of either.
+pub const MIN_TASK_CPU: u32 = 128;
+pub const MAX_TASK_CPU: u32 = 2048;
+pub const MIN_TASK_MEMORY_MIB: u32 = 128;
+pub const MAX_TASK_MEMORY_MIB: u32 = 3584;
+/// Task memory kept out of the passivbot container's reservation, for any
+/// sidecar the task definition runs next to it.
+pub const SIDECAR_MEMORY_MIB: u32 = 64;
+
+/// The CPU/memory a bot's task is launched with, stored on the bot row
+/// (`resource_profile`) and applied as ECS task and container overrides.
+///
+/// Persisted and entered by the user in a compact text form that `parse` and
+/// `Display` round-trip: a named size (`small`, `medium`, `large`) or an
+/// explicit `<cpu units>:<memory MiB>` pair (e.g. `256:1024`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResourceProfile {
+    /// The task definition's own size: enough for a typical single-coin bot.
+    #[default]
+    Small,
+    Medium,
+    Large,
+    Custom {
+        cpu: u32,
+        memory_mib: u32,
+    },
+}
+
+impl ResourceProfile {
+    /// The named sizes, smallest first; also the promotion ladder.
+    const LADDER: [ResourceProfile; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    /// Parse the compact text form. Accepts `:`, `/` or whitespace between
+    /// the explicit CPU and memory values.
+    pub fn parse(s: &str) -> Result<Self, DomainError> {
+        let normalized = s.trim().to_lowercase();
+        match normalized.as_str() {
+            "small" => return Ok(Self::Small),
+            "medium" => return Ok(Self::Medium),
+            "large" => return Ok(Self::Large),
+            _ => {}
+        }
+        let parts: Vec<&str> = normalized
+            .split(|c: char| c == ':' || c == '/' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+        let (cpu, memory_mib) = match parts.as_slice() {
+            [cpu, memory] => match (cpu.parse::<u32>(), memory.parse::<u32>()) {
+                (Ok(cpu), Ok(memory)) => (cpu, memory),
+                _ => return Err(Self::invalid(s)),
+            },
+            _ => return Err(Self::invalid(s)),
+        };
+        if !(MIN_TASK_CPU..=MAX_TASK_CPU).contains(&cpu) {
+            return Err(DomainError::InvalidConfig(format!(
+                "cpu must be between {MIN_TASK_CPU} and {MAX_TASK_CPU} units, got {cpu}"
+            )));
+        }
+        if !(MIN_TASK_MEMORY_MIB..=MAX_TASK_MEMORY_MIB).contains(&memory_mib) {
+            return Err(DomainError::InvalidConfig(format!(
+                "memory must be between {MIN_TASK_MEMORY_MIB} and {MAX_TASK_MEMORY_MIB} MiB, got {memory_mib}"
+            )));
+        }
+        Ok(Self::Custom { cpu, memory_mib })
+    }
+
+    fn invalid(s: &str) -> DomainError {
+        DomainError::InvalidConfig(format!(
+            "invalid resource profile '{}': expected small, medium, large or <cpu units>:<memory MiB>",
+            s.trim()
+        ))
+    }
+
+    /// Task-level CPU units.
+    pub fn cpu(&self) -> u32 {
+        match self {
+            Self::Small => 128,
+            Self::Medium => 256,
+            Self::Large => 512,
+            Self::Custom { cpu, .. } => *cpu,
+        }
+    }
+
+    /// Task-level hard memory limit in MiB. The passivbot container has no
+    /// hard limit of its own and may use all of it.
+    pub fn memory_mib(&self) -> u32 {
+        match self {
+            Self::Small => 400,
+            Self::Medium => 800,
+            Self::Large => 1600,
+            Self::Custom { memory_mib, .. } => *memory_mib,
+        }
+    }
+
+    /// Soft memory reservation of the passivbot container in MiB: the task's
+    /// memory less [`SIDECAR_MEMORY_MIB`], so sidecars still fit in the task.
+    pub fn container_reservation_mib(&self) -> u32 {
+        self.memory_mib().saturating_sub(SIDECAR_MEMORY_MIB)
+    }
+
+    /// The profile to move to after an out-of-memory kill: the next named size
+    /// with more memory, or `None` at the top of the ladder. The CPU never
+    /// drops: an explicit size with more CPU than that step keeps its own CPU
+    /// with the step's memory.
+    pub fn next(&self) -> Option<Self> {
+        let step = Self::LADDER
+            .into_iter()
+            .find(|p| p.memory_mib() > self.memory_mib())?;
+        if step.cpu() >= self.cpu() {
+            return Some(step);
+        }
+        Some(Self::Custom {
+            cpu: self.cpu(),
+            memory_mib: step.memory_mib(),
+        })
+    }
+
+    /// Short human-readable size for display (e.g. "256 CPU units, 800 MiB").
+    pub fn describe(&self) -> String {
+        format!("{} CPU units, {} MiB", self.cpu(), self.memory_mib())
+    }
+}
+
+impl fmt::Display for ResourceProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Small => f.write_str("small"),
+            Self::Medium => f.write_str("medium"),
+            Self::Large => f.write_str("large"),
+            Self::Custom { cpu, memory_mib } => write!(f, "{cpu}:{memory_mib}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trips_named_and_explicit_profiles() {
+        for text in ["small", "medium", "large", "256:1024"] {
+            let profile = ResourceProfile::parse(text).unwrap();
+            assert_eq!(profile.to_string(), text);
+        }
+        assert_eq!(
+            ResourceProfile::parse(" 512 / 2048 ").unwrap(),
+            ResourceProfile::Custom {
+                cpu: 512,
+                memory_mib: 2048
+            }
+        );
+        assert_eq!(
+            ResourceProfile::parse("LARGE").unwrap(),
+            ResourceProfile::Large
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_and_out_of_range_profiles() {
+        for text in [
+            "",
+            "huge",
+            "256",
+            "256:1024:1",
+            "x:1024",
+            "64:1024",
+            "256:8192",
+        ] {
+            assert!(ResourceProfile::parse(text).is_err(), "{text:?} parsed");
+        }
+    }
+
+    #[test]
+    fn promotion_climbs_by_memory_keeps_cpu_and_stops_at_large() {
+        assert_eq!(ResourceProfile::Small.next(), Some(ResourceProfile::Medium));
+        assert_eq!(ResourceProfile::Medium.next(), Some(ResourceProfile::Large));
+        assert_eq!(ResourceProfile::Large.next(), None);
+        let custom = |memory_mib| ResourceProfile::Custom {
+            cpu: 256,
+            memory_mib,
+        };
+        assert_eq!(custom(600).next(), Some(ResourceProfile::Medium));
+        assert_eq!(custom(1600).next(), None);
+        assert_eq!(custom(3000).next(), None);
+        // More CPU than the step offers is kept.
+        assert_eq!(
+            ResourceProfile::Custom {
+                cpu: 1024,
+                memory_mib: 512
+            }
+            .next(),
+            Some(ResourceProfile::Custom {
+                cpu: 1024,
+                memory_mib: 800
+            })
+        );
+    }
+}
@@ -0,0 +1,530 @@
+use crate::domain::error::DomainError;
+use std::fmt;
+
+/// A task that ran at least this long before stopping is considered healthy:
+/// its stop starts a fresh restart budget instead of counting against the
+/// current one.
+pub const RESTART_STABLE_AFTER_SECS: i64 = 3600;
+
+/// How one container of a stopped task ended, as reported in the ECS event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContainerExit {
+    pub name: String,
+    /// `None` when the container never ran (e.g. the image pull failed).
+    pub exit_code: Option<i32>,
+    pub reason: Option<String>,
+}
+
+/// Why an ECS task stopped, classified from the STOPPED event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopCause {
+    /// StopTask from this system or the console (`stopCode = UserInitiated`).
+    /// Never restarted: it is someone's intent.
+    UserInitiated,
+    /// A container was killed for exceeding its memory (an `OutOfMemoryError`
+    /// reason, or exit 137 from the passivbot container).
+    OutOfMemory,
+    /// The process failed: a non-zero exit such as an unhandled Python
+    /// exception (exit 1) or a crash after an exchange disconnect.
+    ProcessFailed { exit_code: i32 },
+    /// Every container exited cleanly (exit 0).
+    Exited,
+    /// The platform took the task away: it failed to start, was interrupted
+    /// on Spot, or was stopped by the scheduler while draining or retiring the
+    /// container instance. `stop_code` is ECS's, for display.
+    Infrastructure { stop_code: String },
+    /// Nothing in the event says why.
+    Unknown,
+}
+
+/// ECS stop codes meaning the platform, not the bot, ended the task.
+const INFRASTRUCTURE_STOP_CODES: &[&str] = &[
+    "TaskFailedToStart",
+    "SpotInterruption",
+    "ServiceSchedulerInitiated",
+    "TerminationNotice",
+];
+
+/// Fragments of `stoppedReason` for instance draining / retirement, which ECS
+/// may report under a generic stop code.
+const INFRASTRUCTURE_REASONS: &[&str] = &["draining", "retire", "maintenance"];
+
+impl StopCause {
+    /// Classify a STOPPED event. Precedence, first match wins:
+    /// 1. `UserInitiated` — a requested stop (its SIGKILL after the stop timeout
+    ///    also exits 137, so it must beat the OOM rule);
+    /// 2. an explicit `OutOfMemoryError` reason on any container or the task —
+    ///    a task that failed to start can still carry one;
+    /// 3. a platform stop code or a draining / retirement reason — the
+    ///    platform SIGKILLs the containers of a task it takes away, so their
+    ///    exit 137 says nothing about memory;
+    /// 4. `main_container` exited 137 — OOM-killed without a reason. A sidecar
+    ///    SIGKILLed as the task winds down also exits 137, so a sidecar counts
+    ///    only with an `OutOfMemoryError` reason;
+    /// 5. any non-zero container exit, `main_container`'s before a sidecar's;
+    /// 6. all containers exited 0;
+    /// 7. unknown.
+    pub fn classify(
+        stop_code: Option<&str>,
+        stopped_reason: Option<&str>,
+        main_container: &str,
+        containers: &[ContainerExit],
+    ) -> Self {
+        let stop_code = stop_code.unwrap_or("");
+        let stopped_reason = stopped_reason.unwrap_or("").to_lowercase();
+        let mut containers = containers.to_vec();
+        containers.sort_by_key(|c| c.name != main_container);
+
+        if stop_code == "UserInitiated" {
+            return Self::UserInitiated;
+        }
+        let oom_reason = containers.iter().any(|c| {
+            c.reason
+                .as_deref()
+                .is_some_and(|r| r.contains("OutOfMemory"))
+        });
+        if oom_reason || stopped_reason.contains("outofmemory") {
+            return Self::OutOfMemory;
+        }
+        if INFRASTRUCTURE_STOP_CODES.contains(&stop_code)
+            || INFRASTRUCTURE_REASONS
+                .iter()
+                .any(|r| stopped_reason.contains(r))
+        {
+            return Self::Infrastructure {
+                stop_code: stop_code.to_string(),
+            };
+        }
+        if containers
+            .iter()
+            .any(|c| c.name == main_container && c.exit_code == Some(137))
+        {
+            return Self::OutOfMemory;
+        }
+        if let Some(exit_code) = containers
+            .iter()
+            .filter_map(|c| c.exit_code)
+            .find(|code| *code != 0)
+        {
+            return Self::ProcessFailed { exit_code };
+        }
+        if !containers.is_empty() && containers.iter().all(|c| c.exit_code == Some(0)) {
+            return Self::Exited;
+        }
+        Self::Unknown
+    }
+
+    /// Short human-readable cause (e.g. "out of memory").
+    pub fn describe(&self) -> String {
+        match self {
+            Self::UserInitiated => "stopped by request".to_string(),
+            Self::OutOfMemory => "out of memory".to_string(),
+            Self::ProcessFailed { exit_code } => format!("process failed (exit {exit_code})"),
+            Self::Exited => "exited cleanly".to_string(),
+            Self::Infrastructure { stop_code } if stop_code.is_empty() => {
+                "infrastructure".to_string()
+            }
+            Self::Infrastructure { stop_code } => format!("infrastructure ({stop_code})"),
+            Self::Unknown => "unknown".to_string(),
+        }
+    }
+
+    /// The cause without its details, as a metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::UserInitiated => "user_initiated",
+            Self::OutOfMemory => "out_of_memory",
+            Self::ProcessFailed { .. } => "process_failed",
+            Self::Exited => "exited",
+            Self::Infrastructure { .. } => "infrastructure",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Everything a STOPPED event says about how a task ended: the task-level stop
+/// code and reason plus every container's exit. `main_container` names the
+/// passivbot container so sidecars (log router, metrics agent) cannot mask its
+/// exit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StopReport {
+    pub stop_code: Option<String>,
+    pub stopped_reason: Option<String>,
+    pub main_container: String,
+    pub containers: Vec<ContainerExit>,
+}
+
+impl StopReport {
+    /// The passivbot container's exit, if the event carried it.
+    pub fn main(&self) -> Option<&ContainerExit> {
+        self.containers
+            .iter()
+            .find(|c| c.name == self.main_container)
+    }
+
+    /// Classify the stop (see [`StopCause::classify`]) with the passivbot
+    /// container as the main one.
+    pub fn cause(&self) -> StopCause {
+        StopCause::classify(
+            self.stop_code.as_deref(),
+            self.stopped_reason.as_deref(),
+            &self.main_container,
+            &self.containers,
+        )
+    }
+
+    /// One line for the bot's history: the cause, the passivbot container's
+    /// exit code, and ECS's stopped reason.
+    pub fn summary(&self) -> String {
+        let cause = self.cause();
+        let mut out = cause.describe();
+        if let Some(code) = self.main().and_then(|c| c.exit_code)
+            && !matches!(cause, StopCause::ProcessFailed { .. })
+        {
+            out.push_str(&format!(", exit {code}"));
+        }
+        if let Some(reason) = self.stopped_reason.as_deref().filter(|r| !r.is_empty()) {
+            out.push_str(&format!(" — {reason}"));
+        }
+        out
+    }
+}
+
+/// Which stop causes a bot is automatically restarted after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartMode {
+    Never,
+    /// Only after an out-of-memory kill (the historical rule).
+    OnOom,
+    /// After an OOM, a process failure, or an infrastructure stop.
+    OnFailure,
+    /// After anything but a requested stop, including a clean exit.
+    Always,
+}
+
+impl RestartMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Never => "never",
+            Self::OnOom => "on-oom",
+            Self::OnFailure => "on-failure",
+            Self::Always => "always",
+        }
+    }
+}
+
+/// The per-bot restart selection stored on the bot row (`restart_policy`).
+///
+/// Persisted and entered by the user in a compact text form that `parse` and
+/// `Display` round-trip: the mode, optionally followed by the maximum number
+/// of consecutive automatic restarts (e.g. `on-failure:5`). Without a maximum
+/// restarts are unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::OnOom,
+            max_attempts: None,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Parse the compact text form. Accepts `:` or whitespace as separators
+    /// and `oom` / `failure` as short mode names; a maximum must be at least 1
+    /// (use `never` to disable restarts).
+    pub fn parse(s: &str) -> Result<Self, DomainError> {
+        let normalized = s.trim().to_lowercase().replace(char::is_whitespace, ":");
+        let parts: Vec<&str> = normalized.split(':').filter(|p| !p.is_empty()).collect();
+        let invalid = || {
+            DomainError::InvalidConfig(format!(
+                "invalid restart policy '{}': expected never, on-oom, on-failure or always, optionally followed by :<max attempts>",
+                parts.join(":")
+            ))
+        };
+
+        let mode = match parts.first().copied() {
+            Some("never") => RestartMode::Never,
+            Some("on-oom" | "oom") => RestartMode::OnOom,
+            Some("on-failure" | "failure") => RestartMode::OnFailure,
+            Some("always") => RestartMode::Always,
+            _ => return Err(invalid()),
+        };
+        let max_attempts = match parts.get(1..).unwrap_or_default() {
+            [] => None,
+            [_] if mode == RestartMode::Never => return Err(invalid()),
+            [max] => match max.parse::<u32>() {
+                Ok(0) | Err(_) => return Err(invalid()),
+                Ok(n) => Some(n),
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(Self { mode, max_attempts })
+    }
+
+    /// Whether a task that stopped for `cause` should be restarted at all.
+    pub fn restarts_on(&self, cause: &StopCause) -> bool {
+        match (self.mode, cause) {
+            (_, StopCause::UserInitiated) => false,
+            (RestartMode::Never, _) => false,
+            (RestartMode::OnOom, cause) => *cause == StopCause::OutOfMemory,
+            (RestartMode::OnFailure, cause) => matches!(
+                cause,
+                StopCause::OutOfMemory
+                    | StopCause::ProcessFailed { .. }
+                    | StopCause::Infrastructure { .. }
+            ),
+            (RestartMode::Always, _) => true,
+        }
+    }
+
+    /// Whether the `attempt`-th consecutive restart (1-based) is allowed.
+    pub fn within_budget(&self, attempt: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempt <= max)
+    }
+
+    /// Short human-readable rule for display (e.g. "after failures, at most 5
+    /// times in a row").
+    pub fn describe(&self) -> String {
+        let when = match self.mode {
+            RestartMode::Never => return "never restart".to_string(),
+            RestartMode::OnOom => "after out-of-memory kills",
+            RestartMode::OnFailure => "after failures",
+            RestartMode::Always => "after any unrequested stop",
+        };
+        match self.max_attempts {
+            Some(max) => format!("restart {when}, at most {max} times in a row"),
+            None => format!("restart {when}"),
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max_attempts {
+            Some(max) => write!(f, "{}:{}", self.mode.as_str(), max),
+            None => f.write_str(self.mode.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "passivbot-container";
+
+    fn exit(code: Option<i32>, reason: Option<&str>) -> ContainerExit {
+        ContainerExit {
+            name: MAIN.to_string(),
+            exit_code: code,
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    fn sidecar(code: Option<i32>, reason: Option<&str>) -> ContainerExit {
+        ContainerExit {
+            name: "log-router".to_string(),
+            ..exit(code, reason)
+        }
+    }
+
+    /// (stop code, stopped reason, containers, expected cause)
+    type ClassifyCase = (
+        Option<&'static str>,
+        Option<&'static str>,
+        Vec<ContainerExit>,
+        StopCause,
+    );
+
+    #[test]
+    fn classify_stop_causes() {
+        let infra = |code: &str| StopCause::Infrastructure {
+            stop_code: code.to_string(),
+        };
+        let cases: Vec<ClassifyCase> = vec![
+            (
+                Some("UserInitiated"),
+                Some("Task stopped by user"),
+                vec![exit(Some(137), None)],
+                StopCause::UserInitiated,
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![exit(Some(137), None)],
+                StopCause::OutOfMemory,
+            ),
+            (
+                Some("TaskFailedToStart"),
+                None,
+                vec![exit(None, Some("OutOfMemoryError: Container killed"))],
+                StopCause::OutOfMemory,
+            ),
+            (
+                Some("SpotInterruption"),
+                None,
+                vec![exit(Some(137), None)],
+                infra("SpotInterruption"),
+            ),
+            (
+                Some("EssentialContainerExited"),
+                Some("Task stopped due to container instance draining"),
+                vec![exit(Some(137), None)],
+                infra("EssentialContainerExited"),
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![exit(None, Some("OutOfMemoryError: Container killed"))],
+                StopCause::OutOfMemory,
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![sidecar(Some(137), None), exit(Some(1), None)],
+                StopCause::ProcessFailed { exit_code: 1 },
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![
+                    sidecar(Some(137), Some("OutOfMemoryError: Container killed")),
+                    exit(Some(1), None),
+                ],
+                StopCause::OutOfMemory,
+            ),
+            (
+                Some("TaskFailedToStart"),
+                Some("CannotPullContainerError"),
+                vec![exit(None, None)],
+                infra("TaskFailedToStart"),
+            ),
+            (
+                Some("SpotInterruption"),
+                None,
+                vec![exit(Some(143), None)],
+                infra("SpotInterruption"),
+            ),
+            (
+                Some("EssentialContainerExited"),
+                Some("Task stopped due to container instance draining"),
+                vec![],
+                infra("EssentialContainerExited"),
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![exit(Some(0), None), exit(Some(1), None)],
+                StopCause::ProcessFailed { exit_code: 1 },
+            ),
+            (
+                Some("EssentialContainerExited"),
+                None,
+                vec![exit(Some(0), None)],
+                StopCause::Exited,
+            ),
+            (None, None, vec![], StopCause::Unknown),
+        ];
+        for (stop_code, reason, containers, expected) in cases {
+            assert_eq!(
+                StopCause::classify(stop_code, reason, MAIN, &containers),
+                expected,
+                "stop_code={stop_code:?} reason={reason:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn policy_decides_per_cause() {
+        use StopCause::*;
+        let failed = ProcessFailed { exit_code: 1 };
+        let infra = Infrastructure {
+            stop_code: "SpotInterruption".to_string(),
+        };
+        // (mode, cause, restarts)
+        let cases = [
+            (RestartMode::Never, OutOfMemory, false),
+            (RestartMode::OnOom, OutOfMemory, true),
+            (RestartMode::OnOom, failed.clone(), false),
+            (RestartMode::OnFailure, failed.clone(), true),
+            (RestartMode::OnFailure, infra.clone(), true),
+            (RestartMode::OnFailure, Exited, false),
+            (RestartMode::OnFailure, Unknown, false),
+            (RestartMode::Always, Exited, true),
+            (RestartMode::Always, Unknown, true),
+            (RestartMode::Always, UserInitiated, false),
+        ];
+        for (mode, cause, expected) in cases {
+            let policy = RestartPolicy {
+                mode,
+                max_attempts: None,
+            };
+            assert_eq!(policy.restarts_on(&cause), expected, "{mode:?} / {cause:?}");
+        }
+    }
+
+    #[test]
+    fn budget_caps_consecutive_attempts() {
+        let capped = RestartPolicy::parse("on-failure:3").unwrap();
+        assert!(capped.within_budget(3));
+        assert!(!capped.within_budget(4));
+        assert!(RestartPolicy::default().within_budget(u32::MAX));
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for s in ["never", "on-oom", "on-failure:5", "always:1"] {
+            assert_eq!(RestartPolicy::parse(s).unwrap().to_string(), s);
+        }
+        assert_eq!(
+            RestartPolicy::parse(" Failure 2 ").unwrap(),
+            RestartPolicy {
+                mode: RestartMode::OnFailure,
+                max_attempts: Some(2)
+            }
+        );
+        for s in [
+            "",
+            "sometimes",
+            "on-oom:0",
+            "on-oom:x",
+            "never:3",
+            "always:1:2",
+        ] {
+            assert!(RestartPolicy::parse(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn report_prefers_the_main_containers_exit_over_a_sidecars() {
+        let report = StopReport {
+            stop_code: Some("EssentialContainerExited".to_string()),
+            stopped_reason: Some("Essential container in task exited".to_string()),
+            main_container: "passivbot".to_string(),
+            containers: vec![
+                ContainerExit {
+                    name: "log-router".to_string(),
+                    exit_code: Some(2),
+                    reason: None,
+                },
+                ContainerExit {
+                    name: "passivbot".to_string(),
+                    exit_code: Some(1),
+                    reason: None,
+                },
+            ],
+        };
+        assert_eq!(report.main().and_then(|c| c.exit_code), Some(1));
+        assert_eq!(report.cause(), StopCause::ProcessFailed { exit_code: 1 });
+        assert_eq!(
+            report.summary(),
+            "process failed (exit 1) — Essential container in task exited"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+
+const DAY: i64 = 86_400;
+
+/// Per-bot loss limits, checked against the bot's account equity by the
+/// risk guard job. A breach trips the guard: both sides go to
+/// `graceful_stop` and the bot is restarted so passivbot picks that up.
+///
+/// A tripped guard stays tripped (and does not trip again) until the user
+/// re-arms it, even if equity recovers meanwhile; re-arming starts the
+/// baselines afresh from the next reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskGuard {
+    pub user_id: String,
+    pub bot_id: String,
+    /// Largest loss allowed since the start of the UTC day, in percent of the
+    /// equity at the first reading of that day.
+    pub max_daily_loss_pct: Option<f64>,
+    /// Largest drop allowed from the equity high-water mark, in percent.
+    pub max_drawdown_pct: Option<f64>,
+    /// Highest equity seen since the guard was (re-)armed.
+    pub high_water_mark: Option<f64>,
+    /// Equity at the first reading of `day`.
+    pub day_start_equity: Option<f64>,
+    /// UTC day (days since the epoch) `day_start_equity` belongs to.
+    pub day: Option<i64>,
+    /// When the guard tripped (unix seconds); `None` while armed.
+    pub tripped_at: Option<i64>,
+    /// Which limit was breached, for the user.
+    pub trip_reason: Option<String>,
+    /// The trip stopped a running bot that still has to be started again.
+    pub restart_pending: bool,
+    pub updated_at: i64,
+}
+
+impl RiskGuard {
+    /// A guard with no limits set: it never trips.
+    pub fn new(user_id: String, bot_id: String, now: i64) -> Self {
+        Self {
+            user_id,
+            bot_id,
+            max_daily_loss_pct: None,
+            max_drawdown_pct: None,
+            high_water_mark: None,
+            day_start_equity: None,
+            day: None,
+            tripped_at: None,
+            trip_reason: None,
+            restart_pending: false,
+            updated_at: now,
+        }
+    }
+
+    pub fn set_max_daily_loss(&mut self, pct: Option<f64>, now: i64) -> Result<(), DomainError> {
+        self.max_daily_loss_pct = validate_pct(pct, "daily loss")?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn set_max_drawdown(&mut self, pct: Option<f64>, now: i64) -> Result<(), DomainError> {
+        self.max_drawdown_pct = validate_pct(pct, "drawdown")?;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped_at.is_some()
+    }
+
+    /// Watching equity: at least one limit set and not tripped.
+    pub fn is_armed(&self) -> bool {
+        !self.is_tripped() && (self.max_daily_loss_pct.is_some() || self.max_drawdown_pct.is_some())
+    }
+
+    /// Take an equity reading: roll the daily baseline over at UTC midnight,
+    /// raise the high-water mark, and check both limits. On a breach the
+    /// guard trips and the reason is returned. Readings on a tripped guard
+    /// are ignored.
+    pub fn observe(&mut self, equity: f64, now: i64) -> Option<String> {
+        if self.is_tripped() {
+            return None;
+        }
+        let today = now.div_euclid(DAY);
+        if self.day != Some(today) || self.day_start_equity.is_none() {
+            self.day = Some(today);
+            self.day_start_equity = Some(equity);
+        }
+        let hwm = self.high_water_mark.map_or(equity, |h| h.max(equity));
+        self.high_water_mark = Some(hwm);
+        self.updated_at = now;
+
+        let breach = |limit: Option<f64>, base: Option<f64>, what: &str| {
+            let (limit, base) = (limit?, base.filter(|b| *b > 0.0)?);
+            let loss = (base - equity) / base * 100.0;
+            (loss >= limit).then(|| {
+                format!("{what} {loss:.1}% reached the {limit}% limit ({base:.2} → {equity:.2})")
+            })
+        };
+        let reason = breach(self.max_daily_loss_pct, self.day_start_equity, "daily loss")
+            .or_else(|| breach(self.max_drawdown_pct, Some(hwm), "drawdown"))?;
+        self.tripped_at = Some(now);
+        self.trip_reason = Some(reason.clone());
+        Some(reason)
+    }
+
+    /// Clear a trip. The baselines are dropped too, so the next reading
+    /// starts them afresh instead of tripping again on the same loss.
+    pub fn rearm(&mut self, now: i64) {
+        self.tripped_at = None;
+        self.trip_reason = None;
+        self.high_water_mark = None;
+        self.day_start_equity = None;
+        self.day = None;
+        self.updated_at = now;
+    }
+}
+
+fn validate_pct(pct: Option<f64>, what: &str) -> Result<Option<f64>, DomainError> {
+    match pct {
+        Some(p) if !(p > 0.0 && p < 100.0) => Err(DomainError::InvalidConfig(format!(
+            "{what} limit {p}% must be between 0 and 100"
+        ))),
+        _ => Ok(pct),
+    }
+}
+
+#[async_trait]
+pub trait RiskGuardRepository: Send + Sync {
+    async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<RiskGuard>, DomainError>;
+    /// Every guard of every user, for the job.
+    async fn find_all(&self) -> Result<Vec<RiskGuard>, DomainError>;
+    /// Store the guard's limits, creating the row if needed. Baselines and
+    /// trip state are left alone, so this never races the job into clearing
+    /// a trip.
+    async fn save_limits(&self, guard: &RiskGuard) -> Result<(), DomainError>;
+    /// Store the baselines of a reading that did not trip the guard. `false`
+    /// when the guard tripped meanwhile (the reading is dropped).
+    async fn record_observation(&self, guard: &RiskGuard) -> Result<bool, DomainError>;
+    /// Latch a trip (with its baselines and `restart_pending`), but only if
+    /// the stored guard is not tripped already. `false` when another run
+    /// tripped it first, so only one run acts on a breach.
+    async fn claim_trip(&self, guard: &RiskGuard) -> Result<bool, DomainError>;
+    /// Clear the trip and the baselines (see `RiskGuard::rearm`).
+    async fn rearm(&self, guard: &RiskGuard) -> Result<(), DomainError>;
+    async fn set_restart_pending(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        pending: bool,
+    ) -> Result<(), DomainError>;
+    /// Remove the bot's guard, when the bot is deleted. A missing guard is a
+    /// no-op.
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-05 00:00:00 UTC.
+    const MIDNIGHT: i64 = 1_704_412_800;
+
+    fn guard(daily: Option<f64>, drawdown: Option<f64>) -> RiskGuard {
+        let mut g = RiskGuard::new("u".into(), "b".into(), MIDNIGHT);
+        g.set_max_daily_loss(daily, MIDNIGHT).unwrap();
+        g.set_max_drawdown(drawdown, MIDNIGHT).unwrap();
+        g
+    }
+
+    #[test]
+    fn daily_loss_is_measured_from_the_first_reading_of_the_utc_day() {
+        let mut g = guard(Some(5.0), None);
+        assert!(g.is_armed());
+        assert_eq!(g.observe(1_000.0, MIDNIGHT + 60), None);
+        assert_eq!(g.observe(960.0, MIDNIGHT + 3_600), None);
+
+        // A new day re-baselines at 900, so a further 4% is still fine.
+        assert_eq!(g.observe(900.0, MIDNIGHT + DAY + 60), None);
+        assert_eq!(g.day_start_equity, Some(900.0));
+        assert_eq!(g.observe(865.0, MIDNIGHT + DAY + 120), None);
+
+        let reason = g.observe(850.0, MIDNIGHT + DAY + 180).unwrap();
+        assert!(reason.starts_with("daily loss 5.6%"), "{reason}");
+        assert_eq!(g.tripped_at, Some(MIDNIGHT + DAY + 180));
+        assert!(!g.is_armed());
+    }
+
+    #[test]
+    fn drawdown_follows_the_high_water_mark_and_the_trip_latches() {
+        let mut g = guard(None, Some(10.0));
+        g.observe(1_000.0, MIDNIGHT);
+        g.observe(1_200.0, MIDNIGHT + DAY);
+        assert_eq!(g.high_water_mark, Some(1_200.0));
+        assert_eq!(g.observe(1_081.0, MIDNIGHT + 2 * DAY), None);
+        assert!(g.observe(1_079.0, MIDNIGHT + 2 * DAY + 60).is_some());
+
+        // Latched: a recovery does not re-arm, further losses do not re-trip.
+        assert_eq!(g.observe(1_300.0, MIDNIGHT + 2 * DAY + 120), None);
+        assert_eq!(g.observe(500.0, MIDNIGHT + 2 * DAY + 180), None);
+        assert_eq!(g.tripped_at, Some(MIDNIGHT + 2 * DAY + 60));
+
+        // Re-armed, the next reading is the new baseline.
+        g.rearm(MIDNIGHT + 3 * DAY);
+        assert!(g.is_armed());
+        assert_eq!(g.observe(900.0, MIDNIGHT + 3 * DAY + 60), None);
+        assert_eq!(g.high_water_mark, Some(900.0));
+    }
+
+    #[test]
+    fn limits_must_be_a_percentage() {
+        let mut g = guard(None, None);
+        assert!(!g.is_armed());
+        assert!(g.set_max_daily_loss(Some(0.0), MIDNIGHT).is_err());
+        assert!(g.set_max_drawdown(Some(100.0), MIDNIGHT).is_err());
+        assert!(g.set_max_drawdown(Some(f64::NAN), MIDNIGHT).is_err());
+        assert_eq!(g.observe(1_000.0, MIDNIGHT), None);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::domain::botconfig::RiskLevel;
+use crate::domain::configtemplate::ConfigTemplate;
+use serde_json::Value;
+
+/// Named starting points for the risk dialogue. Each template maps them to its
+/// own per-side exposure, since a sensible exposure depends on the strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskPreset {
+    Conservative,
+    Balanced,
+    Aggressive,
+}
+
+impl RiskPreset {
+    pub const ALL: [RiskPreset; 3] = [
+        RiskPreset::Conservative,
+        RiskPreset::Balanced,
+        RiskPreset::Aggressive,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskPreset::Conservative => "conservative",
+            RiskPreset::Balanced => "balanced",
+            RiskPreset::Aggressive => "aggressive",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskPreset::Conservative => "🛡️ Conservative",
+            RiskPreset::Balanced => "⚖️ Balanced",
+            RiskPreset::Aggressive => "🔥 Aggressive",
+        }
+    }
+
+    /// Scale applied to the template's own exposure when the template does not
+    /// declare this preset explicitly.
+    fn default_scale(&self) -> f64 {
+        match self {
+            RiskPreset::Conservative => 0.5,
+            RiskPreset::Balanced => 1.0,
+            RiskPreset::Aggressive => 1.5,
+        }
+    }
+
+    /// Resolve every preset for a template. An explicit top-level
+    /// `risk_presets.<preset>.{long,short}` entry wins; otherwise the template's
+    /// `bot.<side>.total_wallet_exposure_limit` is scaled (½×, 1×, 1½×) and
+    /// clamped into `RiskLevel`'s range. Presets that resolve to no valid
+    /// `RiskLevel` are omitted, so the result may be empty.
+    pub fn resolve_all(template: &ConfigTemplate) -> Vec<(RiskPreset, RiskLevel)> {
+        let data = &template.config_data;
+        let base = template_exposure(data);
+        Self::ALL
+            .into_iter()
+            .filter_map(|preset| {
+                let risk = explicit_preset(data, preset).or_else(|| {
+                    let (long, short) = base?;
+                    let scale = |v: f64| round2((v * preset.default_scale()).clamp(0.0, 10.0));
+                    RiskLevel::new(scale(long), scale(short)).ok()
+                })?;
+                Some((preset, risk))
+            })
+            .collect()
+    }
+}
+
+fn explicit_preset(data: &Value, preset: RiskPreset) -> Option<RiskLevel> {
+    let entry = data.get("risk_presets")?.get(preset.as_str())?;
+    let long = entry.get("long")?.as_f64()?;
+    let short = entry.get("short")?.as_f64()?;
+    RiskLevel::new(long, short).ok()
+}
+
+fn template_exposure(data: &Value) -> Option<(f64, f64)> {
+    let side = |name: &str| {
+        data.get("bot")?
+            .get(name)?
+            .get("total_wallet_exposure_limit")?
+            .as_f64()
+    };
+    Some((side("long")?, side("short")?))
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(data: Value) -> ConfigTemplate {
+        ConfigTemplate {
+            name: "t".into(),
+            description: None,
+            config_data: data,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn presets_scale_template_exposure() {
+        let t = template(json!({
+            "bot": {
+                "long": {"total_wallet_exposure_limit": 1.5},
+                "short": {"total_wallet_exposure_limit": 8.0}
+            }
+        }));
+        let presets = RiskPreset::resolve_all(&t);
+        assert_eq!(
+            presets,
+            vec![
+                (RiskPreset::Conservative, RiskLevel::new(0.75, 4.0).unwrap()),
+                (RiskPreset::Balanced, RiskLevel::new(1.5, 8.0).unwrap()),
+                // short is clamped to the RiskLevel ceiling
+                (RiskPreset::Aggressive, RiskLevel::new(2.25, 10.0).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_template_presets_win() {
+        let t = template(json!({
+            "risk_presets": {"aggressive": {"long": 4.0, "short": 0.0}},
+            "bot": {
+                "long": {"total_wallet_exposure_limit": 2.0},
+                "short": {"total_wallet_exposure_limit": 0.0}
+            }
+        }));
+        let presets = RiskPreset::resolve_all(&t);
+        assert_eq!(presets[0].1, RiskLevel::new(1.0, 0.0).unwrap());
+        assert_eq!(presets[2].1, RiskLevel::new(4.0, 0.0).unwrap());
+    }
+
+    #[test]
+    fn template_without_exposure_has_no_presets() {
+        assert!(RiskPreset::resolve_all(&template(json!({"live": {}}))).is_empty());
+    }
+}
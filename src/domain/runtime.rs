@@ -46,6 +46,9 @@ pub struct BotRuntime {
     pub phase: RuntimePhase,
     pub version: i64, // restart counter / task generation
     pub observed_at: i64,
+    /// Consecutive automatic restarts since the last start by a user (or a
+    /// stop after a stable run). Checked against the bot's `RestartPolicy`.
+    pub restart_attempts: u32,
 }
 
 impl BotRuntime {
@@ -63,6 +66,7 @@ impl BotRuntime {
             phase: RuntimePhase::Running,
             version,
             observed_at: now,
+            restart_attempts: 0,
         }
     }
     pub fn stopped(user_id: String, bot_id: String, version: i64, now: i64) -> Self {
@@ -73,6 +77,7 @@ impl BotRuntime {
             phase: RuntimePhase::Stopped,
             version,
             observed_at: now,
+            restart_attempts: 0,
         }
     }
     /// Observed `Stopping`: a task that has been asked to stop but whose terminal
@@ -92,6 +97,7 @@ impl BotRuntime {
             phase: RuntimePhase::Stopping,
             version,
             observed_at: now,
+            restart_attempts: 0,
         }
     }
 }
@@ -109,9 +115,20 @@ pub trait BotRuntimeRepository: Send + Sync {
     ) -> Result<Option<BotRuntime>, DomainError> {
         self.find(user_id, bot_id).await
     }
+    /// Every runtime row of one user, in any order, for views that show many
+    /// bots at once (the bot list) without one read per bot.
+    async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError>;
     async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError>;
 }
 
+/// Cross-user enumeration of runtime rows, for the anti-entropy sweeper only.
+/// Kept apart from `BotRuntimeRepository` because nothing on the request path
+/// may ever read every user's partition.
+#[async_trait]
+pub trait RuntimeScanRepository: Send + Sync {
+    async fn scan_all(&self) -> Result<Vec<BotRuntime>, DomainError>;
+}
+
 /// Outcome of attempting to claim the exclusive right to launch a bot's task.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StartClaim {
@@ -136,7 +153,7 @@ pub trait StartLockRepository: Send + Sync {
     /// it is safe to launch: the row is absent/stopped, or holds a `starting`
     /// lock older than `stale_after` seconds (an abandoned launch). `now` is
     /// wall-clock seconds. Concurrent callers are serialized per row, so at most
-    /// one receives `Acquired`.
+    /// one receives `Acquired`. A claimed start resets `restart_attempts`.
     async fn try_acquire_start(
         &self,
         user_id: &str,
@@ -147,9 +164,9 @@ pub trait StartLockRepository: Send + Sync {
     /// Atomically claim the right to restart after `stopped_task_id` stopped:
     /// transition the row to `starting` ONLY while that task is still the bot's
     /// current task (the row's `task_id` still matches), bumping the restart
-    /// counter. This is the idempotency gate for the Lambda's auto-restart —
-    /// duplicate STOPPED events for the same task find the id already cleared and
-    /// are rejected, so a stopped task can be replaced at most once. `Acquired`
+    /// counter and recording this as consecutive restart number `attempt`. This
+    /// is the idempotency gate for the Lambda's auto-restart — duplicate STOPPED
+    /// events for the same task find the id already cleared and are rejected, so a stopped task can be replaced at most once. `Acquired`
     /// means the caller must launch; any other variant means the stopped task is
     /// no longer current and there is nothing to restart.
     async fn try_acquire_restart(
@@ -157,6 +174,7 @@ pub trait StartLockRepository: Send + Sync {
         user_id: &str,
         bot_id: &str,
         stopped_task_id: &str,
+        attempt: u32,
         now: i64,
     ) -> Result<StartClaim, DomainError>;
     /// Record the launched `task_id` on the held `starting` lock so a stop issued
@@ -0,0 +1,466 @@
+use crate::domain::botconfig::RiskLevel;
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use std::fmt;
+
+/// A five-field cron expression (`minute hour day-of-month month day-of-week`),
+/// evaluated in UTC. Each field accepts `*`, numbers, ranges (`1-5`), lists
+/// (`1,3,5`) and steps (`*/15`, `0-30/10`). Day-of-week is 0-6 with 0 = Sunday
+/// (7 is accepted as Sunday too). As in classic cron, when both day fields are
+/// restricted a day matches if EITHER matches; otherwise both must. As in
+/// Vixie cron, a day field starting with `*` (`*`, `*/2`) does not count as
+/// restricted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    expr: String,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+/// How far ahead `next_after` searches before concluding the expression never
+/// fires (e.g. `0 0 30 2 *`). Covers every leap-year / weekday alignment.
+const SEARCH_DAYS: i64 = 366 * 8;
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, DomainError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(DomainError::InvalidConfig(format!(
+                "cron expression '{expr}' must have 5 fields: minute hour day-of-month month day-of-week"
+            )));
+        }
+        let dow = parse_field(fields[4], 0, 7, "day-of-week")?;
+        // Fold 7 (Sunday) onto 0.
+        let dow = (dow | (dow >> 7)) & 0x7f;
+        Ok(Self {
+            expr: fields.join(" "),
+            minutes: parse_field(fields[0], 0, 59, "minute")?,
+            hours: parse_field(fields[1], 0, 23, "hour")? as u32,
+            days_of_month: parse_field(fields[2], 1, 31, "day-of-month")? as u32,
+            months: parse_field(fields[3], 1, 12, "month")? as u16,
+            days_of_week: dow as u8,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    /// The first minute boundary strictly after `ts` (unix seconds) that the
+    /// expression matches, or `None` if it never fires.
+    pub fn next_after(&self, ts: i64) -> Option<i64> {
+        let start = ts.div_euclid(60) * 60 + 60;
+        let first_day = start.div_euclid(86_400);
+        for day in first_day..first_day + SEARCH_DAYS {
+            if !self.matches_day(day) {
+                continue;
+            }
+            for hour in 0..24 {
+                if self.hours & (1 << hour) == 0 {
+                    continue;
+                }
+                for minute in 0..60 {
+                    if self.minutes & (1 << minute) == 0 {
+                        continue;
+                    }
+                    let candidate = day * 86_400 + hour * 3_600 + minute * 60;
+                    if candidate >= start {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: i64) -> bool {
+        let (_, month, day) = civil_from_days(days_since_epoch);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        // 1970-01-01 was a Thursday (4).
+        let weekday = (days_since_epoch + 4).rem_euclid(7);
+        let dom = self.days_of_month & (1 << day) != 0;
+        let dow = self.days_of_week & (1 << weekday) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+/// Parse one cron field into a bitset of allowed values in `[min, max]`.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, DomainError> {
+    let invalid = || DomainError::InvalidConfig(format!("invalid cron {name} field '{field}'"));
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (
+                r,
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                a.parse::<u32>().map_err(|_| invalid())?,
+                b.parse::<u32>().map_err(|_| invalid())?,
+            )
+        } else {
+            let v = range.parse::<u32>().map_err(|_| invalid())?;
+            // `5/15` means "from 5 to the end, every 15".
+            (v, if part.contains('/') { max } else { v })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(invalid());
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+/// Convert days since the unix epoch to a proleptic Gregorian `(year, month,
+/// day)` (H. Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Render a unix timestamp as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_utc(ts: i64) -> String {
+    let (year, month, day) = civil_from_days(ts.div_euclid(86_400));
+    let secs = ts.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60
+    )
+}
+
+/// What a schedule does when it fires. Each maps onto an existing use case:
+/// Run bot, Stop bot, the risk-level update, and the strategy-side toggle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+    SetRisk { long: f64, short: f64 },
+    SetSide { side: String, enabled: bool },
+}
+
+impl ScheduleAction {
+    /// Parse the compact form that `Display` produces: `start`, `stop`,
+    /// `risk:<long>/<short>`, `side:<long|short>:<on|off>`. Whitespace works as
+    /// a separator too (`risk 1.5/0.5`, `side short off`).
+    pub fn parse(s: &str) -> Result<Self, DomainError> {
+        let normalized = s.trim().to_lowercase().replace(char::is_whitespace, ":");
+        let parts: Vec<&str> = normalized.split(':').filter(|p| !p.is_empty()).collect();
+        let invalid = || {
+            DomainError::InvalidConfig(format!(
+                "invalid schedule action '{}': expected start, stop, risk:<long>/<short> or side:<long|short>:<on|off>",
+                s.trim()
+            ))
+        };
+        match parts.as_slice() {
+            ["start"] => Ok(Self::Start),
+            ["stop"] => Ok(Self::Stop),
+            ["risk", values] => {
+                let (long, short) = values.split_once('/').ok_or_else(invalid)?;
+                let long = long.parse::<f64>().map_err(|_| invalid())?;
+                let short = short.parse::<f64>().map_err(|_| invalid())?;
+                RiskLevel::new(long, short)?;
+                Ok(Self::SetRisk { long, short })
+            }
+            ["side", side @ ("long" | "short"), state @ ("on" | "off")] => Ok(Self::SetSide {
+                side: side.to_string(),
+                enabled: *state == "on",
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for ScheduleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Start => f.write_str("start"),
+            Self::Stop => f.write_str("stop"),
+            Self::SetRisk { long, short } => write!(f, "risk:{}/{}", long, short),
+            Self::SetSide { side, enabled } => {
+                write!(f, "side:{}:{}", side, if *enabled { "on" } else { "off" })
+            }
+        }
+    }
+}
+
+/// A recurring action on one bot. `next_run_at` is the single source of truth
+/// for when it is due; the runner claims a firing by moving it forward with a
+/// conditional write, so a schedule fires at most once per slot even if two
+/// runners overlap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub id: String,
+    pub user_id: String,
+    pub bot_id: String,
+    pub cron: CronSchedule,
+    pub action: ScheduleAction,
+    pub next_run_at: i64,
+    pub last_run_at: Option<i64>,
+    /// Human-readable outcome of the last firing (success or error).
+    pub last_result: Option<String>,
+    pub created_at: i64,
+}
+
+impl Schedule {
+    /// Factory for a new schedule. Fails when the expression never fires.
+    pub fn create(
+        id: String,
+        user_id: String,
+        bot_id: String,
+        cron: CronSchedule,
+        action: ScheduleAction,
+        now: i64,
+    ) -> Result<Self, DomainError> {
+        let next_run_at = cron.next_after(now).ok_or_else(|| {
+            DomainError::InvalidConfig(format!("cron expression '{cron}' never fires"))
+        })?;
+        Ok(Self {
+            id,
+            user_id,
+            bot_id,
+            cron,
+            action,
+            next_run_at,
+            last_run_at: None,
+            last_result: None,
+            created_at: now,
+        })
+    }
+
+    pub fn is_due(&self, now: i64) -> bool {
+        self.next_run_at <= now
+    }
+
+    /// Mark this slot as fired at `now` and move to the next slot after `now`.
+    /// Missed slots (runner outage) are skipped rather than replayed: only the
+    /// latest intent matters for start/stop/risk changes.
+    pub fn advance(&mut self, now: i64) {
+        self.last_run_at = Some(now);
+        // `create` proved the expression fires; an 8-year horizon always finds
+        // the next slot, so the fallback is unreachable in practice.
+        self.next_run_at = self.cron.next_after(now).unwrap_or(i64::MAX);
+    }
+}
+
+#[async_trait]
+pub trait ScheduleRepository: Send + Sync {
+    async fn save(&self, schedule: &Schedule) -> Result<(), DomainError>;
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<Schedule>, DomainError>;
+    /// `Ok(false)` when there was no such schedule.
+    async fn delete(&self, user_id: &str, schedule_id: &str) -> Result<bool, DomainError>;
+    /// All schedules, across users, with `next_run_at <= now`.
+    async fn find_due(&self, now: i64) -> Result<Vec<Schedule>, DomainError>;
+    /// Claim a firing: persist the advanced `next_run_at` / `last_run_at` only
+    /// if the stored `next_run_at` still equals `expected_next_run_at`. `Ok(false)`
+    /// means another runner already claimed this slot (or the schedule was
+    /// deleted) and the action must not fire.
+    async fn claim_run(
+        &self,
+        schedule: &Schedule,
+        expected_next_run_at: i64,
+    ) -> Result<bool, DomainError>;
+    /// Record the outcome of a firing. Best-effort; a deleted schedule is not
+    /// resurrected.
+    async fn record_result(
+        &self,
+        user_id: &str,
+        schedule_id: &str,
+        result: &str,
+    ) -> Result<(), DomainError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-05 00:00:00 UTC, a Friday.
+    const FRI: i64 = 1_704_412_800;
+
+    fn cron(expr: &str) -> CronSchedule {
+        CronSchedule::parse(expr).unwrap()
+    }
+
+    #[test]
+    fn civil_dates_and_formatting() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(
+            format_utc(FRI + 22 * 3_600 + 30 * 60),
+            "2024-01-05 22:30 UTC"
+        );
+        // leap day
+        assert_eq!(format_utc(1_709_164_800), "2024-02-29 00:00 UTC");
+    }
+
+    #[test]
+    fn next_after_finds_weekly_slot() {
+        // Fridays 22:00 -> same day
+        assert_eq!(cron("0 22 * * 5").next_after(FRI), Some(FRI + 22 * 3_600));
+        // exactly at the slot -> strictly after, one week later
+        let slot = FRI + 22 * 3_600;
+        assert_eq!(cron("0 22 * * 5").next_after(slot), Some(slot + 7 * 86_400));
+        // Monday 06:00 -> 3 days later; 7 is Sunday, so "1" is Monday
+        assert_eq!(
+            cron("0 6 * * 1").next_after(FRI),
+            Some(FRI + 3 * 86_400 + 6 * 3_600)
+        );
+        assert_eq!(cron("0 0 * * 7").next_after(FRI), Some(FRI + 2 * 86_400));
+    }
+
+    #[test]
+    fn steps_ranges_and_lists() {
+        assert_eq!(
+            cron("*/15 * * * *").next_after(FRI + 60),
+            Some(FRI + 15 * 60)
+        );
+        assert_eq!(
+            cron("5/20 * * * *").next_after(FRI + 6 * 60),
+            Some(FRI + 25 * 60)
+        );
+        assert_eq!(
+            cron("0 9-17/4 * * *").next_after(FRI + 10 * 3_600),
+            Some(FRI + 13 * 3_600)
+        );
+        assert_eq!(
+            cron("30 1,23 * * *").next_after(FRI + 2 * 3_600),
+            Some(FRI + 23 * 3_600 + 1_800)
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // the 10th OR any Sunday: Sunday 2024-01-07 comes first
+        assert_eq!(cron("0 0 10 * 0").next_after(FRI), Some(FRI + 2 * 86_400));
+        // the 10th of any weekday-unrestricted month
+        assert_eq!(cron("0 0 10 * *").next_after(FRI), Some(FRI + 5 * 86_400));
+        // a stepped `*` day-of-month does not count as restricted, so both
+        // fields must match: odd-numbered Mondays (the 15th), not odd days
+        // (Friday the 5th) or Mondays (the 8th)
+        assert_eq!(
+            cron("0 9 */2 * 1").next_after(FRI),
+            Some(FRI + 10 * 86_400 + 9 * 3_600)
+        );
+    }
+
+    #[test]
+    fn impossible_expression_never_fires() {
+        assert_eq!(cron("0 0 30 2 *").next_after(FRI), None);
+        let err = Schedule::create(
+            "s".into(),
+            "u".into(),
+            "b".into(),
+            cron("0 0 31 4 *"),
+            ScheduleAction::Stop,
+            FRI,
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn parse_rejects_bad_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "accepted {expr:?}");
+        }
+    }
+
+    #[test]
+    fn action_parse_and_display_round_trip() {
+        for s in [
+            "start",
+            "stop",
+            "risk:1.5/0.5",
+            "side:short:off",
+            "side:long:on",
+        ] {
+            assert_eq!(ScheduleAction::parse(s).unwrap().to_string(), s);
+        }
+        assert_eq!(
+            ScheduleAction::parse("Side Long OFF").unwrap(),
+            ScheduleAction::SetSide {
+                side: "long".into(),
+                enabled: false
+            }
+        );
+        for s in [
+            "",
+            "pause",
+            "risk:11/0",
+            "risk:1",
+            "side:both:off",
+            "side:long:maybe",
+        ] {
+            assert!(ScheduleAction::parse(s).is_err(), "accepted {s:?}");
+        }
+    }
+
+    #[test]
+    fn advance_skips_missed_slots() {
+        let mut s = Schedule::create(
+            "s".into(),
+            "u".into(),
+            "b".into(),
+            cron("0 * * * *"),
+            ScheduleAction::Stop,
+            FRI,
+        )
+        .unwrap();
+        assert_eq!(s.next_run_at, FRI + 3_600);
+        assert!(!s.is_due(FRI + 3_599));
+
+        // runner was down for five hours: fire once, next slot is after now
+        let late = FRI + 5 * 3_600 + 120;
+        assert!(s.is_due(late));
+        s.advance(late);
+        assert_eq!(s.last_run_at, Some(late));
+        assert_eq!(s.next_run_at, FRI + 6 * 3_600);
+    }
+}
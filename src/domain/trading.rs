@@ -0,0 +1,69 @@
+use crate::domain::account::OrderSide;
+use crate::domain::bot::Bot;
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+impl PositionSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionSide::Long => "long",
+            PositionSide::Short => "short",
+        }
+    }
+}
+
+/// An open position on the bot's exchange account, as reported by the
+/// exchange. `size` is always positive; the direction is in `side`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub symbol: String,
+    pub side: PositionSide,
+    pub size: f64,
+    pub entry_price: f64,
+    pub mark_price: f64,
+    pub unrealized_pnl: f64,
+    /// `None` when the exchange reports no liquidation price (e.g. fully
+    /// collateralised).
+    pub liq_price: Option<f64>,
+    /// Bybit's `positionIdx`: 0 in one-way mode, 1 (long) or 2 (short) in
+    /// hedge mode. A close has to send the same value back.
+    pub position_idx: u8,
+}
+
+/// A resting order on the bot's exchange account, as reported by the
+/// exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    /// Exchange-assigned id, what a cancel refers to.
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub qty: f64,
+    /// Reduce-only orders can only shrink a position (passivbot's closes).
+    pub reduce_only: bool,
+}
+
+/// Port for acting on a bot's exchange account directly, bypassing the
+/// passivbot task. Credentials come from the bot row.
+#[async_trait]
+pub trait ExchangeTradingGateway: Send + Sync {
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError>;
+
+    async fn open_orders(&self, bot: &Bot) -> Result<Vec<Order>, DomainError>;
+
+    /// Cancel one open order.
+    async fn cancel_order(&self, bot: &Bot, order: &Order) -> Result<(), DomainError>;
+
+    /// Cancel every open order on the account.
+    async fn cancel_all_orders(&self, bot: &Bot) -> Result<(), DomainError>;
+
+    /// Close `position` with a reduce-only market order for its full size.
+    async fn close_position(&self, bot: &Bot, position: &Position) -> Result<(), DomainError>;
+}
@@ -1,10 +1,27 @@
 pub mod apikeyrepository;
 pub mod botconfigrepository;
 pub mod botrepository;
+pub mod bybit;
 pub mod client;
 pub mod configtemplaterepository;
+#[cfg(feature = "docker")]
+pub mod docker;
+pub mod instrumentrepository;
+#[cfg(feature = "testing")]
+pub mod memory;
+pub mod metrics;
+pub mod telegram;
 
 pub use apikeyrepository::S3ApiKeyRepository;
 pub use botconfigrepository::S3BotConfigRepository;
 pub use botrepository::DynamoBotRepository;
+pub use bybit::BybitTradingGateway;
 pub use configtemplaterepository::S3TemplateRepository;
+#[cfg(feature = "docker")]
+pub use docker::DockerTaskBackend;
+#[cfg(feature = "testing")]
+pub use memory::{
+    FakeTaskBackend, InMemoryApiKeyRepository, InMemoryBotConfigRepository, InMemoryBotRepository,
+    InMemoryTemplateRepository, RecordingNotifier,
+};
+pub use metrics::{EmfMetrics, PrometheusMetrics};
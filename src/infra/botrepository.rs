@@ -1,15 +1,27 @@
-use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::apitoken::{ApiToken, ApiTokenRepository};
+use crate::domain::bot::{Bot, BotPage, BotRepository};
 use crate::domain::error::DomainError;
 use crate::domain::exchange::Exchange;
+use crate::domain::history::{
+    BOT_EVENT_RETENTION_SECS, BotEvent, BotEventKind, BotHistoryRepository,
+};
+use crate::domain::leverage::LeveragePolicyKind;
+use crate::domain::preferences::{UserPreferences, UserPreferencesRepository};
+use crate::domain::resources::ResourceProfile;
+use crate::domain::restart::RestartPolicy;
+use crate::domain::riskguard::{RiskGuard, RiskGuardRepository};
 use crate::domain::runtime::{
-    BotRuntime, BotRuntimeRepository, RuntimePhase, StartClaim, StartLockRepository,
+    BotRuntime, BotRuntimeRepository, RuntimePhase, RuntimeScanRepository, StartClaim,
+    StartLockRepository,
 };
+use crate::domain::schedule::{CronSchedule, Schedule, ScheduleAction, ScheduleRepository};
 use async_trait::async_trait;
 use aws_sdk_dynamodb::Client;
 use aws_sdk_dynamodb::error::{DisplayErrorContext, SdkError};
+use aws_sdk_dynamodb::operation::query::builders::QueryFluentBuilder;
 use aws_sdk_dynamodb::operation::update_item::{UpdateItemError, UpdateItemOutput};
-use aws_sdk_dynamodb::types::AttributeValue;
-use std::collections::HashMap;
+use aws_sdk_dynamodb::types::{AttributeValue, Delete, Put, TransactWriteItem};
+use std::collections::{BTreeMap, HashMap};
 
 /// Render an aws-sdk error with its full source chain, surfacing the modeled
 /// service error (code and message). `SdkError`'s bare `Display` collapses every
@@ -55,8 +67,12 @@ pub struct BotItem {
     pub api_key: String,
     pub secret_key: String,
     pub enabled: bool,
-    pub created_at: i64, // Unix timestamp in seconds
-    pub updated_at: i64, // Unix timestamp in seconds
+    pub leverage_policy: Option<String>, // absent on rows written before the policy existed
+    pub exposure_ceiling: Option<f64>,
+    pub restart_policy: Option<String>, // absent on rows written before the policy existed
+    pub resource_profile: Option<String>, // absent on rows written before profiles existed
+    pub created_at: i64,                // Unix timestamp in seconds
+    pub updated_at: i64,                // Unix timestamp in seconds
 }
 
 impl BotItem {
@@ -79,6 +95,22 @@ impl BotItem {
             api_key: item.get("api_key")?.as_s().ok()?.to_string(),
             secret_key: item.get("secret_key")?.as_s().ok()?.to_string(),
             enabled: item.get("enabled")?.as_bool().ok().copied()?,
+            leverage_policy: item
+                .get("leverage_policy")
+                .and_then(|v| v.as_s().ok())
+                .map(|s| s.to_string()),
+            exposure_ceiling: item
+                .get("exposure_ceiling")
+                .and_then(|v| v.as_n().ok())
+                .and_then(|n| n.parse().ok()),
+            restart_policy: item
+                .get("restart_policy")
+                .and_then(|v| v.as_s().ok())
+                .map(|s| s.to_string()),
+            resource_profile: item
+                .get("resource_profile")
+                .and_then(|v| v.as_s().ok())
+                .map(|s| s.to_string()),
             created_at: item.get("created_at")?.as_n().ok()?.parse().ok()?,
             updated_at: item.get("updated_at")?.as_n().ok()?.parse().ok()?,
         })
@@ -102,6 +134,30 @@ impl BotItem {
             AttributeValue::S(self.secret_key.clone()),
         );
         map.insert("enabled".to_string(), AttributeValue::Bool(self.enabled));
+        if let Some(policy) = &self.leverage_policy {
+            map.insert(
+                "leverage_policy".to_string(),
+                AttributeValue::S(policy.clone()),
+            );
+        }
+        if let Some(ceiling) = self.exposure_ceiling {
+            map.insert(
+                "exposure_ceiling".to_string(),
+                AttributeValue::N(ceiling.to_string()),
+            );
+        }
+        if let Some(policy) = &self.restart_policy {
+            map.insert(
+                "restart_policy".to_string(),
+                AttributeValue::S(policy.clone()),
+            );
+        }
+        if let Some(profile) = &self.resource_profile {
+            map.insert(
+                "resource_profile".to_string(),
+                AttributeValue::S(profile.clone()),
+            );
+        }
         map.insert(
             "created_at".to_string(),
             AttributeValue::N(self.created_at.to_string()),
@@ -116,6 +172,27 @@ impl BotItem {
     fn to_domain(&self) -> Option<Bot> {
         let user_id = Self::extract_user_id_from_pk(&self.pk)?;
         let exchange = Exchange::from_str(self.exchange.as_str())?;
+        // A missing or unparseable policy falls back to the default rule rather
+        // than hiding the bot: the policy only matters when risk is next applied.
+        let leverage_policy = self
+            .leverage_policy
+            .as_deref()
+            .and_then(|s| LeveragePolicyKind::parse(s).ok())
+            .unwrap_or_default();
+        // Same fallback for the restart policy: the default is the historical
+        // OOM-only rule.
+        let restart_policy = self
+            .restart_policy
+            .as_deref()
+            .and_then(|s| RestartPolicy::parse(s).ok())
+            .unwrap_or_default();
+        // And for the resource profile: the default is the task definition's
+        // own size.
+        let resource_profile = self
+            .resource_profile
+            .as_deref()
+            .and_then(|s| ResourceProfile::parse(s).ok())
+            .unwrap_or_default();
         Some(Bot {
             id: self.sk.clone(), // bot_id from SK
             user_id,
@@ -124,6 +201,10 @@ impl BotItem {
             api_key: self.api_key.clone(),
             secret_key: self.secret_key.clone(),
             enabled: self.enabled,
+            leverage_policy,
+            exposure_ceiling: self.exposure_ceiling,
+            restart_policy,
+            resource_profile,
             created_at: self.created_at,
             updated_at: self.updated_at,
         })
@@ -138,6 +219,10 @@ impl BotItem {
             api_key: bot.api_key.clone(),
             secret_key: bot.secret_key.clone(),
             enabled: bot.enabled,
+            leverage_policy: Some(bot.leverage_policy.to_string()),
+            exposure_ceiling: bot.exposure_ceiling,
+            restart_policy: Some(bot.restart_policy.to_string()),
+            resource_profile: Some(bot.resource_profile.to_string()),
             created_at: bot.created_at,
             updated_at: bot.updated_at,
         }
@@ -147,7 +232,7 @@ impl BotItem {
 /// Private storage/mapping struct for the observed ECS runtime of a bot.
 /// Item shape: pk = user_id#<user_id>, sk = ecs_task_metadata#<bot_id>,
 /// attributes: status (String), task_id (String), task_updated_at (Number),
-/// task_current_version (Number).
+/// task_current_version (Number), restart_attempts (Number).
 struct BotECSTaskMetadata {
     pk: String, // user_id#<user_id>
     sk: String, // ecs_task_metadata#<bot_id>
@@ -155,16 +240,19 @@ struct BotECSTaskMetadata {
     task_id: String,
     updated_at: i64,
     task_current_version: i64,
+    restart_attempts: u32,
 }
 
 impl BotECSTaskMetadata {
+    const SK_PREFIX: &'static str = "ecs_task_metadata#";
+
     fn construct_sk(bot_id: &str) -> String {
-        format!("ecs_task_metadata#{}", bot_id)
+        format!("{}{}", Self::SK_PREFIX, bot_id)
     }
 
     fn get_bot_id(&self) -> String {
         self.sk
-            .strip_prefix("ecs_task_metadata#")
+            .strip_prefix(Self::SK_PREFIX)
             .unwrap_or(&self.sk)
             .to_string()
     }
@@ -193,6 +281,11 @@ impl BotECSTaskMetadata {
                 .and_then(|v| v.as_n().ok())
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(0),
+            restart_attempts: item
+                .get("restart_attempts")
+                .and_then(|v| v.as_n().ok())
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
         })
     }
 
@@ -213,6 +306,10 @@ impl BotECSTaskMetadata {
             "task_current_version".to_string(),
             AttributeValue::N(self.task_current_version.to_string()),
         );
+        map.insert(
+            "restart_attempts".to_string(),
+            AttributeValue::N(self.restart_attempts.to_string()),
+        );
         map
     }
 
@@ -224,6 +321,7 @@ impl BotECSTaskMetadata {
             task_id: runtime.task_id.clone().unwrap_or_default(),
             updated_at: runtime.observed_at,
             task_current_version: runtime.version,
+            restart_attempts: runtime.restart_attempts,
         }
     }
 
@@ -243,7 +341,413 @@ impl BotECSTaskMetadata {
             phase,
             version: self.task_current_version,
             observed_at: self.updated_at,
+            restart_attempts: self.restart_attempts,
+        }
+    }
+}
+
+/// Raw DynamoDB item.
+type Item = HashMap<String, AttributeValue>;
+
+/// Private storage/mapping struct for a scheduled action.
+/// Item shape: pk = user_id#<user_id>, sk = schedule#<schedule_id>,
+/// attributes: bot_id, cron, action (compact `ScheduleAction` form),
+/// next_run_at, last_run_at, last_result, created_at.
+struct ScheduleItem;
+
+impl ScheduleItem {
+    const SK_PREFIX: &'static str = "schedule#";
+
+    fn construct_sk(schedule_id: &str) -> String {
+        format!("{}{}", Self::SK_PREFIX, schedule_id)
+    }
+
+    fn to_item(schedule: &Schedule) -> HashMap<String, AttributeValue> {
+        let mut map = HashMap::new();
+        map.insert(
+            "pk".to_string(),
+            AttributeValue::S(BotItem::construct_pk(&schedule.user_id)),
+        );
+        map.insert(
+            "sk".to_string(),
+            AttributeValue::S(Self::construct_sk(&schedule.id)),
+        );
+        map.insert(
+            "bot_id".to_string(),
+            AttributeValue::S(schedule.bot_id.clone()),
+        );
+        map.insert(
+            "cron".to_string(),
+            AttributeValue::S(schedule.cron.as_str().to_string()),
+        );
+        map.insert(
+            "action".to_string(),
+            AttributeValue::S(schedule.action.to_string()),
+        );
+        map.insert(
+            "next_run_at".to_string(),
+            AttributeValue::N(schedule.next_run_at.to_string()),
+        );
+        if let Some(last_run_at) = schedule.last_run_at {
+            map.insert(
+                "last_run_at".to_string(),
+                AttributeValue::N(last_run_at.to_string()),
+            );
+        }
+        if let Some(last_result) = &schedule.last_result {
+            map.insert(
+                "last_result".to_string(),
+                AttributeValue::S(last_result.clone()),
+            );
+        }
+        map.insert(
+            "created_at".to_string(),
+            AttributeValue::N(schedule.created_at.to_string()),
+        );
+        map
+    }
+
+    /// `None` for anything that is not a well-formed schedule row, including
+    /// bot and runtime rows sharing the partition.
+    fn to_domain(item: &HashMap<String, AttributeValue>) -> Option<Schedule> {
+        let s = |key: &str| item.get(key).and_then(|v| v.as_s().ok());
+        let n = |key: &str| {
+            item.get(key)
+                .and_then(|v| v.as_n().ok())
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+        let id = s("sk")?.strip_prefix(Self::SK_PREFIX)?.to_string();
+        Some(Schedule {
+            id,
+            user_id: BotItem::extract_user_id_from_pk(s("pk")?)?,
+            bot_id: s("bot_id")?.to_string(),
+            cron: CronSchedule::parse(s("cron")?).ok()?,
+            action: ScheduleAction::parse(s("action")?).ok()?,
+            next_run_at: n("next_run_at")?,
+            last_run_at: n("last_run_at"),
+            last_result: s("last_result").map(|v| v.to_string()),
+            created_at: n("created_at").unwrap_or(0),
+        })
+    }
+}
+
+/// Private storage/mapping struct for a bot lifecycle event.
+/// Item shape: pk = user_id#<user_id>, sk = event#<bot_id>#<at>#<kind>, with
+/// `at` zero-padded so the sort key orders by time. Attributes: bot_id, at,
+/// kind, task_id, detail, restart_attempt, expires_at (the table's TTL
+/// attribute).
+struct EventItem;
+
+impl EventItem {
+    const SK_PREFIX: &'static str = "event#";
+
+    fn bot_prefix(bot_id: &str) -> String {
+        format!("{}{}#", Self::SK_PREFIX, bot_id)
+    }
+
+    fn construct_sk(event: &BotEvent) -> String {
+        format!(
+            "{}{:010}#{}",
+            Self::bot_prefix(&event.bot_id),
+            event.at,
+            event.kind.as_str()
+        )
+    }
+
+    fn to_item(event: &BotEvent) -> HashMap<String, AttributeValue> {
+        let mut map = HashMap::new();
+        map.insert(
+            "pk".to_string(),
+            AttributeValue::S(BotItem::construct_pk(&event.user_id)),
+        );
+        map.insert(
+            "sk".to_string(),
+            AttributeValue::S(Self::construct_sk(event)),
+        );
+        map.insert(
+            "bot_id".to_string(),
+            AttributeValue::S(event.bot_id.clone()),
+        );
+        map.insert("at".to_string(), AttributeValue::N(event.at.to_string()));
+        map.insert(
+            "kind".to_string(),
+            AttributeValue::S(event.kind.as_str().to_string()),
+        );
+        if let Some(task_id) = &event.task_id {
+            map.insert("task_id".to_string(), AttributeValue::S(task_id.clone()));
+        }
+        if let Some(detail) = &event.detail {
+            map.insert("detail".to_string(), AttributeValue::S(detail.clone()));
         }
+        if let Some(attempt) = event.restart_attempt {
+            map.insert(
+                "restart_attempt".to_string(),
+                AttributeValue::N(attempt.to_string()),
+            );
+        }
+        map.insert(
+            "expires_at".to_string(),
+            AttributeValue::N((event.at + BOT_EVENT_RETENTION_SECS).to_string()),
+        );
+        map
+    }
+
+    /// `None` for anything that is not a well-formed event row.
+    fn to_domain(item: &HashMap<String, AttributeValue>) -> Option<BotEvent> {
+        let s = |key: &str| item.get(key).and_then(|v| v.as_s().ok());
+        let n = |key: &str| item.get(key).and_then(|v| v.as_n().ok());
+        s("sk")?.strip_prefix(Self::SK_PREFIX)?;
+        Some(BotEvent {
+            user_id: BotItem::extract_user_id_from_pk(s("pk")?)?,
+            bot_id: s("bot_id")?.to_string(),
+            at: n("at")?.parse().ok()?,
+            kind: BotEventKind::parse(s("kind")?)?,
+            task_id: s("task_id").map(|v| v.to_string()),
+            detail: s("detail").map(|v| v.to_string()),
+            restart_attempt: n("restart_attempt").and_then(|v| v.parse().ok()),
+        })
+    }
+}
+
+/// Private storage/mapping struct for an HTTP API token. Two rows per token:
+/// - lookup row: pk = api_token#<token_hash>, sk = api_token, attributes
+///   user_id, created_at — read on every request;
+/// - user row: pk = user_id#<user_id>, sk = api_token#, attributes
+///   token_hash, created_at — finds the token to rotate or revoke.
+///
+/// Both are written and deleted in one transaction.
+struct ApiTokenItem;
+
+impl ApiTokenItem {
+    const SK_PREFIX: &'static str = "api_token#";
+    const LOOKUP_SK: &'static str = "api_token";
+
+    fn lookup_key(token_hash: &str) -> Item {
+        HashMap::from([
+            (
+                "pk".to_string(),
+                AttributeValue::S(format!("{}{}", Self::SK_PREFIX, token_hash)),
+            ),
+            (
+                "sk".to_string(),
+                AttributeValue::S(Self::LOOKUP_SK.to_string()),
+            ),
+        ])
+    }
+
+    fn user_key(user_id: &str) -> Item {
+        HashMap::from([
+            (
+                "pk".to_string(),
+                AttributeValue::S(BotItem::construct_pk(user_id)),
+            ),
+            (
+                "sk".to_string(),
+                AttributeValue::S(Self::SK_PREFIX.to_string()),
+            ),
+        ])
+    }
+
+    fn to_items(token: &ApiToken) -> (Item, Item) {
+        let created_at = AttributeValue::N(token.created_at.to_string());
+        let mut lookup = Self::lookup_key(&token.token_hash);
+        lookup.insert(
+            "user_id".to_string(),
+            AttributeValue::S(token.user_id.clone()),
+        );
+        lookup.insert("created_at".to_string(), created_at.clone());
+        let mut user = Self::user_key(&token.user_id);
+        user.insert(
+            "token_hash".to_string(),
+            AttributeValue::S(token.token_hash.clone()),
+        );
+        user.insert("created_at".to_string(), created_at);
+        (lookup, user)
+    }
+
+    fn from_lookup(token_hash: &str, item: &Item) -> Option<ApiToken> {
+        Some(ApiToken {
+            token_hash: token_hash.to_string(),
+            user_id: item.get("user_id")?.as_s().ok()?.to_string(),
+            created_at: item.get("created_at")?.as_n().ok()?.parse().ok()?,
+        })
+    }
+
+    fn from_user(user_id: &str, item: &Item) -> Option<ApiToken> {
+        Some(ApiToken {
+            token_hash: item.get("token_hash")?.as_s().ok()?.to_string(),
+            user_id: user_id.to_string(),
+            created_at: item.get("created_at")?.as_n().ok()?.parse().ok()?,
+        })
+    }
+}
+
+/// Private storage/mapping struct for a user's preferences.
+/// Item shape: pk = user_id#<user_id>, sk = preferences#, attributes:
+/// digest_hour and next_digest_at (both absent while the digest is off),
+/// digest_equity (a map of bot id to equity), updated_at.
+struct PreferencesItem;
+
+impl PreferencesItem {
+    const SK: &'static str = "preferences#";
+
+    fn key(user_id: &str) -> Item {
+        HashMap::from([
+            (
+                "pk".to_string(),
+                AttributeValue::S(BotItem::construct_pk(user_id)),
+            ),
+            ("sk".to_string(), AttributeValue::S(Self::SK.to_string())),
+        ])
+    }
+
+    fn equity_attr(equity: &BTreeMap<String, f64>) -> AttributeValue {
+        AttributeValue::M(
+            equity
+                .iter()
+                .map(|(bot_id, e)| (bot_id.clone(), AttributeValue::N(e.to_string())))
+                .collect(),
+        )
+    }
+
+    fn to_item(prefs: &UserPreferences) -> Item {
+        let mut map = Self::key(&prefs.user_id);
+        if let Some(hour) = prefs.digest_hour {
+            map.insert(
+                "digest_hour".to_string(),
+                AttributeValue::N(hour.to_string()),
+            );
+        }
+        if let Some(at) = prefs.next_digest_at {
+            map.insert(
+                "next_digest_at".to_string(),
+                AttributeValue::N(at.to_string()),
+            );
+        }
+        map.insert(
+            "digest_equity".to_string(),
+            Self::equity_attr(&prefs.digest_equity),
+        );
+        map.insert(
+            "updated_at".to_string(),
+            AttributeValue::N(prefs.updated_at.to_string()),
+        );
+        map
+    }
+
+    fn to_domain(item: &Item) -> Option<UserPreferences> {
+        let n = |key: &str| item.get(key).and_then(|v| v.as_n().ok());
+        if item.get("sk")?.as_s().ok()? != Self::SK {
+            return None;
+        }
+        let digest_equity = item
+            .get("digest_equity")
+            .and_then(|v| v.as_m().ok())
+            .map(|m| {
+                m.iter()
+                    .filter_map(|(bot_id, v)| Some((bot_id.clone(), v.as_n().ok()?.parse().ok()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(UserPreferences {
+            user_id: BotItem::extract_user_id_from_pk(item.get("pk")?.as_s().ok()?)?,
+            digest_hour: n("digest_hour").and_then(|v| v.parse().ok()),
+            next_digest_at: n("next_digest_at").and_then(|v| v.parse().ok()),
+            digest_equity,
+            updated_at: n("updated_at").and_then(|v| v.parse().ok()).unwrap_or(0),
+        })
+    }
+}
+
+/// Private storage/mapping struct for a bot's risk guard.
+/// Item shape: pk = user_id#<user_id>, sk = guard#<bot_id>, attributes:
+/// bot_id, max_daily_loss_pct and max_drawdown_pct (absent while unset),
+/// high_water_mark, day_start_equity and equity_day (`day` is a reserved
+/// word; all three absent until the first reading after arming), tripped_at and trip_reason (absent while armed),
+/// restart_pending, updated_at.
+struct GuardItem;
+
+impl GuardItem {
+    const SK_PREFIX: &'static str = "guard#";
+
+    fn key(user_id: &str, bot_id: &str) -> Item {
+        HashMap::from([
+            (
+                "pk".to_string(),
+                AttributeValue::S(BotItem::construct_pk(user_id)),
+            ),
+            (
+                "sk".to_string(),
+                AttributeValue::S(format!("{}{}", Self::SK_PREFIX, bot_id)),
+            ),
+        ])
+    }
+
+    /// `SET` the attributes that have a value and `REMOVE` the others, as one
+    /// update expression with its values.
+    fn set_or_remove(
+        fields: &[(&'static str, Option<String>)],
+    ) -> (Vec<String>, Vec<&'static str>, Item) {
+        let mut set = Vec::new();
+        let mut remove = Vec::new();
+        let mut values = HashMap::new();
+        for (name, value) in fields {
+            match value {
+                Some(v) => {
+                    set.push(format!("{name} = :{name}"));
+                    values.insert(format!(":{name}"), AttributeValue::N(v.clone()));
+                }
+                None => remove.push(*name),
+            }
+        }
+        (set, remove, values)
+    }
+
+    fn update_expression(set: &[String], remove: &[&str]) -> String {
+        let mut expr = format!("SET {}", set.join(", "));
+        if !remove.is_empty() {
+            expr.push_str(&format!(" REMOVE {}", remove.join(", ")));
+        }
+        expr
+    }
+
+    fn baselines(guard: &RiskGuard) -> [(&'static str, Option<String>); 3] {
+        [
+            (
+                "high_water_mark",
+                guard.high_water_mark.map(|v| v.to_string()),
+            ),
+            (
+                "day_start_equity",
+                guard.day_start_equity.map(|v| v.to_string()),
+            ),
+            ("equity_day", guard.day.map(|v| v.to_string())),
+        ]
+    }
+
+    fn to_domain(item: &Item) -> Option<RiskGuard> {
+        let n = |key: &str| item.get(key).and_then(|v| v.as_n().ok());
+        let f = |key: &str| n(key).and_then(|v| v.parse::<f64>().ok());
+        if !item.get("sk")?.as_s().ok()?.starts_with(Self::SK_PREFIX) {
+            return None;
+        }
+        Some(RiskGuard {
+            user_id: BotItem::extract_user_id_from_pk(item.get("pk")?.as_s().ok()?)?,
+            bot_id: item.get("bot_id")?.as_s().ok()?.to_string(),
+            max_daily_loss_pct: f("max_daily_loss_pct"),
+            max_drawdown_pct: f("max_drawdown_pct"),
+            high_water_mark: f("high_water_mark"),
+            day_start_equity: f("day_start_equity"),
+            day: n("equity_day").and_then(|v| v.parse().ok()),
+            tripped_at: n("tripped_at").and_then(|v| v.parse().ok()),
+            trip_reason: item.get("trip_reason").and_then(|v| v.as_s().ok()).cloned(),
+            restart_pending: item
+                .get("restart_pending")
+                .and_then(|v| v.as_bool().ok())
+                .copied()
+                .unwrap_or(false),
+            updated_at: n("updated_at").and_then(|v| v.parse().ok()).unwrap_or(0),
+        })
     }
 }
 
@@ -256,6 +760,87 @@ impl DynamoBotRepository {
     pub fn new(client: Client, table_name: String) -> Self {
         Self { client, table_name }
     }
+
+    /// Run `query` page by page, following `LastEvaluatedKey`, until at least
+    /// `limit` items came back (all of them when `None`). One Query page stops
+    /// at 1 MB, and a user's partition holds bot, runtime, schedule and event
+    /// rows, so reading only the first page silently truncates the result.
+    async fn query_items(
+        &self,
+        query: QueryFluentBuilder,
+        limit: Option<usize>,
+    ) -> Result<Vec<Item>, DomainError> {
+        let mut items = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = query
+                .clone()
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| {
+                    DomainError::Repository(format!("DynamoDB query failed: {}", fmt_sdk_err(e)))
+                })?;
+            items.extend(output.items().iter().cloned());
+            if limit.is_some_and(|limit| items.len() >= limit) {
+                break;
+            }
+            match output.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Query for the user's bot rows, optionally resuming after the bot id
+    /// `after`. Bot rows pre-date sort-key prefixes (their sk is the bare bot
+    /// id), so they are told apart by excluding every prefixed row kind:
+    /// runtime, schedule, event, API token, preferences and guard.
+    fn bots_query(&self, user_id: &str, after: Option<&str>) -> QueryFluentBuilder {
+        let pk = BotItem::construct_pk(user_id);
+        let start_key = after.map(|bot_id| {
+            HashMap::from([
+                ("pk".to_string(), AttributeValue::S(pk.clone())),
+                ("sk".to_string(), AttributeValue::S(bot_id.to_string())),
+            ])
+        });
+        self.client
+            .query()
+            .table_name(&self.table_name)
+            .key_condition_expression("pk = :pk")
+            .filter_expression(
+                "NOT begins_with(sk, :runtime) AND NOT begins_with(sk, :schedule) \
+                 AND NOT begins_with(sk, :event) AND NOT begins_with(sk, :api_token) \
+                 AND NOT begins_with(sk, :preferences) AND NOT begins_with(sk, :guard)",
+            )
+            .expression_attribute_values(":pk", AttributeValue::S(pk))
+            .expression_attribute_values(
+                ":runtime",
+                AttributeValue::S(BotECSTaskMetadata::SK_PREFIX.to_string()),
+            )
+            .expression_attribute_values(
+                ":schedule",
+                AttributeValue::S(ScheduleItem::SK_PREFIX.to_string()),
+            )
+            .expression_attribute_values(
+                ":event",
+                AttributeValue::S(EventItem::SK_PREFIX.to_string()),
+            )
+            .expression_attribute_values(
+                ":api_token",
+                AttributeValue::S(ApiTokenItem::SK_PREFIX.to_string()),
+            )
+            .expression_attribute_values(
+                ":preferences",
+                AttributeValue::S(PreferencesItem::SK.to_string()),
+            )
+            .expression_attribute_values(
+                ":guard",
+                AttributeValue::S(GuardItem::SK_PREFIX.to_string()),
+            )
+            .set_exclusive_start_key(start_key)
+    }
 }
 
 #[async_trait]
@@ -309,6 +894,27 @@ impl BotRuntimeRepository for DynamoBotRepository {
         }
     }
 
+    async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+        let query = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .key_condition_expression("pk = :pk AND begins_with(sk, :prefix)")
+            .expression_attribute_values(":pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .expression_attribute_values(
+                ":prefix",
+                AttributeValue::S(BotECSTaskMetadata::SK_PREFIX.to_string()),
+            );
+
+        Ok(self
+            .query_items(query, None)
+            .await?
+            .iter()
+            .filter_map(BotECSTaskMetadata::from_item)
+            .map(|m| m.to_domain())
+            .collect())
+    }
+
     async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
         let metadata = BotECSTaskMetadata::from_domain(runtime);
 
@@ -335,7 +941,7 @@ impl BotRuntimeRepository for DynamoBotRepository {
             // A terminal STOPPED always settles the row, INCLUDING a `stopping`
             // one stamped slightly in the future by a clock-ahead telebot — without
             // the `#st = :stopping` clause a dropped settlement could leave the row
-            // stuck `stopping` forever (there is no periodic sweeper). It only ever
+            // stuck `stopping` until the next runtime sweep. It only ever
             // fires while the row is actually `stopping`, so it cannot clobber a
             // newer `running`.
             RuntimePhase::Stopped => put
@@ -403,6 +1009,47 @@ impl BotRuntimeRepository for DynamoBotRepository {
     }
 }
 
+#[async_trait]
+impl RuntimeScanRepository for DynamoBotRepository {
+    async fn scan_all(&self) -> Result<Vec<BotRuntime>, DomainError> {
+        // Runtime rows live in every user's partition, so this is a filtered
+        // Scan. The sweeper is its only caller and runs every few minutes.
+        // Strongly consistent so a repair is never decided on a lagging replica.
+        let mut runtimes = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .filter_expression("begins_with(sk, :prefix)")
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(BotECSTaskMetadata::SK_PREFIX.to_string()),
+                )
+                .consistent_read(true)
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| {
+                    DomainError::Repository(format!("DynamoDB scan failed: {}", fmt_sdk_err(e)))
+                })?;
+            runtimes.extend(
+                output
+                    .items()
+                    .iter()
+                    .filter_map(BotECSTaskMetadata::from_item)
+                    .map(|m| m.to_domain()),
+            );
+            match output.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+        Ok(runtimes)
+    }
+}
+
 #[async_trait]
 impl StartLockRepository for DynamoBotRepository {
     async fn try_acquire_start(
@@ -458,7 +1105,7 @@ impl StartLockRepository for DynamoBotRepository {
             .table_name(&self.table_name)
             .key("pk", AttributeValue::S(pk))
             .key("sk", AttributeValue::S(sk))
-            .update_expression("SET #st = :starting, task_updated_at = :now, task_id = :empty")
+            .update_expression("SET #st = :starting, task_updated_at = :now, task_id = :empty, restart_attempts = :zero")
             .condition_expression("attribute_not_exists(#st) OR #st = :stopped OR (#st = :starting AND task_updated_at <= :stale_cutoff) OR (#st = :stopping AND task_updated_at <= :stale_cutoff)")
             .expression_attribute_names("#st", "status")
             .expression_attribute_values(":starting", AttributeValue::S("starting".to_string()))
@@ -467,6 +1114,7 @@ impl StartLockRepository for DynamoBotRepository {
             .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
             .expression_attribute_values(":empty", AttributeValue::S(String::new()))
             .expression_attribute_values(":stale_cutoff", AttributeValue::N(stale_cutoff.to_string()))
+            .expression_attribute_values(":zero", AttributeValue::N("0".to_string()))
             .send()
             .await;
 
@@ -480,6 +1128,7 @@ impl StartLockRepository for DynamoBotRepository {
         user_id: &str,
         bot_id: &str,
         stopped_task_id: &str,
+        attempt: u32,
         now: i64,
     ) -> Result<StartClaim, DomainError> {
         // Authoritative CAS, no pre-read: claim only while the stopped task is
@@ -493,7 +1142,7 @@ impl StartLockRepository for DynamoBotRepository {
             .table_name(&self.table_name)
             .key("pk", AttributeValue::S(BotItem::construct_pk(user_id)))
             .key("sk", AttributeValue::S(BotECSTaskMetadata::construct_sk(bot_id)))
-            .update_expression("SET #st = :starting, task_updated_at = :now, task_id = :empty, task_current_version = if_not_exists(task_current_version, :zero) + :one")
+            .update_expression("SET #st = :starting, task_updated_at = :now, task_id = :empty, task_current_version = if_not_exists(task_current_version, :zero) + :one, restart_attempts = :attempt")
             .condition_expression("task_id = :stopped AND (#st = :running OR #st = :starting)")
             .expression_attribute_names("#st", "status")
             .expression_attribute_values(":starting", AttributeValue::S("starting".to_string()))
@@ -503,6 +1152,7 @@ impl StartLockRepository for DynamoBotRepository {
             .expression_attribute_values(":empty", AttributeValue::S(String::new()))
             .expression_attribute_values(":zero", AttributeValue::N("0".to_string()))
             .expression_attribute_values(":one", AttributeValue::N("1".to_string()))
+            .expression_attribute_values(":attempt", AttributeValue::N(attempt.to_string()))
             .send()
             .await;
 
@@ -635,29 +1285,80 @@ impl BotRepository for DynamoBotRepository {
         Ok(())
     }
 
-    async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError> {
-        let pk_value = BotItem::construct_pk(user_id);
-
-        let output = self
+    async fn promote_resource_profile(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        from: ResourceProfile,
+        to: ResourceProfile,
+        stopped_task_id: &str,
+        now: i64,
+    ) -> Result<bool, DomainError> {
+        // Touch only the profile: a whole-item put from the reconcile Lambda
+        // could revert a concurrent enable/disable. The existence condition
+        // keeps a deleted bot from being resurrected as a bare item; the
+        // profile condition loses to a concurrent change; `resized_after_task`
+        // makes a redelivered STOPPED event for the same task a no-op. A row
+        // written before profiles existed holds the default implicitly.
+        let from_condition = if from == ResourceProfile::default() {
+            "(resource_profile = :from OR attribute_not_exists(resource_profile))"
+        } else {
+            "resource_profile = :from"
+        };
+        let res = self
             .client
-            .query()
+            .update_item()
             .table_name(&self.table_name)
-            .key_condition_expression("pk = :pk")
-            .expression_attribute_values(":pk", AttributeValue::S(pk_value))
+            .key("pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .key("sk", AttributeValue::S(bot_id.to_string()))
+            .update_expression(
+                "SET resource_profile = :to, resized_after_task = :task, updated_at = :now",
+            )
+            .condition_expression(format!(
+                "attribute_exists(pk) AND {from_condition} AND \
+                 (attribute_not_exists(resized_after_task) OR resized_after_task <> :task)"
+            ))
+            .expression_attribute_values(":from", AttributeValue::S(from.to_string()))
+            .expression_attribute_values(":to", AttributeValue::S(to.to_string()))
+            .expression_attribute_values(":task", AttributeValue::S(stopped_task_id.to_string()))
+            .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
             .send()
-            .await
-            .map_err(|e| {
-                DomainError::Repository(format!("DynamoDB query failed: {}", fmt_sdk_err(e)))
-            })?;
+            .await;
 
-        Ok(output
-            .items()
+        cas_result(res, true, false)
+    }
+
+    async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError> {
+        let items = self
+            .query_items(self.bots_query(user_id, None), None)
+            .await?;
+        Ok(items
             .iter()
             .filter_map(BotItem::from_item)
             .filter_map(|bot_item| bot_item.to_domain())
             .collect())
     }
 
+    async fn find_page_by_user_id(
+        &self,
+        user_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<BotPage, DomainError> {
+        // One row past the page tells whether another page follows. The
+        // filter runs after each page is read, so `query_items` keeps paging
+        // until enough bot rows came back, not just enough rows were read.
+        let items = self
+            .query_items(self.bots_query(user_id, after), Some(limit + 1))
+            .await?;
+        let bots = items
+            .iter()
+            .filter_map(BotItem::from_item)
+            .filter_map(|bot_item| bot_item.to_domain())
+            .collect();
+        Ok(BotPage::from_sorted(bots, limit))
+    }
+
     async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String> {
         let pk_value = BotItem::construct_pk(user_id);
 
@@ -673,3 +1374,587 @@ impl BotRepository for DynamoBotRepository {
         Ok(())
     }
 }
+
+#[async_trait]
+impl ScheduleRepository for DynamoBotRepository {
+    async fn save(&self, schedule: &Schedule) -> Result<(), DomainError> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(ScheduleItem::to_item(schedule)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB put_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(())
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<Schedule>, DomainError> {
+        let query = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .key_condition_expression("pk = :pk AND begins_with(sk, :prefix)")
+            .expression_attribute_values(":pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .expression_attribute_values(
+                ":prefix",
+                AttributeValue::S(ScheduleItem::SK_PREFIX.to_string()),
+            );
+
+        Ok(self
+            .query_items(query, None)
+            .await?
+            .iter()
+            .filter_map(ScheduleItem::to_domain)
+            .collect())
+    }
+
+    async fn delete(&self, user_id: &str, schedule_id: &str) -> Result<bool, DomainError> {
+        let output = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .key(
+                "sk",
+                AttributeValue::S(ScheduleItem::construct_sk(schedule_id)),
+            )
+            .return_values(aws_sdk_dynamodb::types::ReturnValue::AllOld)
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB delete_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(output.attributes().is_some())
+    }
+
+    async fn find_due(&self, now: i64) -> Result<Vec<Schedule>, DomainError> {
+        // Schedules span every user's partition, so this is a filtered Scan.
+        // Fine at our table size; a sparse GSI on next_run_at is the upgrade path.
+        let mut due = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .filter_expression("begins_with(sk, :prefix) AND next_run_at <= :now")
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(ScheduleItem::SK_PREFIX.to_string()),
+                )
+                .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| {
+                    DomainError::Repository(format!("DynamoDB scan failed: {}", fmt_sdk_err(e)))
+                })?;
+            due.extend(output.items().iter().filter_map(ScheduleItem::to_domain));
+            match output.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+        Ok(due)
+    }
+
+    async fn claim_run(
+        &self,
+        schedule: &Schedule,
+        expected_next_run_at: i64,
+    ) -> Result<bool, DomainError> {
+        // CAS on next_run_at: only one runner moves a slot forward, so only one
+        // fires it. Fails too if the schedule was deleted meanwhile.
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key(
+                "pk",
+                AttributeValue::S(BotItem::construct_pk(&schedule.user_id)),
+            )
+            .key(
+                "sk",
+                AttributeValue::S(ScheduleItem::construct_sk(&schedule.id)),
+            )
+            .update_expression("SET next_run_at = :next, last_run_at = :last")
+            .condition_expression("next_run_at = :expected")
+            .expression_attribute_values(
+                ":next",
+                AttributeValue::N(schedule.next_run_at.to_string()),
+            )
+            .expression_attribute_values(
+                ":last",
+                AttributeValue::N(schedule.last_run_at.unwrap_or_default().to_string()),
+            )
+            .expression_attribute_values(
+                ":expected",
+                AttributeValue::N(expected_next_run_at.to_string()),
+            )
+            .send()
+            .await;
+
+        cas_result(res, true, false)
+    }
+
+    async fn record_result(
+        &self,
+        user_id: &str,
+        schedule_id: &str,
+        result: &str,
+    ) -> Result<(), DomainError> {
+        // Conditional on the row existing so a schedule deleted while its action
+        // ran is not resurrected as a bare result row.
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .key("pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .key(
+                "sk",
+                AttributeValue::S(ScheduleItem::construct_sk(schedule_id)),
+            )
+            .update_expression("SET last_result = :result")
+            .condition_expression("attribute_exists(pk)")
+            .expression_attribute_values(":result", AttributeValue::S(result.to_string()))
+            .send()
+            .await;
+
+        cas_result(res, (), ())
+    }
+}
+
+#[async_trait]
+impl UserPreferencesRepository for DynamoBotRepository {
+    async fn find(&self, user_id: &str) -> Result<Option<UserPreferences>, DomainError> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(PreferencesItem::key(user_id)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB get_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(result.item().and_then(PreferencesItem::to_domain))
+    }
+
+    async fn save(&self, prefs: &UserPreferences) -> Result<(), DomainError> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(PreferencesItem::to_item(prefs)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB put_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(())
+    }
+
+    async fn find_due_digests(&self, now: i64) -> Result<Vec<UserPreferences>, DomainError> {
+        // One row per user across every partition: a filtered Scan, like
+        // `find_due` for schedules.
+        let mut due = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .filter_expression("sk = :sk AND next_digest_at <= :now")
+                .expression_attribute_values(
+                    ":sk",
+                    AttributeValue::S(PreferencesItem::SK.to_string()),
+                )
+                .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| {
+                    DomainError::Repository(format!("DynamoDB scan failed: {}", fmt_sdk_err(e)))
+                })?;
+            due.extend(output.items().iter().filter_map(PreferencesItem::to_domain));
+            match output.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+        Ok(due)
+    }
+
+    async fn claim_digest(
+        &self,
+        prefs: &UserPreferences,
+        expected_next_digest_at: i64,
+    ) -> Result<bool, DomainError> {
+        // CAS on next_digest_at, as `claim_run` does for schedules: only one
+        // run moves the slot forward, so only one sends the digest. Fails too
+        // if the user turned the digest off or moved it meanwhile.
+        let Some(next) = prefs.next_digest_at else {
+            return Ok(false);
+        };
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(PreferencesItem::key(&prefs.user_id)))
+            .update_expression("SET next_digest_at = :next")
+            .condition_expression("next_digest_at = :expected")
+            .expression_attribute_values(":next", AttributeValue::N(next.to_string()))
+            .expression_attribute_values(
+                ":expected",
+                AttributeValue::N(expected_next_digest_at.to_string()),
+            )
+            .send()
+            .await;
+
+        cas_result(res, true, false)
+    }
+
+    async fn record_digest_equity(
+        &self,
+        user_id: &str,
+        equity: &BTreeMap<String, f64>,
+    ) -> Result<(), DomainError> {
+        // Conditional on the row existing, like `record_result`.
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(PreferencesItem::key(user_id)))
+            .update_expression("SET digest_equity = :equity")
+            .condition_expression("attribute_exists(pk)")
+            .expression_attribute_values(":equity", PreferencesItem::equity_attr(equity))
+            .send()
+            .await;
+
+        cas_result(res, (), ())
+    }
+}
+
+#[async_trait]
+impl RiskGuardRepository for DynamoBotRepository {
+    async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<RiskGuard>, DomainError> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(user_id, bot_id)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB get_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(result.item().and_then(GuardItem::to_domain))
+    }
+
+    async fn find_all(&self) -> Result<Vec<RiskGuard>, DomainError> {
+        // Guards span every user's partition: a filtered Scan, like
+        // `find_due` for schedules.
+        let mut guards = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .filter_expression("begins_with(sk, :prefix)")
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(GuardItem::SK_PREFIX.to_string()),
+                )
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|e| {
+                    DomainError::Repository(format!("DynamoDB scan failed: {}", fmt_sdk_err(e)))
+                })?;
+            guards.extend(output.items().iter().filter_map(GuardItem::to_domain));
+            match output.last_evaluated_key() {
+                Some(key) => start_key = Some(key.clone()),
+                None => break,
+            }
+        }
+        Ok(guards)
+    }
+
+    async fn save_limits(&self, guard: &RiskGuard) -> Result<(), DomainError> {
+        let (mut set, remove, values) = GuardItem::set_or_remove(&[
+            (
+                "max_daily_loss_pct",
+                guard.max_daily_loss_pct.map(|v| v.to_string()),
+            ),
+            (
+                "max_drawdown_pct",
+                guard.max_drawdown_pct.map(|v| v.to_string()),
+            ),
+            ("updated_at", Some(guard.updated_at.to_string())),
+        ]);
+        set.push("bot_id = :bot_id".to_string());
+        set.push("restart_pending = if_not_exists(restart_pending, :false)".to_string());
+        self.client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(&guard.user_id, &guard.bot_id)))
+            .update_expression(GuardItem::update_expression(&set, &remove))
+            .set_expression_attribute_values(Some(values))
+            .expression_attribute_values(":bot_id", AttributeValue::S(guard.bot_id.clone()))
+            .expression_attribute_values(":false", AttributeValue::Bool(false))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB update_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(())
+    }
+
+    async fn record_observation(&self, guard: &RiskGuard) -> Result<bool, DomainError> {
+        let (mut set, remove, values) = GuardItem::set_or_remove(&GuardItem::baselines(guard));
+        set.push("updated_at = :updated_at".to_string());
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(&guard.user_id, &guard.bot_id)))
+            .update_expression(GuardItem::update_expression(&set, &remove))
+            .condition_expression("attribute_exists(pk) AND attribute_not_exists(tripped_at)")
+            .set_expression_attribute_values(Some(values))
+            .expression_attribute_values(
+                ":updated_at",
+                AttributeValue::N(guard.updated_at.to_string()),
+            )
+            .send()
+            .await;
+
+        cas_result(res, true, false)
+    }
+
+    async fn claim_trip(&self, guard: &RiskGuard) -> Result<bool, DomainError> {
+        let (Some(tripped_at), Some(reason)) = (guard.tripped_at, guard.trip_reason.as_ref())
+        else {
+            return Ok(false);
+        };
+        let (mut set, remove, values) = GuardItem::set_or_remove(&GuardItem::baselines(guard));
+        set.extend(
+            [
+                "tripped_at = :tripped_at",
+                "trip_reason = :reason",
+                "restart_pending = :pending",
+                "updated_at = :updated_at",
+            ]
+            .map(String::from),
+        );
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(&guard.user_id, &guard.bot_id)))
+            .update_expression(GuardItem::update_expression(&set, &remove))
+            .condition_expression("attribute_exists(pk) AND attribute_not_exists(tripped_at)")
+            .set_expression_attribute_values(Some(values))
+            .expression_attribute_values(":tripped_at", AttributeValue::N(tripped_at.to_string()))
+            .expression_attribute_values(":reason", AttributeValue::S(reason.clone()))
+            .expression_attribute_values(":pending", AttributeValue::Bool(guard.restart_pending))
+            .expression_attribute_values(
+                ":updated_at",
+                AttributeValue::N(guard.updated_at.to_string()),
+            )
+            .send()
+            .await;
+
+        cas_result(res, true, false)
+    }
+
+    async fn rearm(&self, guard: &RiskGuard) -> Result<(), DomainError> {
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(&guard.user_id, &guard.bot_id)))
+            .update_expression(
+                "SET updated_at = :updated_at \
+                 REMOVE tripped_at, trip_reason, high_water_mark, day_start_equity, equity_day",
+            )
+            .condition_expression("attribute_exists(pk)")
+            .expression_attribute_values(
+                ":updated_at",
+                AttributeValue::N(guard.updated_at.to_string()),
+            )
+            .send()
+            .await;
+
+        cas_result(res, (), ())
+    }
+
+    async fn set_restart_pending(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        pending: bool,
+    ) -> Result<(), DomainError> {
+        let res = self
+            .client
+            .update_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(user_id, bot_id)))
+            .update_expression("SET restart_pending = :pending")
+            .condition_expression("attribute_exists(pk)")
+            .expression_attribute_values(":pending", AttributeValue::Bool(pending))
+            .send()
+            .await;
+
+        cas_result(res, (), ())
+    }
+
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), DomainError> {
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .set_key(Some(GuardItem::key(user_id, bot_id)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB delete_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BotHistoryRepository for DynamoBotRepository {
+    async fn append(&self, event: &BotEvent) -> Result<(), DomainError> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(EventItem::to_item(event)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB put_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(())
+    }
+
+    async fn recent(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        limit: usize,
+    ) -> Result<Vec<BotEvent>, DomainError> {
+        // Newest first. Expired rows linger until TTL actually deletes them, so
+        // the page may include a few past retention; that is harmless here.
+        let query = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .key_condition_expression("pk = :pk AND begins_with(sk, :prefix)")
+            .expression_attribute_values(":pk", AttributeValue::S(BotItem::construct_pk(user_id)))
+            .expression_attribute_values(
+                ":prefix",
+                AttributeValue::S(EventItem::bot_prefix(bot_id)),
+            )
+            .scan_index_forward(false)
+            .limit(i32::try_from(limit).unwrap_or(i32::MAX));
+
+        Ok(self
+            .query_items(query, Some(limit))
+            .await?
+            .iter()
+            .take(limit)
+            .filter_map(EventItem::to_domain)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepository for DynamoBotRepository {
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, DomainError> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(ApiTokenItem::lookup_key(token_hash)))
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB get_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(result
+            .item()
+            .and_then(|item| ApiTokenItem::from_lookup(token_hash, item)))
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Option<ApiToken>, DomainError> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(ApiTokenItem::user_key(user_id)))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("DynamoDB get_item failed: {}", fmt_sdk_err(e)))
+            })?;
+        Ok(result
+            .item()
+            .and_then(|item| ApiTokenItem::from_user(user_id, item)))
+    }
+
+    async fn save(&self, token: &ApiToken) -> Result<(), DomainError> {
+        let (lookup, user) = ApiTokenItem::to_items(token);
+        let put = |item: Item| {
+            Put::builder()
+                .table_name(&self.table_name)
+                .set_item(Some(item))
+                .build()
+                .map(|put| TransactWriteItem::builder().put(put).build())
+        };
+        let (lookup, user) = put(lookup)
+            .and_then(|lookup| Ok((lookup, put(user)?)))
+            .map_err(|e| DomainError::Repository(format!("invalid token item: {}", e)))?;
+        self.client
+            .transact_write_items()
+            .transact_items(lookup)
+            .transact_items(user)
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!(
+                    "DynamoDB transact_write_items failed: {}",
+                    fmt_sdk_err(e)
+                ))
+            })?;
+        Ok(())
+    }
+
+    async fn delete(&self, token: &ApiToken) -> Result<(), DomainError> {
+        let delete = |key: Item| {
+            Delete::builder()
+                .table_name(&self.table_name)
+                .set_key(Some(key))
+                .build()
+                .map(|delete| TransactWriteItem::builder().delete(delete).build())
+        };
+        let (lookup, user) = delete(ApiTokenItem::lookup_key(&token.token_hash))
+            .and_then(|lookup| Ok((lookup, delete(ApiTokenItem::user_key(&token.user_id))?)))
+            .map_err(|e| DomainError::Repository(format!("invalid token key: {}", e)))?;
+        self.client
+            .transact_write_items()
+            .transact_items(lookup)
+            .transact_items(user)
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!(
+                    "DynamoDB transact_write_items failed: {}",
+                    fmt_sdk_err(e)
+                ))
+            })?;
+        Ok(())
+    }
+}
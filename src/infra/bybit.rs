@@ -0,0 +1,761 @@
+use crate::domain::account::{ClosedPnl, ExchangeAccount, Fill, OrderSide};
+use crate::domain::bot::Bot;
+use crate::domain::error::DomainError;
+use crate::domain::instrument::{Instrument, InstrumentSource};
+use crate::domain::trading::{ExchangeTradingGateway, Order, Position, PositionSide};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const BYBIT_MAINNET_URL: &str = "https://api.bybit.com";
+
+/// Passivbot trades USDT-settled linear perpetuals.
+const CATEGORY: &str = "linear";
+const SETTLE_COIN: &str = "USDT";
+const RECV_WINDOW: &str = "5000";
+/// The history endpoints (closed PnL, executions) answer at most seven days
+/// per request.
+const HISTORY_WINDOW_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+const HISTORY_PAGE_LIMIT: usize = 100;
+
+/// Bybit v5 REST client acting with the bot's own API key.
+pub struct BybitTradingGateway {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl BybitTradingGateway {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into(),
+        }
+    }
+
+    /// Headers for a v5 private call. The signature covers
+    /// `timestamp + api_key + recv_window + (query string | JSON body)`.
+    fn auth_headers(bot: &Bot, payload: &str) -> Result<reqwest::header::HeaderMap, DomainError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| DomainError::Exchange(e.to_string()))?
+            .as_millis()
+            .to_string();
+        let mut mac = Hmac::<Sha256>::new_from_slice(bot.secret_key.as_bytes())
+            .map_err(|e| DomainError::Exchange(e.to_string()))?;
+        mac.update(format!("{timestamp}{}{RECV_WINDOW}{payload}", bot.api_key).as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in [
+            ("X-BAPI-API-KEY", bot.api_key.as_str()),
+            ("X-BAPI-TIMESTAMP", timestamp.as_str()),
+            ("X-BAPI-RECV-WINDOW", RECV_WINDOW),
+            ("X-BAPI-SIGN", signature.as_str()),
+        ] {
+            let value = value
+                .parse()
+                .map_err(|_| DomainError::Exchange(format!("invalid {name} header value")))?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+
+    async fn get(&self, bot: &Bot, path: &str, query: &str) -> Result<Value, DomainError> {
+        let res = self
+            .http
+            .get(format!("{}{path}?{query}", self.base_url))
+            .headers(Self::auth_headers(bot, query)?)
+            .send()
+            .await;
+        Self::result(res).await
+    }
+
+    /// Unsigned GET for public market data.
+    async fn get_public(&self, path: &str, query: &str) -> Result<Value, DomainError> {
+        let res = self
+            .http
+            .get(format!("{}{path}?{query}", self.base_url))
+            .send()
+            .await;
+        Self::result(res).await
+    }
+
+    async fn post(&self, bot: &Bot, path: &str, body: Value) -> Result<Value, DomainError> {
+        let body = body.to_string();
+        let res = self
+            .http
+            .post(format!("{}{path}", self.base_url))
+            .headers(Self::auth_headers(bot, &body)?)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await;
+        Self::result(res).await
+    }
+
+    /// Every entry of a current-state endpoint (positions, open orders,
+    /// instruments), following its cursor. Without a bot the call is public.
+    async fn list<T>(
+        &self,
+        bot: Option<&Bot>,
+        path: &str,
+        query: &str,
+        parse: fn(&Value) -> Option<T>,
+    ) -> Result<Vec<T>, DomainError> {
+        let mut entries = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut query = query.to_string();
+            if !cursor.is_empty() {
+                query.push_str(&format!("&cursor={cursor}"));
+            }
+            let result = match bot {
+                Some(bot) => self.get(bot, path, &query).await?,
+                None => self.get_public(path, &query).await?,
+            };
+            if let Some(list) = result.get("list").and_then(Value::as_array) {
+                entries.extend(list.iter().filter_map(parse));
+            }
+            cursor = result
+                .get("nextPageCursor")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string();
+            if cursor.is_empty() {
+                return Ok(entries);
+            }
+        }
+    }
+
+    /// Up to `limit` entries of a history endpoint between `since_ms` and
+    /// `until_ms`, newest first: walks back one seven-day window at a time and
+    /// follows each window's cursor.
+    #[allow(clippy::too_many_arguments)]
+    async fn history<T>(
+        &self,
+        bot: &Bot,
+        path: &str,
+        params: &str,
+        since_ms: i64,
+        until_ms: i64,
+        limit: usize,
+        parse: fn(&Value) -> Option<T>,
+    ) -> Result<Vec<T>, DomainError> {
+        let mut entries = Vec::new();
+        let mut end = until_ms;
+        while end >= since_ms && entries.len() < limit {
+            // Both bounds are inclusive, so the next window ends just before
+            // this one starts.
+            let start = (end - HISTORY_WINDOW_MS + 1).max(since_ms);
+            let mut cursor = String::new();
+            loop {
+                let mut query = format!(
+                    "category={CATEGORY}{params}&startTime={start}&endTime={end}&limit={HISTORY_PAGE_LIMIT}"
+                );
+                if !cursor.is_empty() {
+                    query.push_str(&format!("&cursor={cursor}"));
+                }
+                let result = self.get(bot, path, &query).await?;
+                if let Some(list) = result.get("list").and_then(Value::as_array) {
+                    entries.extend(list.iter().filter_map(parse));
+                }
+                cursor = result
+                    .get("nextPageCursor")
+                    .and_then(Value::as_str)
+                    .unwrap_or("")
+                    .to_string();
+                if cursor.is_empty() || entries.len() >= limit {
+                    break;
+                }
+            }
+            end = start - 1;
+        }
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Unwrap the v5 envelope: `retCode != 0` is an error even on HTTP 200.
+    async fn result(res: reqwest::Result<reqwest::Response>) -> Result<Value, DomainError> {
+        let res = res.map_err(|e| DomainError::Exchange(format!("request failed: {e}")))?;
+        let status = res.status();
+        let body: Value = res
+            .json()
+            .await
+            .map_err(|e| DomainError::Exchange(format!("HTTP {status}: unreadable body: {e}")))?;
+        match body.get("retCode").and_then(Value::as_i64) {
+            Some(0) => Ok(body.get("result").cloned().unwrap_or(Value::Null)),
+            code => Err(DomainError::Exchange(format!(
+                "HTTP {status}, retCode {}: {}",
+                code.map_or("?".to_string(), |c| c.to_string()),
+                body.get("retMsg").and_then(Value::as_str).unwrap_or("")
+            ))),
+        }
+    }
+}
+
+/// One entry of `/v5/position/list`. Flat slots (size 0, empty side) are
+/// reported by Bybit too and map to `None`.
+fn parse_position(v: &Value) -> Option<Position> {
+    let num = |key: &str| v.get(key)?.as_str()?.parse::<f64>().ok();
+    let side = match v.get("side")?.as_str()? {
+        "Buy" => PositionSide::Long,
+        "Sell" => PositionSide::Short,
+        _ => return None,
+    };
+    let size = num("size").filter(|s| *s > 0.0)?;
+    Some(Position {
+        symbol: v.get("symbol")?.as_str()?.to_string(),
+        side,
+        size,
+        entry_price: num("avgPrice").unwrap_or_default(),
+        mark_price: num("markPrice").unwrap_or_default(),
+        unrealized_pnl: num("unrealisedPnl").unwrap_or_default(),
+        liq_price: num("liqPrice").filter(|p| *p > 0.0),
+        position_idx: v.get("positionIdx")?.as_u64()? as u8,
+    })
+}
+
+/// One entry of `/v5/order/realtime`. Only resting orders are listed there,
+/// so no status filtering is needed.
+fn parse_order(v: &Value) -> Option<Order> {
+    let num = |key: &str| v.get(key)?.as_str()?.parse::<f64>().ok();
+    let side = match v.get("side")?.as_str()? {
+        "Buy" => OrderSide::Buy,
+        "Sell" => OrderSide::Sell,
+        _ => return None,
+    };
+    Some(Order {
+        order_id: v.get("orderId")?.as_str()?.to_string(),
+        symbol: v.get("symbol")?.as_str()?.to_string(),
+        side,
+        price: num("price").unwrap_or_default(),
+        qty: num("qty")?,
+        reduce_only: v
+            .get("reduceOnly")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+    })
+}
+
+/// One entry of `/v5/market/instruments-info`. Only USDT-settled perpetuals
+/// are kept: dated futures and USDC contracts are not what passivbot trades.
+fn parse_instrument(v: &Value) -> Option<Instrument> {
+    let str_at = |path: &[&str]| path.iter().try_fold(v, |node, key| node.get(key))?.as_str();
+    let num_at = |path: &[&str]| str_at(path)?.parse::<f64>().ok();
+    if str_at(&["contractType"]) != Some("LinearPerpetual")
+        || str_at(&["settleCoin"]) != Some(SETTLE_COIN)
+    {
+        return None;
+    }
+    Some(Instrument {
+        symbol: str_at(&["symbol"])?.to_string(),
+        base_coin: str_at(&["baseCoin"])?.to_string(),
+        status: str_at(&["status"])?.to_string(),
+        min_qty: num_at(&["lotSizeFilter", "minOrderQty"])?,
+        tick_size: num_at(&["priceFilter", "tickSize"])?,
+        max_leverage: num_at(&["leverageFilter", "maxLeverage"])?,
+    })
+}
+
+/// A Bybit millisecond timestamp (sent as a string) in unix seconds.
+fn millis(v: &Value, key: &str) -> Option<i64> {
+    Some(v.get(key)?.as_str()?.parse::<i64>().ok()? / 1000)
+}
+
+/// One entry of `/v5/position/closed-pnl`. Its `side` is the closing order's,
+/// so `Sell` closed a long.
+fn parse_closed_pnl(v: &Value) -> Option<ClosedPnl> {
+    let num = |key: &str| v.get(key)?.as_str()?.parse::<f64>().ok();
+    let side = match v.get("side")?.as_str()? {
+        "Sell" => PositionSide::Long,
+        "Buy" => PositionSide::Short,
+        _ => return None,
+    };
+    Some(ClosedPnl {
+        symbol: v.get("symbol")?.as_str()?.to_string(),
+        side,
+        qty: num("closedSize").or_else(|| num("qty"))?,
+        entry_price: num("avgEntryPrice").unwrap_or_default(),
+        exit_price: num("avgExitPrice").unwrap_or_default(),
+        pnl: num("closedPnl")?,
+        closed_at: millis(v, "updatedTime").or_else(|| millis(v, "createdTime"))?,
+    })
+}
+
+/// One entry of `/v5/execution/list`. Funding and delivery executions are
+/// not fills of the bot's orders and map to `None`.
+fn parse_fill(v: &Value) -> Option<Fill> {
+    let num = |key: &str| v.get(key)?.as_str()?.parse::<f64>().ok();
+    if v.get("execType").and_then(Value::as_str) != Some("Trade") {
+        return None;
+    }
+    let side = match v.get("side")?.as_str()? {
+        "Buy" => OrderSide::Buy,
+        "Sell" => OrderSide::Sell,
+        _ => return None,
+    };
+    Some(Fill {
+        symbol: v.get("symbol")?.as_str()?.to_string(),
+        side,
+        price: num("execPrice")?,
+        qty: num("execQty")?,
+        fee: num("execFee").unwrap_or_default(),
+        is_maker: v.get("isMaker").and_then(Value::as_bool).unwrap_or(false),
+        executed_at: millis(v, "execTime")?,
+    })
+}
+
+#[async_trait]
+impl ExchangeAccount for BybitTradingGateway {
+    async fn closed_pnl(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<ClosedPnl>, DomainError> {
+        self.history(
+            bot,
+            "/v5/position/closed-pnl",
+            "",
+            since * 1000,
+            until * 1000,
+            usize::MAX,
+            parse_closed_pnl,
+        )
+        .await
+    }
+
+    async fn fills(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+        limit: usize,
+    ) -> Result<Vec<Fill>, DomainError> {
+        self.history(
+            bot,
+            "/v5/execution/list",
+            "&execType=Trade",
+            since * 1000,
+            until * 1000,
+            limit,
+            parse_fill,
+        )
+        .await
+    }
+
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError> {
+        ExchangeTradingGateway::positions(self, bot).await
+    }
+
+    async fn equity(&self, bot: &Bot) -> Result<f64, DomainError> {
+        let result = self
+            .get(bot, "/v5/account/wallet-balance", "accountType=UNIFIED")
+            .await?;
+        result
+            .get("list")
+            .and_then(|l| l.get(0))
+            .and_then(|a| a.get("totalEquity"))
+            .and_then(Value::as_str)
+            .and_then(|e| e.parse::<f64>().ok())
+            .ok_or_else(|| DomainError::Exchange("wallet balance without totalEquity".into()))
+    }
+}
+
+#[async_trait]
+impl ExchangeTradingGateway for BybitTradingGateway {
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError> {
+        self.list(
+            Some(bot),
+            "/v5/position/list",
+            &format!("category={CATEGORY}&settleCoin={SETTLE_COIN}&limit=200"),
+            parse_position,
+        )
+        .await
+    }
+
+    async fn open_orders(&self, bot: &Bot) -> Result<Vec<Order>, DomainError> {
+        self.list(
+            Some(bot),
+            "/v5/order/realtime",
+            &format!("category={CATEGORY}&settleCoin={SETTLE_COIN}&limit=50"),
+            parse_order,
+        )
+        .await
+    }
+
+    async fn cancel_order(&self, bot: &Bot, order: &Order) -> Result<(), DomainError> {
+        self.post(
+            bot,
+            "/v5/order/cancel",
+            json!({
+                "category": CATEGORY,
+                "symbol": order.symbol,
+                "orderId": order.order_id,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn cancel_all_orders(&self, bot: &Bot) -> Result<(), DomainError> {
+        self.post(
+            bot,
+            "/v5/order/cancel-all",
+            json!({ "category": CATEGORY, "settleCoin": SETTLE_COIN }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn close_position(&self, bot: &Bot, position: &Position) -> Result<(), DomainError> {
+        // Bybit rejects a close whose positionIdx does not match the
+        // account's position mode, so echo back the one it reported.
+        let side = match position.side {
+            PositionSide::Long => "Sell",
+            PositionSide::Short => "Buy",
+        };
+        self.post(
+            bot,
+            "/v5/order/create",
+            json!({
+                "category": CATEGORY,
+                "symbol": position.symbol,
+                "side": side,
+                "orderType": "Market",
+                "qty": position.size.to_string(),
+                "reduceOnly": true,
+                "positionIdx": position.position_idx,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+}
+
+#[async_trait]
+impl InstrumentSource for BybitTradingGateway {
+    async fn instruments(&self) -> Result<Vec<Instrument>, DomainError> {
+        self.list(
+            None,
+            "/v5/market/instruments-info",
+            &format!("category={CATEGORY}&limit=1000"),
+            parse_instrument,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::{Bytes, Incoming};
+    use hyper::server::conn::http1;
+    use hyper::service::service_fn;
+    use hyper::{Request, Response};
+    use hyper_util::rt::TokioIo;
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    const CLOSED_PNL_PAGE1: &str = include_str!("../../tests/fixtures/bybit/closed_pnl_page1.json");
+    const CLOSED_PNL_PAGE2: &str = include_str!("../../tests/fixtures/bybit/closed_pnl_page2.json");
+    const EXECUTION_LIST: &str = include_str!("../../tests/fixtures/bybit/execution_list.json");
+    const INSTRUMENTS_INFO: &str = include_str!("../../tests/fixtures/bybit/instruments_info.json");
+    const ORDER_REALTIME_PAGE1: &str =
+        include_str!("../../tests/fixtures/bybit/order_realtime_page1.json");
+    const ORDER_REALTIME_PAGE2: &str =
+        include_str!("../../tests/fixtures/bybit/order_realtime_page2.json");
+    const POSITION_LIST: &str = include_str!("../../tests/fixtures/bybit/position_list.json");
+    const POSITION_LIST_ONE_WAY: &str =
+        include_str!("../../tests/fixtures/bybit/position_list_one_way.json");
+    const WALLET_BALANCE: &str = include_str!("../../tests/fixtures/bybit/wallet_balance.json");
+    const EMPTY_PAGE: &str =
+        r#"{"retCode":0,"retMsg":"OK","result":{"list":[],"nextPageCursor":""}}"#;
+
+    /// Unix seconds the fixtures are recorded against.
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 24 * 60 * 60;
+
+    /// A stand-in for the Bybit API on a local port. `route` answers each
+    /// request from its path and query; every `path?query` is recorded.
+    async fn stub(
+        route: impl Fn(&str, &str) -> &'static str + Send + Sync + 'static,
+    ) -> (BybitTradingGateway, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let route = Arc::new(route);
+        tokio::spawn({
+            let seen = seen.clone();
+            async move {
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    let (seen, route) = (seen.clone(), route.clone());
+                    tokio::spawn(async move {
+                        let service = service_fn(move |req: Request<Incoming>| {
+                            let (seen, route) = (seen.clone(), route.clone());
+                            async move {
+                                let path = req.uri().path().to_string();
+                                let query = req.uri().query().unwrap_or("").to_string();
+                                let sent = req.into_body().collect().await.unwrap().to_bytes();
+                                // POST bodies follow the URL after a space.
+                                let mut entry = format!("{path}?{query}");
+                                if !sent.is_empty() {
+                                    entry.push(' ');
+                                    entry.push_str(&String::from_utf8_lossy(&sent));
+                                }
+                                seen.lock().unwrap().push(entry);
+                                let body = route(&path, &query);
+                                Ok::<_, Infallible>(Response::new(Full::new(Bytes::from(body))))
+                            }
+                        });
+                        let _ = http1::Builder::new()
+                            .serve_connection(TokioIo::new(stream), service)
+                            .await;
+                    });
+                }
+            }
+        });
+        // No proxy: the stub is local and an ambient HTTP(S)_PROXY would
+        // swallow the requests.
+        let gateway = BybitTradingGateway {
+            http: reqwest::Client::builder().no_proxy().build().unwrap(),
+            base_url,
+        };
+        (gateway, seen)
+    }
+
+    fn bot() -> Bot {
+        Bot::create("u".into(), "bot-1".into(), "ak".into(), "sk".into(), 0)
+    }
+
+    #[tokio::test]
+    async fn closed_pnl_walks_back_by_window_and_follows_cursors() {
+        let newest_end = format!("endTime={}", NOW * 1000);
+        let (gateway, seen) = stub(move |_, query| {
+            if query.contains("cursor=") {
+                CLOSED_PNL_PAGE2
+            } else if query.contains(&newest_end) {
+                CLOSED_PNL_PAGE1
+            } else {
+                EMPTY_PAGE
+            }
+        })
+        .await;
+
+        let closed = gateway
+            .closed_pnl(&bot(), NOW - 10 * DAY, NOW)
+            .await
+            .unwrap();
+        assert_eq!(closed.len(), 3);
+        assert_eq!(
+            closed[0],
+            ClosedPnl {
+                symbol: "BTCUSDT".into(),
+                side: PositionSide::Long,
+                qty: 0.01,
+                entry_price: 36500.0,
+                exit_price: 37750.0,
+                pnl: 12.5,
+                closed_at: 1_699_999_000,
+            }
+        );
+        assert_eq!(closed[1].side, PositionSide::Short);
+        assert_eq!(closed[1].pnl, -4.25);
+        assert_eq!(closed[2].closed_at, 1_699_900_000);
+
+        let seen = seen.lock().unwrap().clone();
+        let window_start = NOW * 1000 - HISTORY_WINDOW_MS + 1;
+        assert_eq!(seen.len(), 3, "{seen:?}");
+        assert!(seen[0].starts_with("/v5/position/closed-pnl?category=linear"));
+        assert!(seen[0].contains(&format!("startTime={window_start}&endTime={}", NOW * 1000)));
+        assert!(seen[1].contains("&cursor=6a4d2f3b%3A1699990000000"));
+        assert!(seen[2].contains(&format!(
+            "startTime={}&endTime={}",
+            (NOW - 10 * DAY) * 1000,
+            window_start - 1
+        )));
+    }
+
+    #[tokio::test]
+    async fn fills_skip_funding_and_stop_at_the_limit() {
+        let newest_end = format!("endTime={}", NOW * 1000);
+        let (gateway, seen) = stub(move |_, query| {
+            if query.contains(&newest_end) {
+                EXECUTION_LIST
+            } else {
+                EMPTY_PAGE
+            }
+        })
+        .await;
+
+        let fills = gateway
+            .fills(&bot(), NOW - 30 * DAY, NOW, 10)
+            .await
+            .unwrap();
+        assert_eq!(fills.len(), 2, "the funding execution is not a fill");
+        assert_eq!(
+            fills[0],
+            Fill {
+                symbol: "BTCUSDT".into(),
+                side: OrderSide::Sell,
+                price: 37750.0,
+                qty: 0.01,
+                fee: 0.0755,
+                is_maker: true,
+                executed_at: 1_699_999_000,
+            }
+        );
+        assert!(seen.lock().unwrap()[0].contains("&execType=Trade&"));
+
+        seen.lock().unwrap().clear();
+        let fills = gateway.fills(&bot(), NOW - 30 * DAY, NOW, 1).await.unwrap();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(
+            seen.lock().unwrap().len(),
+            1,
+            "no older window is read once the limit is reached"
+        );
+    }
+
+    #[tokio::test]
+    async fn account_positions_skip_flat_slots() {
+        let (gateway, _) = stub(|_, _| POSITION_LIST).await;
+
+        let positions = ExchangeAccount::positions(&gateway, &bot()).await.unwrap();
+        assert_eq!(
+            positions,
+            vec![Position {
+                symbol: "BTCUSDT".into(),
+                side: PositionSide::Long,
+                size: 0.02,
+                entry_price: 37000.0,
+                mark_price: 37500.0,
+                unrealized_pnl: 10.0,
+                liq_price: Some(33500.0),
+                position_idx: 1,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn one_way_positions_close_with_position_idx_zero() {
+        let (gateway, seen) = stub(|path, _| match path {
+            "/v5/position/list" => POSITION_LIST_ONE_WAY,
+            _ => EMPTY_PAGE,
+        })
+        .await;
+
+        let positions = ExchangeAccount::positions(&gateway, &bot()).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].side, PositionSide::Short);
+        assert_eq!(positions[0].position_idx, 0);
+
+        gateway.close_position(&bot(), &positions[0]).await.unwrap();
+        let close = seen.lock().unwrap()[1].clone();
+        assert!(close.starts_with("/v5/order/create? "), "{close}");
+        assert!(close.contains(r#""positionIdx":0"#), "{close}");
+        assert!(close.contains(r#""side":"Buy""#), "{close}");
+        assert!(close.contains(r#""reduceOnly":true"#), "{close}");
+    }
+
+    #[tokio::test]
+    async fn open_orders_follow_the_cursor_and_cancel_by_id() {
+        let (gateway, seen) = stub(|path, query| match path {
+            "/v5/order/realtime" if query.contains("cursor=") => ORDER_REALTIME_PAGE2,
+            "/v5/order/realtime" => ORDER_REALTIME_PAGE1,
+            _ => EMPTY_PAGE,
+        })
+        .await;
+
+        let orders = gateway.open_orders(&bot()).await.unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(
+            orders[0],
+            Order {
+                order_id: "fd4300ae-7847-404e-b947-b46980a4d140".into(),
+                symbol: "BTCUSDT".into(),
+                side: OrderSide::Buy,
+                price: 36500.0,
+                qty: 0.01,
+                reduce_only: false,
+            }
+        );
+        assert!(orders[1].reduce_only);
+
+        gateway.cancel_order(&bot(), &orders[0]).await.unwrap();
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(
+            seen[..2],
+            [
+                "/v5/order/realtime?category=linear&settleCoin=USDT&limit=50".to_string(),
+                "/v5/order/realtime?category=linear&settleCoin=USDT&limit=50&cursor=page_args%3Dfd4300ae".to_string(),
+            ]
+        );
+        assert_eq!(seen.len(), 3, "{seen:?}");
+        assert!(seen[2].starts_with("/v5/order/cancel? "));
+        assert!(seen[2].contains(r#""orderId":"fd4300ae-7847-404e-b947-b46980a4d140""#));
+    }
+
+    #[tokio::test]
+    async fn instruments_keep_usdt_perpetuals_only() {
+        let (gateway, seen) = stub(|_, _| INSTRUMENTS_INFO).await;
+
+        let instruments = gateway.instruments().await.unwrap();
+        assert_eq!(
+            instruments,
+            vec![
+                Instrument {
+                    symbol: "BTCUSDT".into(),
+                    base_coin: "BTC".into(),
+                    status: "Trading".into(),
+                    min_qty: 0.001,
+                    tick_size: 0.1,
+                    max_leverage: 100.0,
+                },
+                Instrument {
+                    symbol: "1000PEPEUSDT".into(),
+                    base_coin: "1000PEPE".into(),
+                    status: "Trading".into(),
+                    min_qty: 100.0,
+                    tick_size: 0.0000001,
+                    max_leverage: 25.0,
+                },
+            ]
+        );
+        assert_eq!(
+            seen.lock().unwrap()[0],
+            "/v5/market/instruments-info?category=linear&limit=1000"
+        );
+    }
+
+    #[tokio::test]
+    async fn equity_is_the_unified_accounts_total() {
+        let (gateway, seen) = stub(|_, _| WALLET_BALANCE).await;
+
+        assert_eq!(gateway.equity(&bot()).await.unwrap(), 1523.75);
+        assert_eq!(
+            seen.lock().unwrap()[0],
+            "/v5/account/wallet-balance?accountType=UNIFIED"
+        );
+    }
+
+    #[tokio::test]
+    async fn an_error_envelope_is_an_error() {
+        let (gateway, _) =
+            stub(|_, _| r#"{"retCode":10003,"retMsg":"API key is invalid.","result":{}}"#).await;
+
+        let err = gateway
+            .closed_pnl(&bot(), NOW - DAY, NOW)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("retCode 10003: API key is invalid.")
+        );
+    }
+}
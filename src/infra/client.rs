@@ -59,16 +59,20 @@ pub async fn create_ecs_client(config: &EcsConfig) -> EcsClient {
     EcsClient::new(&aws_config)
 }
 
-pub async fn setup_ecs_with_configs(configs: &Configs) -> (EcsClient, String, String) {
-    let client = create_ecs_client(&configs.ecs).await;
+pub async fn setup_ecs_with_configs(ecs: &EcsConfig) -> (EcsClient, String, String) {
+    let client = create_ecs_client(ecs).await;
     (
         client,
-        configs.ecs.cluster_arn.clone(),
-        configs.ecs.td_passivbot_arn.clone(),
+        ecs.cluster_arn.clone(),
+        ecs.td_passivbot_arn.clone(),
     )
 }
 
 pub async fn setup_ecs() -> Result<(EcsClient, String, String)> {
     let configs: Configs = load_config().context("Failed to load configs")?;
-    Ok(setup_ecs_with_configs(&configs).await)
+    let ecs = configs
+        .ecs
+        .as_ref()
+        .context("ECS is not configured (APP__ECS__*)")?;
+    Ok(setup_ecs_with_configs(ecs).await)
 }
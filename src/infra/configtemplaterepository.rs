@@ -1,6 +1,7 @@
 use crate::domain::configtemplate::{ConfigTemplate, ConfigTemplateRepository};
 use async_trait::async_trait;
 use aws_sdk_s3::Client;
+use aws_sdk_s3::primitives::ByteStream;
 
 pub struct S3TemplateRepository {
     client: Client,
@@ -100,4 +101,24 @@ impl ConfigTemplateRepository for S3TemplateRepository {
             }
         }
     }
+
+    async fn save(&self, template: &ConfigTemplate) -> Result<(), String> {
+        let key = Self::template_key(&template.name);
+
+        // Stored as the bare config, like the templates `get` reads.
+        let json = serde_json::to_vec_pretty(&template.config_data)
+            .map_err(|e| format!("Failed to serialize template: {:?}", e))?;
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .body(ByteStream::from(json))
+            .content_type("application/json")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to save template to S3: {:?}", e))?;
+
+        Ok(())
+    }
 }
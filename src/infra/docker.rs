@@ -0,0 +1,473 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use bollard::Docker;
+use bollard::container::{
+    Config, InspectContainerOptions, ListContainersOptions, RemoveContainerOptions,
+    StopContainerOptions,
+};
+use bollard::errors::Error as DockerError;
+use bollard::models::{ContainerState, ContainerStateStatusEnum, EventMessage, HostConfig};
+use bollard::system::EventsOptions;
+use futures_util::{Stream, StreamExt};
+
+use crate::domain::resources::ResourceProfile;
+use crate::domain::restart::{ContainerExit, StopReport};
+use crate::telemetry::{CORRELATION_ID_ENV, CorrelationId, correlation_id};
+use crate::usecase::{LiveTask, TaskController, TaskInventory, TaskLiveness, TaskRunner};
+
+/// Labels stamped on every container the backend launches. `pbtb.cluster`
+/// stands in for the ECS cluster, so several deployments can share one Docker
+/// host; the user/bot/correlation labels carry what the ECS backend reads
+/// back from the container overrides.
+pub const LABEL_CLUSTER: &str = "pbtb.cluster";
+pub const LABEL_USER_ID: &str = "pbtb.user_id";
+pub const LABEL_BOT_ID: &str = "pbtb.bot_id";
+pub const LABEL_CORRELATION_ID: &str = "pbtb.correlation_id";
+
+/// Seconds a requested stop waits after SIGTERM before SIGKILL, matching the
+/// ECS default `stopTimeout`.
+const STOP_TIMEOUT_SECS: i64 = 30;
+
+/// Whether a lifecycle event reports the container running or stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockerTaskEventKind {
+    Running,
+    Stopped,
+}
+
+/// A container lifecycle event, shaped like the ECS Task State Change the
+/// Lambda consumes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DockerTaskEvent {
+    pub kind: DockerTaskEventKind,
+    pub task_id: String,
+    pub user_id: Option<String>,
+    pub bot_id: Option<String>,
+    /// The action that launched the container.
+    pub correlation_id: Option<CorrelationId>,
+    /// Epoch seconds the Docker engine stamped on the event.
+    pub at: i64,
+}
+
+impl DockerTaskEvent {
+    /// Map a Docker `start` / `die` container event; anything else is `None`.
+    /// Docker copies the container's labels into the actor attributes, which
+    /// is where the bot identity comes from.
+    pub fn from_message(msg: &EventMessage) -> Option<Self> {
+        let kind = match msg.action.as_deref()? {
+            "start" => DockerTaskEventKind::Running,
+            "die" => DockerTaskEventKind::Stopped,
+            _ => return None,
+        };
+        let actor = msg.actor.as_ref()?;
+        let attributes = actor.attributes.as_ref();
+        let label = |key: &str| attributes.and_then(|a| a.get(key)).cloned();
+        Some(Self {
+            kind,
+            task_id: actor.id.clone()?,
+            user_id: label(LABEL_USER_ID),
+            bot_id: label(LABEL_BOT_ID),
+            correlation_id: label(LABEL_CORRELATION_ID)
+                .as_deref()
+                .and_then(CorrelationId::parse),
+            at: msg.time.unwrap_or(0),
+        })
+    }
+}
+
+/// Build the STOPPED report for an exited container. A stop this backend was
+/// asked for is reported as `UserInitiated`, as ECS does for StopTask, so it
+/// is never restarted; an OOM kill surfaces as the container reason the
+/// classifier looks for.
+pub fn stop_report_for(
+    state: &ContainerState,
+    requested: bool,
+    main_container: &str,
+) -> StopReport {
+    let oom = state.oom_killed.unwrap_or(false);
+    let (stop_code, stopped_reason) = if requested {
+        ("UserInitiated", Some("Task stopped by user".to_string()))
+    } else {
+        (
+            "EssentialContainerExited",
+            state.error.clone().filter(|e| !e.is_empty()),
+        )
+    };
+    StopReport {
+        stop_code: Some(stop_code.to_string()),
+        stopped_reason,
+        main_container: main_container.to_string(),
+        containers: vec![ContainerExit {
+            name: main_container.to_string(),
+            exit_code: state.exit_code.and_then(|c| i32::try_from(c).ok()),
+            reason: oom.then(|| "OutOfMemoryError: container killed by the kernel".to_string()),
+        }],
+    }
+}
+
+/// Whether a container in `status` still counts as alive. As with ECS, only
+/// a terminal state is gone, so a container that is still shutting down is
+/// never raced by a replacement.
+fn is_alive(status: Option<ContainerStateStatusEnum>) -> bool {
+    !matches!(
+        status,
+        Some(
+            ContainerStateStatusEnum::EXITED
+                | ContainerStateStatusEnum::DEAD
+                | ContainerStateStatusEnum::REMOVING
+        )
+    )
+}
+
+fn is_not_found(e: &DockerError) -> bool {
+    matches!(
+        e,
+        DockerError::DockerResponseServerError {
+            status_code: 404,
+            ..
+        }
+    )
+}
+
+/// Compute backend on a local Docker engine: one container per bot task,
+/// launched from the passivbot image with the same environment contract as
+/// the image's `entrypoint.sh` (`BUCKET`, `USER_ID`, `BOT_ID`, plus whatever
+/// the S3 download needs). The task id is the container id, and the image
+/// takes the place of the ECS task definition.
+pub struct DockerTaskBackend {
+    docker: Docker,
+    /// `NAME=value` pairs set on every container before `USER_ID`/`BOT_ID`.
+    env: Vec<String>,
+    network: Option<String>,
+    /// Containers this backend was asked to stop whose exit is not yet
+    /// reported.
+    requested_stops: Mutex<HashSet<String>>,
+}
+
+impl DockerTaskBackend {
+    pub fn new(docker: Docker, env: Vec<String>, network: Option<String>) -> Self {
+        Self {
+            docker,
+            env,
+            network,
+            requested_stops: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Connect through the local socket (`DOCKER_HOST` is honoured).
+    pub fn connect_local(env: Vec<String>, network: Option<String>) -> Result<Self> {
+        let docker = Docker::connect_with_local_defaults().context("docker connect failed")?;
+        Ok(Self::new(docker, env, network))
+    }
+
+    fn cluster_filter(cluster: &str) -> HashMap<String, Vec<String>> {
+        HashMap::from([(
+            "label".to_string(),
+            vec![format!("{LABEL_CLUSTER}={cluster}")],
+        )])
+    }
+
+    /// Lifecycle events of this cluster's containers, as the engine emits
+    /// them. The stream ends (or errors) when the engine connection drops;
+    /// events missed while disconnected are not replayed.
+    pub fn task_events(&self, cluster: &str) -> impl Stream<Item = Result<DockerTaskEvent>> + '_ {
+        let mut filters = Self::cluster_filter(cluster);
+        filters.insert("type".to_string(), vec!["container".to_string()]);
+        filters.insert(
+            "event".to_string(),
+            vec!["start".to_string(), "die".to_string()],
+        );
+        self.docker
+            .events(Some(EventsOptions::<String> {
+                filters,
+                ..Default::default()
+            }))
+            .filter_map(|msg| async move {
+                match msg {
+                    Ok(msg) => DockerTaskEvent::from_message(&msg).map(Ok),
+                    Err(e) => Some(Err(anyhow!(e).context("docker events stream failed"))),
+                }
+            })
+    }
+
+    /// The STOPPED report for an exited container (see `stop_report_for`).
+    pub async fn stop_report(&self, task_id: &str, main_container: &str) -> Result<StopReport> {
+        let info = self
+            .docker
+            .inspect_container(task_id, None::<InspectContainerOptions>)
+            .await
+            .context("docker inspect_container failed")?;
+        let requested = self.requested_stops.lock().unwrap().remove(task_id);
+        Ok(stop_report_for(
+            &info.state.unwrap_or_default(),
+            requested,
+            main_container,
+        ))
+    }
+
+    /// Delete a stopped container once its exit has been reconciled. An
+    /// already-removed container is fine.
+    pub async fn remove(&self, task_id: &str) -> Result<()> {
+        match self
+            .docker
+            .remove_container(
+                task_id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(anyhow!(e).context("docker remove_container failed")),
+        }
+    }
+}
+
+#[async_trait]
+impl TaskRunner for DockerTaskBackend {
+    async fn run(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        cluster_arn: &str,
+        td_arn: &str,
+        container_name: &str,
+        resources: ResourceProfile,
+    ) -> Result<String> {
+        let correlation_id = correlation_id().unwrap_or_default();
+        let mut env = self.env.clone();
+        env.push(format!("USER_ID={user_id}"));
+        env.push(format!("BOT_ID={bot_id}"));
+        env.push(format!("{CORRELATION_ID_ENV}={correlation_id}"));
+        let labels = HashMap::from([
+            (LABEL_CLUSTER.to_string(), cluster_arn.to_string()),
+            (LABEL_USER_ID.to_string(), user_id.to_string()),
+            (LABEL_BOT_ID.to_string(), bot_id.to_string()),
+            (LABEL_CORRELATION_ID.to_string(), correlation_id.to_string()),
+        ]);
+        // Swap equal to memory: the limit is hard, as on ECS, so an over-limit
+        // bot is OOM-killed rather than slowed down.
+        let memory = i64::from(resources.memory_mib()) * 1024 * 1024;
+        let host_config = HostConfig {
+            memory: Some(memory),
+            memory_swap: Some(memory),
+            nano_cpus: Some(i64::from(resources.cpu()) * 1_000_000_000 / 1024),
+            network_mode: self.network.clone(),
+            ..Default::default()
+        };
+
+        let created = self
+            .docker
+            .create_container::<String, String>(
+                None,
+                Config {
+                    image: Some(td_arn.to_string()),
+                    env: Some(env),
+                    labels: Some(labels),
+                    host_config: Some(host_config),
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("docker create_container failed")?;
+        if let Err(e) = self
+            .docker
+            .start_container::<String>(&created.id, None)
+            .await
+        {
+            // Never leave a created-but-unstarted container behind: the
+            // inventory would count it as alive.
+            if let Err(re) = self.remove(&created.id).await {
+                tracing::warn!(
+                    "failed to remove unstarted container {}: {re:#}",
+                    created.id
+                );
+            }
+            return Err(anyhow!(e).context("docker start_container failed"));
+        }
+
+        tracing::info!(
+            "docker run done: container_id={}, image={}, container={}, resources={}",
+            created.id,
+            td_arn,
+            container_name,
+            resources
+        );
+        Ok(created.id)
+    }
+}
+
+#[async_trait]
+impl TaskController for DockerTaskBackend {
+    async fn stop(&self, _cluster_arn: &str, task_id: &str, reason: &str) -> Result<()> {
+        // Marked before stopping: the `die` event can arrive before
+        // stop_container returns.
+        self.requested_stops
+            .lock()
+            .unwrap()
+            .insert(task_id.to_string());
+        match self
+            .docker
+            .stop_container(
+                task_id,
+                Some(StopContainerOptions {
+                    t: STOP_TIMEOUT_SECS,
+                }),
+            )
+            .await
+        {
+            Ok(()) => {}
+            // Already stopped (304) or already gone (404): nothing to stop.
+            Err(DockerError::DockerResponseServerError {
+                status_code: 304 | 404,
+                ..
+            }) => {}
+            Err(e) => {
+                self.requested_stops.lock().unwrap().remove(task_id);
+                return Err(anyhow!(e).context("docker stop_container failed"));
+            }
+        }
+        tracing::info!("docker stop issued: container_id={task_id}, reason={reason}");
+        Ok(())
+    }
+
+    async fn liveness(&self, _cluster_arn: &str, task_id: &str) -> Result<TaskLiveness> {
+        match self
+            .docker
+            .inspect_container(task_id, None::<InspectContainerOptions>)
+            .await
+        {
+            Ok(info) => Ok(if is_alive(info.state.and_then(|s| s.status)) {
+                TaskLiveness::Alive
+            } else {
+                TaskLiveness::Gone
+            }),
+            Err(e) if is_not_found(&e) => Ok(TaskLiveness::Gone),
+            Err(e) => Err(anyhow!(e).context("docker inspect_container failed")),
+        }
+    }
+}
+
+#[async_trait]
+impl TaskInventory for DockerTaskBackend {
+    async fn list_running(&self, cluster_arn: &str) -> Result<Vec<LiveTask>> {
+        let containers = self
+            .docker
+            .list_containers(Some(ListContainersOptions::<String> {
+                all: true,
+                filters: Self::cluster_filter(cluster_arn),
+                ..Default::default()
+            }))
+            .await
+            .context("docker list_containers failed")?;
+        Ok(containers
+            .into_iter()
+            .filter(|c| !matches!(c.state.as_deref(), Some("exited" | "dead" | "removing")))
+            .filter_map(|c| {
+                let labels = c.labels.unwrap_or_default();
+                Some(LiveTask {
+                    task_id: c.id?,
+                    user_id: labels.get(LABEL_USER_ID).cloned(),
+                    bot_id: labels.get(LABEL_BOT_ID).cloned(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::restart::StopCause;
+    use bollard::models::EventActor;
+
+    fn event(action: &str, labels: &[(&str, &str)]) -> EventMessage {
+        EventMessage {
+            action: Some(action.to_string()),
+            actor: Some(EventActor {
+                id: Some("c0ffee".to_string()),
+                attributes: Some(
+                    labels
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                ),
+            }),
+            time: Some(1_700_000_000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn start_and_die_events_map_to_running_and_stopped() {
+        let labels = [(LABEL_USER_ID, "u"), (LABEL_BOT_ID, "b"), ("exitCode", "1")];
+        let running = DockerTaskEvent::from_message(&event("start", &labels)).unwrap();
+        assert_eq!(
+            running,
+            DockerTaskEvent {
+                kind: DockerTaskEventKind::Running,
+                task_id: "c0ffee".to_string(),
+                user_id: Some("u".to_string()),
+                bot_id: Some("b".to_string()),
+                correlation_id: None,
+                at: 1_700_000_000,
+            }
+        );
+        let stopped = DockerTaskEvent::from_message(&event("die", &labels)).unwrap();
+        assert_eq!(stopped.kind, DockerTaskEventKind::Stopped);
+
+        assert_eq!(DockerTaskEvent::from_message(&event("oom", &labels)), None);
+        let unlabelled = DockerTaskEvent::from_message(&event("start", &[])).unwrap();
+        assert_eq!((unlabelled.user_id, unlabelled.bot_id), (None, None));
+    }
+
+    #[test]
+    fn stop_reports_classify_like_their_ecs_counterparts() {
+        let state = |exit_code: i64, oom_killed: bool| ContainerState {
+            exit_code: Some(exit_code),
+            oom_killed: Some(oom_killed),
+            ..Default::default()
+        };
+        let cases = [
+            (state(137, true), false, StopCause::OutOfMemory),
+            (
+                state(1, false),
+                false,
+                StopCause::ProcessFailed { exit_code: 1 },
+            ),
+            (state(0, false), false, StopCause::Exited),
+            // SIGKILL after the stop timeout still exits 137.
+            (state(137, false), true, StopCause::UserInitiated),
+        ];
+        for (state, requested, expected) in cases {
+            let report = stop_report_for(&state, requested, "passivbot");
+            assert_eq!(report.cause(), expected, "{state:?} requested={requested}");
+            assert_eq!(report.main().map(|c| c.name.as_str()), Some("passivbot"));
+        }
+    }
+
+    #[test]
+    fn only_terminal_states_are_gone() {
+        for status in [
+            ContainerStateStatusEnum::CREATED,
+            ContainerStateStatusEnum::RUNNING,
+            ContainerStateStatusEnum::RESTARTING,
+            ContainerStateStatusEnum::PAUSED,
+        ] {
+            assert!(is_alive(Some(status)), "{status:?}");
+        }
+        for status in [
+            ContainerStateStatusEnum::EXITED,
+            ContainerStateStatusEnum::DEAD,
+            ContainerStateStatusEnum::REMOVING,
+        ] {
+            assert!(!is_alive(Some(status)), "{status:?}");
+        }
+    }
+}
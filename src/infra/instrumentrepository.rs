@@ -0,0 +1,83 @@
+use crate::domain::error::DomainError;
+use crate::domain::exchange::Exchange;
+use crate::domain::instrument::{InstrumentCatalog, InstrumentCatalogRepository};
+use async_trait::async_trait;
+use aws_sdk_s3::Client;
+use aws_sdk_s3::primitives::ByteStream;
+
+/// The instrument catalog as one JSON object per exchange in the config
+/// bucket. Outside the per-user prefixes, which are Telegram user ids.
+pub struct S3InstrumentCatalogRepository {
+    client: Client,
+    bucket_name: String,
+    exchange: Exchange,
+}
+
+impl S3InstrumentCatalogRepository {
+    pub fn new(client: Client, bucket_name: String) -> Self {
+        Self {
+            client,
+            bucket_name,
+            exchange: Exchange::Bybit,
+        }
+    }
+
+    fn catalog_key(exchange: &Exchange) -> String {
+        format!("instruments/{}.json", exchange.as_str())
+    }
+}
+
+#[async_trait]
+impl InstrumentCatalogRepository for S3InstrumentCatalogRepository {
+    async fn load(&self) -> Result<Option<InstrumentCatalog>, DomainError> {
+        let result = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(Self::catalog_key(&self.exchange))
+            .send()
+            .await;
+        let object = match result {
+            Ok(object) => object,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                return Ok(None);
+            }
+            Err(e) => {
+                return Err(DomainError::Repository(format!(
+                    "failed to get the instrument catalog from S3: {e:?}"
+                )));
+            }
+        };
+        let bytes = object
+            .body
+            .collect()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!("failed to read the instrument catalog: {e:?}"))
+            })?
+            .into_bytes();
+        serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            DomainError::Repository(format!("failed to parse the instrument catalog: {e}"))
+        })
+    }
+
+    async fn save(&self, catalog: &InstrumentCatalog) -> Result<(), DomainError> {
+        let body = serde_json::to_vec(catalog).map_err(|e| {
+            DomainError::Repository(format!("failed to serialize the instrument catalog: {e}"))
+        })?;
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(Self::catalog_key(&catalog.exchange))
+            .body(ByteStream::from(body))
+            .content_type("application/json")
+            .send()
+            .await
+            .map_err(|e| {
+                DomainError::Repository(format!(
+                    "failed to save the instrument catalog to S3: {e:?}"
+                ))
+            })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1273 @@
+use crate::domain::account::{ClosedPnl, ExchangeAccount, Fill};
+use crate::domain::apitoken::{ApiToken, ApiTokenRepository};
+use crate::domain::bot::{ApiKeyRepository, Bot, BotRepository};
+use crate::domain::botconfig::{BotConfig, BotConfigRepository};
+use crate::domain::configtemplate::{ConfigTemplate, ConfigTemplateRepository};
+use crate::domain::error::DomainError;
+use crate::domain::history::{BotEvent, BotHistoryRepository};
+use crate::domain::instrument::{InstrumentCatalog, InstrumentCatalogRepository};
+use crate::domain::notify::Notifier;
+use crate::domain::preferences::{UserPreferences, UserPreferencesRepository};
+use crate::domain::resources::ResourceProfile;
+use crate::domain::restart::{ContainerExit, StopReport};
+use crate::domain::riskguard::{RiskGuard, RiskGuardRepository};
+use crate::domain::runtime::{
+    BotRuntime, BotRuntimeRepository, RuntimePhase, RuntimeScanRepository, StartClaim,
+    StartLockRepository,
+};
+use crate::domain::schedule::{Schedule, ScheduleRepository};
+use crate::domain::trading::{ExchangeTradingGateway, Order, Position};
+use crate::telemetry::{CorrelationId, correlation_id};
+use crate::usecase::{LiveTask, TaskController, TaskInventory, TaskLiveness, TaskRunner};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+type BotKey = (String, String);
+
+fn key(user_id: &str, bot_id: &str) -> BotKey {
+    (user_id.to_string(), bot_id.to_string())
+}
+
+/// The runtime row's `task_id` attribute as DynamoDB stores it: an empty
+/// string while no task is attached.
+fn task_attr(task_id: &Option<String>) -> &str {
+    task_id.as_deref().unwrap_or("")
+}
+
+#[derive(Default)]
+struct Table {
+    bots: BTreeMap<BotKey, Bot>,
+    runtimes: BTreeMap<BotKey, BotRuntime>,
+    /// Keyed like the event row's sort key, so a redelivered event overwrites.
+    events: BTreeMap<(String, String, i64, &'static str), BotEvent>,
+    /// Keyed by (user_id, schedule_id).
+    schedules: BTreeMap<BotKey, Schedule>,
+    /// Token lookup rows, keyed by token hash.
+    api_tokens: BTreeMap<String, ApiToken>,
+    /// Token user rows: user_id -> token hash.
+    api_token_users: BTreeMap<String, String>,
+    /// Preferences rows, keyed by user_id.
+    preferences: BTreeMap<String, UserPreferences>,
+    guards: BTreeMap<BotKey, RiskGuard>,
+    /// The bot row's `resized_after_task` attribute: the task whose OOM stop
+    /// last promoted the bot. A whole-bot save drops it, as the put does.
+    resized_after_task: BTreeMap<BotKey, String>,
+}
+
+/// In-memory stand-in for `DynamoBotRepository`: the bot, runtime, start-lock,
+/// schedule, history, API token, preferences and guard rows of one table. Every conditional write applies the same
+/// condition as the DynamoDB expression it replaces, under one lock, so a
+/// scenario sees the real races' outcomes without a database.
+#[derive(Default)]
+pub struct InMemoryBotRepository {
+    table: Mutex<Table>,
+}
+
+impl InMemoryBotRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every recorded event of a bot, oldest first.
+    pub fn history(&self, user_id: &str, bot_id: &str) -> Vec<BotEvent> {
+        self.table
+            .lock()
+            .unwrap()
+            .events
+            .values()
+            .filter(|e| e.user_id == user_id && e.bot_id == bot_id)
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BotRepository for InMemoryBotRepository {
+    async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .bots
+            .get(&key(user_id, bot_id))
+            .cloned())
+    }
+
+    async fn save(&self, bot: &Bot) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let k = key(&bot.user_id, &bot.id);
+        table.resized_after_task.remove(&k);
+        table.bots.insert(k, bot.clone());
+        Ok(())
+    }
+
+    async fn promote_resource_profile(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        from: ResourceProfile,
+        to: ResourceProfile,
+        stopped_task_id: &str,
+        now: i64,
+    ) -> Result<bool, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let table = &mut *table;
+        let k = key(user_id, bot_id);
+        // `attribute_exists(pk)`: a deleted bot stays deleted.
+        let Some(bot) = table.bots.get_mut(&k) else {
+            return Ok(false);
+        };
+        let promoted =
+            table.resized_after_task.get(&k).map(String::as_str) == Some(stopped_task_id);
+        if bot.resource_profile != from || promoted {
+            return Ok(false);
+        }
+        bot.set_resource_profile(to, now);
+        table
+            .resized_after_task
+            .insert(k, stopped_task_id.to_string());
+        Ok(true)
+    }
+
+    async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .bots
+            .values()
+            .filter(|b| b.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String> {
+        let mut table = self.table.lock().unwrap();
+        table.resized_after_task.remove(&key(user_id, bot_id));
+        table.bots.remove(&key(user_id, bot_id));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BotRuntimeRepository for InMemoryBotRepository {
+    async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<BotRuntime>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .runtimes
+            .get(&key(user_id, bot_id))
+            .cloned())
+    }
+
+    async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .runtimes
+            .values()
+            .filter(|r| r.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let row_key = key(&runtime.user_id, &runtime.bot_id);
+        let current = table.runtimes.get(&row_key);
+        let observed_at = runtime.observed_at;
+        // The per-phase conditions of `DynamoBotRepository::record`.
+        let wins = match runtime.phase {
+            RuntimePhase::Stopped => current
+                .is_none_or(|c| c.observed_at <= observed_at || c.phase == RuntimePhase::Stopping),
+            RuntimePhase::Running => current.is_none_or(|c| {
+                c.observed_at < observed_at
+                    || (c.observed_at == observed_at
+                        && c.phase != RuntimePhase::Stopped
+                        && c.phase != RuntimePhase::Stopping)
+            }),
+            RuntimePhase::Stopping => current.is_some_and(|c| {
+                matches!(c.phase, RuntimePhase::Running | RuntimePhase::Starting)
+                    && task_attr(&c.task_id) == task_attr(&runtime.task_id)
+            }),
+            RuntimePhase::Starting => current.is_none_or(|c| c.observed_at < observed_at),
+        };
+        if wins {
+            table.runtimes.insert(row_key, runtime.clone());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RuntimeScanRepository for InMemoryBotRepository {
+    async fn scan_all(&self) -> Result<Vec<BotRuntime>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .runtimes
+            .values()
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StartLockRepository for InMemoryBotRepository {
+    async fn try_acquire_start(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        now: i64,
+        stale_after: i64,
+    ) -> Result<StartClaim, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let row_key = key(user_id, bot_id);
+        let stale_cutoff = now - stale_after;
+        let version = match table.runtimes.get(&row_key) {
+            Some(row) => match row.phase {
+                RuntimePhase::Running => return Ok(StartClaim::AlreadyRunning),
+                RuntimePhase::Starting if row.observed_at > stale_cutoff => {
+                    return Ok(StartClaim::AlreadyStarting);
+                }
+                RuntimePhase::Stopping if row.observed_at > stale_cutoff => {
+                    return Ok(StartClaim::AlreadyStopping);
+                }
+                _ => row.version,
+            },
+            None => 0,
+        };
+        let mut claimed =
+            BotRuntime::stopped(user_id.to_string(), bot_id.to_string(), version, now);
+        claimed.phase = RuntimePhase::Starting;
+        table.runtimes.insert(row_key, claimed);
+        Ok(StartClaim::Acquired)
+    }
+
+    async fn try_acquire_restart(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        stopped_task_id: &str,
+        attempt: u32,
+        now: i64,
+    ) -> Result<StartClaim, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let Some(row) = table.runtimes.get_mut(&key(user_id, bot_id)) else {
+            return Ok(StartClaim::AlreadyStarting);
+        };
+        if task_attr(&row.task_id) != stopped_task_id
+            || !matches!(row.phase, RuntimePhase::Running | RuntimePhase::Starting)
+        {
+            return Ok(StartClaim::AlreadyStarting);
+        }
+        row.phase = RuntimePhase::Starting;
+        row.observed_at = now;
+        row.task_id = None;
+        row.version += 1;
+        row.restart_attempts = attempt;
+        Ok(StartClaim::Acquired)
+    }
+
+    async fn attach_started_task(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        task_id: &str,
+    ) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        if let Some(row) = table.runtimes.get_mut(&key(user_id, bot_id))
+            && row.phase == RuntimePhase::Starting
+        {
+            row.task_id = Some(task_id.to_string());
+        }
+        Ok(())
+    }
+
+    async fn release_start(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        now: i64,
+    ) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        if let Some(row) = table.runtimes.get_mut(&key(user_id, bot_id))
+            && row.phase == RuntimePhase::Starting
+        {
+            row.phase = RuntimePhase::Stopped;
+            row.observed_at = now;
+            row.task_id = None;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BotHistoryRepository for InMemoryBotRepository {
+    async fn append(&self, event: &BotEvent) -> Result<(), DomainError> {
+        self.table.lock().unwrap().events.insert(
+            (
+                event.user_id.clone(),
+                event.bot_id.clone(),
+                event.at,
+                event.kind.as_str(),
+            ),
+            event.clone(),
+        );
+        Ok(())
+    }
+
+    async fn recent(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        limit: usize,
+    ) -> Result<Vec<BotEvent>, DomainError> {
+        let mut events = self.history(user_id, bot_id);
+        events.reverse();
+        events.truncate(limit);
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl ScheduleRepository for InMemoryBotRepository {
+    async fn save(&self, schedule: &Schedule) -> Result<(), DomainError> {
+        self.table
+            .lock()
+            .unwrap()
+            .schedules
+            .insert(key(&schedule.user_id, &schedule.id), schedule.clone());
+        Ok(())
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<Schedule>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .schedules
+            .values()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete(&self, user_id: &str, schedule_id: &str) -> Result<bool, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .schedules
+            .remove(&key(user_id, schedule_id))
+            .is_some())
+    }
+
+    async fn find_due(&self, now: i64) -> Result<Vec<Schedule>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .schedules
+            .values()
+            .filter(|s| s.is_due(now))
+            .cloned()
+            .collect())
+    }
+
+    async fn claim_run(
+        &self,
+        schedule: &Schedule,
+        expected_next_run_at: i64,
+    ) -> Result<bool, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        match table
+            .schedules
+            .get_mut(&key(&schedule.user_id, &schedule.id))
+        {
+            Some(stored) if stored.next_run_at == expected_next_run_at => {
+                stored.next_run_at = schedule.next_run_at;
+                stored.last_run_at = schedule.last_run_at;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn record_result(
+        &self,
+        user_id: &str,
+        schedule_id: &str,
+        result: &str,
+    ) -> Result<(), DomainError> {
+        if let Some(stored) = self
+            .table
+            .lock()
+            .unwrap()
+            .schedules
+            .get_mut(&key(user_id, schedule_id))
+        {
+            stored.last_result = Some(result.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserPreferencesRepository for InMemoryBotRepository {
+    async fn find(&self, user_id: &str) -> Result<Option<UserPreferences>, DomainError> {
+        Ok(self.table.lock().unwrap().preferences.get(user_id).cloned())
+    }
+
+    async fn save(&self, prefs: &UserPreferences) -> Result<(), DomainError> {
+        self.table
+            .lock()
+            .unwrap()
+            .preferences
+            .insert(prefs.user_id.clone(), prefs.clone());
+        Ok(())
+    }
+
+    async fn find_due_digests(&self, now: i64) -> Result<Vec<UserPreferences>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .preferences
+            .values()
+            .filter(|p| p.is_digest_due(now))
+            .cloned()
+            .collect())
+    }
+
+    async fn claim_digest(
+        &self,
+        prefs: &UserPreferences,
+        expected_next_digest_at: i64,
+    ) -> Result<bool, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        match table.preferences.get_mut(&prefs.user_id) {
+            Some(stored) if stored.next_digest_at == Some(expected_next_digest_at) => {
+                stored.next_digest_at = prefs.next_digest_at;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn record_digest_equity(
+        &self,
+        user_id: &str,
+        equity: &BTreeMap<String, f64>,
+    ) -> Result<(), DomainError> {
+        if let Some(stored) = self.table.lock().unwrap().preferences.get_mut(user_id) {
+            stored.digest_equity = equity.clone();
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RiskGuardRepository for InMemoryBotRepository {
+    async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<RiskGuard>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .guards
+            .get(&key(user_id, bot_id))
+            .cloned())
+    }
+
+    async fn find_all(&self) -> Result<Vec<RiskGuard>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .guards
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn save_limits(&self, guard: &RiskGuard) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        let stored = table
+            .guards
+            .entry(key(&guard.user_id, &guard.bot_id))
+            .or_insert_with(|| {
+                RiskGuard::new(
+                    guard.user_id.clone(),
+                    guard.bot_id.clone(),
+                    guard.updated_at,
+                )
+            });
+        stored.max_daily_loss_pct = guard.max_daily_loss_pct;
+        stored.max_drawdown_pct = guard.max_drawdown_pct;
+        stored.updated_at = guard.updated_at;
+        Ok(())
+    }
+
+    async fn record_observation(&self, guard: &RiskGuard) -> Result<bool, DomainError> {
+        let mut table = self.table.lock().unwrap();
+        match table.guards.get_mut(&key(&guard.user_id, &guard.bot_id)) {
+            Some(stored) if stored.tripped_at.is_none() => {
+                stored.high_water_mark = guard.high_water_mark;
+                stored.day_start_equity = guard.day_start_equity;
+                stored.day = guard.day;
+                stored.updated_at = guard.updated_at;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn claim_trip(&self, guard: &RiskGuard) -> Result<bool, DomainError> {
+        if guard.tripped_at.is_none() || guard.trip_reason.is_none() {
+            return Ok(false);
+        }
+        let mut table = self.table.lock().unwrap();
+        match table.guards.get_mut(&key(&guard.user_id, &guard.bot_id)) {
+            Some(stored) if stored.tripped_at.is_none() => {
+                stored.high_water_mark = guard.high_water_mark;
+                stored.day_start_equity = guard.day_start_equity;
+                stored.day = guard.day;
+                stored.tripped_at = guard.tripped_at;
+                stored.trip_reason = guard.trip_reason.clone();
+                stored.restart_pending = guard.restart_pending;
+                stored.updated_at = guard.updated_at;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn rearm(&self, guard: &RiskGuard) -> Result<(), DomainError> {
+        if let Some(stored) = self
+            .table
+            .lock()
+            .unwrap()
+            .guards
+            .get_mut(&key(&guard.user_id, &guard.bot_id))
+        {
+            stored.rearm(guard.updated_at);
+        }
+        Ok(())
+    }
+
+    async fn set_restart_pending(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        pending: bool,
+    ) -> Result<(), DomainError> {
+        if let Some(stored) = self
+            .table
+            .lock()
+            .unwrap()
+            .guards
+            .get_mut(&key(user_id, bot_id))
+        {
+            stored.restart_pending = pending;
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), DomainError> {
+        self.table
+            .lock()
+            .unwrap()
+            .guards
+            .remove(&key(user_id, bot_id));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepository for InMemoryBotRepository {
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, DomainError> {
+        Ok(self
+            .table
+            .lock()
+            .unwrap()
+            .api_tokens
+            .get(token_hash)
+            .cloned())
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Option<ApiToken>, DomainError> {
+        let table = self.table.lock().unwrap();
+        Ok(table
+            .api_token_users
+            .get(user_id)
+            .and_then(|hash| table.api_tokens.get(hash))
+            .cloned())
+    }
+
+    async fn save(&self, token: &ApiToken) -> Result<(), DomainError> {
+        // Like the transaction: the user row is overwritten, but the lookup row
+        // of a previous token stays valid until that token is deleted.
+        let mut table = self.table.lock().unwrap();
+        table
+            .api_tokens
+            .insert(token.token_hash.clone(), token.clone());
+        table
+            .api_token_users
+            .insert(token.user_id.clone(), token.token_hash.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, token: &ApiToken) -> Result<(), DomainError> {
+        let mut table = self.table.lock().unwrap();
+        table.api_tokens.remove(&token.token_hash);
+        table.api_token_users.remove(&token.user_id);
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for `S3BotConfigRepository`.
+#[derive(Default)]
+pub struct InMemoryBotConfigRepository {
+    configs: Mutex<HashMap<BotKey, BotConfig>>,
+}
+
+impl InMemoryBotConfigRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BotConfigRepository for InMemoryBotConfigRepository {
+    async fn get(&self, user_id: &str, bot_id: &str) -> Result<BotConfig, String> {
+        self.configs
+            .lock()
+            .unwrap()
+            .get(&key(user_id, bot_id))
+            .cloned()
+            .ok_or_else(|| format!("Failed to get bot config: no config for {user_id}/{bot_id}"))
+    }
+
+    async fn save(&self, config: &BotConfig) -> Result<(), String> {
+        self.configs
+            .lock()
+            .unwrap()
+            .insert(key(&config.user_id, &config.bot_id), config.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String> {
+        self.configs.lock().unwrap().remove(&key(user_id, bot_id));
+        Ok(())
+    }
+
+    async fn exists(&self, user_id: &str, bot_id: &str) -> Result<bool, String> {
+        Ok(self
+            .configs
+            .lock()
+            .unwrap()
+            .contains_key(&key(user_id, bot_id)))
+    }
+}
+
+/// In-memory stand-in for `S3TemplateRepository`, seeded up front.
+pub struct InMemoryTemplateRepository {
+    templates: Mutex<BTreeMap<String, ConfigTemplate>>,
+}
+
+impl InMemoryTemplateRepository {
+    pub fn new(templates: Vec<ConfigTemplate>) -> Self {
+        Self {
+            templates: Mutex::new(templates.into_iter().map(|t| (t.name.clone(), t)).collect()),
+        }
+    }
+}
+
+#[async_trait]
+impl ConfigTemplateRepository for InMemoryTemplateRepository {
+    async fn get(&self, template_name: &str) -> Result<ConfigTemplate, String> {
+        self.templates
+            .lock()
+            .unwrap()
+            .get(template_name)
+            .cloned()
+            .ok_or_else(|| format!("Failed to get template: no template named {template_name}"))
+    }
+
+    async fn list(&self) -> Result<Vec<String>, String> {
+        Ok(self.templates.lock().unwrap().keys().cloned().collect())
+    }
+
+    async fn exists(&self, template_name: &str) -> Result<bool, String> {
+        Ok(self.templates.lock().unwrap().contains_key(template_name))
+    }
+
+    async fn save(&self, template: &ConfigTemplate) -> Result<(), String> {
+        self.templates
+            .lock()
+            .unwrap()
+            .insert(template.name.clone(), template.clone());
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for `S3InstrumentCatalogRepository`: empty (as before
+/// the first refresh) until a catalog is saved.
+#[derive(Default)]
+pub struct InMemoryInstrumentCatalog {
+    catalog: Mutex<Option<InstrumentCatalog>>,
+}
+
+impl InMemoryInstrumentCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl InstrumentCatalogRepository for InMemoryInstrumentCatalog {
+    async fn load(&self) -> Result<Option<InstrumentCatalog>, DomainError> {
+        Ok(self.catalog.lock().unwrap().clone())
+    }
+
+    async fn save(&self, catalog: &InstrumentCatalog) -> Result<(), DomainError> {
+        *self.catalog.lock().unwrap() = Some(catalog.clone());
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for `S3ApiKeyRepository`.
+#[derive(Default)]
+pub struct InMemoryApiKeyRepository {
+    keys: Mutex<HashMap<BotKey, (String, String)>>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stored `(api key, secret)` of a bot.
+    pub fn get(&self, user_id: &str, bot_id: &str) -> Option<(String, String)> {
+        self.keys
+            .lock()
+            .unwrap()
+            .get(&key(user_id, bot_id))
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn save(&self, bot: &Bot) -> Result<(), String> {
+        self.keys.lock().unwrap().insert(
+            key(&bot.user_id, &bot.id),
+            (bot.api_key.clone(), bot.secret_key.clone()),
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String> {
+        self.keys.lock().unwrap().remove(&key(user_id, bot_id));
+        Ok(())
+    }
+}
+
+/// In-memory stand-in for the exchange behind `BybitTradingGateway`: open
+/// positions per bot, seeded with `open`, resting orders seeded with `place`,
+/// the account history seeded with `close` and `fill`, and the equity set
+/// with `set_equity`. Closing removes the position and cancelling removes the
+/// order; cancel-all also counts the call.
+#[derive(Default)]
+pub struct InMemoryTradingGateway {
+    positions: Mutex<HashMap<String, Vec<Position>>>,
+    orders: Mutex<HashMap<String, Vec<Order>>>,
+    cancels: Mutex<HashMap<String, usize>>,
+    closed: Mutex<HashMap<String, Vec<ClosedPnl>>>,
+    fills: Mutex<HashMap<String, Vec<Fill>>>,
+    equity: Mutex<HashMap<String, f64>>,
+}
+
+impl InMemoryTradingGateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open `position` on the account of the bot with id `bot_id`.
+    pub fn open(&self, bot_id: &str, position: Position) {
+        self.positions
+            .lock()
+            .unwrap()
+            .entry(bot_id.to_string())
+            .or_default()
+            .push(position);
+    }
+
+    /// Rest `order` on the account of the bot with id `bot_id`.
+    pub fn place(&self, bot_id: &str, order: Order) {
+        self.orders
+            .lock()
+            .unwrap()
+            .entry(bot_id.to_string())
+            .or_default()
+            .push(order);
+    }
+
+    /// Record a closed position on the account of the bot with id `bot_id`.
+    pub fn close(&self, bot_id: &str, closed: ClosedPnl) {
+        self.closed
+            .lock()
+            .unwrap()
+            .entry(bot_id.to_string())
+            .or_default()
+            .push(closed);
+    }
+
+    /// Record a trade execution on the account of the bot with id `bot_id`.
+    pub fn fill(&self, bot_id: &str, fill: Fill) {
+        self.fills
+            .lock()
+            .unwrap()
+            .entry(bot_id.to_string())
+            .or_default()
+            .push(fill);
+    }
+
+    /// Set the equity of the bot's account; unset, it reads as an error.
+    pub fn set_equity(&self, bot_id: &str, equity: f64) {
+        self.equity
+            .lock()
+            .unwrap()
+            .insert(bot_id.to_string(), equity);
+    }
+
+    /// How many times the bot's orders were cancelled.
+    pub fn cancels(&self, bot_id: &str) -> usize {
+        self.cancels
+            .lock()
+            .unwrap()
+            .get(bot_id)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl ExchangeTradingGateway for InMemoryTradingGateway {
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError> {
+        Ok(self
+            .positions
+            .lock()
+            .unwrap()
+            .get(&bot.id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn open_orders(&self, bot: &Bot) -> Result<Vec<Order>, DomainError> {
+        Ok(self
+            .orders
+            .lock()
+            .unwrap()
+            .get(&bot.id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn cancel_order(&self, bot: &Bot, order: &Order) -> Result<(), DomainError> {
+        let mut orders = self.orders.lock().unwrap();
+        let open = orders.entry(bot.id.clone()).or_default();
+        let before = open.len();
+        open.retain(|o| o.order_id != order.order_id);
+        if open.len() == before {
+            return Err(DomainError::Exchange(format!(
+                "order {} does not exist",
+                order.order_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn cancel_all_orders(&self, bot: &Bot) -> Result<(), DomainError> {
+        self.orders.lock().unwrap().remove(&bot.id);
+        *self
+            .cancels
+            .lock()
+            .unwrap()
+            .entry(bot.id.clone())
+            .or_default() += 1;
+        Ok(())
+    }
+
+    async fn close_position(&self, bot: &Bot, position: &Position) -> Result<(), DomainError> {
+        if let Some(open) = self.positions.lock().unwrap().get_mut(&bot.id) {
+            open.retain(|p| !(p.symbol == position.symbol && p.side == position.side));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for InMemoryTradingGateway {
+    async fn closed_pnl(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+    ) -> Result<Vec<ClosedPnl>, DomainError> {
+        let mut closed: Vec<ClosedPnl> = self
+            .closed
+            .lock()
+            .unwrap()
+            .get(&bot.id)
+            .into_iter()
+            .flatten()
+            .filter(|c| (since..=until).contains(&c.closed_at))
+            .cloned()
+            .collect();
+        closed.sort_by_key(|c| std::cmp::Reverse(c.closed_at));
+        Ok(closed)
+    }
+
+    async fn fills(
+        &self,
+        bot: &Bot,
+        since: i64,
+        until: i64,
+        limit: usize,
+    ) -> Result<Vec<Fill>, DomainError> {
+        let mut fills: Vec<Fill> = self
+            .fills
+            .lock()
+            .unwrap()
+            .get(&bot.id)
+            .into_iter()
+            .flatten()
+            .filter(|f| (since..=until).contains(&f.executed_at))
+            .cloned()
+            .collect();
+        fills.sort_by_key(|f| std::cmp::Reverse(f.executed_at));
+        fills.truncate(limit);
+        Ok(fills)
+    }
+
+    async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError> {
+        ExchangeTradingGateway::positions(self, bot).await
+    }
+
+    async fn equity(&self, bot: &Bot) -> Result<f64, DomainError> {
+        self.equity
+            .lock()
+            .unwrap()
+            .get(&bot.id)
+            .copied()
+            .ok_or_else(|| DomainError::Exchange(format!("no equity for {}", bot.id)))
+    }
+}
+
+/// Keeps every message instead of delivering it; `fail` makes delivery to a
+/// user fail, as a blocked bot would.
+#[derive(Default)]
+pub struct RecordingNotifier {
+    sent: Mutex<Vec<(String, String)>>,
+    failing: Mutex<Vec<String>>,
+}
+
+impl RecordingNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Messages delivered so far, as `(user_id, text)`, oldest first.
+    pub fn sent(&self) -> Vec<(String, String)> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    pub fn fail(&self, user_id: &str) {
+        self.failing.lock().unwrap().push(user_id.to_string());
+    }
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, user_id: &str, text: &str) -> Result<(), DomainError> {
+        if self.failing.lock().unwrap().iter().any(|u| u == user_id) {
+            return Err(DomainError::Delivery(format!(
+                "cannot deliver to {user_id}"
+            )));
+        }
+        self.sent
+            .lock()
+            .unwrap()
+            .push((user_id.to_string(), text.to_string()));
+        Ok(())
+    }
+}
+
+/// Lifecycle of a fake task: desired RUNNING, asked to stop (still draining,
+/// so alive but no longer listed), or gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FakeTaskStatus {
+    Running,
+    StopRequested,
+    Stopped,
+}
+
+/// One task launched on the fake backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeTask {
+    pub task_id: String,
+    pub user_id: String,
+    pub bot_id: String,
+    pub cluster_arn: String,
+    pub container_name: String,
+    pub resources: ResourceProfile,
+    pub status: FakeTaskStatus,
+    /// The correlation id in scope at launch, which RunTask hands the task.
+    pub correlation_id: Option<CorrelationId>,
+}
+
+#[derive(Default)]
+struct Cluster {
+    fail_launches: bool,
+    /// Every task ever launched, in launch order.
+    tasks: Vec<FakeTask>,
+}
+
+impl Cluster {
+    fn get_mut(&mut self, task_id: &str) -> Option<&mut FakeTask> {
+        self.tasks.iter_mut().find(|t| t.task_id == task_id)
+    }
+}
+
+/// In-memory stand-in for ECS: `TaskRunner`, `TaskController` and
+/// `TaskInventory` over a map of fake tasks. Nothing happens on its own — a
+/// scenario ends a task with `exit` and feeds the returned report to the
+/// reconcile, as the STOPPED event would.
+#[derive(Default)]
+pub struct FakeTaskBackend {
+    cluster: Mutex<Cluster>,
+}
+
+impl FakeTaskBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make every launch fail (`true`) or succeed again (`false`).
+    pub fn fail_launches(&self, fail: bool) {
+        self.cluster.lock().unwrap().fail_launches = fail;
+    }
+
+    pub fn task(&self, task_id: &str) -> Option<FakeTask> {
+        self.tasks().into_iter().find(|t| t.task_id == task_id)
+    }
+
+    /// Every task ever launched, in launch order.
+    pub fn tasks(&self) -> Vec<FakeTask> {
+        self.cluster.lock().unwrap().tasks.clone()
+    }
+
+    /// The bot's tasks that are not yet gone.
+    pub fn alive_tasks(&self, user_id: &str, bot_id: &str) -> Vec<FakeTask> {
+        self.tasks()
+            .into_iter()
+            .filter(|t| {
+                t.user_id == user_id && t.bot_id == bot_id && t.status != FakeTaskStatus::Stopped
+            })
+            .collect()
+    }
+
+    /// End a task with `exit_code` on its passivbot container and return the
+    /// report its STOPPED event would carry: `UserInitiated` if it had been
+    /// asked to stop, an OOM kill for 137, otherwise an essential container
+    /// exit. `None` for an unknown or already stopped task.
+    pub fn exit(&self, task_id: &str, exit_code: i32) -> Option<StopReport> {
+        let mut cluster = self.cluster.lock().unwrap();
+        let task = cluster.get_mut(task_id)?;
+        let requested = match task.status {
+            FakeTaskStatus::Running => false,
+            FakeTaskStatus::StopRequested => true,
+            FakeTaskStatus::Stopped => return None,
+        };
+        task.status = FakeTaskStatus::Stopped;
+        let (stop_code, stopped_reason, reason) = if requested {
+            ("UserInitiated", "Task stopped by user", None)
+        } else if exit_code == 137 {
+            (
+                "EssentialContainerExited",
+                "Essential container in task exited",
+                Some("OutOfMemoryError: Container killed due to memory usage".to_string()),
+            )
+        } else {
+            (
+                "EssentialContainerExited",
+                "Essential container in task exited",
+                None,
+            )
+        };
+        Some(StopReport {
+            stop_code: Some(stop_code.to_string()),
+            stopped_reason: Some(stopped_reason.to_string()),
+            main_container: task.container_name.clone(),
+            containers: vec![ContainerExit {
+                name: task.container_name.clone(),
+                exit_code: Some(exit_code),
+                reason,
+            }],
+        })
+    }
+}
+
+#[async_trait]
+impl TaskRunner for FakeTaskBackend {
+    async fn run(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        cluster_arn: &str,
+        _td_arn: &str,
+        container_name: &str,
+        resources: ResourceProfile,
+    ) -> Result<String> {
+        let mut cluster = self.cluster.lock().unwrap();
+        if cluster.fail_launches {
+            return Err(anyhow!("RunTask failed: no capacity"));
+        }
+        let task_id = format!("task-{}", cluster.tasks.len() + 1);
+        cluster.tasks.push(FakeTask {
+            task_id: task_id.clone(),
+            user_id: user_id.to_string(),
+            bot_id: bot_id.to_string(),
+            cluster_arn: cluster_arn.to_string(),
+            container_name: container_name.to_string(),
+            resources,
+            status: FakeTaskStatus::Running,
+            correlation_id: correlation_id(),
+        });
+        Ok(task_id)
+    }
+}
+
+#[async_trait]
+impl TaskController for FakeTaskBackend {
+    async fn stop(&self, _cluster_arn: &str, task_id: &str, _reason: &str) -> Result<()> {
+        // Like StopTask, stopping an unknown or stopped task is not an error.
+        if let Some(task) = self.cluster.lock().unwrap().get_mut(task_id)
+            && task.status == FakeTaskStatus::Running
+        {
+            task.status = FakeTaskStatus::StopRequested;
+        }
+        Ok(())
+    }
+
+    async fn liveness(&self, _cluster_arn: &str, task_id: &str) -> Result<TaskLiveness> {
+        Ok(match self.task(task_id).map(|t| t.status) {
+            Some(FakeTaskStatus::Running | FakeTaskStatus::StopRequested) => TaskLiveness::Alive,
+            Some(FakeTaskStatus::Stopped) | None => TaskLiveness::Gone,
+        })
+    }
+}
+
+#[async_trait]
+impl TaskInventory for FakeTaskBackend {
+    async fn list_running(&self, cluster_arn: &str) -> Result<Vec<LiveTask>> {
+        Ok(self
+            .tasks()
+            .into_iter()
+            .filter(|t| t.cluster_arn == cluster_arn && t.status == FakeTaskStatus::Running)
+            .map(|t| LiveTask {
+                task_id: t.task_id,
+                user_id: Some(t.user_id),
+                bot_id: Some(t.bot_id),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(phase: RuntimePhase, task_id: Option<&str>, at: i64) -> BotRuntime {
+        let mut r = BotRuntime::stopped("u".into(), "b".into(), 1, at);
+        r.phase = phase;
+        r.task_id = task_id.map(str::to_string);
+        r
+    }
+
+    #[tokio::test]
+    async fn record_applies_the_dynamodb_tie_breaks() {
+        let repo = InMemoryBotRepository::new();
+        repo.record(&runtime(RuntimePhase::Stopped, None, 100))
+            .await
+            .unwrap();
+        // A same-second RUNNING must not resurrect a STOPPED.
+        repo.record(&runtime(RuntimePhase::Running, Some("t1"), 100))
+            .await
+            .unwrap();
+        let row = BotRuntimeRepository::find(&repo, "u", "b").await.unwrap();
+        assert_eq!(row.unwrap().phase, RuntimePhase::Stopped);
+        // Stopping is identity-guarded: never written over a row without its task.
+        repo.record(&runtime(RuntimePhase::Stopping, Some("t1"), 200))
+            .await
+            .unwrap();
+        let row = BotRuntimeRepository::find(&repo, "u", "b").await.unwrap();
+        assert_eq!(row.unwrap().phase, RuntimePhase::Stopped);
+        repo.record(&runtime(RuntimePhase::Running, Some("t1"), 101))
+            .await
+            .unwrap();
+        repo.record(&runtime(RuntimePhase::Stopping, Some("t1"), 150))
+            .await
+            .unwrap();
+        // A stopping row settles even from an older STOPPED.
+        repo.record(&runtime(RuntimePhase::Stopped, None, 120))
+            .await
+            .unwrap();
+        let row = BotRuntimeRepository::find(&repo, "u", "b").await.unwrap();
+        assert_eq!(row.unwrap().phase, RuntimePhase::Stopped);
+    }
+
+    #[tokio::test]
+    async fn start_and_restart_claims_are_exclusive() {
+        let repo = InMemoryBotRepository::new();
+        assert_eq!(
+            repo.try_acquire_start("u", "b", 100, 300).await.unwrap(),
+            StartClaim::Acquired
+        );
+        assert_eq!(
+            repo.try_acquire_start("u", "b", 101, 300).await.unwrap(),
+            StartClaim::AlreadyStarting
+        );
+        repo.attach_started_task("u", "b", "t1").await.unwrap();
+        assert_eq!(
+            repo.try_acquire_restart("u", "b", "t1", 1, 110)
+                .await
+                .unwrap(),
+            StartClaim::Acquired
+        );
+        // A duplicate STOPPED finds the task id already cleared.
+        assert_eq!(
+            repo.try_acquire_restart("u", "b", "t1", 1, 111)
+                .await
+                .unwrap(),
+            StartClaim::AlreadyStarting
+        );
+        let row = BotRuntimeRepository::find(&repo, "u", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.version, 1);
+        assert_eq!(row.restart_attempts, 1);
+        // A stale lock is reclaimable.
+        assert_eq!(
+            repo.try_acquire_start("u", "b", 500, 300).await.unwrap(),
+            StartClaim::Acquired
+        );
+    }
+}
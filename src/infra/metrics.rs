@@ -0,0 +1,262 @@
+// src/infra/metrics.rs
+//! Sinks for the [`Metrics`] port: CloudWatch Embedded Metric Format lines for
+//! the Lambdas, and an in-process registry the telebot serves to Prometheus.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value, json};
+
+use crate::domain::metrics::{Labels, Metrics};
+
+/// CloudWatch namespace the Lambdas' metrics land in.
+pub const EMF_NAMESPACE: &str = "pbtb";
+
+/// Upper bounds (seconds) of the Prometheus histogram buckets. Sized for task
+/// start latency: Fargate usually takes under a minute, a cold image pull more.
+pub const HISTOGRAM_BUCKETS: &[f64] = &[
+    5.0, 10.0, 15.0, 20.0, 30.0, 45.0, 60.0, 90.0, 120.0, 180.0, 300.0, 600.0,
+];
+
+/// Writes each measurement as one Embedded Metric Format JSON line. In a
+/// Lambda, CloudWatch Logs turns the line into a metric with the labels as
+/// dimensions; no API call, no extra IAM.
+pub struct EmfMetrics {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EmfMetrics {
+    /// A sink writing to stdout, next to the JSON log lines.
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Mutex::new(Box::new(out)),
+        }
+    }
+
+    fn emit(&self, name: &'static str, labels: Labels<'_>, value: f64, unit: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let dimensions: Vec<&str> = labels.iter().map(|(k, _)| *k).collect();
+        let mut line = Map::new();
+        line.insert(
+            "_aws".to_string(),
+            json!({
+                "Timestamp": timestamp,
+                "CloudWatchMetrics": [{
+                    "Namespace": EMF_NAMESPACE,
+                    "Dimensions": [dimensions],
+                    "Metrics": [{ "Name": name, "Unit": unit }],
+                }],
+            }),
+        );
+        for (k, v) in labels {
+            line.insert(k.to_string(), Value::from(*v));
+        }
+        line.insert(name.to_string(), json!(value));
+
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        // A metric line that cannot be written is dropped; it must never fail
+        // the action being measured.
+        let _ = writeln!(out, "{}", Value::Object(line)).and_then(|_| out.flush());
+    }
+}
+
+impl Metrics for EmfMetrics {
+    fn increment(&self, name: &'static str, labels: Labels<'_>) {
+        self.emit(name, labels, 1.0, "Count");
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels<'_>, value: f64) {
+        let unit = if name.ends_with("_seconds") {
+            "Seconds"
+        } else {
+            "None"
+        };
+        self.emit(name, labels, value, unit);
+    }
+}
+
+type Series = (&'static str, Vec<(&'static str, String)>);
+
+#[derive(Default)]
+struct Histogram {
+    /// Per-bucket (not cumulative) counts, one per [`HISTOGRAM_BUCKETS`] entry.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// Counters and histograms kept in memory and rendered in the Prometheus text
+/// format on each scrape. Values reset when the process restarts, which
+/// Prometheus' `rate()` handles.
+#[derive(Default)]
+pub struct PrometheusMetrics {
+    counters: Mutex<BTreeMap<Series, u64>>,
+    histograms: Mutex<BTreeMap<Series, Histogram>>,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current values in the text exposition format (version 0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut last = "";
+        for ((name, labels), value) in self.counters.lock().unwrap().iter() {
+            if *name != last {
+                out.push_str(&format!("# TYPE {name} counter\n"));
+                last = name;
+            }
+            out.push_str(&format!("{name}{} {value}\n", render_labels(labels, None)));
+        }
+        last = "";
+        for ((name, labels), h) in self.histograms.lock().unwrap().iter() {
+            if *name != last {
+                out.push_str(&format!("# TYPE {name} histogram\n"));
+                last = name;
+            }
+            let mut cumulative = 0;
+            for (bound, n) in HISTOGRAM_BUCKETS.iter().zip(&h.buckets) {
+                cumulative += n;
+                let le = bound.to_string();
+                out.push_str(&format!(
+                    "{name}_bucket{} {cumulative}\n",
+                    render_labels(labels, Some(&le))
+                ));
+            }
+            out.push_str(&format!(
+                "{name}_bucket{} {}\n",
+                render_labels(labels, Some("+Inf")),
+                h.count
+            ));
+            let plain = render_labels(labels, None);
+            out.push_str(&format!("{name}_sum{plain} {}\n", h.sum));
+            out.push_str(&format!("{name}_count{plain} {}\n", h.count));
+        }
+        out
+    }
+}
+
+fn series(name: &'static str, labels: Labels<'_>) -> Series {
+    let mut labels: Vec<_> = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+    labels.sort();
+    (name, labels)
+}
+
+fn render_labels(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{le}\""));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Metrics for PrometheusMetrics {
+    fn increment(&self, name: &'static str, labels: Labels<'_>) {
+        *self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(series(name, labels))
+            .or_default() += 1;
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels<'_>, value: f64) {
+        let mut histograms = self.histograms.lock().unwrap();
+        let h = histograms.entry(series(name, labels)).or_default();
+        if h.buckets.is_empty() {
+            h.buckets = vec![0; HISTOGRAM_BUCKETS.len()];
+        }
+        if let Some(i) = HISTOGRAM_BUCKETS.iter().position(|bound| value <= *bound) {
+            h.buckets[i] += 1;
+        }
+        h.sum += value;
+        h.count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// A writer the test can read back after handing it to the sink.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn emf_line_declares_the_metric_and_its_dimensions() {
+        let buffer = Buffer::default();
+        let metrics = EmfMetrics::new(buffer.clone());
+        metrics.increment(
+            "bot_restarts_total",
+            &[("cause", "out_of_memory"), ("outcome", "restarted")],
+        );
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(written.lines().count(), 1);
+        let line: Value = serde_json::from_str(written.trim()).unwrap();
+        let declared = &line["_aws"]["CloudWatchMetrics"][0];
+        assert_eq!(declared["Namespace"], EMF_NAMESPACE);
+        assert_eq!(declared["Dimensions"], json!([["cause", "outcome"]]));
+        assert_eq!(
+            declared["Metrics"],
+            json!([{ "Name": "bot_restarts_total", "Unit": "Count" }])
+        );
+        assert_eq!(line["cause"], "out_of_memory");
+        assert_eq!(line["bot_restarts_total"], 1.0);
+    }
+
+    #[test]
+    fn prometheus_renders_counters_and_cumulative_buckets() {
+        let metrics = PrometheusMetrics::new();
+        metrics.increment("bot_starts_total", &[("outcome", "started")]);
+        metrics.increment("bot_starts_total", &[("outcome", "started")]);
+        metrics.increment("bot_starts_total", &[("outcome", "error")]);
+        metrics.observe("bot_time_to_running_seconds", &[], 12.0);
+        metrics.observe("bot_time_to_running_seconds", &[], 700.0);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE bot_starts_total counter\n"));
+        assert!(text.contains("bot_starts_total{outcome=\"started\"} 2\n"));
+        assert!(text.contains("bot_starts_total{outcome=\"error\"} 1\n"));
+        assert!(text.contains("# TYPE bot_time_to_running_seconds histogram\n"));
+        assert!(text.contains("bot_time_to_running_seconds_bucket{le=\"10\"} 0\n"));
+        assert!(text.contains("bot_time_to_running_seconds_bucket{le=\"15\"} 1\n"));
+        assert!(text.contains("bot_time_to_running_seconds_bucket{le=\"600\"} 1\n"));
+        assert!(text.contains("bot_time_to_running_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("bot_time_to_running_seconds_sum 712\n"));
+        assert!(text.contains("bot_time_to_running_seconds_count 2\n"));
+    }
+}
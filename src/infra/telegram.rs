@@ -0,0 +1,81 @@
+use crate::config::telegram::TelegramConfig;
+use crate::domain::error::DomainError;
+use crate::domain::notify::Notifier;
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use serde::Deserialize;
+use teloxide::prelude::*;
+use teloxide::types::ChatId;
+
+/// `Notifier` over the Telegram Bot API, with the telebot's own token. A
+/// user's private chat with the bot has the user's id as its chat id, so the
+/// message reaches the chat the user talks to the bot in. Delivery fails when
+/// the user never started the bot or blocked it.
+pub struct TelegramNotifier {
+    bot: Bot,
+}
+
+impl TelegramNotifier {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            bot: Bot::new(token),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn notify(&self, user_id: &str, text: &str) -> Result<(), DomainError> {
+        let chat_id = user_id
+            .parse::<i64>()
+            .map_err(|_| DomainError::Delivery(format!("{user_id} is not a Telegram user id")))?;
+        self.bot
+            .send_message(ChatId(chat_id), text)
+            .await
+            .map_err(|e| DomainError::Delivery(format!("sendMessage to {user_id}: {e}")))?;
+        Ok(())
+    }
+}
+
+/// The telebot's Bot API token: `TELOXIDE_TOKEN` when set (local runs), else
+/// the SSM parameter, read through the AWS Parameters and Secrets Lambda
+/// Extension so the secret never sits in the function's configuration.
+pub async fn resolve_token(telegram: Option<&TelegramConfig>) -> anyhow::Result<String> {
+    if let Ok(token) = std::env::var("TELOXIDE_TOKEN") {
+        return Ok(token);
+    }
+    let param = telegram
+        .map(|t| t.token_param.as_str())
+        .ok_or_else(|| anyhow!("set TELOXIDE_TOKEN or APP__TELEGRAM__TOKEN_PARAM"))?;
+
+    let port = std::env::var("PARAMETERS_SECRETS_EXTENSION_HTTP_PORT")
+        .unwrap_or_else(|_| "2773".to_string());
+    let session_token =
+        std::env::var("AWS_SESSION_TOKEN").context("AWS_SESSION_TOKEN is not set")?;
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(rename = "Parameter")]
+        parameter: Parameter,
+    }
+    #[derive(Deserialize)]
+    struct Parameter {
+        #[serde(rename = "Value")]
+        value: String,
+    }
+
+    let response: Response = reqwest::Client::new()
+        .get(format!(
+            "http://localhost:{port}/systemsmanager/parameters/get"
+        ))
+        .query(&[("name", param), ("withDecryption", "true")])
+        .header("X-Aws-Parameters-Secrets-Token", session_token)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("failed to read {param}"))?
+        .json()
+        .await
+        .with_context(|| format!("failed to read {param}"))?;
+    Ok(response.parameter.value)
+}
@@ -0,0 +1,216 @@
+// Rust
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tracing::Instrument;
+
+use crate::domain::clock::Clock;
+use crate::infra::docker::{DockerTaskBackend, DockerTaskEvent, DockerTaskEventKind};
+use crate::telemetry::{CorrelationId, with_correlation_id};
+use crate::usecase::{
+    ReconcileOutcome, ReconcileStoppedTaskUseCase, RecordRunningOutcome, RecordRunningTaskUseCase,
+    SweepRuntimesUseCase,
+};
+
+/// Pause before resubscribing after the engine's event stream drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// The local stand-in for the ECS Task State Change Lambda and the runtime
+/// sweeper: feeds the Docker backend's container events into the same use
+/// cases the Lambdas call, and sweeps periodically so events missed while the
+/// stream was down (or the telebot was not running) are still repaired.
+pub struct DockerSupervisor {
+    backend: Arc<DockerTaskBackend>,
+    record_running: Arc<RecordRunningTaskUseCase>,
+    reconcile: Arc<ReconcileStoppedTaskUseCase>,
+    sweeper: Arc<SweepRuntimesUseCase>,
+    clock: Arc<dyn Clock>,
+    cluster: String,
+    image: String,
+    container_name: String,
+    sweep_interval: Duration,
+}
+
+impl DockerSupervisor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        backend: Arc<DockerTaskBackend>,
+        record_running: Arc<RecordRunningTaskUseCase>,
+        reconcile: Arc<ReconcileStoppedTaskUseCase>,
+        sweeper: Arc<SweepRuntimesUseCase>,
+        clock: Arc<dyn Clock>,
+        cluster: String,
+        image: String,
+        container_name: String,
+        sweep_interval: Duration,
+    ) -> Self {
+        Self {
+            backend,
+            record_running,
+            reconcile,
+            sweeper,
+            clock,
+            cluster,
+            image,
+            container_name,
+            sweep_interval,
+        }
+    }
+
+    /// Run until the process exits. Events are handled one at a time, in the
+    /// order the engine emits them.
+    pub async fn run(self) {
+        // The first tick fires immediately: catch up on anything that changed
+        // while the telebot was down.
+        let mut sweep_timer = tokio::time::interval(self.sweep_interval);
+        loop {
+            let events = self.backend.task_events(&self.cluster);
+            tokio::pin!(events);
+            loop {
+                tokio::select! {
+                    _ = sweep_timer.tick() => self.sweep().await,
+                    next = events.next() => match next {
+                        Some(Ok(event)) => self.handle(event).await,
+                        Some(Err(e)) => {
+                            tracing::warn!("docker event stream failed: {e:#}");
+                            break;
+                        }
+                        None => {
+                            tracing::warn!("docker event stream ended");
+                            break;
+                        }
+                    },
+                }
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            // Whatever happened while disconnected is not replayed.
+            sweep_timer.reset_immediately();
+        }
+    }
+
+    async fn sweep(&self) {
+        let correlation_id = CorrelationId::new();
+        let span = tracing::info_span!("docker_sweep", correlation_id = %correlation_id);
+        let swept = with_correlation_id(correlation_id, self.sweeper.execute().instrument(span));
+        match swept.await {
+            Ok(summary) => {
+                for action in &summary.actions {
+                    tracing::info!("docker sweep: {action:?}");
+                }
+            }
+            Err(e) => tracing::error!("docker sweep failed: {e}"),
+        }
+    }
+
+    async fn handle(&self, event: DockerTaskEvent) {
+        let (Some(user_id), Some(bot_id)) = (event.user_id.as_deref(), event.bot_id.as_deref())
+        else {
+            tracing::warn!(
+                "ignoring docker event for container {} without bot labels",
+                event.task_id
+            );
+            return;
+        };
+        // Continue the launching action's correlation id, as the ECS event
+        // Lambda does.
+        let correlation_id = event.correlation_id.clone().unwrap_or_default();
+        let span = tracing::info_span!(
+            "container_event",
+            correlation_id = %correlation_id,
+            user_id = %user_id,
+            bot_id = %bot_id,
+            task_id = %event.task_id,
+        );
+        with_correlation_id(
+            correlation_id,
+            self.handle_identified(user_id, bot_id, &event)
+                .instrument(span),
+        )
+        .await;
+    }
+
+    async fn handle_identified(&self, user_id: &str, bot_id: &str, event: &DockerTaskEvent) {
+        match event.kind {
+            DockerTaskEventKind::Running => {
+                match self
+                    .record_running
+                    .execute(user_id, bot_id, &event.task_id, &self.cluster, event.at)
+                    .await
+                {
+                    Ok(RecordRunningOutcome::Conflict {
+                        owner_task_id,
+                        newcomer_stopped,
+                    }) => tracing::error!(
+                        "duplicate container {} for bot {bot_id} (owner {owner_task_id}), stopped: {newcomer_stopped}",
+                        event.task_id
+                    ),
+                    Ok(outcome) => tracing::info!(
+                        "recorded running container {} for bot {bot_id}: {outcome:?}",
+                        event.task_id
+                    ),
+                    Err(e) => tracing::error!(
+                        "failed to record running container {} for bot {bot_id}: {e:#}",
+                        event.task_id
+                    ),
+                }
+            }
+            DockerTaskEventKind::Stopped => self.reconcile_stop(user_id, bot_id, event).await,
+        }
+    }
+
+    /// Reconcile an exited container, then remove it. A failed reconcile
+    /// leaves the container for inspection; the sweeper repairs the row.
+    async fn reconcile_stop(&self, user_id: &str, bot_id: &str, event: &DockerTaskEvent) {
+        let report = match self
+            .backend
+            .stop_report(&event.task_id, &self.container_name)
+            .await
+        {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::error!(
+                    "failed to inspect stopped container {} for bot {bot_id}: {e:#}",
+                    event.task_id
+                );
+                return;
+            }
+        };
+        tracing::info!(
+            "container {} for bot {bot_id} stopped: {}",
+            event.task_id,
+            report.summary()
+        );
+        match self
+            .reconcile
+            .execute(
+                user_id,
+                bot_id,
+                &event.task_id,
+                &self.cluster,
+                &self.image,
+                &self.container_name,
+                &report,
+                event.at,
+                self.clock.now(),
+            )
+            .await
+        {
+            Ok(outcome) => {
+                match &outcome {
+                    ReconcileOutcome::Restarted { task_id, attempt } => tracing::info!(
+                        "restarted bot {bot_id} as container {task_id} (attempt {attempt})"
+                    ),
+                    other => tracing::info!("reconciled bot {bot_id}: {other:?}"),
+                }
+                if let Err(e) = self.backend.remove(&event.task_id).await {
+                    tracing::warn!("failed to remove container {}: {e:#}", event.task_id);
+                }
+            }
+            Err(e) => tracing::error!(
+                "failed to reconcile stopped container {} for bot {bot_id}: {e:#}",
+                event.task_id
+            ),
+        }
+    }
+}
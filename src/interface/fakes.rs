@@ -0,0 +1,241 @@
+//! The in-memory adapters (`testing` feature) wired into a full [`Deps`], for
+//! driving either front end in tests.
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::domain::clock::MockClock;
+use crate::domain::configtemplate::ConfigTemplate;
+use crate::domain::metrics::NoopMetrics;
+use crate::infra::memory::{
+    FakeTaskBackend, InMemoryApiKeyRepository, InMemoryBotConfigRepository, InMemoryBotRepository,
+    InMemoryInstrumentCatalog, InMemoryTemplateRepository, InMemoryTradingGateway,
+};
+use crate::usecase::*;
+
+use super::Deps;
+
+const CLUSTER: &str = "arn:aws:ecs:ap-northeast-1:000000000000:cluster/test";
+const TD: &str = "arn:aws:ecs:ap-northeast-1:000000000000:task-definition/passivbot:1";
+const CONTAINER: &str = "passivbot";
+
+/// The adapters behind the test [`Deps`], for seeding and assertions.
+pub(crate) struct Fakes {
+    pub clock: Arc<MockClock>,
+    pub repo: Arc<InMemoryBotRepository>,
+    pub configs: Arc<InMemoryBotConfigRepository>,
+    pub api_keys: Arc<InMemoryApiKeyRepository>,
+    pub backend: Arc<FakeTaskBackend>,
+    pub gateway: Arc<InMemoryTradingGateway>,
+    /// Empty until a test saves a catalog, like before the first refresh.
+    pub instruments: Arc<InMemoryInstrumentCatalog>,
+}
+
+impl Fakes {
+    pub fn new() -> Self {
+        Self {
+            clock: Arc::new(MockClock::new(1_700_000_000)),
+            repo: Arc::new(InMemoryBotRepository::new()),
+            configs: Arc::new(InMemoryBotConfigRepository::new()),
+            api_keys: Arc::new(InMemoryApiKeyRepository::new()),
+            backend: Arc::new(FakeTaskBackend::new()),
+            gateway: Arc::new(InMemoryTradingGateway::new()),
+            instruments: Arc::new(InMemoryInstrumentCatalog::new()),
+        }
+    }
+
+    /// The same use cases as `main`, over the fakes.
+    pub fn deps(&self) -> Deps {
+        let fakes = self;
+        let repo = fakes.repo.clone();
+        let configs = fakes.configs.clone();
+        let clock = fakes.clock.clone();
+        let templates = Arc::new(InMemoryTemplateRepository::new(vec![ConfigTemplate {
+            name: "btc_grid".to_string(),
+            description: None,
+            config_data: json!({
+                "live": { "user": "template" },
+                "bot": {
+                    "long": { "total_wallet_exposure_limit": 1.0 },
+                    "short": { "total_wallet_exposure_limit": 0.0 }
+                }
+            }),
+            version: Some("1".to_string()),
+        }]));
+
+        let list_bots_usecase = Arc::new(ListBotsUseCase::new(repo.clone()));
+        let update_risk_level_usecase = Arc::new(UpdateRiskLevelUseCase::new(
+            repo.clone(),
+            configs.clone(),
+            fakes.instruments.clone(),
+            clock.clone(),
+        ));
+        let set_strategy_side_usecase =
+            Arc::new(SetStrategySideUseCase::new(configs.clone(), clock.clone()));
+        let start_bot_usecase = Arc::new(StartBotUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            fakes.backend.clone(),
+            fakes.backend.clone(),
+            clock.clone(),
+            repo.clone(),
+            Arc::new(NoopMetrics),
+            configs.clone(),
+            fakes.instruments.clone(),
+            CLUSTER.to_string(),
+            TD.to_string(),
+            CONTAINER.to_string(),
+        ));
+        let stop_bot_usecase = Arc::new(StopBotUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            fakes.backend.clone(),
+            clock.clone(),
+            repo.clone(),
+            Arc::new(NoopMetrics),
+            CLUSTER.to_string(),
+        ));
+        let action_runner: Arc<dyn ScheduleActionRunner> = Arc::new(UseCaseActionRunner::new(
+            start_bot_usecase.clone(),
+            stop_bot_usecase.clone(),
+            update_risk_level_usecase.clone(),
+            set_strategy_side_usecase.clone(),
+        ));
+
+        Deps {
+            add_bot_usecase: Arc::new(AddBotUseCase::new(
+                repo.clone(),
+                fakes.api_keys.clone(),
+                clock.clone(),
+            )),
+            delete_bot_usecase: Arc::new(DeleteBotUseCase::new(
+                repo.clone(),
+                fakes.api_keys.clone(),
+                repo.clone(),
+                repo.clone(),
+            )),
+            list_templates_usecase: Arc::new(ListTemplatesUseCase::new(templates.clone())),
+            apply_template_usecase: Arc::new(ApplyTemplateUseCase::new(
+                templates.clone(),
+                configs.clone(),
+                clock.clone(),
+            )),
+            get_approved_coins_usecase: Arc::new(GetApprovedCoinsUseCase::new(
+                configs.clone(),
+                fakes.instruments.clone(),
+                clock.clone(),
+            )),
+            set_approved_coins_usecase: Arc::new(SetApprovedCoinsUseCase::new(
+                configs.clone(),
+                fakes.instruments.clone(),
+                clock.clone(),
+            )),
+            get_bot_config_usecase: Arc::new(GetBotConfigUseCase::new(configs.clone())),
+            update_bot_config_usecase: Arc::new(UpdateBotConfigUseCase::new(
+                configs.clone(),
+                clock.clone(),
+            )),
+            get_risk_presets_usecase: Arc::new(GetRiskPresetsUseCase::new(
+                configs.clone(),
+                templates,
+            )),
+            set_leverage_policy_usecase: Arc::new(SetLeveragePolicyUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            set_restart_policy_usecase: Arc::new(SetRestartPolicyUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            set_resource_profile_usecase: Arc::new(SetResourceProfileUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            set_exposure_ceiling_usecase: Arc::new(SetExposureCeilingUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            get_bot_runtime_usecase: Arc::new(GetBotRuntimeUseCase::new(repo.clone())),
+            get_bot_history_usecase: Arc::new(GetBotHistoryUseCase::new(repo.clone())),
+            create_schedule_usecase: Arc::new(CreateScheduleUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                clock.clone(),
+            )),
+            list_schedules_usecase: Arc::new(ListSchedulesUseCase::new(repo.clone())),
+            delete_schedule_usecase: Arc::new(DeleteScheduleUseCase::new(repo.clone())),
+            bulk_bot_action_usecase: Arc::new(BulkBotActionUseCase::new(
+                list_bots_usecase.clone(),
+                action_runner.clone(),
+                BULK_MAX_CONCURRENCY,
+            )),
+            panic_usecase: Arc::new(PanicUseCase::new(
+                list_bots_usecase.clone(),
+                configs.clone(),
+                action_runner,
+                fakes.gateway.clone(),
+                clock.clone(),
+                BULK_MAX_CONCURRENCY,
+            )),
+            get_performance_usecase: Arc::new(GetPerformanceUseCase::new(
+                repo.clone(),
+                fakes.gateway.clone(),
+                clock.clone(),
+            )),
+            get_positions_usecase: Arc::new(GetPositionsUseCase::new(
+                repo.clone(),
+                fakes.gateway.clone(),
+                clock.clone(),
+            )),
+            close_position_usecase: Arc::new(ClosePositionUseCase::new(
+                repo.clone(),
+                fakes.gateway.clone(),
+            )),
+            cancel_order_usecase: Arc::new(CancelOrderUseCase::new(
+                repo.clone(),
+                fakes.gateway.clone(),
+            )),
+            get_preferences_usecase: Arc::new(GetPreferencesUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            set_digest_hour_usecase: Arc::new(SetDigestHourUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            build_daily_digest_usecase: Arc::new(BuildDailyDigestUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                configs,
+                fakes.gateway.clone(),
+                repo.clone(),
+                clock.clone(),
+            )),
+            get_risk_guard_usecase: Arc::new(GetRiskGuardUseCase::new(repo.clone(), clock.clone())),
+            set_risk_guard_limit_usecase: Arc::new(SetRiskGuardLimitUseCase::new(
+                repo.clone(),
+                clock.clone(),
+            )),
+            rearm_risk_guard_usecase: Arc::new(RearmRiskGuardUseCase::new(repo.clone(), clock)),
+            list_bots_with_runtime_usecase: Arc::new(ListBotsWithRuntimeUseCase::new(
+                repo.clone(),
+                repo.clone(),
+            )),
+            list_bots_usecase,
+            update_risk_level_usecase,
+            set_strategy_side_usecase,
+            start_bot_usecase,
+            stop_bot_usecase,
+            issue_api_token_usecase: Arc::new(IssueApiTokenUseCase::new(
+                repo.clone(),
+                fakes.clock.clone(),
+            )),
+            revoke_api_token_usecase: Arc::new(RevokeApiTokenUseCase::new(repo.clone())),
+            authenticate_api_token_usecase: Arc::new(AuthenticateApiTokenUseCase::new(repo)),
+        }
+    }
+}
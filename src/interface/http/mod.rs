@@ -0,0 +1,56 @@
+//! The HTTP/JSON management API: the Telegram bot's bot-management actions as
+//! REST endpoints over the same [`Deps`], for scripts and dashboards.
+//!
+//! Every `/v1` route needs `Authorization: Bearer <token>`, with a token the
+//! user issued to themselves through `/apitoken`; the token decides whose bots
+//! the request sees. `GET /openapi.json` describes all routes.
+
+mod routes;
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::{TokioIo, TokioTimer};
+use tokio::net::TcpListener;
+
+use crate::interface::Deps;
+
+/// A client gets this long to send a request's headers, so a slow one cannot
+/// hold a connection open by trickling them.
+const HEADER_READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest a connection lives, keep-alive included; it is closed after that,
+/// whatever it is doing.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Serve the API on `listener` until the process exits.
+pub async fn serve(listener: TcpListener, deps: Deps) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                // Usually out of file descriptors; back off instead of spinning.
+                tracing::warn!("http: accept failed: {e}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let deps = deps.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let deps = deps.clone();
+                async move { Ok::<_, Infallible>(routes::handle(&deps, req).await) }
+            });
+            let connection = http1::Builder::new()
+                .timer(TokioTimer::new())
+                .header_read_timeout(HEADER_READ_TIMEOUT)
+                .serve_connection(TokioIo::new(stream), service);
+            match tokio::time::timeout(CONNECTION_TIMEOUT, connection).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::debug!("http: connection closed: {e}"),
+                Err(_) => tracing::debug!("http: connection timed out"),
+            }
+        });
+    }
+}
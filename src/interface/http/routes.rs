@@ -0,0 +1,772 @@
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tracing::Instrument;
+
+use crate::domain::bot::Bot;
+use crate::domain::runtime::BotRuntime;
+use crate::interface::Deps;
+use crate::telemetry::{CorrelationId, correlation_id, with_correlation_id};
+use crate::usecase::{AddOutcome, StartOutcome, StopOutcome};
+
+/// The OpenAPI 3 description of every route below.
+pub(super) const OPENAPI: &str = include_str!("openapi.json");
+
+/// Default and largest `limit` of `GET /v1/bots`.
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 100;
+/// Request bodies are a few short strings; anything bigger is refused.
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// A failed request: the status and the message sent as `{"error": ...}`,
+/// with the request's `correlation_id` when the failure is on our side.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+    correlation_id: Option<CorrelationId>,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            correlation_id: None,
+        }
+    }
+
+    fn bot_not_found(bot_id: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("no bot {bot_id}"))
+    }
+
+    /// A use case refused the request's values (bad side, unknown template,
+    /// risk out of range, ...).
+    fn unprocessable(message: String) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, message)
+    }
+
+    /// A read or action failed for reasons the caller cannot fix. The detail
+    /// may name tables, buckets or hosts, so it is only logged; the caller
+    /// gets the correlation id to quote instead.
+    fn internal(message: String) -> Self {
+        tracing::error!("http: {message}");
+        Self {
+            correlation_id: correlation_id(),
+            ..Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    }
+
+    fn body(&self) -> Value {
+        match &self.correlation_id {
+            Some(id) => json!({ "error": self.message, "correlation_id": id.to_string() }),
+            None => json!({ "error": self.message }),
+        }
+    }
+}
+
+type ApiResult = Result<Response<Full<Bytes>>, ApiError>;
+
+/// Route one request. Never fails: errors become JSON error responses.
+///
+/// Each request is one action, with its own correlation id.
+pub(super) async fn handle(deps: &Deps, req: Request<Incoming>) -> Response<Full<Bytes>> {
+    let correlation_id = CorrelationId::new();
+    let span = tracing::info_span!(
+        "http_request",
+        correlation_id = %correlation_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+        user_id = tracing::field::Empty,
+    );
+    with_correlation_id(correlation_id, route(deps, req).instrument(span))
+        .await
+        .unwrap_or_else(|e| json_response(e.status, e.body()))
+}
+
+async fn route(deps: &Deps, req: Request<Incoming>) -> ApiResult {
+    let path = req.uri().path().to_string();
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(|s| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    if req.method() == Method::GET && segments == ["openapi.json"] {
+        return Ok(Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Full::new(Bytes::from_static(OPENAPI.as_bytes())))
+            .expect("static response"));
+    }
+    if segments.first() != Some(&"v1") {
+        return Err(no_such_route());
+    }
+
+    let user_id = authenticate(deps, &req).await?;
+    let user_id = user_id.as_str();
+    tracing::Span::current().record("user_id", user_id);
+    match (req.method().clone(), &segments[1..]) {
+        (Method::GET, ["templates"]) => list_templates(deps).await,
+        (Method::GET, ["bots"]) => list_bots(deps, user_id, req.uri().query()).await,
+        (Method::POST, ["bots"]) => add_bot(deps, user_id, read_json(req).await?).await,
+        (Method::DELETE, ["bots", bot_id]) => delete_bot(deps, user_id, bot_id).await,
+        (Method::GET, ["bots", bot_id, "state"]) => bot_state(deps, user_id, bot_id).await,
+        (Method::POST, ["bots", bot_id, "start"]) => start_bot(deps, user_id, bot_id).await,
+        (Method::POST, ["bots", bot_id, "stop"]) => stop_bot(deps, user_id, bot_id).await,
+        (Method::PUT, ["bots", bot_id, "template"]) => {
+            let body = read_json(req).await?;
+            apply_template(deps, user_id, bot_id, body).await
+        }
+        (Method::PUT, ["bots", bot_id, "risk"]) => {
+            let body = read_json(req).await?;
+            set_risk(deps, user_id, bot_id, body).await
+        }
+        (Method::PUT, ["bots", bot_id, "sides", side]) => {
+            let body = read_json(req).await?;
+            set_side(deps, user_id, bot_id, side, body).await
+        }
+        _ => Err(no_such_route()),
+    }
+}
+
+fn no_such_route() -> ApiError {
+    ApiError::new(StatusCode::NOT_FOUND, "no such route")
+}
+
+/// The user the request's bearer token was issued to.
+async fn authenticate(deps: &Deps, req: &Request<Incoming>) -> Result<String, ApiError> {
+    let unauthorized = || ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid API token");
+    let secret = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(unauthorized)?;
+    deps.authenticate_api_token_usecase
+        .execute(secret)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(unauthorized)
+}
+
+async fn read_json<T: DeserializeOwned>(req: Request<Incoming>) -> Result<T, ApiError> {
+    let body = Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, format!("unreadable body: {e}")))?
+        .to_bytes();
+    serde_json::from_slice(&body)
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, format!("invalid JSON body: {e}")))
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("static response")
+}
+
+fn ok(body: Value) -> ApiResult {
+    Ok(json_response(StatusCode::OK, body))
+}
+
+/// A bot as the API shows it. Never includes the exchange keys.
+fn bot_json(bot: &Bot, runtime: Option<&BotRuntime>) -> Value {
+    json!({
+        "id": bot.id,
+        "name": bot.name,
+        "exchange": bot.exchange.as_str(),
+        "enabled": bot.enabled,
+        "phase": runtime.map(|r| r.phase.as_str()),
+        "task_id": runtime.and_then(|r| r.task_id.as_deref()),
+        "created_at": bot.created_at,
+        "updated_at": bot.updated_at,
+    })
+}
+
+/// The caller's bot `bot_id`, or 404: a bot of another user is reported
+/// exactly like one that does not exist.
+async fn own_bot(deps: &Deps, user_id: &str, bot_id: &str) -> Result<Bot, ApiError> {
+    deps.list_bots_usecase
+        .find(user_id, bot_id)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bot_not_found(bot_id))
+}
+
+async fn list_templates(deps: &Deps) -> ApiResult {
+    let templates = deps
+        .list_templates_usecase
+        .execute()
+        .await
+        .map_err(ApiError::internal)?;
+    ok(json!({ "templates": templates }))
+}
+
+async fn list_bots(deps: &Deps, user_id: &str, query: Option<&str>) -> ApiResult {
+    let mut after = None;
+    let mut limit = DEFAULT_PAGE_SIZE;
+    for (key, value) in form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+        match key.as_ref() {
+            "after" if !value.is_empty() => after = Some(value.into_owned()),
+            "limit" => {
+                limit = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=MAX_PAGE_SIZE).contains(n))
+                    .ok_or_else(|| {
+                        ApiError::new(
+                            StatusCode::BAD_REQUEST,
+                            format!("limit must be between 1 and {MAX_PAGE_SIZE}"),
+                        )
+                    })?
+            }
+            _ => {}
+        }
+    }
+    let page = deps
+        .list_bots_with_runtime_usecase
+        .page(user_id, after.as_deref(), limit)
+        .await
+        .map_err(ApiError::internal)?;
+    let bots: Vec<Value> = page
+        .bots
+        .iter()
+        .map(|(bot, runtime)| bot_json(bot, runtime.as_ref()))
+        .collect();
+    ok(json!({ "bots": bots, "next_cursor": page.next_cursor }))
+}
+
+#[derive(Deserialize)]
+struct AddBotRequest {
+    name: String,
+    api_key: String,
+    secret_key: String,
+    /// Replace the keys of an existing bot of the same name instead of 409.
+    #[serde(default)]
+    overwrite: bool,
+}
+
+async fn add_bot(deps: &Deps, user_id: &str, req: AddBotRequest) -> ApiResult {
+    let AddBotRequest {
+        name,
+        api_key,
+        secret_key,
+        overwrite,
+    } = req;
+    if overwrite {
+        let bot = deps
+            .add_bot_usecase
+            .overwrite(user_id, name, api_key, secret_key)
+            .await
+            .map_err(ApiError::unprocessable)?;
+        return ok(bot_json(&bot, None));
+    }
+    match deps
+        .add_bot_usecase
+        .execute(user_id, name, api_key, secret_key)
+        .await
+        .map_err(ApiError::unprocessable)?
+    {
+        AddOutcome::Added(bot) => Ok(json_response(StatusCode::CREATED, bot_json(&bot, None))),
+        AddOutcome::AlreadyExists(bot) => Err(ApiError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "bot {} already exists; send \"overwrite\": true to replace its keys",
+                bot.id
+            ),
+        )),
+    }
+}
+
+async fn delete_bot(deps: &Deps, user_id: &str, bot_id: &str) -> ApiResult {
+    own_bot(deps, user_id, bot_id).await?;
+    deps.delete_bot_usecase
+        .execute(user_id, bot_id)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Full::new(Bytes::new()))
+        .expect("static response"))
+}
+
+async fn bot_state(deps: &Deps, user_id: &str, bot_id: &str) -> ApiResult {
+    let bot = own_bot(deps, user_id, bot_id).await?;
+    let runtime = deps
+        .get_bot_runtime_usecase
+        .execute(user_id, bot_id)
+        .await
+        .map_err(ApiError::internal)?;
+    // A bot without a config yet (no template applied) has no sides.
+    let sides = deps
+        .get_bot_config_usecase
+        .execute(user_id, bot_id)
+        .await
+        .ok()
+        .map(|config| {
+            json!({
+                "long": config.side_enabled("long"),
+                "short": config.side_enabled("short"),
+            })
+        });
+    let mut body = bot_json(&bot, runtime.as_ref());
+    body["observed_at"] = json!(runtime.as_ref().map(|r| r.observed_at));
+    body["restart_attempts"] = json!(runtime.as_ref().map(|r| r.restart_attempts));
+    body["sides"] = json!(sides);
+    ok(body)
+}
+
+async fn start_bot(deps: &Deps, user_id: &str, bot_id: &str) -> ApiResult {
+    let outcome = deps
+        .start_bot_usecase
+        .execute(user_id, bot_id)
+        .await
+        .map_err(ApiError::internal)?;
+    match outcome {
+        StartOutcome::Started { task_id } => {
+            ok(json!({ "outcome": "started", "task_id": task_id }))
+        }
+        StartOutcome::AlreadyRunning => ok(json!({ "outcome": "already_running" })),
+        StartOutcome::AlreadyStarting => ok(json!({ "outcome": "already_starting" })),
+        StartOutcome::Stopping => Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "the previous task is still stopping; retry once it has stopped",
+        )),
+        StartOutcome::BotNotFound => Err(ApiError::bot_not_found(bot_id)),
+        StartOutcome::Rejected(problems) => Err(ApiError::unprocessable(problems)),
+    }
+}
+
+async fn stop_bot(deps: &Deps, user_id: &str, bot_id: &str) -> ApiResult {
+    let outcome = deps
+        .stop_bot_usecase
+        .execute(user_id, bot_id)
+        .await
+        .map_err(ApiError::internal)?;
+    match outcome {
+        StopOutcome::Stopped { task_id } => ok(json!({ "outcome": "stopped", "task_id": task_id })),
+        StopOutcome::NotRunning => ok(json!({ "outcome": "not_running" })),
+        StopOutcome::AlreadyStopping => ok(json!({ "outcome": "already_stopping" })),
+        // Desired state is already off; a retry once the task is up stops it.
+        StopOutcome::StartInProgress => Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "the bot is still starting; retry once it is running",
+        )),
+        StopOutcome::BotNotFound => Err(ApiError::bot_not_found(bot_id)),
+    }
+}
+
+#[derive(Deserialize)]
+struct TemplateRequest {
+    template: String,
+}
+
+async fn apply_template(
+    deps: &Deps,
+    user_id: &str,
+    bot_id: &str,
+    req: TemplateRequest,
+) -> ApiResult {
+    own_bot(deps, user_id, bot_id).await?;
+    deps.apply_template_usecase
+        .execute(user_id, bot_id, &req.template)
+        .await
+        .map_err(ApiError::unprocessable)?;
+    ok(json!({ "template": req.template }))
+}
+
+#[derive(Deserialize)]
+struct RiskRequest {
+    long: f64,
+    short: f64,
+    /// Save even when the leveraged exposure is above the bot's ceiling.
+    #[serde(default)]
+    force: bool,
+}
+
+async fn set_risk(deps: &Deps, user_id: &str, bot_id: &str, req: RiskRequest) -> ApiResult {
+    own_bot(deps, user_id, bot_id).await?;
+    // The ceiling is a warning in the chat; here the caller acknowledges it
+    // with `force`.
+    let preview = deps
+        .update_risk_level_usecase
+        .preview(user_id, bot_id, req.long, req.short)
+        .await
+        .map_err(ApiError::unprocessable)?;
+    if preview.exceeds_ceiling() && !req.force {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "leveraged exposure {:.2} is above the bot's ceiling {:.2}; send \"force\": true to save anyway",
+                preview.leveraged_exposure,
+                preview.ceiling.unwrap_or_default()
+            ),
+        ));
+    }
+    let leverage = deps
+        .update_risk_level_usecase
+        .execute(user_id, bot_id, req.long, req.short)
+        .await
+        .map_err(ApiError::unprocessable)?;
+    ok(json!({
+        "risk": { "long": req.long, "short": req.short },
+        "leverage": { "long": leverage.long, "short": leverage.short },
+    }))
+}
+
+#[derive(Deserialize)]
+struct SideRequest {
+    enabled: bool,
+}
+
+async fn set_side(
+    deps: &Deps,
+    user_id: &str,
+    bot_id: &str,
+    side: &str,
+    req: SideRequest,
+) -> ApiResult {
+    own_bot(deps, user_id, bot_id).await?;
+    let enabled = deps
+        .set_strategy_side_usecase
+        .execute(user_id, bot_id, side, req.enabled)
+        .await
+        .map_err(ApiError::unprocessable)?;
+    ok(json!({ "side": side, "enabled": enabled }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::runtime::BotRuntimeRepository;
+    use crate::interface::fakes::Fakes;
+    use tokio::net::TcpListener;
+
+    /// The API served on a local port over the in-memory adapters.
+    struct Api {
+        base: String,
+        client: reqwest::Client,
+        fakes: Fakes,
+        deps: Deps,
+    }
+
+    impl Api {
+        async fn start() -> Self {
+            let fakes = Fakes::new();
+            let deps = fakes.deps();
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(crate::interface::http::serve(listener, deps.clone()));
+            Self {
+                base,
+                // No proxy: the server is local.
+                client: reqwest::Client::builder().no_proxy().build().unwrap(),
+                fakes,
+                deps,
+            }
+        }
+
+        async fn token(&self, user_id: &str) -> String {
+            self.deps
+                .issue_api_token_usecase
+                .execute(user_id)
+                .await
+                .unwrap()
+        }
+
+        /// Send a request; returns the status and the JSON body (`null` when
+        /// empty).
+        async fn call(
+            &self,
+            method: &str,
+            path: &str,
+            token: Option<&str>,
+            body: Option<Value>,
+        ) -> (u16, Value) {
+            let method = reqwest::Method::from_bytes(method.as_bytes()).unwrap();
+            let mut req = self
+                .client
+                .request(method, format!("{}{}", self.base, path));
+            if let Some(token) = token {
+                req = req.bearer_auth(token);
+            }
+            if let Some(body) = body {
+                req = req.json(&body);
+            }
+            let resp = req.send().await.unwrap();
+            let status = resp.status().as_u16();
+            let text = resp.text().await.unwrap();
+            let body = if text.is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_str(&text).unwrap()
+            };
+            (status, body)
+        }
+    }
+
+    #[tokio::test]
+    async fn an_internal_error_shows_only_its_correlation_id() {
+        let id = CorrelationId::new();
+        let err = with_correlation_id(id.clone(), async {
+            ApiError::internal("DynamoDB get_item failed: table pbtb-prod".to_string())
+        })
+        .await;
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            err.body(),
+            json!({ "error": "internal error", "correlation_id": id.to_string() })
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_need_a_live_token() {
+        let api = Api::start().await;
+        let (status, body) = api.call("GET", "/v1/bots", None, None).await;
+        assert_eq!(status, 401);
+        assert!(body["error"].as_str().unwrap().contains("API token"));
+        let (status, _) = api.call("GET", "/v1/bots", Some("pbtb_nope"), None).await;
+        assert_eq!(status, 401);
+
+        let token = api.token("alice").await;
+        let (status, _) = api.call("GET", "/v1/bots", Some(&token), None).await;
+        assert_eq!(status, 200);
+
+        api.deps
+            .revoke_api_token_usecase
+            .execute("alice")
+            .await
+            .unwrap();
+        let (status, _) = api.call("GET", "/v1/bots", Some(&token), None).await;
+        assert_eq!(status, 401);
+    }
+
+    #[tokio::test]
+    async fn a_bot_from_creation_to_deletion() {
+        let api = Api::start().await;
+        let token = api.token("alice").await;
+        let t = Some(token.as_str());
+        let keys = json!({ "name": "grid", "api_key": "ak", "secret_key": "sk" });
+
+        let (status, bot) = api.call("POST", "/v1/bots", t, Some(keys.clone())).await;
+        assert_eq!(status, 201);
+        assert_eq!(bot["id"], "grid");
+        assert!(bot.get("api_key").is_none() && bot.get("secret_key").is_none());
+        let (status, _) = api.call("POST", "/v1/bots", t, Some(keys)).await;
+        assert_eq!(status, 409);
+        let overwrite =
+            json!({ "name": "grid", "api_key": "ak2", "secret_key": "sk2", "overwrite": true });
+        let (status, _) = api.call("POST", "/v1/bots", t, Some(overwrite)).await;
+        assert_eq!(status, 200);
+
+        let (status, body) = api.call("GET", "/v1/templates", t, None).await;
+        assert_eq!(
+            (status, body["templates"].clone()),
+            (200, json!(["btc_grid"]))
+        );
+        let (status, _) = api
+            .call(
+                "PUT",
+                "/v1/bots/grid/template",
+                t,
+                Some(json!({ "template": "nope" })),
+            )
+            .await;
+        assert_eq!(status, 422);
+        let (status, _) = api
+            .call(
+                "PUT",
+                "/v1/bots/grid/template",
+                t,
+                Some(json!({ "template": "btc_grid" })),
+            )
+            .await;
+        assert_eq!(status, 200);
+
+        let (status, body) = api
+            .call(
+                "PUT",
+                "/v1/bots/grid/risk",
+                t,
+                Some(json!({ "long": 1.0, "short": 0.5 })),
+            )
+            .await;
+        assert_eq!(status, 200, "{body}");
+        assert_eq!(body["risk"], json!({ "long": 1.0, "short": 0.5 }));
+        let (status, body) = api
+            .call(
+                "PUT",
+                "/v1/bots/grid/sides/short",
+                t,
+                Some(json!({ "enabled": false })),
+            )
+            .await;
+        assert_eq!((status, body["enabled"].clone()), (200, json!(false)));
+        let (status, _) = api
+            .call(
+                "PUT",
+                "/v1/bots/grid/sides/sideways",
+                t,
+                Some(json!({ "enabled": true })),
+            )
+            .await;
+        assert_eq!(status, 422);
+
+        let (status, body) = api.call("POST", "/v1/bots/grid/start", t, None).await;
+        assert_eq!((status, body["outcome"].clone()), (200, json!("started")));
+        assert_eq!(api.fakes.backend.tasks().len(), 1);
+        let (status, body) = api.call("POST", "/v1/bots/grid/start", t, None).await;
+        assert_eq!(status, 200);
+        assert_ne!(body["outcome"], "started");
+        assert_eq!(api.fakes.backend.tasks().len(), 1);
+
+        let (status, state) = api.call("GET", "/v1/bots/grid/state", t, None).await;
+        assert_eq!(status, 200);
+        assert_eq!(state["enabled"], true);
+        assert_eq!(state["sides"], json!({ "long": true, "short": false }));
+        let runtime = BotRuntimeRepository::find(api.fakes.repo.as_ref(), "alice", "grid")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state["phase"], runtime.phase.as_str());
+
+        let (status, body) = api.call("POST", "/v1/bots/grid/stop", t, None).await;
+        assert_eq!(status, 200, "{body}");
+        let (status, body) = api.call("GET", "/v1/bots", t, None).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["bots"][0]["enabled"], false);
+        assert_eq!(body["next_cursor"], Value::Null);
+
+        let (status, body) = api.call("DELETE", "/v1/bots/grid", t, None).await;
+        assert_eq!((status, body), (204, Value::Null));
+        let (status, _) = api.call("GET", "/v1/bots/grid/state", t, None).await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn a_bot_name_that_cannot_be_a_key_is_unprocessable() {
+        let api = Api::start().await;
+        let token = api.token("alice").await;
+        let t = Some(token.as_str());
+
+        for overwrite in [false, true] {
+            let keys = json!({
+                "name": "grid#1",
+                "api_key": "ak",
+                "secret_key": "sk",
+                "overwrite": overwrite,
+            });
+            let (status, body) = api.call("POST", "/v1/bots", t, Some(keys)).await;
+            assert_eq!(status, 422, "{body}");
+            assert!(body["error"].as_str().unwrap().contains("invalid bot name"));
+        }
+        let (_, body) = api.call("GET", "/v1/bots", t, None).await;
+        assert_eq!(body["bots"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn the_bot_list_pages_by_cursor() {
+        let api = Api::start().await;
+        let token = api.token("alice").await;
+        let t = Some(token.as_str());
+        for name in ["a", "b", "c"] {
+            let keys = json!({ "name": name, "api_key": "ak", "secret_key": "sk" });
+            assert_eq!(api.call("POST", "/v1/bots", t, Some(keys)).await.0, 201);
+        }
+
+        let (_, first) = api.call("GET", "/v1/bots?limit=2", t, None).await;
+        assert_eq!(first["bots"].as_array().unwrap().len(), 2);
+        assert_eq!(first["next_cursor"], "b");
+        let (_, rest) = api.call("GET", "/v1/bots?limit=2&after=b", t, None).await;
+        assert_eq!(rest["bots"][0]["id"], "c");
+        assert_eq!(rest["next_cursor"], Value::Null);
+
+        let (status, _) = api.call("GET", "/v1/bots?limit=0", t, None).await;
+        assert_eq!(status, 400);
+    }
+
+    #[tokio::test]
+    async fn another_users_bots_look_absent() {
+        let api = Api::start().await;
+        let alice = api.token("alice").await;
+        let bob = api.token("bob").await;
+        let keys = json!({ "name": "grid", "api_key": "ak", "secret_key": "sk" });
+        assert_eq!(
+            api.call("POST", "/v1/bots", Some(&alice), Some(keys))
+                .await
+                .0,
+            201
+        );
+
+        let bob = Some(bob.as_str());
+        for (method, path, body) in [
+            ("GET", "/v1/bots/grid/state", None),
+            ("POST", "/v1/bots/grid/start", None),
+            ("POST", "/v1/bots/grid/stop", None),
+            (
+                "PUT",
+                "/v1/bots/grid/template",
+                Some(json!({ "template": "btc_grid" })),
+            ),
+            (
+                "PUT",
+                "/v1/bots/grid/sides/long",
+                Some(json!({ "enabled": false })),
+            ),
+            ("DELETE", "/v1/bots/grid", None),
+        ] {
+            let (status, _) = api.call(method, path, bob, body).await;
+            assert_eq!(status, 404, "{method} {path}");
+        }
+        let (_, list) = api.call("GET", "/v1/bots", bob, None).await;
+        assert_eq!(list["bots"], json!([]));
+        assert!(api.fakes.backend.tasks().is_empty());
+        let (_, list) = api.call("GET", "/v1/bots", Some(&alice), None).await;
+        assert_eq!(list["bots"][0]["id"], "grid");
+    }
+
+    /// Every operation in the OpenAPI document reaches a handler.
+    #[tokio::test]
+    async fn every_documented_route_is_served() {
+        let api = Api::start().await;
+        let token = api.token("alice").await;
+        let doc: Value = serde_json::from_str(OPENAPI).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.len() >= 8);
+        for (path, item) in paths {
+            let concrete = path.replace("{bot_id}", "grid").replace("{side}", "long");
+            for method in ["get", "post", "put", "delete"] {
+                if item.get(method).is_none() {
+                    continue;
+                }
+                let (_, body) = api
+                    .call(
+                        &method.to_uppercase(),
+                        &concrete,
+                        Some(&token),
+                        Some(json!({})),
+                    )
+                    .await;
+                assert_ne!(body["error"], "no such route", "{method} {path}");
+            }
+        }
+        let (status, body) = api.call("GET", "/v1/nope", Some(&token), None).await;
+        assert_eq!(
+            (status, body["error"].clone()),
+            (404, json!("no such route"))
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! The Prometheus scrape endpoint: `GET /metrics` renders the telebot's
+//! [`PrometheusMetrics`]. It runs on its own listener, apart from the
+//! authenticated management API, and serves nothing else.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use crate::infra::PrometheusMetrics;
+
+const CONTENT_TYPE_TEXT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serve `metrics` on `listener` until the process exits.
+pub async fn serve(listener: TcpListener, metrics: Arc<PrometheusMetrics>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                tracing::warn!("metrics: accept failed: {e}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let metrics = metrics.clone();
+                async move { Ok::<_, Infallible>(handle(&metrics, &req)) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("metrics: connection closed: {e}");
+            }
+        });
+    }
+}
+
+fn handle<B>(metrics: &PrometheusMetrics, req: &Request<B>) -> Response<Full<Bytes>> {
+    let (status, body) = if req.method() == Method::GET && req.uri().path() == "/metrics" {
+        (StatusCode::OK, metrics.render())
+    } else {
+        (StatusCode::NOT_FOUND, String::new())
+    };
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, CONTENT_TYPE_TEXT)
+        .body(Full::new(Bytes::from(body)))
+        .expect("static response")
+}
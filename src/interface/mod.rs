@@ -1,2 +1,79 @@
 // Rust
+#[cfg(feature = "docker")]
+pub mod docker_events;
+#[cfg(test)]
+mod fakes;
+pub mod http;
+pub mod metrics;
 pub mod telegram;
+
+// Dependencies aggregation for handlers, shared by the Telegram and HTTP
+// front ends.
+use crate::usecase::*;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct Deps {
+    // Bot management
+    pub list_bots_usecase: Arc<ListBotsUseCase>,
+    pub list_bots_with_runtime_usecase: Arc<ListBotsWithRuntimeUseCase>,
+    pub add_bot_usecase: Arc<AddBotUseCase>,
+    pub delete_bot_usecase: Arc<DeleteBotUseCase>,
+
+    // Template management
+    pub list_templates_usecase: Arc<ListTemplatesUseCase>,
+
+    // Bot config management
+    pub apply_template_usecase: Arc<ApplyTemplateUseCase>,
+    pub get_bot_config_usecase: Arc<GetBotConfigUseCase>,
+    pub update_bot_config_usecase: Arc<UpdateBotConfigUseCase>,
+    pub update_risk_level_usecase: Arc<UpdateRiskLevelUseCase>,
+    pub get_risk_presets_usecase: Arc<GetRiskPresetsUseCase>,
+    pub set_leverage_policy_usecase: Arc<SetLeveragePolicyUseCase>,
+    pub set_restart_policy_usecase: Arc<SetRestartPolicyUseCase>,
+    pub set_resource_profile_usecase: Arc<SetResourceProfileUseCase>,
+    pub set_exposure_ceiling_usecase: Arc<SetExposureCeilingUseCase>,
+    pub set_strategy_side_usecase: Arc<SetStrategySideUseCase>,
+    pub get_approved_coins_usecase: Arc<GetApprovedCoinsUseCase>,
+    pub set_approved_coins_usecase: Arc<SetApprovedCoinsUseCase>,
+
+    // Runtime / desired-state management
+    pub get_bot_runtime_usecase: Arc<GetBotRuntimeUseCase>,
+    pub get_bot_history_usecase: Arc<GetBotHistoryUseCase>,
+
+    // ECS actuation (desired state -> real RunTask/StopTask)
+    pub start_bot_usecase: Arc<StartBotUseCase>,
+    pub stop_bot_usecase: Arc<StopBotUseCase>,
+
+    // Scheduled actions
+    pub create_schedule_usecase: Arc<CreateScheduleUseCase>,
+    pub list_schedules_usecase: Arc<ListSchedulesUseCase>,
+    pub delete_schedule_usecase: Arc<DeleteScheduleUseCase>,
+
+    // Bulk actions across all of a user's bots
+    pub bulk_bot_action_usecase: Arc<BulkBotActionUseCase>,
+    pub panic_usecase: Arc<PanicUseCase>,
+
+    // Performance read from the bot's exchange sub-account
+    pub get_performance_usecase: Arc<GetPerformanceUseCase>,
+
+    // Open positions and orders, closed / cancelled by hand
+    pub get_positions_usecase: Arc<GetPositionsUseCase>,
+    pub close_position_usecase: Arc<ClosePositionUseCase>,
+    pub cancel_order_usecase: Arc<CancelOrderUseCase>,
+
+    // Daily digest settings and on-demand preview
+    pub get_preferences_usecase: Arc<GetPreferencesUseCase>,
+    pub set_digest_hour_usecase: Arc<SetDigestHourUseCase>,
+    pub build_daily_digest_usecase: Arc<BuildDailyDigestUseCase>,
+
+    // Per-bot risk guard settings (the guard job itself is a Lambda)
+    pub get_risk_guard_usecase: Arc<GetRiskGuardUseCase>,
+    pub set_risk_guard_limit_usecase: Arc<SetRiskGuardLimitUseCase>,
+    pub rearm_risk_guard_usecase: Arc<RearmRiskGuardUseCase>,
+
+    // HTTP API tokens
+    pub issue_api_token_usecase: Arc<IssueApiTokenUseCase>,
+    pub revoke_api_token_usecase: Arc<RevokeApiTokenUseCase>,
+    pub authenticate_api_token_usecase: Arc<AuthenticateApiTokenUseCase>,
+}
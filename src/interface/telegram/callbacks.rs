@@ -3,6 +3,8 @@ use super::{
     states::{BotContext, DialogueState},
     types,
 };
+use crate::domain::trading::PositionSide;
+use crate::usecase::{BulkAction, CancelOrderOutcome, ClosePositionOutcome};
 use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
 use teloxide::prelude::*;
 use teloxide::types::CallbackQuery;
@@ -10,6 +12,12 @@ use teloxide::types::CallbackQuery;
 type MyDialogue = Dialogue<DialogueState, InMemStorage<DialogueState>>;
 type MyBotContext = Dialogue<BotContext, InMemStorage<BotContext>>;
 
+/// Amount one tap on a risk stepper moves a side's exposure.
+const RISK_STEP: f64 = 0.1;
+
+/// How long the final kill switch button stays valid after the first tap.
+const PANIC_CONFIRM_SECS: i64 = 60;
+
 pub fn routes() -> teloxide::dispatching::UpdateHandler<DependencyMap> {
     dptree::entry().branch(
         Update::filter_callback_query()
@@ -43,6 +51,13 @@ async fn handle_callback(
         return Ok(());
     }
 
+    // Paging through the bot list re-renders the list keyboard in place
+    if let Some(cursor) = data.strip_prefix("list_page:") {
+        let after = (!cursor.is_empty()).then(|| cursor.to_string());
+        handle_list_page(bot, q, deps, after.as_deref()).await?;
+        return Ok(());
+    }
+
     // Check if this is a strategy-side toggle callback
     if data.starts_with("toggle_side:") {
         handle_toggle_side(bot, q, deps, bot_context).await?;
@@ -61,6 +76,30 @@ async fn handle_callback(
         return Ok(());
     }
 
+    // Risk-level editor: presets, steppers, save and cancel.
+    if data.starts_with("risk_") {
+        handle_risk_editor(bot, q, deps, dialogue, bot_context).await?;
+        return Ok(());
+    }
+
+    // Bulk actions across all of the user's bots (confirm / cancel).
+    if data.starts_with("bulk_") {
+        handle_bulk(bot, q, deps).await?;
+        return Ok(());
+    }
+
+    // Kill switch: two confirmations, then flatten and stop everything.
+    if data.starts_with("panic_") {
+        handle_panic(bot, q, deps).await?;
+        return Ok(());
+    }
+
+    // Positions screen: close (after a confirmation), cancel an order, refresh.
+    if data.starts_with("pos_") {
+        handle_positions(bot, q, deps, bot_context).await?;
+        return Ok(());
+    }
+
     // Check if this is a cancel template selection callback
     if data == "cancel_template_selection" {
         handle_cancel_template_selection(bot, q).await?;
@@ -109,6 +148,41 @@ async fn handle_action(
 
 /// Handle bot selection callback
 /// Records the selected bot_id in BotContext for later use
+/// Replace the bot-list keyboard with the page after `after` (the first page
+/// when `None`).
+async fn handle_list_page(
+    bot: Bot,
+    q: CallbackQuery,
+    deps: Deps,
+    after: Option<&str>,
+) -> anyhow::Result<()> {
+    let user_id = q.from.id.to_string();
+    bot.answer_callback_query(&q.id).await?;
+
+    let page = match deps
+        .list_bots_with_runtime_usecase
+        .page(&user_id, after, super::BOT_LIST_PAGE_SIZE)
+        .await
+    {
+        Ok(page) => page,
+        Err(e) => {
+            if let Some(Message { chat, .. }) = q.message {
+                bot.send_message(chat.id, format!("❌ Error fetching bots: {}", e))
+                    .await?;
+            }
+            return Ok(());
+        }
+    };
+
+    if let Some(Message { id, chat, .. }) = q.message {
+        let keyboard = super::bot_list_markup(page, after);
+        bot.edit_message_reply_markup(chat.id, id)
+            .reply_markup(keyboard)
+            .await?;
+    }
+    Ok(())
+}
+
 async fn handle_bot_selection(
     bot: Bot,
     q: CallbackQuery,
@@ -398,3 +472,721 @@ async fn handle_cancel_template_selection(bot: Bot, q: CallbackQuery) -> anyhow:
 
     Ok(())
 }
+
+/// Risk-level editor buttons. The pending values and the bot they were
+/// previewed for live in the dialogue state (`EditRiskLevel`); presets and
+/// steppers replace them and re-render the editor in place, Save persists
+/// them, Cancel closes the editor. Nothing but Cancel acts once another bot
+/// is selected.
+async fn handle_risk_editor(
+    bot: Bot,
+    q: CallbackQuery,
+    deps: Deps,
+    dialogue: MyDialogue,
+    bot_context: MyBotContext,
+) -> anyhow::Result<()> {
+    let data = q.data.clone().unwrap_or_default();
+    if data == "risk_noop" {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    }
+
+    // Buttons on an editor the user already left do nothing.
+    let (bot_id, risk_long, risk_short) = match dialogue.get().await? {
+        Some(DialogueState::EditRiskLevel {
+            bot_id,
+            risk_long,
+            risk_short,
+        }) => (bot_id, risk_long, risk_short),
+        _ => {
+            bot.answer_callback_query(&q.id)
+                .text("This risk editor is closed. Tap 'Risk level' to open it again.")
+                .show_alert(true)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let user_id = q.from.id.to_string();
+
+    if data == "risk_cancel" {
+        dialogue.update(DialogueState::Start).await?;
+        bot.answer_callback_query(&q.id)
+            .text("❌ Cancelled")
+            .await?;
+        if let Some(Message { id, chat, .. }) = q.message {
+            bot.edit_message_text(chat.id, id, "🚫 Risk level update cancelled.")
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let selected = bot_context.get().await?.unwrap_or_default().selected_bot_id;
+    if selected.as_deref() != Some(bot_id.as_str()) {
+        bot.answer_callback_query(&q.id)
+            .text(super::views::format_risk_editor_moved(&bot_id))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    if data == "risk_save" {
+        match deps
+            .update_risk_level_usecase
+            .execute(&user_id, &bot_id, risk_long, risk_short)
+            .await
+        {
+            Ok(leverage) => {
+                dialogue.update(DialogueState::Start).await?;
+                bot.answer_callback_query(&q.id).text("✅ Saved").await?;
+                if let Some(Message { id, chat, .. }) = q.message {
+                    bot.edit_message_text(
+                        chat.id,
+                        id,
+                        super::views::format_risk_saved(&bot_id, risk_long, risk_short, &leverage),
+                    )
+                    .await?;
+                }
+            }
+            Err(e) => {
+                bot.answer_callback_query(&q.id)
+                    .text(format!("❌ {}", e))
+                    .show_alert(true)
+                    .await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let (new_long, new_short) = if let Some(values) = data.strip_prefix("risk_set:") {
+        match values
+            .split_once('/')
+            .and_then(|(l, s)| Some((l.parse::<f64>().ok()?, s.parse::<f64>().ok()?)))
+        {
+            Some(v) => v,
+            None => return Ok(()),
+        }
+    } else if let Some(step) = data.strip_prefix("risk_step:") {
+        let delta = if step.ends_with(":up") {
+            RISK_STEP
+        } else {
+            -RISK_STEP
+        };
+        let stepped = |v: f64| ((v + delta).clamp(0.0, 10.0) * 100.0).round() / 100.0;
+        if step.starts_with("long:") {
+            (stepped(risk_long), risk_short)
+        } else {
+            (risk_long, stepped(risk_short))
+        }
+    } else {
+        return Ok(());
+    };
+
+    match super::risk_editor(&deps, &user_id, &bot_id, new_long, new_short).await {
+        Ok((text, keyboard)) => {
+            dialogue
+                .update(DialogueState::EditRiskLevel {
+                    bot_id: bot_id.clone(),
+                    risk_long: new_long,
+                    risk_short: new_short,
+                })
+                .await?;
+            bot.answer_callback_query(&q.id).await?;
+            if let Some(Message { id, chat, .. }) = q.message {
+                // Editing to identical content fails (e.g. a stepper at its
+                // bound); that is harmless.
+                bot.edit_message_text(chat.id, id, text)
+                    .reply_markup(keyboard)
+                    .await
+                    .ok();
+            }
+        }
+        Err(e) => {
+            bot.answer_callback_query(&q.id)
+                .text(format!("❌ {}", e))
+                .show_alert(true)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a confirmed bulk action (`bulk_run:<action>`) and replace the prompt
+/// with the per-bot report, or drop the prompt on `bulk_cancel`.
+async fn handle_bulk(bot: Bot, q: CallbackQuery, deps: Deps) -> anyhow::Result<()> {
+    let data = q.data.as_deref().unwrap_or("");
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+
+    if data == "bulk_cancel" {
+        bot.answer_callback_query(&q.id).text("Cancelled").await?;
+        bot.edit_message_text(message.chat.id, message.id, "❌ Bulk action cancelled.")
+            .await?;
+        return Ok(());
+    }
+
+    let action = match data.strip_prefix("bulk_run:").map(BulkAction::parse) {
+        Some(Ok(action)) => action,
+        _ => {
+            bot.answer_callback_query(&q.id)
+                .text("⚠️ Unknown bulk action")
+                .show_alert(true)
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Drop the buttons first so a second tap cannot run the action twice.
+    bot.answer_callback_query(&q.id)
+        .text("⏳ Working...")
+        .await?;
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        format!("⏳ {}...", action.label()),
+    )
+    .await?;
+
+    let user_id = q.from.id.to_string();
+    let text = match deps
+        .bulk_bot_action_usecase
+        .execute(&user_id, &action)
+        .await
+    {
+        Ok(report) => super::views::format_bulk_report(&report),
+        Err(e) => format!("❌ {} failed:\n\n{}", action.label(), e),
+    };
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .await?;
+    Ok(())
+}
+
+/// Kill switch callbacks: `panic_arm` (first confirmation) swaps in the final
+/// button stamped with the current time; `panic_fire:<armed_at>` runs
+/// `PanicUseCase` if the stamp is recent enough; `panic_cancel` aborts.
+async fn handle_panic(bot: Bot, q: CallbackQuery, deps: Deps) -> anyhow::Result<()> {
+    let data = q.data.as_deref().unwrap_or("");
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    if data == "panic_cancel" {
+        bot.answer_callback_query(&q.id).text("Cancelled").await?;
+        bot.edit_message_text(message.chat.id, message.id, "❌ Emergency stop cancelled.")
+            .await?;
+        return Ok(());
+    }
+
+    if data == "panic_arm" {
+        bot.answer_callback_query(&q.id).await?;
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            super::views::format_panic_armed(PANIC_CONFIRM_SECS),
+        )
+        .reply_markup(super::keyboards::panic_fire_keyboard(now))
+        .await?;
+        return Ok(());
+    }
+
+    let armed_at = data
+        .strip_prefix("panic_fire:")
+        .and_then(|ts| ts.parse::<i64>().ok());
+    match armed_at {
+        Some(ts) if now - ts <= PANIC_CONFIRM_SECS => {}
+        Some(_) => {
+            bot.answer_callback_query(&q.id)
+                .text("⌛ Confirmation expired. Send /panic again.")
+                .show_alert(true)
+                .await?;
+            bot.edit_message_text(message.chat.id, message.id, "⌛ Emergency stop expired.")
+                .await?;
+            return Ok(());
+        }
+        None => {
+            bot.answer_callback_query(&q.id).await?;
+            return Ok(());
+        }
+    }
+
+    // Drop the buttons first so a second tap cannot run it twice.
+    bot.answer_callback_query(&q.id)
+        .text("🚨 Emergency stop running...")
+        .await?;
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        "🚨 Emergency stop running: closing positions and stopping bots...",
+    )
+    .await?;
+
+    let user_id = q.from.id.to_string();
+    let text = match deps.panic_usecase.execute(&user_id).await {
+        Ok(report) => super::views::format_panic_report(&report),
+        Err(e) => format!(
+            "❌ Emergency stop failed before reaching the bots:\n\n{}\n\n\
+            Close positions on the exchange directly.",
+            e
+        ),
+    };
+    bot.send_message(message.chat.id, text).await?;
+    Ok(())
+}
+
+/// Positions screen callbacks, all acting on the selected bot and carrying a
+/// tag of the bot the screen was rendered for (refused when they differ):
+/// `pos_refresh:<tag>` re-renders the screen; `pos_close:<tag>:<symbol>:<side>`
+/// swaps in a confirmation for that position; `pos_confirm:<tag>:<symbol>:<side>`
+/// closes it; `pos_cancel:<tag>:<order_id>` cancels the order. Each action
+/// ends on a fresh screen with its outcome on top.
+async fn handle_positions(
+    bot: Bot,
+    q: CallbackQuery,
+    deps: Deps,
+    bot_context: MyBotContext,
+) -> anyhow::Result<()> {
+    let data = q.data.as_deref().unwrap_or("");
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(&q.id).await?;
+        return Ok(());
+    };
+    let Some(bot_id) = bot_context.get().await?.unwrap_or_default().selected_bot_id else {
+        bot.answer_callback_query(&q.id)
+            .text("❌ No bot selected")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    };
+    let user_id = q.from.id.to_string();
+
+    // A screen left open from before another bot was selected must not act
+    // on the new one.
+    let tag = super::keyboards::positions_bot_tag(&bot_id);
+    if data.split(':').nth(1) != Some(tag.as_str()) {
+        bot.answer_callback_query(&q.id)
+            .text("❌ This screen is for another bot. Open Positions again for the selected bot.")
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+    let args = |prefix: &str| {
+        data.strip_prefix(prefix)?
+            .split_once(':')
+            .map(|(_, rest)| rest)
+    };
+
+    let position_key = |rest: &str| {
+        let (symbol, side) = rest.rsplit_once(':')?;
+        let side = match side {
+            "long" => PositionSide::Long,
+            "short" => PositionSide::Short,
+            _ => return None,
+        };
+        Some((symbol.to_string(), side))
+    };
+
+    let status = if let Some(key) = args("pos_close:").and_then(position_key) {
+        bot.answer_callback_query(&q.id).await?;
+        let (symbol, side) = key;
+        let open = deps
+            .get_positions_usecase
+            .execute(&user_id, &bot_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|r| {
+                r.positions
+                    .into_iter()
+                    .find(|p| p.symbol == symbol && p.side == side)
+            });
+        match open {
+            Some(position) => {
+                bot.edit_message_text(
+                    message.chat.id,
+                    message.id,
+                    super::views::format_close_confirm(&bot_id, &position),
+                )
+                .reply_markup(super::keyboards::close_position_confirm_keyboard(
+                    &bot_id, &position,
+                ))
+                .await?;
+                return Ok(());
+            }
+            None => Some(format!(
+                "ℹ️ {} {} is no longer open.",
+                symbol,
+                side.as_str()
+            )),
+        }
+    } else if let Some(key) = args("pos_confirm:").and_then(position_key) {
+        let (symbol, side) = key;
+        // Drop the buttons first so a second tap cannot close twice.
+        bot.answer_callback_query(&q.id)
+            .text("⏳ Closing...")
+            .await?;
+        bot.edit_message_text(
+            message.chat.id,
+            message.id,
+            format!("⏳ Closing {} {}...", symbol, side.as_str()),
+        )
+        .await?;
+        Some(
+            match deps
+                .close_position_usecase
+                .execute(&user_id, &bot_id, &symbol, side)
+                .await
+            {
+                Ok(ClosePositionOutcome::Closed(p)) => format!(
+                    "✅ Sent a reduce-only market order closing {} {} {}.",
+                    p.symbol,
+                    p.side.as_str(),
+                    p.size
+                ),
+                Ok(ClosePositionOutcome::NotOpen) => {
+                    format!("ℹ️ {} {} is no longer open.", symbol, side.as_str())
+                }
+                Ok(ClosePositionOutcome::BotNotFound) => format!("❌ Bot {} not found.", bot_id),
+                Err(e) => format!("❌ Failed to close {} {}:\n\n{}", symbol, side.as_str(), e),
+            },
+        )
+    } else if let Some(order_id) = args("pos_cancel:") {
+        bot.answer_callback_query(&q.id).await?;
+        Some(
+            match deps
+                .cancel_order_usecase
+                .execute(&user_id, &bot_id, order_id)
+                .await
+            {
+                Ok(CancelOrderOutcome::Cancelled(o)) => {
+                    format!("✅ Cancelled {}.", super::views::format_order(&o))
+                }
+                Ok(CancelOrderOutcome::NotOpen) => "ℹ️ That order is no longer open.".to_string(),
+                Ok(CancelOrderOutcome::BotNotFound) => format!("❌ Bot {} not found.", bot_id),
+                Err(e) => format!("❌ Failed to cancel the order:\n\n{}", e),
+            },
+        )
+    } else {
+        // `pos_refresh:<tag>`, and the Back button of the close confirmation.
+        bot.answer_callback_query(&q.id).await?;
+        None
+    };
+
+    let (text, keyboard) =
+        super::positions_screen(&deps, &user_id, &bot_id, status.as_deref()).await;
+    match keyboard {
+        Some(keyboard) => {
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        None => {
+            bot.edit_message_text(message.chat.id, message.id, text)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::account::OrderSide;
+    use crate::domain::bot::{Bot, BotRepository};
+    use crate::domain::botconfig::{BotConfig, BotConfigRepository, BotType};
+    use crate::domain::runtime::{BotRuntimeRepository, RuntimePhase};
+    use crate::domain::trading::{ExchangeTradingGateway, Order, Position, PositionSide};
+    use crate::interface::telegram::BOT_LIST_PAGE_SIZE;
+    use crate::interface::telegram::harness::{Conversation, USER_ID, last_text};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn selecting_a_bot_from_the_list_then_running_it() {
+        let mut chat = Conversation::new().await;
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+
+        let replies = chat.send("/list").await;
+        let list = replies.last().unwrap();
+        assert_eq!(list.buttons.len(), 1);
+        let (label, data) = &list.buttons[0];
+        assert!(label.contains("grid-1"), "{label}");
+        let bot_id = data.strip_prefix("select_bot:").unwrap().to_string();
+
+        let replies = chat.press("grid-1").await;
+        assert_eq!(
+            replies[0].method.to_ascii_lowercase(),
+            "answercallbackquery"
+        );
+        let selected = replies.last().unwrap();
+        assert!(selected.text().starts_with("✅ Bot selected!"));
+        assert!(selected.reply_keyboard);
+
+        let replies = chat.send("Run bot").await;
+        assert_eq!(
+            last_text(&replies),
+            format!("▶️ Bot {} is starting up.", bot_id)
+        );
+        let runtime =
+            BotRuntimeRepository::find(chat.fakes.repo.as_ref(), &USER_ID.to_string(), &bot_id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(runtime.phase, RuntimePhase::Starting);
+        assert_eq!(chat.fakes.backend.tasks().len(), 1);
+        // The update's correlation id reaches the task it launched.
+        assert!(chat.fakes.backend.tasks()[0].correlation_id.is_some());
+
+        // A second tap while the launch is in flight launches nothing.
+        let replies = chat.send("Run bot").await;
+        assert!(last_text(&replies).contains("is already starting"));
+        assert_eq!(chat.fakes.backend.tasks().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn the_bot_list_pages_past_the_first_screen() {
+        let mut chat = Conversation::new().await;
+        for i in 0..BOT_LIST_PAGE_SIZE + 2 {
+            let bot = Bot::create(
+                USER_ID.to_string(),
+                format!("grid-{i:02}"),
+                "key".into(),
+                "secret".into(),
+                0,
+            );
+            chat.fakes.repo.save(&bot).await.unwrap();
+        }
+
+        let replies = chat.send("List").await;
+        let first = replies.last().unwrap();
+        let labels: Vec<&str> = first.buttons.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels.len(), BOT_LIST_PAGE_SIZE + 1, "{labels:?}");
+        assert!(labels[0].contains("grid-00"));
+        assert_eq!(labels.last(), Some(&"Next ▶"));
+
+        let replies = chat.press("Next").await;
+        let second = replies.last().unwrap();
+        assert_eq!(second.method.to_ascii_lowercase(), "editmessagereplymarkup");
+        let labels: Vec<&str> = second.buttons.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels.len(), 3, "{labels:?}");
+        assert!(labels[0].contains("grid-10"));
+        assert!(labels[1].contains("grid-11"));
+        assert_eq!(labels[2], "⏮ First");
+
+        let replies = chat.press("First").await;
+        assert_eq!(
+            replies.last().unwrap().buttons.len(),
+            BOT_LIST_PAGE_SIZE + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn positions_are_closed_after_confirming_and_orders_cancelled() {
+        let mut chat = Conversation::new().await;
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+        chat.fakes.gateway.open(
+            "grid-1",
+            Position {
+                symbol: "BTCUSDT".into(),
+                side: PositionSide::Long,
+                size: 0.02,
+                entry_price: 37000.0,
+                mark_price: 37500.0,
+                unrealized_pnl: 10.0,
+                liq_price: Some(33500.0),
+                position_idx: 1,
+            },
+        );
+        chat.fakes.gateway.place(
+            "grid-1",
+            Order {
+                order_id: "fd4300ae-7847-404e-b947-b46980a4d140".into(),
+                symbol: "ETHUSDT".into(),
+                side: OrderSide::Buy,
+                price: 1950.0,
+                qty: 0.5,
+                reduce_only: false,
+            },
+        );
+        chat.send("List").await;
+        chat.press("grid-1").await;
+
+        let replies = chat.send("Positions").await;
+        let screen = replies.last().unwrap();
+        assert!(
+            screen
+                .text()
+                .contains("• BTCUSDT long 0.02 — entry 37000, mark 37500, uPnL +10.00, liq 33500"),
+            "{}",
+            screen.text()
+        );
+        assert!(screen.text().contains("• ETHUSDT buy 0.5 @ 1950"));
+        for (_, data) in &screen.buttons {
+            assert!(data.len() <= 64, "{data}");
+        }
+
+        // The first tap only asks; Back leaves the position open.
+        let replies = chat.press("Close BTCUSDT long").await;
+        assert!(last_text(&replies).starts_with("⚠️ Close this position on grid-1"));
+        chat.press("Back").await;
+        let bot = BotRepository::find(chat.fakes.repo.as_ref(), &USER_ID.to_string(), "grid-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(chat.fakes.gateway.positions(&bot).await.unwrap().len(), 1);
+
+        chat.press("Close BTCUSDT long").await;
+        let replies = chat.press("Close at market").await;
+        let text = last_text(&replies);
+        assert!(
+            text.starts_with("✅ Sent a reduce-only market order closing BTCUSDT long 0.02."),
+            "{text}"
+        );
+        assert!(chat.fakes.gateway.positions(&bot).await.unwrap().is_empty());
+
+        let replies = chat.press("Cancel ETHUSDT").await;
+        let screen = replies.last().unwrap();
+        assert!(
+            screen
+                .text()
+                .starts_with("✅ Cancelled ETHUSDT buy 0.5 @ 1950."),
+            "{}",
+            screen.text()
+        );
+        assert!(screen.text().contains("Open orders (0):"));
+        assert_eq!(screen.buttons.len(), 1, "only Refresh is left");
+    }
+
+    #[tokio::test]
+    async fn a_close_is_refused_after_switching_to_another_bot() {
+        let mut chat = Conversation::new().await;
+        for name in ["grid-1", "grid-2"] {
+            let bot = Bot::create(
+                USER_ID.to_string(),
+                name.into(),
+                "key".into(),
+                "secret".into(),
+                0,
+            );
+            chat.fakes.repo.save(&bot).await.unwrap();
+            chat.fakes.gateway.open(
+                name,
+                Position {
+                    symbol: "BTCUSDT".into(),
+                    side: PositionSide::Long,
+                    size: 0.02,
+                    entry_price: 37000.0,
+                    mark_price: 37500.0,
+                    unrealized_pnl: 10.0,
+                    liq_price: None,
+                    position_idx: 1,
+                },
+            );
+        }
+        chat.send("List").await;
+        chat.press("grid-1").await;
+        chat.send("Positions").await;
+        chat.press("Close BTCUSDT long").await;
+
+        // Another bot is selected before the confirmation is tapped.
+        chat.send("List").await;
+        chat.press("grid-2").await;
+        let replies = chat.press("Close at market").await;
+        assert!(
+            last_text(&replies).starts_with("❌ This screen is for another bot."),
+            "{replies:?}"
+        );
+
+        for name in ["grid-1", "grid-2"] {
+            let bot = BotRepository::find(chat.fakes.repo.as_ref(), &USER_ID.to_string(), name)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(chat.fakes.gateway.positions(&bot).await.unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn the_risk_editor_refuses_to_save_after_another_bot_is_selected() {
+        let mut chat = Conversation::new().await;
+        for name in ["grid-1", "grid-2"] {
+            let bot = Bot::create(
+                USER_ID.to_string(),
+                name.into(),
+                "key".into(),
+                "secret".into(),
+                0,
+            );
+            chat.fakes.repo.save(&bot).await.unwrap();
+            chat.fakes
+                .configs
+                .save(&BotConfig {
+                    user_id: USER_ID.to_string(),
+                    bot_id: name.into(),
+                    bot_type: BotType::Passivbot,
+                    template_name: "btc_grid".into(),
+                    template_version: None,
+                    config_data: json!({
+                        "bot": {
+                            "long": { "total_wallet_exposure_limit": 1.0 },
+                            "short": { "total_wallet_exposure_limit": 0.5 }
+                        },
+                        "live": { "leverage": 10.0 }
+                    }),
+                    created_at: 0,
+                    updated_at: 0,
+                })
+                .await
+                .unwrap();
+        }
+        chat.send("List").await;
+        chat.press("grid-1").await;
+        let replies = chat.send("Risk level").await;
+        assert!(
+            replies
+                .last()
+                .unwrap()
+                .buttons
+                .iter()
+                .any(|(l, _)| l.contains("Save"))
+        );
+        chat.press("➕").await;
+
+        // Another bot is selected before Save is tapped.
+        chat.send("List").await;
+        chat.press("grid-2").await;
+        let replies = chat.press("Save").await;
+        assert!(
+            last_text(&replies).starts_with("❌ This risk editor is for grid-1"),
+            "{replies:?}"
+        );
+
+        for name in ["grid-1", "grid-2"] {
+            let config = chat
+                .fakes
+                .configs
+                .get(&USER_ID.to_string(), name)
+                .await
+                .unwrap();
+            assert_eq!(config.risk_level().unwrap().long, 1.0, "{name}");
+        }
+
+        // Back on the previewed bot, the pending value is saved to it.
+        chat.send("List").await;
+        chat.press("grid-1").await;
+        let replies = chat.press("Save").await;
+        assert!(
+            last_text(&replies).starts_with("✅ Risk level updated"),
+            "{replies:?}"
+        );
+    }
+}
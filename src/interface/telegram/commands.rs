@@ -3,6 +3,9 @@ use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
 use teloxide::prelude::*;
 use teloxide::utils::command::BotCommands;
 
+use crate::domain::schedule;
+use crate::usecase::{BulkAction, GuardLimit};
+
 use super::{
     Deps, keyboards,
     states::{BotContext, DialogueState},
@@ -18,6 +21,40 @@ pub enum Command {
     Start,
     #[command(description = "list bots")]
     List,
+    #[command(description = "show or set the selected bot's leverage policy")]
+    Leverage(String),
+    #[command(description = "show or set the selected bot's restart policy")]
+    RestartPolicy(String),
+    #[command(description = "show or set the selected bot's task CPU/memory")]
+    Resources(String),
+    #[command(description = "show, set or clear (off) the selected bot's exposure ceiling")]
+    Ceiling(String),
+    #[command(description = "schedule an action for the selected bot (cron, UTC)")]
+    Schedule(String),
+    #[command(description = "list scheduled actions")]
+    Schedules,
+    #[command(description = "remove a scheduled action by id")]
+    Unschedule(String),
+    #[command(description = "stop all of your bots")]
+    StopAll,
+    #[command(description = "start all bots that are turned on")]
+    StartAll,
+    #[command(description = "set both sides of all bots to graceful_stop")]
+    GracefulAll,
+    #[command(description = "apply a risk level (<long>/<short>) to all bots")]
+    RiskAll(String),
+    #[command(description = "EMERGENCY: close all positions and stop all bots")]
+    Panic,
+    #[command(description = "issue (or revoke) your HTTP API token")]
+    ApiToken(String),
+    #[command(description = "show or set (UTC hour, off, now) your daily digest")]
+    Digest(String),
+    #[command(
+        description = "show, set (daily/drawdown <pct|off>) or rearm the selected bot's risk guard"
+    )]
+    Guard(String),
+    #[command(description = "show or set (long/short <COIN ...|none>) the selected bot's coins")]
+    Coins(String),
 }
 
 pub fn routes() -> teloxide::dispatching::UpdateHandler<DependencyMap> {
@@ -105,10 +142,14 @@ async fn dispatch_command(
                     .map(|user| user.id.to_string())
                     .unwrap_or_else(|| "unknown".to_string());
 
-                // Call use case to get bots
-                match deps.list_bots_usecase.execute(&user_id).await {
-                    Ok(bots) => {
-                        if bots.is_empty() {
+                // Call use case to get the first page of bots
+                match deps
+                    .list_bots_with_runtime_usecase
+                    .page(&user_id, None, super::BOT_LIST_PAGE_SIZE)
+                    .await
+                {
+                    Ok(page) => {
+                        if page.bots.is_empty() {
                             bot.send_message(
                                 msg.chat.id,
                                 "📋 Your bots:\n\n(No bots configured yet)",
@@ -118,7 +159,8 @@ async fn dispatch_command(
                             let ctx = bot_context.get().await?.unwrap_or_default();
 
                             let header = if let Some(ref bot_id) = ctx.selected_bot_id {
-                                let selected = bots.iter()
+                                let selected = page.bots.iter()
+                                    .map(|(b, _)| b)
                                     .find(|b| &b.id == bot_id)
                                     .map(|b| format!(
                                         "{} | {} | {}",
@@ -132,9 +174,9 @@ async fn dispatch_command(
                                 "📋 Select a bot:\n\n(No bot selected)".to_string()
                             };
 
-                            let augmented = super::bots_with_phase(&deps, &user_id, bots).await;
+                            let keyboard = super::bot_list_markup(page, None);
                             bot.send_message(msg.chat.id, header)
-                                .reply_markup(keyboards::bot_list_keyboard(&augmented))
+                                .reply_markup(keyboard)
                                 .await?;
                         }
                     }
@@ -144,6 +186,626 @@ async fn dispatch_command(
                     }
                 }
             }
+            Command::Leverage(policy) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                if policy.trim().is_empty() {
+                    let current = deps
+                        .list_bots_usecase
+                        .execute(&user_id)
+                        .await
+                        .ok()
+                        .and_then(|bots| bots.into_iter().find(|b| b.id == bot_id))
+                        .map(|b| format!("{} ({})", b.leverage_policy, b.leverage_policy.describe()))
+                        .unwrap_or_else(|| "unknown".to_string());
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "🧮 Leverage policy for {}: {}\n\n\
+                            Usage: /leverage <policy>\n\
+                            • offset:<n> — max risk + n\n\
+                            • multiplier:<n> — max risk × n\n\
+                            • fixed:<n> — constant leverage\n\
+                            • cap:<max>:<policy> — clamp another policy at max (1-125)\n\n\
+                            Each coin's exchange maximum applies on top of any cap.\n\n\
+                            Example: /leverage cap:25:multiplier:1.5",
+                            bot_id, current
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+
+                match deps
+                    .set_leverage_policy_usecase
+                    .execute(&user_id, &bot_id, &policy)
+                    .await
+                {
+                    Ok(kind) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "✅ Leverage policy for {} set to {} ({}).\n\n\
+                                It applies the next time the risk level is set.",
+                                bot_id,
+                                kind,
+                                kind.describe()
+                            ),
+                        )
+                        .await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!("❌ Failed to set leverage policy:\n\n{}", e),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            Command::RestartPolicy(policy) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                if policy.trim().is_empty() {
+                    let current = deps
+                        .list_bots_usecase
+                        .execute(&user_id)
+                        .await
+                        .ok()
+                        .and_then(|bots| bots.into_iter().find(|b| b.id == bot_id))
+                        .map(|b| format!("{} ({})", b.restart_policy, b.restart_policy.describe()))
+                        .unwrap_or_else(|| "unknown".to_string());
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "🔁 Restart policy for {}: {}\n\n\
+                            Usage: /restartpolicy <mode>[:<max attempts>]\n\
+                            • never — never restart automatically\n\
+                            • on-oom — only after an out-of-memory kill\n\
+                            • on-failure — after OOM, crashes and infrastructure stops\n\
+                            • always — after any stop you did not request\n\n\
+                            The max caps consecutive restarts; a run of an hour or more resets it.\n\
+                            Example: /restartpolicy on-failure:5",
+                            bot_id, current
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+
+                match deps
+                    .set_restart_policy_usecase
+                    .execute(&user_id, &bot_id, &policy)
+                    .await
+                {
+                    Ok(policy) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "✅ Restart policy for {} set to {} ({}).",
+                                bot_id,
+                                policy,
+                                policy.describe()
+                            ),
+                        )
+                        .await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!("❌ Failed to set restart policy:\n\n{}", e),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            Command::Resources(profile) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                if profile.trim().is_empty() {
+                    let current = deps
+                        .list_bots_usecase
+                        .execute(&user_id)
+                        .await
+                        .ok()
+                        .and_then(|bots| bots.into_iter().find(|b| b.id == bot_id))
+                        .map(|b| format!("{} ({})", b.resource_profile, b.resource_profile.describe()))
+                        .unwrap_or_else(|| "unknown".to_string());
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "📐 Resources for {}: {}\n\n\
+                            Usage: /resources <profile>\n\
+                            • small — 128 CPU units, 400 MiB\n\
+                            • medium — 256 CPU units, 800 MiB\n\
+                            • large — 512 CPU units, 1600 MiB\n\
+                            • <cpu>:<memory> — explicit CPU units and MiB\n\n\
+                            Applies from the next start. An out-of-memory stop moves the bot up one size automatically.\n\
+                            Example: /resources 256:1024",
+                            bot_id, current
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+
+                match deps
+                    .set_resource_profile_usecase
+                    .execute(&user_id, &bot_id, &profile)
+                    .await
+                {
+                    Ok(profile) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "✅ Resources for {} set to {} ({}). Restart the bot to apply.",
+                                bot_id,
+                                profile,
+                                profile.describe()
+                            ),
+                        )
+                        .await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!("❌ Failed to set resources:\n\n{}", e),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            Command::Ceiling(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                let arg = arg.trim();
+                if arg.is_empty() {
+                    let current = deps
+                        .list_bots_usecase
+                        .execute(&user_id)
+                        .await
+                        .ok()
+                        .and_then(|bots| bots.into_iter().find(|b| b.id == bot_id))
+                        .and_then(|b| b.exposure_ceiling)
+                        .map(|c| format!("{:.2}", c))
+                        .unwrap_or_else(|| "not set".to_string());
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "🚧 Exposure ceiling for {}: {}\n\n\
+                            The risk editor warns when (long × leverage + short × leverage) \
+                            exceeds the ceiling.\n\n\
+                            Usage: /ceiling <n> or /ceiling off",
+                            bot_id, current
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+
+                let ceiling = if arg.eq_ignore_ascii_case("off") {
+                    None
+                } else {
+                    match arg.parse::<f64>() {
+                        Ok(v) => Some(v),
+                        Err(_) => {
+                            bot.send_message(
+                                msg.chat.id,
+                                "❌ Please enter a number, e.g. /ceiling 30, or /ceiling off.",
+                            )
+                            .await?;
+                            return anyhow::Ok(());
+                        }
+                    }
+                };
+
+                match deps
+                    .set_exposure_ceiling_usecase
+                    .execute(&user_id, &bot_id, ceiling)
+                    .await
+                {
+                    Ok(()) => {
+                        let text = match ceiling {
+                            Some(c) => format!("✅ Exposure ceiling for {} set to {:.2}.", bot_id, c),
+                            None => format!("✅ Exposure ceiling for {} cleared.", bot_id),
+                        };
+                        bot.send_message(msg.chat.id, text).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!("❌ Failed to set exposure ceiling:\n\n{}", e),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            Command::Schedule(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                // Five cron fields, then the action as the last token.
+                let fields: Vec<&str> = arg.split_whitespace().collect();
+                if fields.len() != 6 {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "🗓️ Schedule an action for {}\n\n\
+                            Usage: /schedule <min> <hour> <day> <month> <weekday> <action>\n\
+                            Times are UTC; fields take *, lists, ranges and steps.\n\n\
+                            Actions:\n\
+                            • start / stop\n\
+                            • risk:<long>/<short>\n\
+                            • side:<long|short>:<on|off>\n\n\
+                            Example: /schedule 0 22 * * 5 stop (Fridays 22:00)",
+                            bot_id
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+                let cron = fields[..5].join(" ");
+
+                match deps
+                    .create_schedule_usecase
+                    .execute(&user_id, &bot_id, &cron, fields[5])
+                    .await
+                {
+                    Ok(s) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "✅ Scheduled {} for {} at `{}`.\n\nId: {}\nNext run: {}",
+                                s.action,
+                                s.bot_id,
+                                s.cron,
+                                s.id,
+                                schedule::format_utc(s.next_run_at)
+                            ),
+                        )
+                        .await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!("❌ Failed to create schedule:\n\n{}", e),
+                        )
+                        .await?;
+                    }
+                }
+            }
+            Command::Schedules => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let text = match deps.list_schedules_usecase.execute(&user_id).await {
+                    Ok(schedules) => super::views::format_schedules(&schedules),
+                    Err(e) => format!("❌ Error fetching schedules: {}", e),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::Unschedule(id) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let id = id.trim();
+                if id.is_empty() {
+                    bot.send_message(msg.chat.id, "Usage: /unschedule <id> (see /schedules)")
+                        .await?;
+                    return anyhow::Ok(());
+                }
+
+                let text = match deps.delete_schedule_usecase.execute(&user_id, id).await {
+                    Ok(true) => format!("✅ Schedule {} removed.", id),
+                    Ok(false) => format!("❌ No schedule with id {}.", id),
+                    Err(e) => format!("❌ Failed to remove schedule:\n\n{}", e),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::StopAll => prompt_bulk(&bot, &msg, &deps, BulkAction::StopAll).await?,
+            Command::StartAll => {
+                prompt_bulk(&bot, &msg, &deps, BulkAction::StartAllEnabled).await?
+            }
+            Command::GracefulAll => {
+                prompt_bulk(&bot, &msg, &deps, BulkAction::GracefulStopAll).await?
+            }
+            Command::Panic => {
+                bot.send_message(msg.chat.id, super::views::format_panic_warning())
+                    .reply_markup(keyboards::panic_arm_keyboard())
+                    .await?;
+            }
+            Command::ApiToken(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let text = match arg.trim() {
+                    "" => match deps.issue_api_token_usecase.execute(&user_id).await {
+                        Ok(secret) => super::views::format_api_token(&secret),
+                        Err(e) => format!("❌ Failed to issue a token:\n\n{}", e),
+                    },
+                    "revoke" => match deps.revoke_api_token_usecase.execute(&user_id).await {
+                        Ok(true) => "✅ API token revoked.".to_string(),
+                        Ok(false) => "You have no API token.".to_string(),
+                        Err(e) => format!("❌ Failed to revoke the token:\n\n{}", e),
+                    },
+                    _ => "Usage: /apitoken to issue a new token, /apitoken revoke to revoke it"
+                        .to_string(),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::Digest(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let arg = arg.trim();
+                let hour = match arg {
+                    "" => {
+                        let text = match deps.get_preferences_usecase.execute(&user_id).await {
+                            Ok(prefs) => super::views::format_digest_setting(&prefs),
+                            Err(e) => format!("❌ Error fetching your settings: {}", e),
+                        };
+                        bot.send_message(msg.chat.id, text).await?;
+                        return anyhow::Ok(());
+                    }
+                    "now" => {
+                        let text = match deps.build_daily_digest_usecase.execute(&user_id).await {
+                            Ok(digest) => digest.render(),
+                            Err(e) => format!("❌ Failed to build the digest:\n\n{}", e),
+                        };
+                        bot.send_message(msg.chat.id, text).await?;
+                        return anyhow::Ok(());
+                    }
+                    "off" => None,
+                    _ => match arg.parse::<u32>() {
+                        Ok(h) => Some(h),
+                        Err(_) => {
+                            bot.send_message(
+                                msg.chat.id,
+                                "❌ Please enter an hour (UTC), e.g. /digest 7, or /digest off.",
+                            )
+                            .await?;
+                            return anyhow::Ok(());
+                        }
+                    },
+                };
+
+                let text = match deps.set_digest_hour_usecase.execute(&user_id, hour).await {
+                    Ok(prefs) => match prefs.next_digest_at {
+                        Some(next) => format!(
+                            "✅ Daily digest set to {:02}:00 UTC. First one: {}.",
+                            hour.unwrap_or_default(),
+                            schedule::format_utc(next)
+                        ),
+                        None => "✅ Daily digest turned off.".to_string(),
+                    },
+                    Err(e) => format!("❌ Failed to set the daily digest:\n\n{}", e),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::Guard(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                let mut words = arg.split_whitespace();
+                let (limit, value) = match (words.next(), words.next(), words.next()) {
+                    (None, _, _) => {
+                        let text = match deps.get_risk_guard_usecase.execute(&user_id, &bot_id).await
+                        {
+                            Ok(guard) => super::views::format_risk_guard(&guard),
+                            Err(e) => format!("❌ Error fetching the risk guard: {}", e),
+                        };
+                        bot.send_message(msg.chat.id, text).await?;
+                        return anyhow::Ok(());
+                    }
+                    (Some("rearm"), None, _) => {
+                        let text = match deps.rearm_risk_guard_usecase.execute(&user_id, &bot_id).await
+                        {
+                            Ok(true) => format!(
+                                "✅ Risk guard for {} re-armed. Both sides stay in graceful_stop \
+                                until you turn them back on.",
+                                bot_id
+                            ),
+                            Ok(false) => format!("ℹ️ Risk guard for {} is not tripped.", bot_id),
+                            Err(e) => format!("❌ Failed to re-arm the risk guard:\n\n{}", e),
+                        };
+                        bot.send_message(msg.chat.id, text).await?;
+                        return anyhow::Ok(());
+                    }
+                    (Some("daily"), Some(value), None) => (GuardLimit::DailyLoss, value),
+                    (Some("drawdown"), Some(value), None) => (GuardLimit::Drawdown, value),
+                    _ => {
+                        bot.send_message(
+                            msg.chat.id,
+                            "❌ Usage: /guard daily <pct|off>, /guard drawdown <pct|off>, or /guard rearm",
+                        )
+                        .await?;
+                        return anyhow::Ok(());
+                    }
+                };
+                let pct = if value.eq_ignore_ascii_case("off") {
+                    None
+                } else {
+                    match value.trim_end_matches('%').parse::<f64>() {
+                        Ok(v) => Some(v),
+                        Err(_) => {
+                            bot.send_message(
+                                msg.chat.id,
+                                "❌ Please enter a percentage, e.g. /guard daily 5, or /guard daily off.",
+                            )
+                            .await?;
+                            return anyhow::Ok(());
+                        }
+                    }
+                };
+
+                let text = match deps
+                    .set_risk_guard_limit_usecase
+                    .execute(&user_id, &bot_id, limit, pct)
+                    .await
+                {
+                    Ok(guard) => format!("✅ Saved.\n\n{}", super::views::format_risk_guard(&guard)),
+                    Err(e) => format!("❌ Failed to set the risk guard:\n\n{}", e),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::Coins(arg) => {
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let ctx = bot_context.get().await?.unwrap_or_default();
+                let Some(bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ No bot selected. Please use 'List' to select a bot first.",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                };
+
+                let mut words = arg.split_whitespace();
+                let side = match words.next().map(str::to_lowercase) {
+                    None => None,
+                    Some(side) if side == "long" || side == "short" => Some(side),
+                    Some(_) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            "❌ Usage: /coins long <COIN ...>, /coins short <COIN ...>, or /coins <side> none",
+                        )
+                        .await?;
+                        return anyhow::Ok(());
+                    }
+                };
+                if let Some(side) = side {
+                    let coins: Vec<String> = words
+                        .filter(|w| !w.eq_ignore_ascii_case("none"))
+                        .map(|w| w.trim_matches(',').to_string())
+                        .collect();
+                    if let Err(e) = deps
+                        .set_approved_coins_usecase
+                        .execute(&user_id, &bot_id, &side, &coins)
+                        .await
+                    {
+                        bot.send_message(msg.chat.id, format!("❌ Coins not saved:\n\n{}", e))
+                            .await?;
+                        return anyhow::Ok(());
+                    }
+                }
+
+                let text = match deps.get_approved_coins_usecase.execute(&user_id, &bot_id).await {
+                    Ok(coins) => {
+                        let view = super::views::format_approved_coins(&bot_id, &coins);
+                        if arg.trim().is_empty() {
+                            view
+                        } else {
+                            format!("✅ Saved. Applies on the next start.\n\n{}", view)
+                        }
+                    }
+                    Err(e) => format!("❌ Error fetching the coins: {}", e),
+                };
+                bot.send_message(msg.chat.id, text).await?;
+            }
+            Command::RiskAll(arg) => {
+                if arg.trim().is_empty() {
+                    bot.send_message(
+                        msg.chat.id,
+                        "Usage: /riskall <long>/<short>\n\nExample: /riskall 1.0/0.5",
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
+                match BulkAction::parse(&format!("risk:{}", arg.trim())) {
+                    Ok(action) => prompt_bulk(&bot, &msg, &deps, action).await?,
+                    Err(e) => {
+                        bot.send_message(msg.chat.id, format!("❌ {}", e)).await?;
+                    }
+                }
+            }
         }
         anyhow::Ok(())
     }
@@ -151,3 +813,211 @@ async fn dispatch_command(
 
     result.map_err(|_| DependencyMap::new())
 }
+
+/// Ask for confirmation before a bulk action, listing the bots it will touch.
+/// The action itself runs from the `bulk_run:` callback.
+async fn prompt_bulk(
+    bot: &Bot,
+    msg: &Message,
+    deps: &Deps,
+    action: BulkAction,
+) -> anyhow::Result<()> {
+    let user_id = msg
+        .from()
+        .map(|user| user.id.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    match deps
+        .bulk_bot_action_usecase
+        .targets(&user_id, &action)
+        .await
+    {
+        Ok(targets) if targets.is_empty() => {
+            bot.send_message(
+                msg.chat.id,
+                format!("ℹ️ {}: no bots to act on.", action.label()),
+            )
+            .await?;
+        }
+        Ok(targets) => {
+            bot.send_message(
+                msg.chat.id,
+                super::views::format_bulk_confirm(&action, &targets),
+            )
+            .reply_markup(keyboards::bulk_confirm_keyboard(&action))
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error fetching bots: {}", e))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::bot::BotRepository;
+    use crate::domain::botconfig::{BotConfig, BotConfigRepository, BotType};
+    use crate::domain::exchange::Exchange;
+    use crate::domain::instrument::{Instrument, InstrumentCatalog, InstrumentCatalogRepository};
+    use crate::interface::telegram::harness::{Conversation, USER_ID, last_text};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn digest_is_set_shown_previewed_and_turned_off() {
+        let mut chat = Conversation::new().await;
+        let user = USER_ID.to_string();
+
+        let replies = chat.send("/digest").await;
+        assert!(last_text(&replies).starts_with("📰 Daily digest: off"));
+
+        // The fakes' clock is 2023-11-14 22:13 UTC.
+        let replies = chat.send("/digest 7").await;
+        assert_eq!(
+            last_text(&replies),
+            "✅ Daily digest set to 07:00 UTC. First one: 2023-11-15 07:00 UTC."
+        );
+        let replies = chat.send("/digest").await;
+        assert!(
+            last_text(&replies)
+                .starts_with("📰 Daily digest: every day at 07:00 UTC\nNext: 2023-11-15 07:00 UTC"),
+            "{replies:?}"
+        );
+
+        let replies = chat.send("/digest 24").await;
+        assert!(
+            last_text(&replies).contains("must be between 0 and 23"),
+            "{replies:?}"
+        );
+        let replies = chat.send("/digest seven").await;
+        assert!(last_text(&replies).starts_with("❌ Please enter an hour"));
+
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+        let bot_id = chat.fakes.repo.find_by_user_id(&user).await.unwrap()[0]
+            .id
+            .clone();
+        chat.fakes.gateway.set_equity(&bot_id, 250.0);
+        let replies = chat.send("/digest now").await;
+        let text = last_text(&replies);
+        assert!(
+            text.starts_with("📰 Daily digest — 2023-11-14 22:13 UTC"),
+            "{text}"
+        );
+        assert!(
+            text.contains("• grid-1 — ❔ Unknown (turned off)"),
+            "{text}"
+        );
+        assert!(text.contains("💰 Equity 250.00"), "{text}");
+
+        let replies = chat.send("/digest off").await;
+        assert_eq!(last_text(&replies), "✅ Daily digest turned off.");
+    }
+
+    #[tokio::test]
+    async fn guard_limits_are_set_shown_and_validated() {
+        let mut chat = Conversation::new().await;
+        let replies = chat.send("/guard").await;
+        assert!(last_text(&replies).starts_with("❌ No bot selected"));
+
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+        chat.send("/list").await;
+        chat.press("grid-1").await;
+        let replies = chat.send("/guard").await;
+        assert!(
+            last_text(&replies).contains(": ⚪ no limits set"),
+            "{replies:?}"
+        );
+
+        let replies = chat.send("/guard daily 5%").await;
+        let text = last_text(&replies);
+        assert!(text.starts_with("✅ Saved."), "{text}");
+        assert!(text.contains(": 🟢 armed"), "{text}");
+        assert!(
+            text.contains("• Max daily loss: 5%\n• Max drawdown: off"),
+            "{text}"
+        );
+
+        let replies = chat.send("/guard drawdown 150").await;
+        assert!(
+            last_text(&replies).contains("must be between 0 and 100"),
+            "{replies:?}"
+        );
+        let replies = chat.send("/guard drawdown lots").await;
+        assert!(last_text(&replies).starts_with("❌ Please enter a percentage"));
+        let replies = chat.send("/guard weekly 5").await;
+        assert!(last_text(&replies).starts_with("❌ Usage: /guard"));
+
+        let replies = chat.send("/guard rearm").await;
+        assert!(
+            last_text(&replies).ends_with("is not tripped."),
+            "{replies:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn coins_are_shown_with_their_listing_and_unlisted_ones_refused() {
+        let mut chat = Conversation::new().await;
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+        chat.send("/list").await;
+        chat.press("grid-1").await;
+        chat.fakes
+            .configs
+            .save(&BotConfig {
+                user_id: USER_ID.to_string(),
+                bot_id: "grid-1".into(),
+                bot_type: BotType::Passivbot,
+                template_name: "btc_grid".into(),
+                template_version: None,
+                config_data: json!({
+                    "live": { "leverage": 10.0, "approved_coins": { "long": ["BTC"], "short": [] } }
+                }),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .await
+            .unwrap();
+
+        let replies = chat.send("/coins").await;
+        let text = last_text(&replies);
+        assert!(text.contains("Long:\n  • BTC\nShort:\n  (none)"), "{text}");
+        assert!(text.contains("coins are not checked"), "{text}");
+
+        chat.fakes
+            .instruments
+            .save(&InstrumentCatalog {
+                exchange: Exchange::Bybit,
+                fetched_at: 1_700_000_000,
+                instruments: vec![Instrument {
+                    symbol: "BTCUSDT".into(),
+                    base_coin: "BTC".into(),
+                    status: "Trading".into(),
+                    min_qty: 0.001,
+                    tick_size: 0.1,
+                    max_leverage: 100.0,
+                }],
+            })
+            .await
+            .unwrap();
+        let replies = chat.send("/coins short btc, DOGGO").await;
+        let text = last_text(&replies);
+        assert!(text.starts_with("❌ Coins not saved"), "{text}");
+        assert!(text.contains("DOGGO is not listed on bybit"), "{text}");
+
+        let replies = chat.send("/coins short btc").await;
+        let text = last_text(&replies);
+        assert!(text.starts_with("✅ Saved."), "{text}");
+        assert!(
+            text.contains("Short:\n  • BTC — BTCUSDT, max 100x"),
+            "{text}"
+        );
+        let replies = chat.send("/coins long none").await;
+        assert!(last_text(&replies).contains("Long:\n  (none)"));
+    }
+}
@@ -5,7 +5,8 @@ use super::{
     Deps,
     states::{BotContext, DialogueState},
 };
-use crate::usecase::{AddOutcome, StartOutcome, StopOutcome};
+use crate::domain::error::DomainError;
+use crate::usecase::{AddOutcome, BOT_HISTORY_VIEW_LIMIT, StartOutcome, StopOutcome};
 
 type MyDialogue = Dialogue<DialogueState, InMemStorage<DialogueState>>;
 type MyBotContext = Dialogue<BotContext, InMemStorage<BotContext>>;
@@ -31,7 +32,14 @@ pub fn routes() -> teloxide::dispatching::UpdateHandler<DependencyMap> {
                 }]
                 .endpoint(confirm_overwrite_bot),
             )
-            .branch(dptree::case![DialogueState::ReceiveRiskLevel].endpoint(receive_risk_level)),
+            .branch(
+                dptree::case![DialogueState::EditRiskLevel {
+                    bot_id,
+                    risk_long,
+                    risk_short
+                }]
+                .endpoint(edit_risk_level),
+            ),
     )
 }
 
@@ -69,15 +77,21 @@ async fn handle_start_state(
                     .map(|user| user.id.to_string())
                     .unwrap_or_else(|| "unknown".to_string());
 
-                // Fetch the bot once and reuse it for name, exchange, and desired state.
-                let (bot_name, bot_exchange, bot_enabled) = match deps.list_bots_usecase.execute(&user_id).await {
+                // Fetch the bot once and reuse it for name, exchange, desired state and size.
+                let unknown = || (bot_id.clone(), "UNKNOWN".to_string(), false, "unknown".to_string());
+                let (bot_name, bot_exchange, bot_enabled, resources_text) = match deps.list_bots_usecase.execute(&user_id).await {
                     Ok(bots) => {
                         bots.iter()
                             .find(|b| &b.id == bot_id)
-                            .map(|b| (b.name.clone(), b.exchange.as_str().to_uppercase(), b.enabled))
-                            .unwrap_or_else(|| (bot_id.clone(), "UNKNOWN".to_string(), false))
+                            .map(|b| (
+                                b.name.clone(),
+                                b.exchange.as_str().to_uppercase(),
+                                b.enabled,
+                                format!("{} ({})", b.resource_profile, b.resource_profile.describe()),
+                            ))
+                            .unwrap_or_else(unknown)
                     }
-                    Err(_) => (bot_id.clone(), "UNKNOWN".to_string(), false),
+                    Err(_) => unknown(),
                 };
 
                 // Observed runtime (actual task phase), independent of desired state.
@@ -150,7 +164,8 @@ async fn handle_start_state(
                                • Name: {}\n\
                                • ID: {}\n\
                                • Desired: {}\n\
-                               • Actual: {}\n\n\
+                               • Actual: {}\n\
+                               • Resources: {}\n\n\
                             📋 Configuration:\n\
                                • Template: {}\n\
                                • Strategy: {}\n\
@@ -167,6 +182,7 @@ async fn handle_start_state(
                             bot_id,
                             desired_text,
                             actual_text,
+                            resources_text,
                             template_name,
                             strategy_info,
                             description_info,
@@ -195,14 +211,16 @@ async fn handle_start_state(
                                    • Name: {}\n\
                                    • ID: {}\n\
                                    • Desired: {}\n\
-                                   • Actual: {}\n\n\
+                                   • Actual: {}\n\
+                                   • Resources: {}\n\n\
                                 ⚠️ No configuration found for this bot.\n\n\
                                 Please apply a configuration template first using 'Choose config...'.",
                                 bot_exchange,
                                 bot_name,
                                 bot_id,
                                 desired_text,
-                                actual_text
+                                actual_text,
+                                resources_text
                             )
                         )
                             .reply_markup(super::keyboards::main_menu_keyboard())
@@ -286,37 +304,33 @@ async fn handle_start_state(
 
                 match deps.get_bot_config_usecase.execute(&user_id, bot_id).await {
                     Ok(config) => {
-                        // Get current risk level
-                        let current_risk = config.risk_level()
-                            .map(|r| format!("Long: {:.2}, Short: {:.2}", r.long, r.short))
-                            .unwrap_or_else(|_| "Not set".to_string());
-
-                        let current_leverage = config.leverage()
-                            .map(|l| format!("{:.1}x", l.long))
-                            .unwrap_or_else(|_| "Not set".to_string());
-
-                        bot.send_message(
-                            msg.chat.id,
-                            format!(
-                                "⚠️ Risk Level Configuration\n\n\
-                                🤖 Bot: {}\n\
-                                📊 Current Risk Level: {}\n\
-                                📈 Current Leverage: {}\n\n\
-                                Please enter the new risk level values in the following format:\n\
-                                `<long_risk>/<short_risk>`\n\n\
-                                Example: `3.0/1.5`\n\n\
-                                Note:\n\
-                                - Values should be decimal numbers (0.0 - 10.0)\n\
-                                - Leverage will be automatically set to (max_risk + 1)\n\
-                                - Send 'cancel' to abort",
-                                bot_id,
-                                current_risk,
-                                current_leverage
-                            )
-                        )
-                            .await?;
-
-                        dialogue.update(DialogueState::ReceiveRiskLevel).await?;
+                        // Start the editor from the current values; a config
+                        // without a risk level starts from zero exposure.
+                        let current = config.risk_level().ok();
+                        let (risk_long, risk_short) =
+                            current.map(|r| (r.long, r.short)).unwrap_or((0.0, 0.0));
+
+                        match super::risk_editor(&deps, &user_id, bot_id, risk_long, risk_short).await {
+                            Ok((text, keyboard)) => {
+                                bot.send_message(msg.chat.id, text)
+                                    .reply_markup(keyboard)
+                                    .await?;
+                                dialogue
+                                    .update(DialogueState::EditRiskLevel {
+                                        bot_id: bot_id.clone(),
+                                        risk_long,
+                                        risk_short,
+                                    })
+                                    .await?;
+                            }
+                            Err(e) => {
+                                bot.send_message(
+                                    msg.chat.id,
+                                    format!("❌ Cannot edit the risk level:\n\n{}", e),
+                                )
+                                    .await?;
+                            }
+                        }
                     }
                     Err(_) => {
                         bot.send_message(
@@ -343,6 +357,7 @@ async fn handle_start_state(
                         Ok(StartOutcome::AlreadyStarting) => format!("⏳ Bot {} is already starting — give it a few seconds.", bot_id),
                         Ok(StartOutcome::Stopping) => format!("🛑 Bot {} is still stopping — wait a few seconds, then tap Run again.", bot_id),
                         Ok(StartOutcome::BotNotFound) => format!("❌ Bot {} not found.", bot_id),
+                        Ok(StartOutcome::Rejected(problems)) => format!("❌ Bot {} was not started — fix its config first:\n\n{}", bot_id, problems),
                         Err(e) => format!("❌ Failed to start bot {}:\n\n{}", bot_id, e),
                     }
                 } else {
@@ -383,6 +398,82 @@ async fn handle_start_state(
                     .reply_markup(super::keyboards::main_menu_keyboard())
                     .await?;
             }
+            "History" => {
+                let ctx = bot_context.get().await?.unwrap_or_default();
+
+                let text = if let Some(ref bot_id) = ctx.selected_bot_id {
+                    let user_id = msg.from()
+                        .map(|user| user.id.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+
+                    match deps
+                        .get_bot_history_usecase
+                        .execute(&user_id, bot_id, BOT_HISTORY_VIEW_LIMIT)
+                        .await
+                    {
+                        Ok(events) => super::views::format_history(bot_id, &events),
+                        Err(e) => format!("❌ Failed to load history for bot {}:\n\n{}", bot_id, e),
+                    }
+                } else {
+                    "❌ Please select a bot first using 'List'".to_string()
+                };
+
+                bot.send_message(msg.chat.id, text)
+                    .reply_markup(super::keyboards::main_menu_keyboard())
+                    .await?;
+            }
+            "Performance" => {
+                let ctx = bot_context.get().await?.unwrap_or_default();
+
+                let text = if let Some(ref bot_id) = ctx.selected_bot_id {
+                    let user_id = msg.from()
+                        .map(|user| user.id.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+
+                    match deps.get_performance_usecase.execute(&user_id, bot_id).await {
+                        Ok(Some(report)) => super::views::format_performance(bot_id, &report),
+                        Ok(None) => format!("❌ Bot {} not found.", bot_id),
+                        Err(e) => format!(
+                            "❌ Failed to load performance for bot {}:\n\n{}",
+                            bot_id, e
+                        ),
+                    }
+                } else {
+                    "❌ Please select a bot first using 'List'".to_string()
+                };
+
+                bot.send_message(msg.chat.id, text)
+                    .reply_markup(super::keyboards::main_menu_keyboard())
+                    .await?;
+            }
+            "Positions" => {
+                let ctx = bot_context.get().await?.unwrap_or_default();
+
+                let Some(ref bot_id) = ctx.selected_bot_id else {
+                    bot.send_message(msg.chat.id, "❌ Please select a bot first using 'List'")
+                        .reply_markup(super::keyboards::main_menu_keyboard())
+                        .await?;
+                    return Ok(());
+                };
+                let user_id = msg
+                    .from()
+                    .map(|user| user.id.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let (text, keyboard) = super::positions_screen(&deps, &user_id, bot_id, None).await;
+                match keyboard {
+                    Some(keyboard) => {
+                        bot.send_message(msg.chat.id, text)
+                            .reply_markup(keyboard)
+                            .await?;
+                    }
+                    None => {
+                        bot.send_message(msg.chat.id, text)
+                            .reply_markup(super::keyboards::main_menu_keyboard())
+                            .await?;
+                    }
+                }
+            }
             "Sides" => {
                 let ctx = bot_context.get().await?.unwrap_or_default();
 
@@ -474,10 +565,10 @@ async fn handle_start_state(
                     .map(|user| user.id.to_string())
                     .unwrap_or_else(|| "unknown".to_string());
 
-                // Call use case to get bots
-                match deps.list_bots_usecase.execute(&user_id).await {
-                    Ok(bots) => {
-                        if bots.is_empty() {
+                // Call use case to get the first page of bots
+                match deps.list_bots_with_runtime_usecase.page(&user_id, None, super::BOT_LIST_PAGE_SIZE).await {
+                    Ok(page) => {
+                        if page.bots.is_empty() {
                             bot.send_message(
                                 msg.chat.id,
                                 "📋 Your bots:\n\n(No bots configured yet)",
@@ -493,9 +584,9 @@ async fn handle_start_state(
                                 "📋 Select a bot:\n\n(No bot selected)".to_string()
                             };
 
-                            let augmented = super::bots_with_phase(&deps, &user_id, bots).await;
+                            let keyboard = super::bot_list_markup(page, None);
                             bot.send_message(msg.chat.id, header)
-                                .reply_markup(super::keyboards::bot_list_keyboard(&augmented))
+                                .reply_markup(keyboard)
                                 .await?;
                         }
                     }
@@ -528,6 +619,21 @@ async fn receive_bot_name(
     let result = async {
         match msg.text() {
             Some(name) => {
+                // Checked again when the bot is saved; asking here spares the
+                // user typing both keys before hearing the name is unusable.
+                if let Err(DomainError::InvalidBotName(problem)) =
+                    crate::domain::bot::Bot::validate_name(name)
+                {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "❌ That name cannot be used: {}.\n\nPlease send another name:",
+                            problem
+                        ),
+                    )
+                    .await?;
+                    return anyhow::Ok(());
+                }
                 bot.send_message(
                     msg.chat.id,
                     format!("✅ Bot name: {}\n\nNow, please enter the API key:", name),
@@ -811,134 +917,115 @@ fn format_template_list(templates: &[String]) -> String {
     message
 }
 
-async fn receive_risk_level(
+/// Text input while the risk editor is open: `yes` saves the pending values, a
+/// typed `<long>/<short>` replaces them and re-renders the editor, `cancel`
+/// aborts. The preset and stepper buttons are handled in `callbacks.rs`.
+async fn edit_risk_level(
     bot: Bot,
     dialogue: MyDialogue,
     bot_context: MyBotContext,
+    (bot_id, risk_long, risk_short): (String, f64, f64),
     msg: Message,
     deps: Deps,
 ) -> Result<(), DependencyMap> {
     let result = async {
-        match msg.text() {
-            Some(text) => {
-                // Allow cancellation
-                if text.trim().eq_ignore_ascii_case("cancel") {
-                    bot.send_message(msg.chat.id, "🚫 Risk level update cancelled.")
-                        .await?;
-                    dialogue.update(DialogueState::Start).await?;
-                    return Ok(());
-                }
+        let text = match msg.text() {
+            Some(t) => t.trim(),
+            None => {
+                bot.send_message(
+                    msg.chat.id,
+                    "❌ Please use the buttons, send `<long>/<short>`, 'yes' to save or 'cancel'.",
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+        // Allow cancellation
+        if text.eq_ignore_ascii_case("cancel") {
+            bot.send_message(msg.chat.id, "🚫 Risk level update cancelled.")
+                .await?;
+            dialogue.update(DialogueState::Start).await?;
+            return Ok(());
+        }
+
+        let user_id = msg
+            .from()
+            .map(|user| user.id.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
 
-                // Parse input: "3.0/1.5"
-                let parts: Vec<&str> = text.trim().split('/').collect();
+        let selected = bot_context.get().await?.unwrap_or_default().selected_bot_id;
+        if selected.as_deref() != Some(bot_id.as_str()) {
+            bot.send_message(msg.chat.id, super::views::format_risk_editor_moved(&bot_id))
+                .await?;
+            return Ok(());
+        }
 
-                if parts.len() != 2 {
+        if text.eq_ignore_ascii_case("yes") {
+            match deps
+                .update_risk_level_usecase
+                .execute(&user_id, &bot_id, risk_long, risk_short)
+                .await
+            {
+                Ok(leverage) => {
                     bot.send_message(
                         msg.chat.id,
-                        "❌ Invalid format. Please enter two numbers separated by /\n\n\
-                        Example: `3.0/1.5`\n\
-                        Or send 'cancel' to abort.",
+                        super::views::format_risk_saved(&bot_id, risk_long, risk_short, &leverage),
                     )
                     .await?;
-                    return Ok(());
                 }
-
-                // Parse risk values
-                let risk_long: f64 = match parts[0].trim().parse() {
-                    Ok(v) => v,
-                    Err(_) => {
-                        bot.send_message(
-                            msg.chat.id,
-                            "❌ Invalid long risk value. Please enter a decimal number.\n\n\
-                            Example: `3.0/1.5`",
-                        )
-                        .await?;
-                        return Ok(());
-                    }
-                };
-
-                let risk_short: f64 = match parts[1].trim().parse() {
-                    Ok(v) => v,
-                    Err(_) => {
-                        bot.send_message(
-                            msg.chat.id,
-                            "❌ Invalid short risk value. Please enter a decimal number.\n\n\
-                            Example: `3.0/1.5`",
-                        )
-                        .await?;
-                        return Ok(());
-                    }
-                };
-
-                // Validate range
-                if risk_long < 0.0 || risk_long > 10.0 || risk_short < 0.0 || risk_short > 10.0 {
+                Err(e) => {
                     bot.send_message(
                         msg.chat.id,
-                        "❌ Risk values must be between 0.0 and 10.0.\n\n\
-                        Please try again or send 'cancel'.",
+                        format!("❌ Failed to update risk level:\n\n{}", e),
                     )
                     .await?;
-                    return Ok(());
-                }
-
-                // Get user_id and bot_id
-                let user_id = msg
-                    .from()
-                    .map(|user| user.id.to_string())
-                    .unwrap_or_else(|| "unknown".to_string());
-
-                let ctx = bot_context.get().await?.unwrap_or_default();
-
-                let bot_id = match ctx.selected_bot_id {
-                    Some(id) => id,
-                    None => {
-                        bot.send_message(msg.chat.id, "❌ No bot selected.").await?;
-                        dialogue.update(DialogueState::Start).await?;
-                        return Ok(());
-                    }
-                };
-
-                // Update risk level
-                match deps
-                    .update_risk_level_usecase
-                    .execute(&user_id, &bot_id, risk_long, risk_short)
-                    .await
-                {
-                    Ok(_) => {
-                        let max_risk = risk_long.max(risk_short);
-                        let leverage = max_risk + 1.0;
-
-                        bot.send_message(
-                            msg.chat.id,
-                            format!(
-                                "✅ Risk level updated successfully!\n\n\
-                                🤖 Bot: {}\n\
-                                📊 New Risk Level:\n\
-                                   • Long: {:.2}\n\
-                                   • Short: {:.2}\n\
-                                📈 Leverage automatically set to: {:.1}x\n\n\
-                                The configuration has been saved.",
-                                bot_id, risk_long, risk_short, leverage
-                            ),
-                        )
-                        .await?;
-                    }
-                    Err(e) => {
-                        bot.send_message(
-                            msg.chat.id,
-                            format!("❌ Failed to update risk level:\n\n{}", e),
-                        )
-                        .await?;
-                    }
                 }
-
-                // Reset dialogue to start
-                dialogue.update(DialogueState::Start).await?;
             }
+            dialogue.update(DialogueState::Start).await?;
+            return Ok(());
+        }
+
+        // Parse input: "3.0/1.5"
+        let parsed = text
+            .split_once('/')
+            .and_then(|(l, s)| Some((l.trim().parse::<f64>().ok()?, s.trim().parse::<f64>().ok()?)));
+        let (new_long, new_short) = match parsed {
+            Some(values) => values,
             None => {
-                bot.send_message(msg.chat.id, "❌ Please send text with risk level values.")
+                bot.send_message(
+                    msg.chat.id,
+                    "❌ Invalid format. Please enter two numbers separated by /\n\n\
+                    Example: `3.0/1.5`\n\
+                    Or reply 'yes' to save the values shown, or 'cancel' to abort.",
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+        // Out-of-range values and underivable leverage are both rejected here,
+        // keeping the previous pending values.
+        match super::risk_editor(&deps, &user_id, &bot_id, new_long, new_short).await {
+            Ok((text, keyboard)) => {
+                bot.send_message(msg.chat.id, text)
+                    .reply_markup(keyboard)
+                    .await?;
+                dialogue
+                    .update(DialogueState::EditRiskLevel {
+                        bot_id: bot_id.clone(),
+                        risk_long: new_long,
+                        risk_short: new_short,
+                    })
                     .await?;
             }
+            Err(e) => {
+                bot.send_message(
+                    msg.chat.id,
+                    format!("❌ Cannot apply this risk level:\n\n{}\n\nPlease try again or send 'cancel'.", e),
+                )
+                .await?;
+            }
         }
         anyhow::Ok(())
     }
@@ -946,3 +1033,181 @@ async fn receive_risk_level(
 
     result.map_err(|_| DependencyMap::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::account::{ClosedPnl, Fill, OrderSide};
+    use crate::domain::bot::BotRepository;
+    use crate::domain::trading::{Position, PositionSide};
+    use crate::interface::telegram::harness::{Conversation, USER_ID, last_text};
+
+    #[tokio::test]
+    async fn an_unusable_bot_name_is_asked_for_again() {
+        let mut chat = Conversation::new().await;
+
+        chat.send("Add bot").await;
+        let replies = chat.send("grid#1").await;
+        assert_eq!(
+            last_text(&replies),
+            "❌ That name cannot be used: it must not contain '#'.\n\nPlease send another name:"
+        );
+        chat.send("grid-1").await;
+        chat.send("key").await;
+        let replies = chat.send("secret").await;
+        assert!(last_text(&replies).starts_with("✅ Bot added successfully!"));
+        let bots = chat
+            .fakes
+            .repo
+            .find_by_user_id(&USER_ID.to_string())
+            .await
+            .unwrap();
+        assert_eq!(bots.len(), 1);
+        assert_eq!(bots[0].id, "grid-1");
+    }
+
+    #[tokio::test]
+    async fn adding_a_bot_twice_asks_before_overwriting_its_keys() {
+        let mut chat = Conversation::new().await;
+        let user = USER_ID.to_string();
+
+        chat.send("Add bot").await;
+        chat.send("grid-1").await;
+        chat.send("key-1").await;
+        let replies = chat.send("secret-1").await;
+        assert!(last_text(&replies).starts_with("✅ Bot added successfully!"));
+        let bots = chat.fakes.repo.find_by_user_id(&user).await.unwrap();
+        assert_eq!(bots.len(), 1);
+        let bot_id = bots[0].id.clone();
+
+        chat.send("Add bot").await;
+        chat.send("grid-1").await;
+        chat.send("key-2").await;
+        let replies = chat.send("secret-2").await;
+        assert!(
+            last_text(&replies).starts_with("⚠️ A bot named \"grid-1\" already exists."),
+            "{replies:?}"
+        );
+        // Nothing is written until the user confirms.
+        assert_eq!(
+            chat.fakes.api_keys.get(&user, &bot_id),
+            Some(("key-1".to_string(), "secret-1".to_string()))
+        );
+
+        let replies = chat.send("yes").await;
+        assert!(last_text(&replies).starts_with("✅ Bot overwritten successfully!"));
+        assert_eq!(
+            chat.fakes.api_keys.get(&user, &bot_id),
+            Some(("key-2".to_string(), "secret-2".to_string()))
+        );
+        assert_eq!(
+            chat.fakes.repo.find_by_user_id(&user).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn declining_the_overwrite_keeps_the_existing_keys() {
+        let mut chat = Conversation::new().await;
+        let user = USER_ID.to_string();
+        for text in ["Add bot", "grid-1", "key-1", "secret-1"] {
+            chat.send(text).await;
+        }
+        let bot_id = chat.fakes.repo.find_by_user_id(&user).await.unwrap()[0]
+            .id
+            .clone();
+
+        for text in ["Add bot", "grid-1", "key-2", "secret-2"] {
+            chat.send(text).await;
+        }
+        let replies = chat.send("no").await;
+        assert!(last_text(&replies).starts_with("🚫 Overwrite cancelled."));
+        assert_eq!(
+            chat.fakes.api_keys.get(&user, &bot_id),
+            Some(("key-1".to_string(), "secret-1".to_string()))
+        );
+        // The dialogue is back at the menu: a menu button works again.
+        let replies = chat.send("Balance").await;
+        assert_eq!(last_text(&replies), "💰 Balance: $0.00");
+    }
+
+    #[tokio::test]
+    async fn performance_shows_the_selected_bots_pnl_and_positions() {
+        let mut chat = Conversation::new().await;
+        let replies = chat.send("Performance").await;
+        assert_eq!(
+            last_text(&replies),
+            "❌ Please select a bot first using 'List'"
+        );
+
+        for text in ["Add bot", "grid-1", "key", "secret"] {
+            chat.send(text).await;
+        }
+        let bot_id = chat
+            .fakes
+            .repo
+            .find_by_user_id(&USER_ID.to_string())
+            .await
+            .unwrap()[0]
+            .id
+            .clone();
+        let now = 1_700_000_000;
+        let closed = |pnl: f64, closed_at: i64| ClosedPnl {
+            symbol: "BTCUSDT".into(),
+            side: PositionSide::Long,
+            qty: 0.01,
+            entry_price: 37000.0,
+            exit_price: 37500.0,
+            pnl,
+            closed_at,
+        };
+        chat.fakes.gateway.close(&bot_id, closed(12.5, now - 3600));
+        chat.fakes
+            .gateway
+            .close(&bot_id, closed(-2.5, now - 3 * 86400));
+        chat.fakes.gateway.open(
+            &bot_id,
+            Position {
+                symbol: "BTCUSDT".into(),
+                side: PositionSide::Long,
+                size: 0.02,
+                entry_price: 37000.0,
+                mark_price: 37500.0,
+                unrealized_pnl: 10.0,
+                liq_price: None,
+                position_idx: 1,
+            },
+        );
+        chat.fakes.gateway.fill(
+            &bot_id,
+            Fill {
+                symbol: "BTCUSDT".into(),
+                side: OrderSide::Sell,
+                price: 37500.0,
+                qty: 0.01,
+                fee: 0.075,
+                is_maker: true,
+                executed_at: now - 3600,
+            },
+        );
+
+        chat.send("List").await;
+        chat.press("grid-1").await;
+        let replies = chat.send("Performance").await;
+        let text = last_text(&replies);
+        assert!(
+            text.starts_with(&format!("📈 Performance for {bot_id}")),
+            "{text}"
+        );
+        assert!(
+            text.contains("• 24h: +12.50 (1 closed, 100% win)"),
+            "{text}"
+        );
+        assert!(text.contains("• 7d: +10.00 (2 closed, 50% win)"), "{text}");
+        assert!(
+            text.contains("Open positions (unrealized +10.00):"),
+            "{text}"
+        );
+        assert!(text.contains("• BTCUSDT long 0.02 @ 37000"), "{text}");
+        assert!(text.contains("sell 0.01 BTCUSDT @ 37500 (maker)"), "{text}");
+    }
+}
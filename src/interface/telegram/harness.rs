@@ -0,0 +1,309 @@
+//! Scripted conversations against the real handler tree.
+//!
+//! [`Conversation`] wires [`router::schema`] to the in-memory adapters of the
+//! `testing` feature ([`Fakes`]) and feeds it synthetic `Message` /
+//! `CallbackQuery` updates, one at a time, as a single Telegram user would. The handlers talk
+//! to a local stand-in for the Bot API (a minimal HTTP/1.1 server on
+//! 127.0.0.1) that records every call and answers with the smallest payload
+//! teloxide accepts, so tests can assert on the texts and keyboards sent back.
+
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+use teloxide::dispatching::UpdateHandler;
+use teloxide::prelude::*;
+use teloxide::types::{Me, Update, UpdateKind};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::interface::fakes::Fakes;
+
+use super::router;
+
+pub(crate) const USER_ID: u64 = 1001;
+const BOT_USER_ID: u64 = 4242;
+
+/// One Bot API call made by a handler.
+#[derive(Debug, Clone)]
+pub(crate) struct Sent {
+    /// Method name as sent by teloxide, e.g. `SendMessage`.
+    pub method: String,
+    /// Message id assigned (sendMessage) or targeted (edits) by the call.
+    pub message_id: Option<i32>,
+    pub text: Option<String>,
+    /// Inline keyboard buttons, row-major, as `(label, callback_data)`.
+    pub buttons: Vec<(String, String)>,
+    /// Whether the call attached a reply (menu) keyboard.
+    pub reply_keyboard: bool,
+}
+
+impl Sent {
+    fn from_call(method: &str, params: &Value, message_id: Option<i32>) -> Self {
+        let markup = params.get("reply_markup");
+        let buttons = markup
+            .and_then(|m| m.get("inline_keyboard"))
+            .and_then(Value::as_array)
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(Value::as_array)
+                    .flatten()
+                    .map(|b| {
+                        (
+                            b["text"].as_str().unwrap_or_default().to_string(),
+                            b["callback_data"].as_str().unwrap_or_default().to_string(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            method: method.to_string(),
+            message_id,
+            text: params["text"].as_str().map(str::to_string),
+            buttons,
+            reply_keyboard: markup.is_some_and(|m| m.get("keyboard").is_some()),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_deref().unwrap_or_default()
+    }
+}
+
+/// Recorded calls and the message id counter shared by the stub and driver.
+struct ApiState {
+    calls: Mutex<Vec<Sent>>,
+    next_message_id: AtomicI32,
+}
+
+impl ApiState {
+    /// Record `method` and build its `result`: a `Message` for sends and
+    /// edits, the bot's `User` for `getMe`, `true` for everything else.
+    fn handle(&self, method: &str, params: &Value) -> Value {
+        let chat_id = params["chat_id"].as_i64().unwrap_or(USER_ID as i64);
+        match method.to_ascii_lowercase().as_str() {
+            "sendmessage" => {
+                let id = self.next_message_id.fetch_add(1, Ordering::SeqCst);
+                self.record(Sent::from_call(method, params, Some(id)));
+                message_json(id, chat_id, bot_user(), params)
+            }
+            "editmessagetext" | "editmessagereplymarkup" => {
+                let id = params["message_id"].as_i64().unwrap_or_default() as i32;
+                self.record(Sent::from_call(method, params, Some(id)));
+                message_json(id, chat_id, bot_user(), params)
+            }
+            "getme" => bot_user(),
+            _ => {
+                self.record(Sent::from_call(method, params, None));
+                Value::Bool(true)
+            }
+        }
+    }
+
+    fn record(&self, sent: Sent) {
+        self.calls.lock().unwrap().push(sent);
+    }
+}
+
+fn user_json() -> Value {
+    json!({ "id": USER_ID, "is_bot": false, "first_name": "Tester" })
+}
+
+fn bot_user() -> Value {
+    json!({
+        "id": BOT_USER_ID,
+        "is_bot": true,
+        "first_name": "pbtb",
+        "username": "pbtb_test_bot",
+    })
+}
+
+/// A private-chat text message. `reply_markup` is echoed only when inline:
+/// a `Message` cannot carry a reply keyboard.
+fn message_json(id: i32, chat_id: i64, from: Value, params: &Value) -> Value {
+    let mut message = json!({
+        "message_id": id,
+        "date": 0,
+        "chat": { "id": chat_id, "type": "private", "first_name": "Tester" },
+        "from": from,
+        "text": params["text"].as_str().unwrap_or_default(),
+    });
+    if let Some(markup) = params.get("reply_markup")
+        && markup.get("inline_keyboard").is_some()
+    {
+        message["reply_markup"] = markup.clone();
+    }
+    message
+}
+
+/// Serve `POST /bot<token>/<Method>` requests until the listener is dropped.
+async fn serve(listener: TcpListener, state: Arc<ApiState>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve_connection(stream, state.clone()));
+    }
+}
+
+/// One keep-alive connection: requests are read in order, each answered with
+/// `{"ok": true, "result": ...}`.
+async fn serve_connection(mut stream: TcpStream, state: Arc<ApiState>) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    loop {
+        let header_end = loop {
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        };
+        let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+        let content_length = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        while buf.len() < header_end + content_length {
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        }
+        let params: Value = serde_json::from_slice(&buf[header_end..header_end + content_length])
+            .unwrap_or_default();
+        buf.drain(..header_end + content_length);
+
+        let path = head.split_whitespace().nth(1).unwrap_or_default();
+        let method = path.rsplit('/').next().unwrap_or_default();
+        let body = json!({ "ok": true, "result": state.handle(method, &params) }).to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+    }
+}
+
+async fn read_more(stream: &mut TcpStream, buf: &mut Vec<u8>) -> std::io::Result<bool> {
+    let mut chunk = [0u8; 4096];
+    let n = stream.read(&mut chunk).await?;
+    buf.extend_from_slice(&chunk[..n]);
+    Ok(n > 0)
+}
+
+/// A single user's chat with the bot.
+pub(crate) struct Conversation {
+    pub fakes: Fakes,
+    state: Arc<ApiState>,
+    handler: UpdateHandler<DependencyMap>,
+    deps: DependencyMap,
+    next_update_id: i32,
+}
+
+impl Conversation {
+    pub async fn new() -> Self {
+        let fakes = Fakes::new();
+        let deps = fakes.deps();
+
+        let state = Arc::new(ApiState {
+            calls: Mutex::new(Vec::new()),
+            next_message_id: AtomicI32::new(1),
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, state.clone()));
+
+        // No proxy: the stub is local and an ambient HTTP(S)_PROXY would
+        // swallow the requests.
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let bot = Bot::with_client("4242:TEST", client).set_api_url(url.parse().unwrap());
+        let me: Me = serde_json::from_value(json!({
+            "id": BOT_USER_ID,
+            "is_bot": true,
+            "first_name": "pbtb",
+            "username": "pbtb_test_bot",
+            "can_join_groups": false,
+            "can_read_all_group_messages": false,
+            "supports_inline_queries": false,
+        }))
+        .unwrap();
+
+        let mut deps = router::dependencies(deps);
+        deps.insert(bot);
+        deps.insert(me);
+        Self {
+            fakes,
+            state,
+            handler: router::schema(),
+            deps,
+            next_update_id: 1,
+        }
+    }
+
+    /// The user types `text` (a command, a menu button or a dialogue answer).
+    /// Returns the Bot API calls the handlers made in response.
+    pub async fn send(&mut self, text: &str) -> Vec<Sent> {
+        let id = self.state.next_message_id.fetch_add(1, Ordering::SeqCst);
+        let message = message_json(id, USER_ID as i64, user_json(), &json!({ "text": text }));
+        let message = serde_json::from_value(message).expect("valid message");
+        self.dispatch(UpdateKind::Message(message)).await
+    }
+
+    /// The user taps the most recent inline button whose label contains
+    /// `label`. Panics when no such button was ever sent.
+    pub async fn press(&mut self, label: &str) -> Vec<Sent> {
+        let (message_id, text, data) = self
+            .state
+            .calls
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|sent| {
+                let (_, data) = sent.buttons.iter().find(|(l, _)| l.contains(label))?;
+                Some((sent.message_id?, sent.text.clone(), data.clone()))
+            })
+            .unwrap_or_else(|| panic!("no inline button labelled {label:?} was sent"));
+        let message = message_json(
+            message_id,
+            USER_ID as i64,
+            bot_user(),
+            &json!({ "text": text.unwrap_or_default() }),
+        );
+        let update_id = self.next_update_id;
+        let query = serde_json::from_value(json!({
+            "id": format!("cb-{update_id}"),
+            "from": user_json(),
+            "message": message,
+            "chat_instance": "test",
+            "data": data,
+        }))
+        .expect("valid callback query");
+        self.dispatch(UpdateKind::CallbackQuery(query)).await
+    }
+
+    async fn dispatch(&mut self, kind: UpdateKind) -> Vec<Sent> {
+        let update = Update {
+            id: self.next_update_id,
+            kind,
+        };
+        self.next_update_id += 1;
+
+        let seen = self.state.calls.lock().unwrap().len();
+        let mut deps = self.deps.clone();
+        deps.insert(update);
+        let _ = self.handler.dispatch(deps).await;
+        self.state.calls.lock().unwrap()[seen..].to_vec()
+    }
+}
+
+/// The text of the last message sent or edited in `replies`.
+pub(crate) fn last_text(replies: &[Sent]) -> &str {
+    replies
+        .iter()
+        .rev()
+        .find(|s| s.text.is_some())
+        .map(Sent::text)
+        .unwrap_or_default()
+}
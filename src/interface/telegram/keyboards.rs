@@ -7,6 +7,7 @@ pub(crate) fn main_menu_keyboard() -> KeyboardMarkup {
             KeyboardButton::new("/start"),
             KeyboardButton::new("State"),
             KeyboardButton::new("Balance"),
+            KeyboardButton::new("History"),
         ],
         vec![
             KeyboardButton::new("Add bot"),
@@ -17,11 +18,13 @@ pub(crate) fn main_menu_keyboard() -> KeyboardMarkup {
             KeyboardButton::new("Run bot"),
             KeyboardButton::new("Stop bot"),
             KeyboardButton::new("Unstuck"),
+            KeyboardButton::new("Positions"),
         ],
         vec![
             KeyboardButton::new("Delete API key"),
             KeyboardButton::new("List"),
             KeyboardButton::new("Sides"),
+            KeyboardButton::new("Performance"),
         ],
     ])
     .resize_keyboard(true)
@@ -47,14 +50,62 @@ pub(crate) fn strategy_sides_keyboard(
     ])
 }
 
+/// Inline keyboard for the risk-level editor: one button per template preset
+/// (`risk_set:<long>/<short>`), -/+ steppers per side around the pending value
+/// (`risk_step:<side>:<down|up>`; the middle button is a label), then Save /
+/// Cancel.
+pub(crate) fn risk_editor_keyboard(
+    pending: &crate::domain::botconfig::RiskLevel,
+    presets: &[(
+        crate::domain::riskpreset::RiskPreset,
+        crate::domain::botconfig::RiskLevel,
+    )],
+) -> InlineKeyboardMarkup {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+    if !presets.is_empty() {
+        keyboard.push(
+            presets
+                .iter()
+                .map(|(preset, risk)| {
+                    InlineKeyboardButton::callback(
+                        preset.label(),
+                        format!("risk_set:{}/{}", risk.long, risk.short),
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    let stepper = |side: &str, label: &str, value: f64| {
+        vec![
+            InlineKeyboardButton::callback("➖", format!("risk_step:{}:down", side)),
+            InlineKeyboardButton::callback(format!("{} {:.2}", label, value), "risk_noop"),
+            InlineKeyboardButton::callback("➕", format!("risk_step:{}:up", side)),
+        ]
+    };
+    keyboard.push(stepper("long", "Long", pending.long));
+    keyboard.push(stepper("short", "Short", pending.short));
+
+    keyboard.push(vec![
+        InlineKeyboardButton::callback("✅ Save", "risk_save"),
+        InlineKeyboardButton::callback("❌ Cancel", "risk_cancel"),
+    ]);
+
+    InlineKeyboardMarkup::new(keyboard)
+}
+
 /// Create inline keyboard for bot list. Each button leads with the bot's OBSERVED
 /// run-state glyph (not desired) so a fresh ✅ Running reads differently from a
-/// 🛑 Stopping or ⏸️ Stopped one.
+/// 🛑 Stopping or ⏸️ Stopped one. A page past the first gets a "⏮ First" button
+/// and a page with more bots after it a "Next ▶" one (`list_page:<cursor>`).
 pub(crate) fn bot_list_keyboard(
     bots: &[(
         crate::domain::bot::Bot,
         Option<crate::domain::runtime::RuntimePhase>,
     )],
+    first_page: bool,
+    next_cursor: Option<&str>,
 ) -> InlineKeyboardMarkup {
     let mut keyboard: Vec<Vec<InlineKeyboardButton>> = Vec::new();
 
@@ -75,6 +126,20 @@ pub(crate) fn bot_list_keyboard(
         keyboard.push(vec![button]);
     }
 
+    let mut nav = Vec::new();
+    if !first_page {
+        nav.push(InlineKeyboardButton::callback("⏮ First", "list_page:"));
+    }
+    if let Some(cursor) = next_cursor {
+        nav.push(InlineKeyboardButton::callback(
+            "Next ▶",
+            format!("list_page:{}", cursor),
+        ));
+    }
+    if !nav.is_empty() {
+        keyboard.push(nav);
+    }
+
     InlineKeyboardMarkup::new(keyboard)
 }
 
@@ -87,6 +152,98 @@ pub(crate) fn template_confirm_keyboard(template_name: &str) -> InlineKeyboardMa
     ]])
 }
 
+/// Confirm / cancel for a bulk action; the action rides in the callback data in
+/// its compact form (`bulk_run:stop`, `bulk_run:risk:1.5/0.5`).
+pub(crate) fn bulk_confirm_keyboard(action: &crate::usecase::BulkAction) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("✅ Confirm", format!("bulk_run:{}", action)),
+        InlineKeyboardButton::callback("❌ Cancel", "bulk_cancel"),
+    ]])
+}
+
+/// First step of the kill switch confirmation.
+pub(crate) fn panic_arm_keyboard() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            "🚨 Yes, continue",
+            "panic_arm",
+        )],
+        vec![InlineKeyboardButton::callback("❌ Cancel", "panic_cancel")],
+    ])
+}
+
+/// Second and final step; `armed_at` lets the handler reject a stale tap.
+pub(crate) fn panic_fire_keyboard(armed_at: i64) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            "🛑 CLOSE EVERYTHING NOW",
+            format!("panic_fire:{}", armed_at),
+        )],
+        vec![InlineKeyboardButton::callback("❌ Cancel", "panic_cancel")],
+    ])
+}
+
+/// A short tag for `bot_id` carried by the Positions screen's callbacks, so
+/// a tap can be refused once another bot is selected. The full id would not
+/// fit next to an order id in Telegram's 64 bytes.
+pub(crate) fn positions_bot_tag(bot_id: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(&Sha256::digest(bot_id.as_bytes())[..4])
+}
+
+/// Buttons of the Positions screen for `bot_id`: close per position
+/// (`pos_close:<tag>:<symbol>:<side>`, which asks to confirm), cancel per
+/// order up to `order_limit` (`pos_cancel:<tag>:<order_id>`), then refresh
+/// (`pos_refresh:<tag>`). `<tag>` is [`positions_bot_tag`]; Bybit order ids
+/// are UUIDs, so every callback stays within Telegram's 64 bytes.
+pub(crate) fn positions_keyboard(
+    bot_id: &str,
+    report: &crate::usecase::PositionsReport,
+    order_limit: usize,
+) -> InlineKeyboardMarkup {
+    let tag = positions_bot_tag(bot_id);
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+    for p in &report.positions {
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            format!("✖️ Close {} {}", p.symbol, p.side.as_str()),
+            format!("pos_close:{}:{}:{}", tag, p.symbol, p.side.as_str()),
+        )]);
+    }
+    for o in report.orders.iter().take(order_limit) {
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            format!("🗑 Cancel {}", super::views::format_order(o)),
+            format!("pos_cancel:{}:{}", tag, o.order_id),
+        )]);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        "🔄 Refresh",
+        format!("pos_refresh:{}", tag),
+    )]);
+    InlineKeyboardMarkup::new(keyboard)
+}
+
+/// Confirm / back for a manual close on `bot_id`
+/// (`pos_confirm:<tag>:<symbol>:<side>`); back re-renders the Positions
+/// screen.
+pub(crate) fn close_position_confirm_keyboard(
+    bot_id: &str,
+    position: &crate::domain::trading::Position,
+) -> InlineKeyboardMarkup {
+    let tag = positions_bot_tag(bot_id);
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(
+            "✅ Close at market",
+            format!(
+                "pos_confirm:{}:{}:{}",
+                tag,
+                position.symbol,
+                position.side.as_str()
+            ),
+        ),
+        InlineKeyboardButton::callback("❌ Back", format!("pos_refresh:{}", tag)),
+    ]])
+}
+
 /// Create inline keyboard for template list
 /// Each template is shown as a button with callback data containing template_name
 pub(crate) fn template_list_keyboard(templates: &[String]) -> InlineKeyboardMarkup {
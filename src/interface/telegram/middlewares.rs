@@ -1,8 +1,36 @@
 // Rust
+use crate::telemetry::{CorrelationId, with_correlation_id};
+use teloxide::dispatching::DpHandlerDescription;
+use teloxide::dptree::HandlerDescription;
+use teloxide::dptree::di::DependencySupplier;
 use teloxide::prelude::*;
+use tracing::Instrument;
 
 // 可在此添加鉴权、节流、统一错误拦截、日志上下文等横切逻辑。
-// 返回一个可链式组合的 Handler。当前先返回一个空的入口节点。
+// 返回一个可链式组合的 Handler；其后的处理器在它的上下文中运行。
+
+/// Run the rest of the tree for one update as one action: under a fresh
+/// correlation id and an `update` span naming the Telegram user, so every
+/// line the update causes, down to the task it launches, can be found
+/// together.
 pub fn install() -> teloxide::dispatching::UpdateHandler<DependencyMap> {
-    dptree::entry()
+    // An `entry` description keeps the allowed update kinds those of the
+    // handlers behind it.
+    dptree::from_fn_with_description(
+        DpHandlerDescription::entry(),
+        |deps: DependencyMap, cont| async move {
+            let update: std::sync::Arc<Update> = deps.get();
+            let correlation_id = CorrelationId::new();
+            let span = tracing::info_span!(
+                "update",
+                correlation_id = %correlation_id,
+                update_id = update.id,
+                user_id = tracing::field::Empty,
+            );
+            if let Some(user) = update.user() {
+                span.record("user_id", tracing::field::display(user.id));
+            }
+            with_correlation_id(correlation_id, cont(deps).instrument(span)).await
+        },
+    )
 }
@@ -2,6 +2,8 @@
 pub mod callbacks;
 pub mod commands;
 pub mod dialogue;
+#[cfg(test)]
+mod harness;
 pub mod keyboards;
 pub mod middlewares;
 pub mod router;
@@ -9,54 +11,86 @@ pub mod states;
 pub mod types;
 pub mod views;
 
-// Dependencies aggregation for handlers
 use crate::domain::bot::Bot;
 use crate::domain::runtime::RuntimePhase;
+use crate::interface::Deps;
 use crate::usecase::*;
-use std::sync::Arc;
+use teloxide::types::InlineKeyboardMarkup;
 
-/// Pair each bot with its OBSERVED runtime phase (for list buttons / status
-/// lines). One runtime read per bot; phase is `None` when no record exists.
-pub(crate) async fn bots_with_phase(
+/// Bots per page of the bot-list keyboard.
+pub(crate) const BOT_LIST_PAGE_SIZE: usize = 10;
+
+/// Resting orders listed (each with a cancel button) on the Positions screen.
+pub(crate) const POSITIONS_ORDER_LIMIT: usize = 20;
+
+/// The Positions screen for a bot, with `status` (the outcome of the action
+/// that led here) on top. The keyboard is `None` when there is nothing to act
+/// on because the bot or its account could not be read.
+pub(crate) async fn positions_screen(
     deps: &Deps,
     user_id: &str,
-    bots: Vec<Bot>,
-) -> Vec<(Bot, Option<RuntimePhase>)> {
-    let mut out = Vec::with_capacity(bots.len());
-    for b in bots {
-        let phase = deps
-            .get_bot_runtime_usecase
-            .execute(user_id, &b.id)
-            .await
-            .ok()
-            .flatten()
-            .map(|r| r.phase);
-        out.push((b, phase));
-    }
-    out
+    bot_id: &str,
+    status: Option<&str>,
+) -> (String, Option<InlineKeyboardMarkup>) {
+    let report = match deps.get_positions_usecase.execute(user_id, bot_id).await {
+        Ok(Some(report)) => report,
+        failed => {
+            let error = match failed {
+                Err(e) => format!("❌ Failed to load positions for bot {}:\n\n{}", bot_id, e),
+                _ => format!("❌ Bot {} not found.", bot_id),
+            };
+            let text = match status {
+                Some(status) => format!("{}\n\n{}", status, error),
+                None => error,
+            };
+            return (text, None);
+        }
+    };
+    (
+        views::format_positions(bot_id, &report, POSITIONS_ORDER_LIMIT, status),
+        Some(keyboards::positions_keyboard(
+            bot_id,
+            &report,
+            POSITIONS_ORDER_LIMIT,
+        )),
+    )
 }
 
-#[derive(Clone)]
-pub struct Deps {
-    // Bot management
-    pub list_bots_usecase: Arc<ListBotsUseCase>,
-    pub add_bot_usecase: Arc<AddBotUseCase>,
-    pub delete_bot_usecase: Arc<DeleteBotUseCase>,
-
-    // Template management
-    pub list_templates_usecase: Arc<ListTemplatesUseCase>,
-
-    // Bot config management
-    pub apply_template_usecase: Arc<ApplyTemplateUseCase>,
-    pub get_bot_config_usecase: Arc<GetBotConfigUseCase>,
-    pub update_bot_config_usecase: Arc<UpdateBotConfigUseCase>,
-    pub update_risk_level_usecase: Arc<UpdateRiskLevelUseCase>,
-    pub set_strategy_side_usecase: Arc<SetStrategySideUseCase>,
-
-    // Runtime / desired-state management
-    pub get_bot_runtime_usecase: Arc<GetBotRuntimeUseCase>,
+/// The bot-list keyboard for one page of the user's bots, resuming after
+/// `after` (`None` for the first page).
+pub(crate) fn bot_list_markup(page: BotListPage, after: Option<&str>) -> InlineKeyboardMarkup {
+    let bots: Vec<(Bot, Option<RuntimePhase>)> = page
+        .bots
+        .into_iter()
+        .map(|(bot, runtime)| (bot, runtime.map(|r| r.phase)))
+        .collect();
+    keyboards::bot_list_keyboard(&bots, after.is_none(), page.next_cursor.as_deref())
+}
 
-    // ECS actuation (desired state -> real RunTask/StopTask)
-    pub start_bot_usecase: Arc<StartBotUseCase>,
-    pub stop_bot_usecase: Arc<StopBotUseCase>,
+/// Render the risk-level editor for pending values: the confirmation text
+/// (risk, derived leverage, ceiling check, presets) and its inline keyboard.
+/// `Err` when the values cannot be applied (out of range, or the bot's policy
+/// cannot derive a valid leverage), so the caller keeps the previous values.
+pub(crate) async fn risk_editor(
+    deps: &Deps,
+    user_id: &str,
+    bot_id: &str,
+    risk_long: f64,
+    risk_short: f64,
+) -> Result<(String, InlineKeyboardMarkup), String> {
+    let preview = deps
+        .update_risk_level_usecase
+        .preview(user_id, bot_id, risk_long, risk_short)
+        .await?;
+    // Presets are a shortcut only; if the template cannot be read the editor
+    // still works without them.
+    let presets = deps
+        .get_risk_presets_usecase
+        .execute(user_id, bot_id)
+        .await
+        .unwrap_or_default();
+    Ok((
+        views::format_risk_editor(bot_id, &preview, &presets),
+        keyboards::risk_editor_keyboard(&preview.risk, &presets),
+    ))
 }
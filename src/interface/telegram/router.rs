@@ -3,26 +3,36 @@ use crate::interface::telegram::{
     Deps, callbacks, commands, dialogue, middlewares,
     states::{BotContext, DialogueState},
 };
+use teloxide::dispatching::UpdateHandler;
 use teloxide::dispatching::dialogue::InMemStorage;
 use teloxide::{dispatching::Dispatcher, prelude::*};
 
-pub async fn run(bot: Bot, deps: Deps) -> anyhow::Result<()> {
-    // Inject dependencies into DependencyMap for extraction in handlers
-    let deps_map = dptree::deps![
+/// The update handler tree: middlewares first, then commands, callbacks and
+/// dialogue steps. Shared by the dispatcher and the conversation tests.
+pub fn schema() -> UpdateHandler<DependencyMap> {
+    // The routes are chained behind the middlewares, not branched after
+    // them, so they run inside the middlewares' context.
+    dptree::entry().chain(middlewares::install()).chain(
+        dptree::entry()
+            .branch(commands::routes())
+            .branch(callbacks::routes())
+            .branch(dialogue::routes()),
+    )
+}
+
+/// Dependencies extracted by the handlers: the use cases and the per-chat
+/// dialogue / bot-context storages.
+pub fn dependencies(deps: Deps) -> DependencyMap {
+    dptree::deps![
         deps,
         InMemStorage::<DialogueState>::new(),
         InMemStorage::<BotContext>::new()
-    ];
-
-    // Explicitly annotate schema type as UpdateHandler<DependencyMap>
-    let schema: teloxide::dispatching::UpdateHandler<DependencyMap> = dptree::entry()
-        .chain(middlewares::install())
-        .branch(commands::routes())
-        .branch(callbacks::routes())
-        .branch(dialogue::routes());
+    ]
+}
 
-    let mut dispatcher = Dispatcher::builder(bot, schema)
-        .dependencies(deps_map)
+pub async fn run(bot: Bot, deps: Deps) -> anyhow::Result<()> {
+    let mut dispatcher = Dispatcher::builder(bot, schema())
+        .dependencies(dependencies(deps))
         .enable_ctrlc_handler()
         .build();
 
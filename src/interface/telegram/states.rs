@@ -21,7 +21,15 @@ pub enum DialogueState {
         api_key: String,
         secret_key: String,
     },
-    ReceiveRiskLevel,
+    /// Risk-level editor open for `bot_id`. Holds the pending values, adjusted
+    /// by the preset and stepper buttons or a typed `<long>/<short>`; nothing
+    /// is saved until the user confirms, and only while `bot_id` is still the
+    /// selected bot.
+    EditRiskLevel {
+        bot_id: String,
+        risk_long: f64,
+        risk_short: f64,
+    },
 }
 
 /// Main state that tracks selected bot (if any)
@@ -1,6 +1,17 @@
 // Rust
-use crate::domain::botconfig::{BotConfig, StrategyRef};
+use crate::domain::bot::Bot;
+use crate::domain::botconfig::{BotConfig, Leverage, RiskLevel, StrategyRef};
+use crate::domain::history::{BotEvent, BotEventKind};
+use crate::domain::preferences::UserPreferences;
+use crate::domain::riskguard::RiskGuard;
+use crate::domain::riskpreset::RiskPreset;
 use crate::domain::runtime::RuntimePhase;
+use crate::domain::schedule::{Schedule, format_utc};
+use crate::domain::trading::{Order, Position};
+use crate::usecase::{
+    ApprovedCoins, BulkAction, BulkOutcome, BulkReport, CoinListing, PanicReport,
+    PerformanceReport, PnlSummary, PositionsReport, RiskPreview,
+};
 
 pub fn welcome_text() -> String {
     "Welcome! Use the menu below to get started.".to_owned()
@@ -112,3 +123,503 @@ pub fn format_template_confirm(template_name: &str, preview: &BotConfig) -> Stri
         template_name, strategies, description, exposure, coins
     )
 }
+
+/// Render the risk-level editor: the pending risk, the leverage the bot's policy
+/// derives from it, the guard-rail check against the exposure ceiling, and the
+/// template's presets.
+pub fn format_risk_editor(
+    bot_id: &str,
+    preview: &RiskPreview,
+    presets: &[(RiskPreset, RiskLevel)],
+) -> String {
+    let guard = match preview.ceiling {
+        Some(ceiling) if preview.exceeds_ceiling() => format!(
+            "🚨 Leveraged exposure {:.2} is ABOVE your ceiling of {:.2}.\n\
+            Save only if this is intended.",
+            preview.leveraged_exposure, ceiling
+        ),
+        Some(ceiling) => format!(
+            "🟢 Leveraged exposure {:.2} is within your ceiling of {:.2}.",
+            preview.leveraged_exposure, ceiling
+        ),
+        None => format!(
+            "Leveraged exposure: {:.2} (no ceiling set — /ceiling <n>)",
+            preview.leveraged_exposure
+        ),
+    };
+
+    let presets_info = if presets.is_empty() {
+        "   • None for this template".to_owned()
+    } else {
+        presets
+            .iter()
+            .map(|(preset, risk)| {
+                format!(
+                    "   • {}: {:.2}/{:.2}",
+                    preset.label(),
+                    risk.long,
+                    risk.short
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(
+        "⚠️ Risk Level Configuration\n\n\
+        🤖 Bot: {}\n\
+        📊 Pending Risk Level:\n\
+           • Long: {:.2}\n\
+           • Short: {:.2}\n\
+        📈 Derived leverage: {:.1}x\n\n\
+        {}\n\n\
+        🎚️ Presets (long/short):\n\
+        {}\n\n\
+        Adjust with the buttons or send `<long>/<short>` (e.g. `3.0/1.5`).\n\
+        Tap Save or reply 'yes' to save; /leverage changes how leverage is derived.",
+        bot_id, preview.risk.long, preview.risk.short, preview.leverage.long, guard, presets_info
+    )
+}
+
+/// Confirmation shown once a risk level has been saved.
+pub fn format_risk_saved(
+    bot_id: &str,
+    risk_long: f64,
+    risk_short: f64,
+    leverage: &Leverage,
+) -> String {
+    format!(
+        "✅ Risk level updated successfully!\n\n\
+        🤖 Bot: {}\n\
+        📊 New Risk Level:\n\
+           • Long: {:.2}\n\
+           • Short: {:.2}\n\
+        📈 Leverage set to: {:.1}x\n\n\
+        The configuration has been saved.",
+        bot_id, risk_long, risk_short, leverage.long
+    )
+}
+
+/// Refusal shown when the risk editor is used after another bot was selected.
+pub fn format_risk_editor_moved(bot_id: &str) -> String {
+    format!(
+        "❌ This risk editor is for {}, which is no longer the selected bot. \
+        Select it again, or cancel and open Risk level for the selected bot.",
+        bot_id
+    )
+}
+
+/// List the user's schedules, soonest first, with the outcome of each one's
+/// last firing.
+pub fn format_schedules(schedules: &[Schedule]) -> String {
+    if schedules.is_empty() {
+        return "🗓️ Your schedules:\n\n(No schedules yet)\n\n\
+            Add one with /schedule <min> <hour> <day> <month> <weekday> <action>"
+            .to_owned();
+    }
+    let mut out = String::from("🗓️ Your schedules (times in UTC):\n");
+    for s in schedules {
+        out.push_str(&format!(
+            "\n• {} — {} `{}` → {}\n   Next: {}\n",
+            s.id,
+            s.bot_id,
+            s.cron,
+            s.action,
+            format_utc(s.next_run_at)
+        ));
+        if let (Some(at), Some(result)) = (s.last_run_at, s.last_result.as_deref()) {
+            out.push_str(&format!("   Last: {} — {}\n", format_utc(at), result));
+        }
+    }
+    out.push_str("\nRemove one with /unschedule <id>");
+    out
+}
+
+/// List a bot's recent lifecycle events, newest first, with the stop cause or
+/// outcome and the consecutive restart number where there is one.
+pub fn format_history(bot_id: &str, events: &[BotEvent]) -> String {
+    if events.is_empty() {
+        return format!(
+            "📜 History for {}:\n\n(No events in the last 30 days)",
+            bot_id
+        );
+    }
+    let mut out = format!("📜 History for {} (newest first, UTC):\n", bot_id);
+    for e in events {
+        let label = match e.kind {
+            BotEventKind::Started => "▶️ Started",
+            BotEventKind::StopRequested => "⏹️ Stop requested",
+            BotEventKind::Running => "✅ Running",
+            BotEventKind::DuplicateRefused => "⚠️ Duplicate task refused",
+            BotEventKind::Stopped => "⏸️ Stopped",
+            BotEventKind::Restarted => "🔁 Restarted",
+            BotEventKind::Resized => "📐 Resized",
+            BotEventKind::GuardTripped => "🛡️ Risk guard tripped",
+        };
+        out.push_str(&format!("\n• {} — {}", format_utc(e.at), label));
+        if let Some(attempt) = e.restart_attempt {
+            out.push_str(&format!(" (restart #{})", attempt));
+        }
+        if let Some(detail) = &e.detail {
+            out.push_str(&format!(": {}", detail));
+        }
+    }
+    out
+}
+
+/// Realized PnL and win rate per rolling period, then what is open now and
+/// the latest fills. Amounts are in the settle coin (USDT).
+pub fn format_performance(bot_id: &str, report: &PerformanceReport) -> String {
+    let period = |label: &str, s: &PnlSummary| {
+        let win_rate = s
+            .win_rate()
+            .map(|r| format!("{:.0}% win", r * 100.0))
+            .unwrap_or_else(|| "no closed trades".to_owned());
+        format!(
+            "\n• {}: {:+.2} ({} closed, {})",
+            label, s.realized_pnl, s.closed_trades, win_rate
+        )
+    };
+    let mut out = format!(
+        "📈 Performance for {} (as of {} UTC)\n\nRealized PnL:",
+        bot_id,
+        format_utc(report.as_of)
+    );
+    out.push_str(&period("24h", &report.day));
+    out.push_str(&period("7d", &report.week));
+    out.push_str(&period("30d", &report.month));
+
+    out.push_str(&format!(
+        "\n\nOpen positions (unrealized {:+.2}):",
+        report.unrealized_pnl()
+    ));
+    if report.positions.is_empty() {
+        out.push_str("\n(None)");
+    }
+    for p in &report.positions {
+        out.push_str(&format!(
+            "\n• {} {} {} @ {} (mark {}, {:+.2})",
+            p.symbol,
+            p.side.as_str(),
+            p.size,
+            p.entry_price,
+            p.mark_price,
+            p.unrealized_pnl
+        ));
+    }
+
+    out.push_str("\n\nRecent fills (7d):");
+    if report.recent_fills.is_empty() {
+        out.push_str("\n(None)");
+    }
+    for f in &report.recent_fills {
+        out.push_str(&format!(
+            "\n• {} — {} {} {} @ {}{}",
+            format_utc(f.executed_at),
+            f.side.as_str(),
+            f.qty,
+            f.symbol,
+            f.price,
+            if f.is_maker { " (maker)" } else { "" }
+        ));
+    }
+    out
+}
+
+/// One position line: size, entry, mark, uPnL and liquidation price.
+fn format_position_line(p: &Position) -> String {
+    format!(
+        "• {} {} {} — entry {}, mark {}, uPnL {:+.2}, liq {}",
+        p.symbol,
+        p.side.as_str(),
+        p.size,
+        p.entry_price,
+        p.mark_price,
+        p.unrealized_pnl,
+        p.liq_price
+            .map_or_else(|| "none".to_owned(), |l| l.to_string())
+    )
+}
+
+/// Short order description, shared by the list and its cancel buttons.
+pub fn format_order(o: &Order) -> String {
+    format!(
+        "{} {} {} @ {}{}",
+        o.symbol,
+        o.side.as_str(),
+        o.qty,
+        o.price,
+        if o.reduce_only { " (reduce-only)" } else { "" }
+    )
+}
+
+/// The Positions screen: every open position, then the resting orders (the
+/// first `order_limit` of them, which are the ones with a cancel button).
+/// `status` is the outcome of the action that led here, if any.
+pub fn format_positions(
+    bot_id: &str,
+    report: &PositionsReport,
+    order_limit: usize,
+    status: Option<&str>,
+) -> String {
+    let mut out = String::new();
+    if let Some(status) = status {
+        out.push_str(status);
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!(
+        "📊 Positions for {} (as of {} UTC)\n\nOpen positions (unrealized {:+.2}):",
+        bot_id,
+        format_utc(report.as_of),
+        report
+            .positions
+            .iter()
+            .map(|p| p.unrealized_pnl)
+            .sum::<f64>()
+    ));
+    if report.positions.is_empty() {
+        out.push_str("\n(None)");
+    }
+    for p in &report.positions {
+        out.push('\n');
+        out.push_str(&format_position_line(p));
+    }
+
+    out.push_str(&format!("\n\nOpen orders ({}):", report.orders.len()));
+    if report.orders.is_empty() {
+        out.push_str("\n(None)");
+    }
+    for o in report.orders.iter().take(order_limit) {
+        out.push_str(&format!("\n• {}", format_order(o)));
+    }
+    if report.orders.len() > order_limit {
+        out.push_str(&format!(
+            "\n…and {} more (cancel those on the exchange)",
+            report.orders.len() - order_limit
+        ));
+    }
+
+    if !report.positions.is_empty() || !report.orders.is_empty() {
+        out.push_str(
+            "\n\nTap a position to close it at market (you will be asked to confirm), \
+            or an order to cancel it. The bot keeps running and may reopen a closed side.",
+        );
+    }
+    out
+}
+
+/// Confirmation prompt for a manual close.
+pub fn format_close_confirm(bot_id: &str, p: &Position) -> String {
+    format!(
+        "⚠️ Close this position on {} with a reduce-only market order?\n\n{}\n\n\
+        The bot keeps running: unless its {} side is stopped, passivbot may open it again.",
+        bot_id,
+        format_position_line(p),
+        p.side.as_str()
+    )
+}
+
+/// Confirmation prompt for a bulk action: what it does and which bots it hits.
+pub fn format_bulk_confirm(action: &BulkAction, targets: &[Bot]) -> String {
+    let effect = match action {
+        BulkAction::StopAll => "Stops every bot's task and turns its desired state off.",
+        BulkAction::StartAllEnabled => "Starts every bot that is turned on but not running.",
+        BulkAction::GracefulStopAll => {
+            "Sets both sides of every bot to graceful_stop (no new entries; \
+            open positions are closed normally). Applies on the next launch."
+        }
+        BulkAction::ApplyRisk { .. } => {
+            "Sets this risk level on every bot; leverage follows each bot's own policy."
+        }
+    };
+    let names = targets
+        .iter()
+        .map(|b| format!("• {}", b.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "⚠️ {}\n\n{}\n\nBots ({}):\n{}\n\nContinue?",
+        action.label(),
+        effect,
+        targets.len(),
+        names
+    )
+}
+
+/// Per-bot outcome of a bulk action, with totals.
+pub fn format_bulk_report(report: &BulkReport) -> String {
+    let mut out = format!(
+        "📋 {}: {} done, {} skipped, {} failed\n",
+        report.action.label(),
+        report.done(),
+        report.skipped(),
+        report.failed()
+    );
+    for r in &report.results {
+        let line = match &r.outcome {
+            BulkOutcome::Done(s) => format!("✅ {} — {}", r.bot_name, s),
+            BulkOutcome::Skipped(s) => format!("⏭️ {} — skipped ({})", r.bot_name, s),
+            BulkOutcome::Failed(e) => format!("❌ {} — {}", r.bot_name, e),
+        };
+        out.push('\n');
+        out.push_str(&line);
+    }
+    out
+}
+
+/// First confirmation of the kill switch: spell out everything it does.
+pub fn format_panic_warning() -> String {
+    "🚨 EMERGENCY STOP\n\n\
+    For EVERY one of your bots this will:\n\
+    • set both sides to panic mode in the config\n\
+    • stop the bot's task\n\
+    • cancel all open orders\n\
+    • close all positions at market\n\n\
+    Market closes can fill at a bad price. Continue?"
+        .to_owned()
+}
+
+/// Second confirmation, shown after the first tap.
+pub fn format_panic_armed(expires_in_secs: i64) -> String {
+    format!(
+        "🚨 Last confirmation.\n\n\
+        Tap below to flatten and stop ALL bots now. \
+        This button expires in {} seconds.",
+        expires_in_secs
+    )
+}
+
+/// Per-bot outcome of the kill switch, leading with whether it is flat.
+pub fn format_panic_report(report: &PanicReport) -> String {
+    let mut out = if report.all_flat() {
+        "✅ Emergency stop finished: all bots flat.\n".to_owned()
+    } else {
+        "⚠️ Emergency stop finished, but NOT everything is confirmed flat. \
+        Check the exchange now.\n"
+            .to_owned()
+    };
+    if report.results.is_empty() {
+        out.push_str("\n(No bots)");
+    }
+    let step = |r: &Result<String, String>| match r {
+        Ok(s) => s.clone(),
+        Err(e) => format!("❌ {}", e),
+    };
+    for r in &report.results {
+        let flat = match (&r.remaining, r.is_flat()) {
+            (_, Some(true)) => "🟢 flat".to_owned(),
+            (Ok(left), _) => format!(
+                "🔴 NOT flat: {}",
+                left.iter()
+                    .map(|p| format!("{} {} {}", p.symbol, p.side.as_str(), p.size))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Err(e), _) => format!("❔ unknown ({})", e),
+        };
+        out.push_str(&format!(
+            "\n{} — {}\n   config: {}\n   task: {}\n   close: {}\n",
+            r.bot_name,
+            flat,
+            step(&r.config.clone().map(|_| "panic mode set".to_owned())),
+            step(&r.stop),
+            step(
+                &r.flatten
+                    .clone()
+                    .map(|n| format!("{} position(s) closed", n))
+            ),
+        ));
+    }
+    out
+}
+
+/// A freshly issued HTTP API token. It is shown only this once.
+pub fn format_api_token(secret: &str) -> String {
+    format!(
+        "🔑 Your HTTP API token:\n\n{}\n\n\
+        Send it as `Authorization: Bearer <token>`. It is shown only once; \
+        any previous token no longer works. /apitoken revoke disables it.",
+        secret
+    )
+}
+
+/// The daily digest setting, as `/digest` shows it.
+pub fn format_digest_setting(prefs: &UserPreferences) -> String {
+    let current = match (prefs.digest_hour, prefs.next_digest_at) {
+        (Some(hour), Some(next)) => format!(
+            "every day at {:02}:00 UTC\nNext: {}",
+            hour,
+            format_utc(next)
+        ),
+        _ => "off".to_string(),
+    };
+    format!(
+        "📰 Daily digest: {}\n\n\
+        A summary of all your bots: state, restarts, config changes and equity.\n\n\
+        Usage: /digest <hour 0-23, UTC>, /digest off, or /digest now for a preview",
+        current
+    )
+}
+
+/// The selected bot's risk guard, for `/guard`.
+pub fn format_risk_guard(guard: &RiskGuard) -> String {
+    let limit = |pct: Option<f64>| pct.map_or_else(|| "off".to_string(), |p| format!("{p}%"));
+    let state = match (guard.tripped_at, guard.is_armed()) {
+        (Some(at), _) => format!(
+            "🔴 tripped {}: {}",
+            format_utc(at),
+            guard.trip_reason.as_deref().unwrap_or("limit breached")
+        ),
+        (None, true) => "🟢 armed".to_string(),
+        (None, false) => "⚪ no limits set".to_string(),
+    };
+    format!(
+        "🛡️ Risk guard for {}: {}\n\
+        • Max daily loss: {}\n\
+        • Max drawdown: {}\n\n\
+        On a breach both sides go to graceful_stop and the bot restarts; \
+        the guard stays tripped until re-armed.\n\n\
+        Usage: /guard daily <pct|off>, /guard drawdown <pct|off>, /guard rearm",
+        guard.bot_id,
+        state,
+        limit(guard.max_daily_loss_pct),
+        limit(guard.max_drawdown_pct)
+    )
+}
+
+/// The selected bot's approved coins with their exchange listing, for
+/// `/coins`.
+pub fn format_approved_coins(bot_id: &str, coins: &ApprovedCoins) -> String {
+    let side = |listings: &[CoinListing]| {
+        if listings.is_empty() {
+            return "  (none)".to_string();
+        }
+        listings
+            .iter()
+            .map(|l| match (&l.instrument, coins.catalog_fetched_at) {
+                (Some(i), _) if i.is_trading() => {
+                    format!("  • {} — {}, max {}x", l.coin, i.symbol, i.max_leverage)
+                }
+                (Some(i), _) => format!("  • {} — ⚠️ {} is {}", l.coin, i.symbol, i.status),
+                (None, Some(_)) => format!("  • {} — ❌ not listed", l.coin),
+                (None, None) => format!("  • {}", l.coin),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let catalog = match coins.catalog_fetched_at {
+        Some(at) => format!("Instruments as of {}.", format_utc(at)),
+        None => "No instrument list yet; coins are not checked.".to_string(),
+    };
+    format!(
+        "🪙 Approved coins for {}\n\
+        Long:\n{}\n\
+        Short:\n{}\n\n\
+        {}\n\n\
+        Usage: /coins long <COIN ...>, /coins short <COIN ...>, /coins <side> none",
+        bot_id,
+        side(&coins.long),
+        side(&coins.short),
+        catalog
+    )
+}
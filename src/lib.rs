@@ -1,4 +1,5 @@
 pub mod config;
 pub mod domain;
 pub mod infra;
+pub mod telemetry;
 pub mod usecase;
@@ -2,12 +2,17 @@ mod config;
 mod domain;
 mod infra;
 mod interface;
+mod telemetry;
 mod usecase;
 
 use anyhow::Context;
 use domain::SystemClock;
-use infra::{DynamoBotRepository, S3ApiKeyRepository, S3BotConfigRepository, S3TemplateRepository};
+use infra::{
+    BybitTradingGateway, DynamoBotRepository, PrometheusMetrics, S3ApiKeyRepository,
+    S3BotConfigRepository, S3TemplateRepository,
+};
 use pbtb_rust::config::configs::{Configs, load_config};
+use pbtb_rust::config::docker::DockerConfig;
 use pbtb_rust::infra::client::{
     setup_dynamodb_with_configs, setup_ecs_with_configs, setup_s3_with_configs,
 };
@@ -17,10 +22,9 @@ use usecase::*;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    println!("Starting Telegram bot...");
-
-    // Initialize logger
-    env_logger::init();
+    // JSON log lines; teloxide's `log` records are forwarded too.
+    telemetry::init_tracing();
+    tracing::info!("Starting Telegram bot...");
 
     // Read token from TELEGRAM_BOT_TOKEN environment variable
     let bot = Bot::from_env();
@@ -30,9 +34,9 @@ async fn main() -> anyhow::Result<()> {
     let (dynamodb_client, table_name) = setup_dynamodb_with_configs(&configs).await;
     // Setup S3
     let (s3_client, bucket_name) = setup_s3_with_configs(&configs).await;
-    // Setup ECS (for telebot Run/Stop -> RunTask/StopTask actuation)
-    let (ecs_client, cluster_arn, td_arn) = setup_ecs_with_configs(&configs).await;
-    let container_name = configs.ecs.td_passivbot_container_name.clone();
+    // Setup the compute backend (for telebot Run/Stop -> task launch/stop):
+    // ECS RunTask/StopTask, or containers on the local Docker engine.
+    let compute = setup_compute(&configs, &bucket_name).await?;
 
     // Create repositories
     let bot_repository = Arc::new(DynamoBotRepository::new(dynamodb_client, table_name));
@@ -44,12 +48,23 @@ async fn main() -> anyhow::Result<()> {
         s3_client.clone(),
         bucket_name.clone(),
     ));
+    // The instrument catalog is written by the instrument_refresh Lambda; the
+    // bot only reads it to check coins and cap leverage.
+    let instrument_catalog: Arc<dyn domain::instrument::InstrumentCatalogRepository> = Arc::new(
+        infra::instrumentrepository::S3InstrumentCatalogRepository::new(
+            s3_client.clone(),
+            bucket_name.clone(),
+        ),
+    );
     let api_keys_repository = Arc::new(S3ApiKeyRepository::new(s3_client, bucket_name));
     // Use cases depend on the domain ApiKeyRepository port, not the concrete infra impl.
     let api_keys_repo: Arc<dyn domain::ApiKeyRepository> = api_keys_repository.clone();
 
     // Create clock
     let clock = Arc::new(SystemClock);
+    // Lifecycle metrics, scraped from the Prometheus endpoint when it is enabled.
+    let metrics = Arc::new(PrometheusMetrics::new());
+    let metrics_dyn: Arc<dyn domain::Metrics> = metrics.clone();
 
     // Create use cases - Bot management
     let list_bots_usecase = Arc::new(ListBotsUseCase::new(bot_repository.clone()));
@@ -61,6 +76,8 @@ async fn main() -> anyhow::Result<()> {
     let delete_bot_usecase = Arc::new(DeleteBotUseCase::new(
         bot_repository.clone(),
         api_keys_repo.clone(),
+        bot_repository.clone(),
+        bot_repository.clone(),
     ));
 
     // Create use cases - Template management
@@ -78,13 +95,45 @@ async fn main() -> anyhow::Result<()> {
         clock.clone(),
     ));
     let update_risk_level_usecase = Arc::new(UpdateRiskLevelUseCase::new(
+        bot_repository.clone(),
+        bot_config_repository.clone(),
+        instrument_catalog.clone(),
+        clock.clone(),
+    ));
+    let get_risk_presets_usecase = Arc::new(GetRiskPresetsUseCase::new(
         bot_config_repository.clone(),
+        template_repository.clone(),
+    ));
+    let set_leverage_policy_usecase = Arc::new(SetLeveragePolicyUseCase::new(
+        bot_repository.clone(),
+        clock.clone(),
+    ));
+    let set_restart_policy_usecase = Arc::new(SetRestartPolicyUseCase::new(
+        bot_repository.clone(),
+        clock.clone(),
+    ));
+    let set_resource_profile_usecase = Arc::new(SetResourceProfileUseCase::new(
+        bot_repository.clone(),
+        clock.clone(),
+    ));
+    let set_exposure_ceiling_usecase = Arc::new(SetExposureCeilingUseCase::new(
+        bot_repository.clone(),
         clock.clone(),
     ));
     let set_strategy_side_usecase = Arc::new(SetStrategySideUseCase::new(
         bot_config_repository.clone(),
         clock.clone(),
     ));
+    let get_approved_coins_usecase = Arc::new(GetApprovedCoinsUseCase::new(
+        bot_config_repository.clone(),
+        instrument_catalog.clone(),
+        clock.clone(),
+    ));
+    let set_approved_coins_usecase = Arc::new(SetApprovedCoinsUseCase::new(
+        bot_config_repository.clone(),
+        instrument_catalog.clone(),
+        clock.clone(),
+    ));
 
     // Create use cases - Runtime / desired-state management
     // DynamoBotRepository implements BotRepository, BotRuntimeRepository and
@@ -92,34 +141,153 @@ async fn main() -> anyhow::Result<()> {
     let bots_dyn: Arc<dyn domain::BotRepository> = bot_repository.clone();
     let runtimes_dyn: Arc<dyn domain::BotRuntimeRepository> = bot_repository.clone();
     let start_locks: Arc<dyn domain::StartLockRepository> = bot_repository.clone();
+    let history_dyn: Arc<dyn domain::BotHistoryRepository> = bot_repository.clone();
     let get_bot_runtime_usecase = Arc::new(GetBotRuntimeUseCase::new(runtimes_dyn.clone()));
+    let get_bot_history_usecase = Arc::new(GetBotHistoryUseCase::new(history_dyn.clone()));
+    let list_bots_with_runtime_usecase = Arc::new(ListBotsWithRuntimeUseCase::new(
+        bots_dyn.clone(),
+        runtimes_dyn.clone(),
+    ));
 
-    // Create use cases - ECS actuation (Run/Stop buttons -> RunTask/StopTask)
-    let task_runner: Arc<dyn TaskRunner> = Arc::new(RunTaskUseCase::new(ecs_client.clone()));
-    let task_controller: Arc<dyn TaskController> = Arc::new(EcsTaskController::new(ecs_client));
+    // Create use cases - Task actuation (Run/Stop buttons -> launch/stop)
     let start_bot_usecase = Arc::new(StartBotUseCase::new(
         bots_dyn.clone(),
         runtimes_dyn.clone(),
-        start_locks,
-        task_runner,
-        task_controller.clone(),
+        start_locks.clone(),
+        compute.runner.clone(),
+        compute.controller.clone(),
         clock.clone(),
-        cluster_arn.clone(),
-        td_arn,
-        container_name,
+        history_dyn.clone(),
+        metrics_dyn.clone(),
+        bot_config_repository.clone(),
+        instrument_catalog,
+        compute.cluster_arn.clone(),
+        compute.td_arn.clone(),
+        compute.container_name.clone(),
     ));
     let stop_bot_usecase = Arc::new(StopBotUseCase::new(
+        bots_dyn.clone(),
+        runtimes_dyn.clone(),
+        bot_repository.clone(),
+        compute.controller.clone(),
+        clock.clone(),
+        history_dyn.clone(),
+        metrics_dyn.clone(),
+        compute.cluster_arn.clone(),
+    ));
+    // Locally there are no Lambdas: the telebot records, reconciles and sweeps
+    // its own containers.
+    #[cfg(feature = "docker")]
+    if let Some((backend, sweep_interval)) = compute.docker.clone() {
+        spawn_docker_supervisor(
+            &compute,
+            backend,
+            sweep_interval,
+            bot_repository.clone(),
+            clock.clone(),
+            metrics_dyn.clone(),
+        );
+    }
+
+    // Create use cases - Scheduled actions (fired by the schedule_runner Lambda)
+    let schedules_dyn: Arc<dyn domain::ScheduleRepository> = bot_repository.clone();
+    let create_schedule_usecase = Arc::new(CreateScheduleUseCase::new(
+        schedules_dyn.clone(),
         bots_dyn,
-        runtimes_dyn,
-        task_controller,
         clock.clone(),
-        cluster_arn,
+    ));
+    let list_schedules_usecase = Arc::new(ListSchedulesUseCase::new(schedules_dyn.clone()));
+    let delete_schedule_usecase = Arc::new(DeleteScheduleUseCase::new(schedules_dyn));
+
+    // Create use cases - Bulk actions (same per-bot use cases, bounded fan-out)
+    let action_runner: Arc<dyn ScheduleActionRunner> = Arc::new(UseCaseActionRunner::new(
+        start_bot_usecase.clone(),
+        stop_bot_usecase.clone(),
+        update_risk_level_usecase.clone(),
+        set_strategy_side_usecase.clone(),
+    ));
+    let bulk_bot_action_usecase = Arc::new(BulkBotActionUseCase::new(
+        list_bots_usecase.clone(),
+        action_runner.clone(),
+        BULK_MAX_CONCURRENCY,
+    ));
+    // Kill switch: closes positions directly on the exchange with each bot's key.
+    let bybit = Arc::new(BybitTradingGateway::new(infra::bybit::BYBIT_MAINNET_URL));
+    let trading_gateway: Arc<dyn domain::ExchangeTradingGateway> = bybit.clone();
+    let panic_usecase = Arc::new(PanicUseCase::new(
+        list_bots_usecase.clone(),
+        bot_config_repository.clone(),
+        action_runner,
+        trading_gateway.clone(),
+        clock.clone(),
+        BULK_MAX_CONCURRENCY,
+    ));
+
+    // Create use cases - Performance (read from the same Bybit sub-accounts)
+    let account: Arc<dyn domain::account::ExchangeAccount> = bybit;
+    let get_performance_usecase = Arc::new(GetPerformanceUseCase::new(
+        bot_repository.clone(),
+        account.clone(),
+        clock.clone(),
     ));
 
+    // Create use cases - Positions screen (manual close / cancel)
+    let get_positions_usecase = Arc::new(GetPositionsUseCase::new(
+        bot_repository.clone(),
+        trading_gateway.clone(),
+        clock.clone(),
+    ));
+    let close_position_usecase = Arc::new(ClosePositionUseCase::new(
+        bot_repository.clone(),
+        trading_gateway.clone(),
+    ));
+    let cancel_order_usecase = Arc::new(CancelOrderUseCase::new(
+        bot_repository.clone(),
+        trading_gateway,
+    ));
+
+    // Create use cases - Daily digest settings and preview (sent by the
+    // daily_digest Lambda)
+    let prefs_dyn: Arc<dyn domain::preferences::UserPreferencesRepository> = bot_repository.clone();
+    let get_preferences_usecase =
+        Arc::new(GetPreferencesUseCase::new(prefs_dyn.clone(), clock.clone()));
+    let set_digest_hour_usecase =
+        Arc::new(SetDigestHourUseCase::new(prefs_dyn.clone(), clock.clone()));
+    let build_daily_digest_usecase = Arc::new(BuildDailyDigestUseCase::new(
+        bot_repository.clone(),
+        runtimes_dyn.clone(),
+        history_dyn.clone(),
+        bot_config_repository.clone(),
+        account,
+        prefs_dyn,
+        clock.clone(),
+    ));
+
+    // Create use cases - Risk guard settings (evaluated by the risk_guard
+    // Lambda)
+    let guards_dyn: Arc<dyn domain::riskguard::RiskGuardRepository> = bot_repository.clone();
+    let get_risk_guard_usecase =
+        Arc::new(GetRiskGuardUseCase::new(guards_dyn.clone(), clock.clone()));
+    let set_risk_guard_limit_usecase = Arc::new(SetRiskGuardLimitUseCase::new(
+        guards_dyn.clone(),
+        clock.clone(),
+    ));
+    let rearm_risk_guard_usecase = Arc::new(RearmRiskGuardUseCase::new(guards_dyn, clock.clone()));
+
+    // Create use cases - HTTP API tokens
+    let api_tokens_dyn: Arc<dyn domain::apitoken::ApiTokenRepository> = bot_repository.clone();
+    let issue_api_token_usecase = Arc::new(IssueApiTokenUseCase::new(
+        api_tokens_dyn.clone(),
+        clock.clone(),
+    ));
+    let revoke_api_token_usecase = Arc::new(RevokeApiTokenUseCase::new(api_tokens_dyn.clone()));
+    let authenticate_api_token_usecase = Arc::new(AuthenticateApiTokenUseCase::new(api_tokens_dyn));
+
     // Construct dependencies
-    let deps = interface::telegram::Deps {
+    let deps = interface::Deps {
         // Bot management
         list_bots_usecase,
+        list_bots_with_runtime_usecase,
         add_bot_usecase,
         delete_bot_usecase,
         // Template management
@@ -129,13 +297,195 @@ async fn main() -> anyhow::Result<()> {
         get_bot_config_usecase,
         update_bot_config_usecase,
         update_risk_level_usecase,
+        get_risk_presets_usecase,
+        set_leverage_policy_usecase,
+        set_restart_policy_usecase,
+        set_resource_profile_usecase,
+        set_exposure_ceiling_usecase,
         set_strategy_side_usecase,
+        get_approved_coins_usecase,
+        set_approved_coins_usecase,
         // Runtime / desired-state management
         get_bot_runtime_usecase,
+        get_bot_history_usecase,
         // ECS actuation
         start_bot_usecase,
         stop_bot_usecase,
+        // Scheduled actions
+        create_schedule_usecase,
+        list_schedules_usecase,
+        delete_schedule_usecase,
+        // Bulk actions
+        bulk_bot_action_usecase,
+        panic_usecase,
+        // Performance
+        get_performance_usecase,
+        // Positions
+        get_positions_usecase,
+        close_position_usecase,
+        cancel_order_usecase,
+        // Daily digest
+        get_preferences_usecase,
+        set_digest_hour_usecase,
+        build_daily_digest_usecase,
+        // Risk guard
+        get_risk_guard_usecase,
+        set_risk_guard_limit_usecase,
+        rearm_risk_guard_usecase,
+        // HTTP API tokens
+        issue_api_token_usecase,
+        revoke_api_token_usecase,
+        authenticate_api_token_usecase,
     };
 
+    // The HTTP API runs alongside the bot when a bind address is configured.
+    if let Some(http) = &configs.http {
+        let listener = tokio::net::TcpListener::bind(&http.bind_addr)
+            .await
+            .with_context(|| format!("Failed to bind the HTTP API on {}", http.bind_addr))?;
+        tracing::info!("HTTP API listening on {}", http.bind_addr);
+        if listener.local_addr().is_ok_and(|a| !a.ip().is_loopback()) {
+            tracing::warn!(
+                "the HTTP API serves plain HTTP on a non-loopback address; put a TLS-terminating proxy in front of it"
+            );
+        }
+        tokio::spawn(interface::http::serve(listener, deps.clone()));
+    }
+
+    if let Some(cfg) = &configs.metrics {
+        let listener = tokio::net::TcpListener::bind(&cfg.bind_addr)
+            .await
+            .with_context(|| format!("Failed to bind the metrics endpoint on {}", cfg.bind_addr))?;
+        tracing::info!("Prometheus metrics on {}/metrics", cfg.bind_addr);
+        tokio::spawn(interface::metrics::serve(listener, metrics));
+    }
+
     interface::telegram::router::run(bot, deps).await
 }
+
+/// Where bot tasks run, and the cluster / task definition / container names
+/// the use cases pass along with each launch and stop.
+struct Compute {
+    runner: Arc<dyn TaskRunner>,
+    controller: Arc<dyn TaskController>,
+    cluster_arn: String,
+    td_arn: String,
+    container_name: String,
+    /// The Docker backend and its sweep interval, when it is the one in use.
+    #[cfg(feature = "docker")]
+    docker: Option<(Arc<infra::DockerTaskBackend>, std::time::Duration)>,
+}
+
+/// Docker when `APP__DOCKER__*` is set, otherwise ECS.
+async fn setup_compute(configs: &Configs, bucket_name: &str) -> anyhow::Result<Compute> {
+    if let Some(docker) = &configs.docker {
+        return setup_docker_compute(configs, docker, bucket_name);
+    }
+    let ecs = configs
+        .ecs
+        .as_ref()
+        .context("No compute backend configured: set APP__ECS__* or APP__DOCKER__IMAGE")?;
+    let (ecs_client, cluster_arn, td_arn) = setup_ecs_with_configs(ecs).await;
+    Ok(Compute {
+        runner: Arc::new(RunTaskUseCase::new(ecs_client.clone())),
+        controller: Arc::new(EcsTaskController::new(ecs_client)),
+        cluster_arn,
+        td_arn,
+        container_name: ecs.td_passivbot_container_name.clone(),
+        #[cfg(feature = "docker")]
+        docker: None,
+    })
+}
+
+#[cfg(feature = "docker")]
+fn setup_docker_compute(
+    configs: &Configs,
+    docker: &DockerConfig,
+    bucket_name: &str,
+) -> anyhow::Result<Compute> {
+    // The same environment contract as the ECS task definition, pointed at
+    // the S3 the telebot itself uses.
+    let mut env = vec![
+        format!("BUCKET={bucket_name}"),
+        format!("AWS_DEFAULT_REGION={}", configs.s3.region),
+    ];
+    if !configs.s3.endpoint_url.is_empty() {
+        env.push(format!("AWS_ENDPOINT_URL_S3={}", configs.s3.endpoint_url));
+    }
+    env.extend(docker.forwarded_env());
+    let backend = Arc::new(
+        infra::DockerTaskBackend::connect_local(env, docker.network.clone())
+            .context("Failed to connect to the local Docker engine")?,
+    );
+    Ok(Compute {
+        runner: backend.clone(),
+        controller: backend.clone(),
+        cluster_arn: docker.cluster.clone(),
+        td_arn: docker.image.clone(),
+        container_name: docker.container_name.clone(),
+        docker: Some((
+            backend,
+            std::time::Duration::from_secs(docker.sweep_interval_secs),
+        )),
+    })
+}
+
+#[cfg(not(feature = "docker"))]
+fn setup_docker_compute(
+    _configs: &Configs,
+    _docker: &DockerConfig,
+    _bucket_name: &str,
+) -> anyhow::Result<Compute> {
+    anyhow::bail!("APP__DOCKER__* is set but this build lacks the `docker` feature")
+}
+
+#[cfg(feature = "docker")]
+fn spawn_docker_supervisor(
+    compute: &Compute,
+    backend: Arc<infra::DockerTaskBackend>,
+    sweep_interval: std::time::Duration,
+    bot_repository: Arc<DynamoBotRepository>,
+    clock: Arc<SystemClock>,
+    metrics: Arc<dyn domain::Metrics>,
+) {
+    let record_running = Arc::new(RecordRunningTaskUseCase::new(
+        bot_repository.clone(),
+        compute.controller.clone(),
+        bot_repository.clone(),
+        metrics.clone(),
+    ));
+    let reconcile = Arc::new(ReconcileStoppedTaskUseCase::new(
+        bot_repository.clone(),
+        bot_repository.clone(),
+        bot_repository.clone(),
+        compute.runner.clone(),
+        compute.controller.clone(),
+        bot_repository.clone(),
+        metrics,
+    ));
+    let sweeper = Arc::new(SweepRuntimesUseCase::new(
+        bot_repository.clone(),
+        bot_repository.clone(),
+        bot_repository.clone(),
+        bot_repository,
+        backend.clone(),
+        compute.runner.clone(),
+        compute.controller.clone(),
+        clock.clone(),
+        compute.cluster_arn.clone(),
+        compute.td_arn.clone(),
+        compute.container_name.clone(),
+    ));
+    let supervisor = interface::docker_events::DockerSupervisor::new(
+        backend,
+        record_running,
+        reconcile,
+        sweeper,
+        clock,
+        compute.cluster_arn.clone(),
+        compute.td_arn.clone(),
+        compute.container_name.clone(),
+        sweep_interval,
+    );
+    tokio::spawn(supervisor.run());
+}
@@ -0,0 +1,136 @@
+//! Logging shared by every binary: one `tracing` subscriber writing JSON
+//! lines, and the correlation id that ties the lines of one user action
+//! together across the telebot, the ECS task and the Lambdas.
+//!
+//! A correlation id is created where an action enters the system (a Telegram
+//! update, an HTTP request, a schedule or sweep run, a `pbtb-admin` command)
+//! and scoped over the future that handles it with [`with_correlation_id`].
+//! Task launches read it back with [`correlation_id`] and hand it to the task
+//! as the `CORRELATION_ID` environment variable and `correlation_id` tag, where
+//! the task state change Lambda picks it up from the event's overrides. Spans
+//! carry `correlation_id`, `user_id`, `bot_id` and `task_id`; never a key or a
+//! secret.
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Environment variable carrying the correlation id into a task.
+pub const CORRELATION_ID_ENV: &str = "CORRELATION_ID";
+/// ECS task tag carrying the correlation id.
+pub const CORRELATION_ID_TAG: &str = "correlation_id";
+
+/// Identifies one user action and everything it sets off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    /// A fresh id for an action entering the system.
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    /// An id read back from a task's environment or labels. `None` when it is
+    /// empty or not something this module issued, so a malformed value never
+    /// reaches a tag or a log line.
+    pub fn parse(value: &str) -> Option<Self> {
+        let valid = !value.is_empty()
+            && value.len() <= 64
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        valid.then(|| Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+tokio::task_local! {
+    static CORRELATION_ID: CorrelationId;
+}
+
+/// Run `fut` with `id` as the current correlation id. The id does not follow
+/// `tokio::spawn`; a spawned task that launches tasks re-scopes it (see
+/// `run_bounded`).
+pub async fn with_correlation_id<F: Future>(id: CorrelationId, fut: F) -> F::Output {
+    CORRELATION_ID.scope(id, fut).await
+}
+
+/// The correlation id of the action being handled, if any.
+pub fn correlation_id() -> Option<CorrelationId> {
+    CORRELATION_ID.try_with(Clone::clone).ok()
+}
+
+/// Install the JSON subscriber on stdout. See [`init_tracing_with_writer`].
+pub fn init_tracing() {
+    init_tracing_with_writer(std::io::stdout);
+}
+
+/// Install the JSON subscriber: one object per event with its fields and the
+/// fields of every enclosing span. The level comes from `AWS_LAMBDA_LOG_LEVEL`
+/// (Lambda's logging controls), then `RUST_LOG`, defaulting to `info`.
+/// Records of the `log` crate (teloxide) are forwarded.
+pub fn init_tracing_with_writer<W>(writer: W)
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let directives = std::env::var("AWS_LAMBDA_LOG_LEVEL")
+        .or_else(|_| std::env::var("RUST_LOG"))
+        .unwrap_or_default();
+    // A bare level such as `DEBUG` (Lambda's spelling) is not a valid filter
+    // directive in upper case; everything else is passed through as is.
+    let directives = match LevelFilter::from_str(&directives) {
+        Ok(level) if !directives.is_empty() => level.to_string().to_lowercase(),
+        _ => directives,
+    };
+    tracing_subscriber::fmt()
+        .json()
+        .with_current_span(false)
+        .with_span_list(true)
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .parse_lossy(directives),
+        )
+        .with_writer(writer)
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn correlation_id_is_scoped_to_the_action() {
+        assert_eq!(correlation_id(), None);
+        let id = CorrelationId::new();
+        let seen = with_correlation_id(id.clone(), async { correlation_id() }).await;
+        assert_eq!(seen, Some(id));
+        assert_eq!(correlation_id(), None);
+    }
+
+    #[test]
+    fn parse_accepts_only_issued_shapes() {
+        let id = CorrelationId::new();
+        assert_eq!(CorrelationId::parse(id.as_str()), Some(id));
+        assert_eq!(CorrelationId::parse(""), None);
+        assert_eq!(CorrelationId::parse("a b"), None);
+        assert_eq!(CorrelationId::parse(&"a".repeat(65)), None);
+    }
+}
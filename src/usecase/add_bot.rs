@@ -40,6 +40,7 @@ impl AddBotUseCase {
         api_key: String,
         secret_key: String,
     ) -> Result<AddOutcome, String> {
+        Bot::validate_name(&name).map_err(|e| e.to_string())?;
         // Detect by name, not by id: a same-name bot whose id was not derived
         // from the name (an older row keyed on a numeric account id) would slip
         // past an id lookup and a second row would be created — the duplicate
@@ -63,7 +64,7 @@ impl AddBotUseCase {
     /// the existing bot's id so the existing row is updated in place; saving
     /// under a name-derived id instead would leave a same-name row whose id is
     /// a numeric account id untouched and spawn yet another duplicate. The
-    /// desired state (`enabled`) and `created_at` are preserved — an overwrite
+    /// desired state (`enabled`), leverage policy and `created_at` are preserved — an overwrite
     /// rotates the keys, it does not reset the bot. Falls back to a fresh
     /// create when no bot by that name is found.
     pub async fn overwrite(
@@ -73,19 +74,27 @@ impl AddBotUseCase {
         api_key: String,
         secret_key: String,
     ) -> Result<Bot, String> {
+        Bot::validate_name(&name).map_err(|e| e.to_string())?;
         let now = self.clock.now();
         let bot = match self.find_by_name(user_id, &name).await? {
-            Some(existing) => Bot::new(
-                existing.id,
-                user_id.to_string(),
-                existing.exchange,
-                name,
-                api_key,
-                secret_key,
-                existing.enabled,
-                existing.created_at,
-                now,
-            ),
+            Some(existing) => {
+                let mut bot = Bot::new(
+                    existing.id,
+                    user_id.to_string(),
+                    existing.exchange,
+                    name,
+                    api_key,
+                    secret_key,
+                    existing.enabled,
+                    existing.created_at,
+                    now,
+                );
+                bot.leverage_policy = existing.leverage_policy;
+                bot.exposure_ceiling = existing.exposure_ceiling;
+                bot.restart_policy = existing.restart_policy;
+                bot.resource_profile = existing.resource_profile;
+                bot
+            }
             None => Bot::create(user_id.to_string(), name, api_key, secret_key, now),
         };
         self.persist(&bot).await?;
@@ -239,6 +248,31 @@ mod tests {
         assert_eq!(api_saved.exchange, Exchange::Bybit);
     }
 
+    #[tokio::test]
+    async fn names_that_cannot_be_keys_are_refused_before_anything_is_written() {
+        let bots = Arc::new(InMemoryBots::default());
+        let api_keys = Arc::new(MockApiKeyRepository::default());
+        let uc = AddBotUseCase::new(bots.clone(), api_keys.clone(), Arc::new(FixedClock));
+
+        for name in ["grid#1", "  "] {
+            let err = match uc
+                .execute("user-1", name.into(), "ak".into(), "sk".into())
+                .await
+            {
+                Err(e) => e,
+                Ok(_) => panic!("{name:?} must be refused"),
+            };
+            assert!(err.starts_with("invalid bot name"), "{err}");
+            assert!(
+                uc.overwrite("user-1", name.into(), "ak".into(), "sk".into())
+                    .await
+                    .is_err()
+            );
+        }
+        assert!(bots.find_by_user_id("user-1").await.unwrap().is_empty());
+        assert!(api_keys.saved.lock().unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn execute_reports_existing_without_overwriting() {
         let bots = Arc::new(InMemoryBots::default());
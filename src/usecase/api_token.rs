@@ -0,0 +1,154 @@
+use crate::domain::apitoken::{ApiToken, ApiTokenRepository};
+use crate::domain::clock::Clock;
+use std::sync::Arc;
+
+/// Mint a user's HTTP API token, replacing (and invalidating) any previous
+/// one. Returns the secret, which is not stored and cannot be shown again.
+pub struct IssueApiTokenUseCase {
+    tokens: Arc<dyn ApiTokenRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl IssueApiTokenUseCase {
+    pub fn new(tokens: Arc<dyn ApiTokenRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { tokens, clock }
+    }
+
+    pub async fn execute(&self, user_id: &str) -> Result<String, String> {
+        // Delete the old token first: saving the new one only repoints the
+        // user row, and the old secret would keep authenticating.
+        if let Some(old) = self
+            .tokens
+            .find_by_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            self.tokens.delete(&old).await.map_err(|e| e.to_string())?;
+        }
+        let (secret, token) = ApiToken::issue(user_id, self.clock.now());
+        self.tokens.save(&token).await.map_err(|e| e.to_string())?;
+        Ok(secret)
+    }
+}
+
+/// Invalidate a user's HTTP API token. `Ok(false)` when there was none.
+pub struct RevokeApiTokenUseCase {
+    tokens: Arc<dyn ApiTokenRepository>,
+}
+
+impl RevokeApiTokenUseCase {
+    pub fn new(tokens: Arc<dyn ApiTokenRepository>) -> Self {
+        Self { tokens }
+    }
+
+    pub async fn execute(&self, user_id: &str) -> Result<bool, String> {
+        match self
+            .tokens
+            .find_by_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some(token) => {
+                self.tokens
+                    .delete(&token)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Resolve a bearer secret to the user it was issued to. `Ok(None)` for an
+/// unknown or revoked secret; `Err` only when the lookup itself failed.
+pub struct AuthenticateApiTokenUseCase {
+    tokens: Arc<dyn ApiTokenRepository>,
+}
+
+impl AuthenticateApiTokenUseCase {
+    pub fn new(tokens: Arc<dyn ApiTokenRepository>) -> Self {
+        Self { tokens }
+    }
+
+    pub async fn execute(&self, secret: &str) -> Result<Option<String>, String> {
+        Ok(self
+            .tokens
+            .find_by_hash(&ApiToken::hash(secret))
+            .await
+            .map_err(|e| e.to_string())?
+            .map(|token| token.user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Lookup rows by hash and user rows by user, like the table.
+    #[derive(Default)]
+    struct Tokens {
+        by_hash: Mutex<HashMap<String, ApiToken>>,
+        by_user: Mutex<HashMap<String, String>>,
+    }
+
+    #[async_trait]
+    impl ApiTokenRepository for Tokens {
+        async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, DomainError> {
+            Ok(self.by_hash.lock().unwrap().get(token_hash).cloned())
+        }
+        async fn find_by_user(&self, user_id: &str) -> Result<Option<ApiToken>, DomainError> {
+            let hash = self.by_user.lock().unwrap().get(user_id).cloned();
+            Ok(hash.and_then(|h| self.by_hash.lock().unwrap().get(&h).cloned()))
+        }
+        async fn save(&self, token: &ApiToken) -> Result<(), DomainError> {
+            self.by_hash
+                .lock()
+                .unwrap()
+                .insert(token.token_hash.clone(), token.clone());
+            self.by_user
+                .lock()
+                .unwrap()
+                .insert(token.user_id.clone(), token.token_hash.clone());
+            Ok(())
+        }
+        async fn delete(&self, token: &ApiToken) -> Result<(), DomainError> {
+            self.by_hash.lock().unwrap().remove(&token.token_hash);
+            self.by_user.lock().unwrap().remove(&token.user_id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn reissuing_invalidates_the_previous_secret() {
+        let tokens = Arc::new(Tokens::default());
+        let issue = IssueApiTokenUseCase::new(tokens.clone(), Arc::new(MockClock::new(100)));
+        let auth = AuthenticateApiTokenUseCase::new(tokens.clone());
+
+        let first = issue.execute("u").await.unwrap();
+        assert_eq!(auth.execute(&first).await.unwrap().as_deref(), Some("u"));
+
+        let second = issue.execute("u").await.unwrap();
+        assert_eq!(auth.execute(&first).await.unwrap(), None);
+        assert_eq!(auth.execute(&second).await.unwrap().as_deref(), Some("u"));
+        assert_eq!(tokens.by_hash.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn revoking_removes_the_token() {
+        let tokens = Arc::new(Tokens::default());
+        let issue = IssueApiTokenUseCase::new(tokens.clone(), Arc::new(MockClock::new(100)));
+        let revoke = RevokeApiTokenUseCase::new(tokens.clone());
+        let auth = AuthenticateApiTokenUseCase::new(tokens.clone());
+
+        let secret = issue.execute("u").await.unwrap();
+        assert!(revoke.execute("u").await.unwrap());
+        assert_eq!(auth.execute(&secret).await.unwrap(), None);
+        assert!(!revoke.execute("u").await.unwrap());
+    }
+}
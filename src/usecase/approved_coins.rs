@@ -0,0 +1,193 @@
+use crate::domain::botconfig::{BotConfigRepository, Coins};
+use crate::domain::clock::Clock;
+use crate::domain::instrument::{Instrument, InstrumentCatalogRepository};
+use crate::usecase::refresh_instruments::current_catalog;
+use std::sync::Arc;
+
+/// One approved coin and the instrument it trades as, when the catalog has it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoinListing {
+    pub coin: String,
+    pub instrument: Option<Instrument>,
+}
+
+/// A bot's approved coins per side, looked up in the instrument catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovedCoins {
+    pub long: Vec<CoinListing>,
+    pub short: Vec<CoinListing>,
+    /// When the catalog was fetched (unix seconds); `None` without one, in
+    /// which case no coin has an instrument.
+    pub catalog_fetched_at: Option<i64>,
+}
+
+/// Reads a bot's approved coins with each coin's listing.
+pub struct GetApprovedCoinsUseCase {
+    bot_config_repository: Arc<dyn BotConfigRepository>,
+    instruments: Arc<dyn InstrumentCatalogRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl GetApprovedCoinsUseCase {
+    pub fn new(
+        bot_config_repository: Arc<dyn BotConfigRepository>,
+        instruments: Arc<dyn InstrumentCatalogRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            bot_config_repository,
+            instruments,
+            clock,
+        }
+    }
+
+    pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<ApprovedCoins, String> {
+        let config = self.bot_config_repository.get(user_id, bot_id).await?;
+        let coins = config.coins().map_err(|e| e.to_string())?;
+        let catalog = current_catalog(self.instruments.as_ref(), self.clock.now()).await;
+        let listings = |side: Vec<String>| {
+            side.into_iter()
+                .map(|coin| CoinListing {
+                    instrument: catalog.as_ref().and_then(|c| c.resolve(&coin)).cloned(),
+                    coin,
+                })
+                .collect()
+        };
+        Ok(ApprovedCoins {
+            long: listings(coins.long),
+            short: listings(coins.short),
+            catalog_fetched_at: catalog.as_ref().map(|c| c.fetched_at),
+        })
+    }
+}
+
+/// Replace one side's approved coins in the bot's config. Coins are
+/// upper-cased and de-duplicated; with a cached instrument catalog the
+/// resulting config must pass `BotConfig::check_instruments`, so a coin the
+/// exchange does not list (or a leverage one of the coins cannot take) is
+/// refused before it is saved. Applies on the next launch.
+pub struct SetApprovedCoinsUseCase {
+    bot_config_repository: Arc<dyn BotConfigRepository>,
+    instruments: Arc<dyn InstrumentCatalogRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetApprovedCoinsUseCase {
+    pub fn new(
+        bot_config_repository: Arc<dyn BotConfigRepository>,
+        instruments: Arc<dyn InstrumentCatalogRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            bot_config_repository,
+            instruments,
+            clock,
+        }
+    }
+
+    /// Returns both sides' coins as saved.
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        side: &str,
+        coins: &[String],
+    ) -> Result<Coins, String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for coin in coins.iter().map(|c| c.trim().to_uppercase()) {
+            if !coin.is_empty() && !normalized.contains(&coin) {
+                normalized.push(coin);
+            }
+        }
+
+        let mut config = self.bot_config_repository.get(user_id, bot_id).await?;
+        config
+            .set_coins(side, &normalized, self.clock.now())
+            .map_err(|e| e.to_string())?;
+        if let Some(catalog) = current_catalog(self.instruments.as_ref(), self.clock.now()).await {
+            config
+                .check_instruments(&catalog)
+                .map_err(|e| e.to_string())?;
+        }
+        self.bot_config_repository.save(&config).await?;
+        config.coins().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::botconfig::{BotConfig, BotType};
+    use crate::domain::clock::MockClock;
+    use crate::domain::exchange::Exchange;
+    use crate::domain::instrument::{Instrument, InstrumentCatalog};
+    use crate::infra::memory::{InMemoryBotConfigRepository, InMemoryInstrumentCatalog};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn coins_are_normalized_listed_and_unlisted_ones_refused() {
+        let configs = Arc::new(InMemoryBotConfigRepository::new());
+        configs
+            .save(&BotConfig {
+                user_id: "u".into(),
+                bot_id: "b".into(),
+                bot_type: BotType::Passivbot,
+                template_name: "t".into(),
+                template_version: None,
+                config_data: json!({
+                    "live": { "leverage": 10.0, "approved_coins": { "long": ["BTC"], "short": [] } }
+                }),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .await
+            .unwrap();
+        let instruments = Arc::new(InMemoryInstrumentCatalog::new());
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let uc = SetApprovedCoinsUseCase::new(configs.clone(), instruments.clone(), clock.clone());
+
+        // No catalog yet: nothing to check against.
+        let coins = uc
+            .execute("u", "b", "short", &["xrp".into(), "XRP".into(), " ".into()])
+            .await
+            .unwrap();
+        assert_eq!(coins.short, vec!["XRP"]);
+
+        instruments
+            .save(&InstrumentCatalog {
+                exchange: Exchange::Bybit,
+                fetched_at: 1_699_990_000,
+                instruments: ["BTC", "XRP"]
+                    .map(|coin| Instrument {
+                        symbol: format!("{coin}USDT"),
+                        base_coin: coin.into(),
+                        status: "Trading".into(),
+                        min_qty: 0.001,
+                        tick_size: 0.1,
+                        max_leverage: 50.0,
+                    })
+                    .into(),
+            })
+            .await
+            .unwrap();
+        let err = uc
+            .execute("u", "b", "long", &["BTC".into(), "DOGGO".into()])
+            .await
+            .unwrap_err();
+        assert_eq!(err, "invalid config: DOGGO is not listed on bybit");
+        let saved = configs.get("u", "b").await.unwrap();
+        assert_eq!(saved.coins().unwrap().long, vec!["BTC"]);
+        assert_eq!(saved.updated_at, 1_700_000_000);
+
+        let listed = GetApprovedCoinsUseCase::new(configs, instruments, clock)
+            .execute("u", "b")
+            .await
+            .unwrap();
+        assert_eq!(listed.catalog_fetched_at, Some(1_699_990_000));
+        assert_eq!(listed.short[0].coin, "XRP");
+        assert_eq!(
+            listed.short[0].instrument.as_ref().unwrap().symbol,
+            "XRPUSDT"
+        );
+    }
+}
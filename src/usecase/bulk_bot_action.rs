@@ -0,0 +1,443 @@
+use crate::domain::bot::Bot;
+use crate::domain::botconfig::RiskLevel;
+use crate::domain::schedule::ScheduleAction;
+use crate::telemetry::{correlation_id, with_correlation_id};
+use crate::usecase::{ListBotsUseCase, ScheduleActionRunner};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::Instrument;
+
+/// How many bots a bulk action works on at once. Every start/stop is an ECS
+/// call plus a few DynamoDB writes; a small bound keeps a user with many bots
+/// well inside the API rate limits.
+pub const BULK_MAX_CONCURRENCY: usize = 4;
+
+/// An action applied to every one of a user's bots.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    StopAll,
+    /// Start every bot whose desired state is on (e.g. after an outage);
+    /// bots the user turned off are left alone.
+    StartAllEnabled,
+    /// Put both sides of every bot into `graceful_stop`.
+    GracefulStopAll,
+    ApplyRisk {
+        long: f64,
+        short: f64,
+    },
+}
+
+impl BulkAction {
+    /// Parse the compact form used in callback data: `stop`, `start`,
+    /// `graceful` or `risk:<long>/<short>`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        match s {
+            "stop" => return Ok(BulkAction::StopAll),
+            "start" => return Ok(BulkAction::StartAllEnabled),
+            "graceful" => return Ok(BulkAction::GracefulStopAll),
+            _ => {}
+        }
+        let Some(values) = s.strip_prefix("risk:") else {
+            return Err(format!("unknown bulk action '{s}'"));
+        };
+        let (long, short) = values
+            .split_once('/')
+            .ok_or_else(|| format!("risk must be <long>/<short>, got '{values}'"))?;
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("'{v}' is not a number"))
+        };
+        let risk = RiskLevel::new(parse(long)?, parse(short)?).map_err(|e| e.to_string())?;
+        Ok(BulkAction::ApplyRisk {
+            long: risk.long,
+            short: risk.short,
+        })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            BulkAction::StopAll => "Stop all".to_string(),
+            BulkAction::StartAllEnabled => "Start all enabled".to_string(),
+            BulkAction::GracefulStopAll => "Set all sides graceful_stop".to_string(),
+            BulkAction::ApplyRisk { long, short } => {
+                format!("Apply risk {long:.2}/{short:.2} to all")
+            }
+        }
+    }
+
+    /// Why `bot` is left out, or `None` when the action applies to it.
+    fn skip_reason(&self, bot: &Bot) -> Option<&'static str> {
+        match self {
+            BulkAction::StartAllEnabled if !bot.enabled => Some("turned off"),
+            _ => None,
+        }
+    }
+
+    /// The per-bot steps, run in order; the first failure ends the bot's run.
+    fn steps(&self) -> Vec<ScheduleAction> {
+        match self {
+            BulkAction::StopAll => vec![ScheduleAction::Stop],
+            BulkAction::StartAllEnabled => vec![ScheduleAction::Start],
+            BulkAction::GracefulStopAll => ["long", "short"]
+                .into_iter()
+                .map(|side| ScheduleAction::SetSide {
+                    side: side.to_string(),
+                    enabled: false,
+                })
+                .collect(),
+            BulkAction::ApplyRisk { long, short } => vec![ScheduleAction::SetRisk {
+                long: *long,
+                short: *short,
+            }],
+        }
+    }
+}
+
+impl fmt::Display for BulkAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkAction::StopAll => f.write_str("stop"),
+            BulkAction::StartAllEnabled => f.write_str("start"),
+            BulkAction::GracefulStopAll => f.write_str("graceful"),
+            BulkAction::ApplyRisk { long, short } => write!(f, "risk:{long}/{short}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkOutcome {
+    Done(String),
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkBotResult {
+    pub bot_id: String,
+    pub bot_name: String,
+    pub outcome: BulkOutcome,
+}
+
+/// Per-bot results of one bulk action, in the order the bots were listed.
+#[derive(Debug, Clone)]
+pub struct BulkReport {
+    pub action: BulkAction,
+    pub results: Vec<BulkBotResult>,
+}
+
+impl BulkReport {
+    pub fn done(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Done(_)))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Skipped(_)))
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, BulkOutcome::Failed(_)))
+    }
+
+    fn count(&self, pred: impl Fn(&BulkOutcome) -> bool) -> usize {
+        self.results.iter().filter(|r| pred(&r.outcome)).count()
+    }
+}
+
+/// Apply one action to all of a user's bots. Each bot goes through the same
+/// per-bot use cases as a single-bot action (via `ScheduleActionRunner`), at
+/// most `max_concurrency` bots at a time. One bot failing never stops the
+/// others; every bot gets a line in the report.
+pub struct BulkBotActionUseCase {
+    list_bots: Arc<ListBotsUseCase>,
+    runner: Arc<dyn ScheduleActionRunner>,
+    max_concurrency: usize,
+}
+
+impl BulkBotActionUseCase {
+    pub fn new(
+        list_bots: Arc<ListBotsUseCase>,
+        runner: Arc<dyn ScheduleActionRunner>,
+        max_concurrency: usize,
+    ) -> Self {
+        Self {
+            list_bots,
+            runner,
+            max_concurrency: max_concurrency.max(1),
+        }
+    }
+
+    /// The bots `action` would touch, for the confirmation prompt.
+    pub async fn targets(&self, user_id: &str, action: &BulkAction) -> Result<Vec<Bot>, String> {
+        let bots = self.list_bots.execute(user_id).await?;
+        Ok(bots
+            .into_iter()
+            .filter(|b| action.skip_reason(b).is_none())
+            .collect())
+    }
+
+    pub async fn execute(&self, user_id: &str, action: &BulkAction) -> Result<BulkReport, String> {
+        let bots = self.list_bots.execute(user_id).await?;
+
+        let mut outcomes: Vec<Option<BulkOutcome>> = vec![None; bots.len()];
+        let mut targets = Vec::new();
+        for (i, bot) in bots.iter().enumerate() {
+            match action.skip_reason(bot) {
+                Some(reason) => outcomes[i] = Some(BulkOutcome::Skipped(reason.to_string())),
+                None => targets.push((i, bot.id.clone())),
+            }
+        }
+        let steps = Arc::new(action.steps());
+        let runs = run_bounded(
+            targets.iter().map(|(_, id)| id.clone()).collect(),
+            self.max_concurrency,
+            |bot_id| {
+                let runner = self.runner.clone();
+                let user_id = user_id.to_string();
+                let steps = steps.clone();
+                async move { run_steps(runner.as_ref(), &user_id, &bot_id, &steps).await }
+            },
+        )
+        .await;
+        for ((i, _), outcome) in targets.into_iter().zip(runs) {
+            outcomes[i] = outcome;
+        }
+
+        let results = bots
+            .into_iter()
+            .zip(outcomes)
+            .map(|(bot, outcome)| BulkBotResult {
+                bot_id: bot.id,
+                bot_name: bot.name,
+                outcome: outcome
+                    .unwrap_or_else(|| BulkOutcome::Failed("internal error".to_string())),
+            })
+            .collect();
+        Ok(BulkReport {
+            action: action.clone(),
+            results,
+        })
+    }
+}
+
+/// Run `f` over `items` with at most `limit` futures in flight, returning the
+/// results in input order. A slot is `None` only if its task panicked.
+pub(crate) async fn run_bounded<I, T, F, Fut>(items: Vec<I>, limit: usize, f: F) -> Vec<Option<T>>
+where
+    I: Send + 'static,
+    T: Send + 'static,
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let mut results: Vec<Option<T>> = items.iter().map(|_| None).collect();
+    let permits = Arc::new(Semaphore::new(limit.max(1)));
+    let mut tasks = JoinSet::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let permits = permits.clone();
+        let work = f(item);
+        let task = async move {
+            // The semaphore is never closed, so acquire cannot fail.
+            let _permit = permits.acquire_owned().await.ok();
+            (i, work.await)
+        }
+        .instrument(tracing::Span::current());
+        // Spawned tasks start outside the caller's correlation scope.
+        match correlation_id() {
+            Some(id) => tasks.spawn(with_correlation_id(id, task)),
+            None => tasks.spawn(task),
+        };
+    }
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((i, result)) => results[i] = Some(result),
+            Err(e) => tracing::error!(error = %e, "bounded task panicked"),
+        }
+    }
+    results
+}
+
+async fn run_steps(
+    runner: &dyn ScheduleActionRunner,
+    user_id: &str,
+    bot_id: &str,
+    steps: &[ScheduleAction],
+) -> BulkOutcome {
+    let mut done = Vec::with_capacity(steps.len());
+    for step in steps {
+        match runner.run(user_id, bot_id, step).await {
+            Ok(summary) => done.push(summary),
+            // Say what already went through, so a half-applied bot is visible.
+            Err(e) if done.is_empty() => return BulkOutcome::Failed(e),
+            Err(e) => return BulkOutcome::Failed(format!("{e} (after: {})", done.join("; "))),
+        }
+    }
+    BulkOutcome::Done(done.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::BotRepository;
+    use crate::domain::error::DomainError;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct FixedBots {
+        bots: Vec<Bot>,
+    }
+    #[async_trait]
+    impl BotRepository for FixedBots {
+        async fn find(&self, _user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+            Ok(self.bots.iter().find(|b| b.id == bot_id).cloned())
+        }
+        async fn save(&self, _bot: &Bot) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn find_by_user_id(&self, _user_id: &str) -> Result<Vec<Bot>, DomainError> {
+            Ok(self.bots.clone())
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Records calls and the peak number of bots in flight; fails the short
+    /// side of bot `half`, and everything for bot `broken`.
+    #[derive(Default)]
+    struct CountingRunner {
+        calls: Mutex<Vec<(String, String)>>,
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+    #[async_trait]
+    impl ScheduleActionRunner for CountingRunner {
+        async fn run(
+            &self,
+            _user_id: &str,
+            bot_id: &str,
+            action: &ScheduleAction,
+        ) -> Result<String, String> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            self.calls
+                .lock()
+                .unwrap()
+                .push((bot_id.to_string(), action.to_string()));
+            match (bot_id, action.to_string().as_str()) {
+                ("broken", _) => Err("ecs unavailable".to_string()),
+                ("half", "side:short:off") => Err("config write failed".to_string()),
+                _ => Ok(format!("did {action}")),
+            }
+        }
+    }
+
+    fn bot(id: &str, enabled: bool) -> Bot {
+        let mut b = Bot::create("u".into(), id.into(), "ak".into(), "sk".into(), 0);
+        if enabled {
+            b.enable(0);
+        }
+        b
+    }
+
+    fn setup(bots: Vec<Bot>, limit: usize) -> (Arc<CountingRunner>, BulkBotActionUseCase) {
+        let list = Arc::new(ListBotsUseCase::new(Arc::new(FixedBots { bots })));
+        let runner = Arc::new(CountingRunner::default());
+        let uc = BulkBotActionUseCase::new(list, runner.clone(), limit);
+        (runner, uc)
+    }
+
+    #[test]
+    fn parses_compact_actions() {
+        assert_eq!(BulkAction::parse("stop").unwrap(), BulkAction::StopAll);
+        assert_eq!(
+            BulkAction::parse("risk:1.5/0.5").unwrap(),
+            BulkAction::ApplyRisk {
+                long: 1.5,
+                short: 0.5
+            }
+        );
+        for action in [
+            BulkAction::StopAll,
+            BulkAction::StartAllEnabled,
+            BulkAction::GracefulStopAll,
+            BulkAction::ApplyRisk {
+                long: 2.0,
+                short: 0.25,
+            },
+        ] {
+            assert_eq!(BulkAction::parse(&action.to_string()).unwrap(), action);
+        }
+        assert!(BulkAction::parse("risk:11/0").is_err());
+        assert!(BulkAction::parse("pause").is_err());
+    }
+
+    #[tokio::test]
+    async fn runs_every_bot_with_bounded_parallelism() {
+        let bots = (0..10).map(|i| bot(&format!("b{i}"), true)).collect();
+        let (runner, uc) = setup(bots, 3);
+
+        let report = uc.execute("u", &BulkAction::StopAll).await.unwrap();
+
+        assert_eq!(report.done(), 10);
+        assert_eq!(runner.calls.lock().unwrap().len(), 10);
+        assert!(runner.peak.load(Ordering::SeqCst) <= 3);
+        // report keeps list order regardless of completion order
+        let ids: Vec<_> = report.results.iter().map(|r| r.bot_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["b0", "b1", "b2", "b3", "b4", "b5", "b6", "b7", "b8", "b9"]
+        );
+    }
+
+    #[tokio::test]
+    async fn start_all_skips_disabled_bots_and_reports_failures() {
+        let (runner, uc) = setup(
+            vec![bot("on", true), bot("off", false), bot("broken", true)],
+            BULK_MAX_CONCURRENCY,
+        );
+
+        let report = uc.execute("u", &BulkAction::StartAllEnabled).await.unwrap();
+
+        assert_eq!(
+            report
+                .results
+                .iter()
+                .map(|r| r.outcome.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                BulkOutcome::Done("did start".into()),
+                BulkOutcome::Skipped("turned off".into()),
+                BulkOutcome::Failed("ecs unavailable".into()),
+            ]
+        );
+        assert_eq!(
+            (report.done(), report.skipped(), report.failed()),
+            (1, 1, 1)
+        );
+        assert!(!runner.calls.lock().unwrap().iter().any(|(b, _)| b == "off"));
+    }
+
+    #[tokio::test]
+    async fn graceful_stop_reports_half_applied_bot() {
+        let (_, uc) = setup(vec![bot("ok", false), bot("half", false)], 2);
+
+        let report = uc.execute("u", &BulkAction::GracefulStopAll).await.unwrap();
+
+        assert_eq!(
+            report.results[0].outcome,
+            BulkOutcome::Done("did side:long:off; did side:short:off".into())
+        );
+        assert_eq!(
+            report.results[1].outcome,
+            BulkOutcome::Failed("config write failed (after: did side:long:off)".into())
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::schedule::{CronSchedule, Schedule, ScheduleAction, ScheduleRepository};
+use std::sync::Arc;
+
+/// Create a recurring action for one of the user's bots. The cron expression
+/// and action are validated up front so a stored schedule can always fire.
+pub struct CreateScheduleUseCase {
+    schedules: Arc<dyn ScheduleRepository>,
+    bots: Arc<dyn BotRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl CreateScheduleUseCase {
+    pub fn new(
+        schedules: Arc<dyn ScheduleRepository>,
+        bots: Arc<dyn BotRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            schedules,
+            bots,
+            clock,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        cron: &str,
+        action: &str,
+    ) -> Result<Schedule, String> {
+        let cron = CronSchedule::parse(cron).map_err(|e| e.to_string())?;
+        let action = ScheduleAction::parse(action).map_err(|e| e.to_string())?;
+        self.bots
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("bot {bot_id} not found"))?;
+
+        // Short ids: users type them back into /unschedule.
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let schedule = Schedule::create(
+            id,
+            user_id.to_string(),
+            bot_id.to_string(),
+            cron,
+            action,
+            self.clock.now(),
+        )
+        .map_err(|e| e.to_string())?;
+        self.schedules
+            .save(&schedule)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(schedule)
+    }
+}
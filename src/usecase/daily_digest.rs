@@ -0,0 +1,533 @@
+use crate::domain::account::ExchangeAccount;
+use crate::domain::bot::BotRepository;
+use crate::domain::botconfig::BotConfigRepository;
+use crate::domain::clock::Clock;
+use crate::domain::history::{BotEventKind, BotHistoryRepository};
+use crate::domain::notify::Notifier;
+use crate::domain::preferences::{UserPreferences, UserPreferencesRepository};
+use crate::domain::runtime::{BotRuntimeRepository, RuntimePhase};
+use crate::domain::schedule::format_utc;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// The period the digest reports restarts over.
+const DIGEST_PERIOD: i64 = 24 * 60 * 60;
+/// How many of a bot's latest events are read to count its restarts; more
+/// than a crash-looping bot's restart policy allows in a day.
+const DIGEST_HISTORY_LIMIT: usize = 50;
+
+/// One bot's line in the digest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotDigest {
+    pub bot_id: String,
+    pub name: String,
+    /// Desired state (`Bot.enabled`).
+    pub enabled: bool,
+    /// Observed state; `None` when the bot never ran.
+    pub phase: Option<RuntimePhase>,
+    /// Automatic restarts in the last 24 hours.
+    pub restarts: usize,
+    /// The bot's config was changed after its running task launched, so the
+    /// task is not running what the config says until it is restarted.
+    pub config_drift: bool,
+    /// `None` when the exchange could not be read.
+    pub equity: Option<f64>,
+    /// Change since the previous digest; `None` on the first one.
+    pub equity_change: Option<f64>,
+}
+
+impl BotDigest {
+    /// Desired and observed state disagree and no transition is in flight.
+    pub fn state_mismatch(&self) -> bool {
+        match self.phase {
+            Some(RuntimePhase::Running) => !self.enabled,
+            Some(RuntimePhase::Stopped) | None => self.enabled,
+            Some(RuntimePhase::Starting) | Some(RuntimePhase::Stopping) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyDigest {
+    pub user_id: String,
+    /// Unix seconds.
+    pub as_of: i64,
+    pub bots: Vec<BotDigest>,
+}
+
+impl DailyDigest {
+    /// Equity per bot, for the next digest to compare against.
+    pub fn equity(&self) -> BTreeMap<String, f64> {
+        self.bots
+            .iter()
+            .filter_map(|b| Some((b.bot_id.clone(), b.equity?)))
+            .collect()
+    }
+
+    /// The message the user receives. Plain text, so it reads the same in the
+    /// Lambda's push and in the `/digest now` preview.
+    pub fn render(&self) -> String {
+        let mut out = format!("📰 Daily digest — {}\n", format_utc(self.as_of));
+        if self.bots.is_empty() {
+            out.push_str("\n(No bots)");
+            return out;
+        }
+        for b in &self.bots {
+            let phase = match b.phase {
+                Some(RuntimePhase::Starting) => "⏳ Starting",
+                Some(RuntimePhase::Running) => "✅ Running",
+                Some(RuntimePhase::Stopping) => "🛑 Stopping",
+                Some(RuntimePhase::Stopped) => "⏸️ Stopped",
+                None => "❔ Unknown",
+            };
+            out.push_str(&format!(
+                "\n• {} — {} (turned {})",
+                b.name,
+                phase,
+                if b.enabled { "on" } else { "off" }
+            ));
+            if b.state_mismatch() {
+                out.push_str(if b.enabled {
+                    "\n   ⚠️ Should be running"
+                } else {
+                    "\n   ⚠️ Should be stopped"
+                });
+            }
+            if b.restarts > 0 {
+                out.push_str(&format!("\n   🔁 {} restart(s) in 24h", b.restarts));
+            }
+            if b.config_drift {
+                out.push_str("\n   ✏️ Config changed since launch; restart to apply");
+            }
+            match (b.equity, b.equity_change) {
+                (Some(e), Some(change)) => {
+                    out.push_str(&format!("\n   💰 Equity {:.2} ({:+.2})", e, change))
+                }
+                (Some(e), None) => out.push_str(&format!("\n   💰 Equity {:.2}", e)),
+                (None, _) => out.push_str("\n   💰 Equity unavailable"),
+            }
+        }
+        let equity = self.equity();
+        if !equity.is_empty() {
+            let changes: Vec<f64> = self.bots.iter().filter_map(|b| b.equity_change).collect();
+            out.push_str(&format!(
+                "\n\nTotal equity: {:.2}",
+                equity.values().sum::<f64>()
+            ));
+            if !changes.is_empty() {
+                out.push_str(&format!(" ({:+.2})", changes.iter().sum::<f64>()));
+            }
+        }
+        out
+    }
+}
+
+/// Put together a user's digest: every bot with its desired vs observed
+/// state, restarts in the last 24 hours, config drift, and its account
+/// equity with the change since the previous digest. A source that cannot
+/// be read leaves its part of a line out rather than failing the digest.
+pub struct BuildDailyDigestUseCase {
+    bots: Arc<dyn BotRepository>,
+    runtimes: Arc<dyn BotRuntimeRepository>,
+    history: Arc<dyn BotHistoryRepository>,
+    bot_configs: Arc<dyn BotConfigRepository>,
+    account: Arc<dyn ExchangeAccount>,
+    prefs: Arc<dyn UserPreferencesRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl BuildDailyDigestUseCase {
+    pub fn new(
+        bots: Arc<dyn BotRepository>,
+        runtimes: Arc<dyn BotRuntimeRepository>,
+        history: Arc<dyn BotHistoryRepository>,
+        bot_configs: Arc<dyn BotConfigRepository>,
+        account: Arc<dyn ExchangeAccount>,
+        prefs: Arc<dyn UserPreferencesRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            bots,
+            runtimes,
+            history,
+            bot_configs,
+            account,
+            prefs,
+            clock,
+        }
+    }
+
+    pub async fn execute(&self, user_id: &str) -> Result<DailyDigest, String> {
+        let now = self.clock.now();
+        let bots = self
+            .bots
+            .find_by_user_id(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let runtimes = self
+            .runtimes
+            .find_all_for_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let previous = self
+            .prefs
+            .find(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .map(|p| p.digest_equity)
+            .unwrap_or_default();
+
+        let mut lines = Vec::with_capacity(bots.len());
+        for bot in &bots {
+            let runtime = runtimes.iter().find(|r| r.bot_id == bot.id);
+
+            let restarts = match self
+                .history
+                .recent(user_id, &bot.id, DIGEST_HISTORY_LIMIT)
+                .await
+            {
+                Ok(events) => events
+                    .iter()
+                    .filter(|e| e.kind == BotEventKind::Restarted && e.at > now - DIGEST_PERIOD)
+                    .count(),
+                Err(e) => {
+                    tracing::warn!(bot_id = %bot.id, error = %e, "digest: history unavailable");
+                    0
+                }
+            };
+
+            // Only a running task can be behind its config.
+            let config_drift = match runtime {
+                Some(r) if r.phase == RuntimePhase::Running => {
+                    match self.bot_configs.get(user_id, &bot.id).await {
+                        Ok(config) => config.updated_at > r.observed_at,
+                        Err(e) => {
+                            tracing::warn!(bot_id = %bot.id, error = %e, "digest: config unavailable");
+                            false
+                        }
+                    }
+                }
+                _ => false,
+            };
+
+            let equity = match self.account.equity(bot).await {
+                Ok(e) => Some(e),
+                Err(e) => {
+                    tracing::warn!(bot_id = %bot.id, error = %e, "digest: equity unavailable");
+                    None
+                }
+            };
+            let equity_change = equity.zip(previous.get(&bot.id)).map(|(e, p)| e - p);
+
+            lines.push(BotDigest {
+                bot_id: bot.id.clone(),
+                name: bot.name.clone(),
+                enabled: bot.enabled,
+                phase: runtime.map(|r| r.phase.clone()),
+                restarts,
+                config_drift,
+                equity,
+                equity_change,
+            });
+        }
+        Ok(DailyDigest {
+            user_id: user_id.to_string(),
+            as_of: now,
+            bots: lines,
+        })
+    }
+}
+
+/// Outcome of one digest run, for the Lambda's log.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DigestRun {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// Send every due digest once. Each is claimed first (conditional advance of
+/// `next_digest_at`), so overlapping runs never send a user two; a digest
+/// that fails is not retried until the user's next slot.
+pub struct SendDueDigestsUseCase {
+    prefs: Arc<dyn UserPreferencesRepository>,
+    build: Arc<BuildDailyDigestUseCase>,
+    notifier: Arc<dyn Notifier>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SendDueDigestsUseCase {
+    pub fn new(
+        prefs: Arc<dyn UserPreferencesRepository>,
+        build: Arc<BuildDailyDigestUseCase>,
+        notifier: Arc<dyn Notifier>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            prefs,
+            build,
+            notifier,
+            clock,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<DigestRun, String> {
+        let now = self.clock.now();
+        let due = self
+            .prefs
+            .find_due_digests(now)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut run = DigestRun::default();
+        for mut prefs in due {
+            // `find_due_digests` may come from an eventually-consistent read.
+            let Some(expected) = prefs.next_digest_at.filter(|_| prefs.is_digest_due(now)) else {
+                continue;
+            };
+            prefs.advance_digest(now);
+            match self.prefs.claim_digest(&prefs, expected).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!(user_id = %prefs.user_id, error = %e, "failed to claim digest");
+                    continue;
+                }
+            }
+
+            let span = tracing::info_span!("digest", user_id = %prefs.user_id);
+            match self.send(&prefs).instrument(span).await {
+                Ok(()) => run.sent += 1,
+                Err(e) => {
+                    tracing::warn!(user_id = %prefs.user_id, error = %e, "failed to send digest");
+                    run.failed += 1;
+                }
+            }
+        }
+        Ok(run)
+    }
+
+    async fn send(&self, prefs: &UserPreferences) -> Result<(), String> {
+        let digest = self.build.execute(&prefs.user_id).await?;
+        self.notifier
+            .notify(&prefs.user_id, &digest.render())
+            .await
+            .map_err(|e| e.to_string())?;
+        // Only a delivered digest moves the baseline, so the next one reports
+        // the change since the last one the user actually saw.
+        if let Err(e) = self
+            .prefs
+            .record_digest_equity(&prefs.user_id, &digest.equity())
+            .await
+        {
+            tracing::warn!(error = %e, "failed to record digest equity");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::botconfig::{BotConfig, BotType};
+    use crate::domain::clock::MockClock;
+    use crate::domain::history::BotEvent;
+    use crate::domain::runtime::BotRuntime;
+    use crate::infra::memory::{
+        InMemoryBotConfigRepository, InMemoryBotRepository, InMemoryTradingGateway,
+        RecordingNotifier,
+    };
+    use serde_json::json;
+
+    // 2024-01-05 07:00:00 UTC.
+    const SEVEN_AM: i64 = 1_704_438_000;
+    const DAY: i64 = 86_400;
+
+    struct Fixture {
+        clock: Arc<MockClock>,
+        repo: Arc<InMemoryBotRepository>,
+        configs: Arc<InMemoryBotConfigRepository>,
+        exchange: Arc<InMemoryTradingGateway>,
+        notifier: Arc<RecordingNotifier>,
+        send: SendDueDigestsUseCase,
+    }
+
+    fn fixture() -> Fixture {
+        let clock = Arc::new(MockClock::new(SEVEN_AM));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let configs = Arc::new(InMemoryBotConfigRepository::new());
+        let exchange = Arc::new(InMemoryTradingGateway::new());
+        let notifier = Arc::new(RecordingNotifier::new());
+        let build = Arc::new(BuildDailyDigestUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            configs.clone(),
+            exchange.clone(),
+            repo.clone(),
+            clock.clone(),
+        ));
+        let send = SendDueDigestsUseCase::new(repo.clone(), build, notifier.clone(), clock.clone());
+        Fixture {
+            clock,
+            repo,
+            configs,
+            exchange,
+            notifier,
+            send,
+        }
+    }
+
+    async fn add_bot(f: &Fixture, user_id: &str, bot_id: &str, enabled: bool) {
+        let mut bot = Bot::create(user_id.into(), bot_id.into(), "ak".into(), "sk".into(), 0);
+        bot.enabled = enabled;
+        BotRepository::save(f.repo.as_ref(), &bot).await.unwrap();
+    }
+
+    async fn digest_at(f: &Fixture, user_id: &str, hour: u32, set_at: i64) {
+        let mut prefs = UserPreferences::new(user_id.into(), set_at);
+        prefs.set_digest_hour(Some(hour), set_at).unwrap();
+        UserPreferencesRepository::save(f.repo.as_ref(), &prefs)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn digest_reports_state_restarts_drift_and_equity_change() {
+        let f = fixture();
+        let u = "42";
+        add_bot(&f, u, "grid-1", true).await;
+        add_bot(&f, u, "grid-2", true).await;
+        add_bot(&f, u, "grid-3", false).await;
+
+        // grid-1 runs since 6h ago, restarted twice today and once yesterday,
+        // and its config was edited after the launch.
+        let running = BotRuntime::running(
+            u.into(),
+            "grid-1".into(),
+            "t1".into(),
+            3,
+            SEVEN_AM - 6 * 3_600,
+        );
+        f.repo.record(&running).await.unwrap();
+        for at in [
+            SEVEN_AM - 7 * 3_600,
+            SEVEN_AM - 8 * 3_600,
+            SEVEN_AM - 2 * DAY,
+        ] {
+            let event = BotEvent::new(u, "grid-1", BotEventKind::Restarted, at);
+            f.repo.append(&event).await.unwrap();
+        }
+        f.configs
+            .save(&BotConfig {
+                user_id: u.into(),
+                bot_id: "grid-1".into(),
+                bot_type: BotType::Passivbot,
+                template_name: "t".into(),
+                template_version: None,
+                config_data: json!({ "live": {} }),
+                created_at: 0,
+                updated_at: SEVEN_AM - 3_600,
+            })
+            .await
+            .unwrap();
+        // grid-2 is turned on but stopped; grid-3 off and never ran.
+        let stopped = BotRuntime::stopped(u.into(), "grid-2".into(), 1, SEVEN_AM - DAY);
+        f.repo.record(&stopped).await.unwrap();
+
+        f.exchange.set_equity("grid-1", 1_100.0);
+        f.exchange.set_equity("grid-2", 500.0);
+        // grid-3's exchange cannot be read.
+
+        let mut prefs = UserPreferences::new(u.into(), 0);
+        prefs.set_digest_hour(Some(7), SEVEN_AM - 3_600).unwrap();
+        prefs.digest_equity = BTreeMap::from([("grid-1".to_string(), 1_000.0)]);
+        UserPreferencesRepository::save(f.repo.as_ref(), &prefs)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            f.send.execute().await.unwrap(),
+            DigestRun { sent: 1, failed: 0 }
+        );
+
+        let sent = f.notifier.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, u);
+        assert_eq!(
+            sent[0].1,
+            "📰 Daily digest — 2024-01-05 07:00 UTC\n\
+            \n• grid-1 — ✅ Running (turned on)\
+            \n   🔁 2 restart(s) in 24h\
+            \n   ✏️ Config changed since launch; restart to apply\
+            \n   💰 Equity 1100.00 (+100.00)\
+            \n• grid-2 — ⏸️ Stopped (turned on)\
+            \n   ⚠️ Should be running\
+            \n   💰 Equity 500.00\
+            \n• grid-3 — ❔ Unknown (turned off)\
+            \n   💰 Equity unavailable\
+            \n\nTotal equity: 1600.00 (+100.00)"
+        );
+
+        // The snapshot is stored for the next digest, and the slot moved on.
+        let stored = UserPreferencesRepository::find(f.repo.as_ref(), u)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.digest_equity,
+            BTreeMap::from([
+                ("grid-1".to_string(), 1_100.0),
+                ("grid-2".to_string(), 500.0)
+            ])
+        );
+        assert_eq!(stored.next_digest_at, Some(SEVEN_AM + DAY));
+    }
+
+    #[tokio::test]
+    async fn only_due_digests_are_sent_and_each_slot_once() {
+        let f = fixture();
+        add_bot(&f, "1", "a", false).await;
+        digest_at(&f, "1", 7, SEVEN_AM - 3_600).await;
+        // Due at 08:00, an hour from now.
+        digest_at(&f, "2", 8, SEVEN_AM - 3_600).await;
+
+        assert_eq!(
+            f.send.execute().await.unwrap(),
+            DigestRun { sent: 1, failed: 0 }
+        );
+        // A second run in the same hour finds nothing due.
+        assert_eq!(f.send.execute().await.unwrap(), DigestRun::default());
+        assert_eq!(f.notifier.sent().len(), 1);
+
+        f.clock.advance(3_600);
+        assert_eq!(
+            f.send.execute().await.unwrap(),
+            DigestRun { sent: 1, failed: 0 }
+        );
+        assert_eq!(f.notifier.sent()[1].0, "2");
+        assert!(f.notifier.sent()[1].1.ends_with("(No bots)"));
+    }
+
+    #[tokio::test]
+    async fn an_undelivered_digest_keeps_the_equity_baseline() {
+        let f = fixture();
+        add_bot(&f, "1", "a", true).await;
+        f.exchange.set_equity("a", 900.0);
+        digest_at(&f, "1", 7, SEVEN_AM - 3_600).await;
+        f.notifier.fail("1");
+
+        assert_eq!(
+            f.send.execute().await.unwrap(),
+            DigestRun { sent: 0, failed: 1 }
+        );
+        let stored = UserPreferencesRepository::find(f.repo.as_ref(), "1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.digest_equity.is_empty());
+        // Not retried until tomorrow's slot.
+        assert_eq!(stored.next_digest_at, Some(SEVEN_AM + DAY));
+    }
+}
@@ -1,19 +1,27 @@
 use crate::domain::bot::{ApiKeyRepository, BotRepository};
+use crate::domain::riskguard::RiskGuardRepository;
+use crate::domain::schedule::ScheduleRepository;
 use std::sync::Arc;
 
 pub struct DeleteBotUseCase {
     bot_repository: Arc<dyn BotRepository + Send + Sync>,
     api_keys_repository: Arc<dyn ApiKeyRepository>,
+    schedules: Arc<dyn ScheduleRepository>,
+    guards: Arc<dyn RiskGuardRepository>,
 }
 
 impl DeleteBotUseCase {
     pub fn new(
         bot_repository: Arc<dyn BotRepository + Send + Sync>,
         api_keys_repository: Arc<dyn ApiKeyRepository>,
+        schedules: Arc<dyn ScheduleRepository>,
+        guards: Arc<dyn RiskGuardRepository>,
     ) -> Self {
         Self {
             bot_repository,
             api_keys_repository,
+            schedules,
+            guards,
         }
     }
 
@@ -21,6 +29,16 @@ impl DeleteBotUseCase {
         // Delete from DynamoDB
         self.bot_repository.delete(user_id, bot_id).await?;
 
+        // The bot's schedules go with it. Best-effort: one left behind is
+        // deleted by the schedule runner the next time it comes due.
+        self.delete_schedules(user_id, bot_id).await;
+
+        // So does its risk guard, so a bot re-added under the same name does
+        // not inherit the old limits or a latched trip.
+        if let Err(e) = self.guards.delete(user_id, bot_id).await {
+            tracing::warn!("failed to delete risk guard of deleted bot {bot_id}: {e}");
+        }
+
         // Delete API keys from S3
         self.api_keys_repository
             .delete(user_id, bot_id)
@@ -29,4 +47,86 @@ impl DeleteBotUseCase {
 
         Ok(())
     }
+
+    async fn delete_schedules(&self, user_id: &str, bot_id: &str) {
+        let schedules = match self.schedules.find_by_user(user_id).await {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                tracing::warn!("failed to list schedules of deleted bot {bot_id}: {e}");
+                return;
+            }
+        };
+        for schedule in schedules.iter().filter(|s| s.bot_id == bot_id) {
+            if let Err(e) = self.schedules.delete(user_id, &schedule.id).await {
+                tracing::warn!(
+                    "failed to delete schedule {} of deleted bot {bot_id}: {e}",
+                    schedule.id
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::riskguard::RiskGuard;
+    use crate::domain::schedule::{CronSchedule, Schedule, ScheduleAction};
+    use crate::infra::memory::{InMemoryApiKeyRepository, InMemoryBotRepository};
+
+    #[tokio::test]
+    async fn deleting_a_bot_deletes_its_schedules_and_guard() {
+        let repo = Arc::new(InMemoryBotRepository::new());
+        for name in ["grid-1", "grid-2"] {
+            let bot = Bot::create("u".into(), name.into(), "ak".into(), "sk".into(), 0);
+            BotRepository::save(repo.as_ref(), &bot).await.unwrap();
+            let schedule = Schedule::create(
+                format!("stop-{name}"),
+                "u".into(),
+                name.into(),
+                CronSchedule::parse("0 22 * * 5").unwrap(),
+                ScheduleAction::Stop,
+                0,
+            )
+            .unwrap();
+            ScheduleRepository::save(repo.as_ref(), &schedule)
+                .await
+                .unwrap();
+            let mut guard = RiskGuard::new("u".into(), name.into(), 0);
+            guard.set_max_drawdown(Some(10.0), 0).unwrap();
+            repo.save_limits(&guard).await.unwrap();
+        }
+        let uc = DeleteBotUseCase::new(
+            repo.clone(),
+            Arc::new(InMemoryApiKeyRepository::new()),
+            repo.clone(),
+            repo.clone(),
+        );
+
+        uc.execute("u", "grid-1").await.unwrap();
+
+        assert!(
+            BotRepository::find(repo.as_ref(), "u", "grid-1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let left: Vec<String> = repo
+            .find_by_user("u")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(left, vec!["stop-grid-2".to_string()]);
+        let guards: Vec<String> = repo
+            .find_all()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|g| g.bot_id)
+            .collect();
+        assert_eq!(guards, vec!["grid-2".to_string()]);
+    }
 }
@@ -0,0 +1,20 @@
+use crate::domain::schedule::ScheduleRepository;
+use std::sync::Arc;
+
+pub struct DeleteScheduleUseCase {
+    schedules: Arc<dyn ScheduleRepository>,
+}
+
+impl DeleteScheduleUseCase {
+    pub fn new(schedules: Arc<dyn ScheduleRepository>) -> Self {
+        Self { schedules }
+    }
+
+    /// Returns `Ok(false)` when the user has no schedule with that id.
+    pub async fn execute(&self, user_id: &str, schedule_id: &str) -> Result<bool, String> {
+        self.schedules
+            .delete(user_id, schedule_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
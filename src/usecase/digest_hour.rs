@@ -0,0 +1,89 @@
+use crate::domain::clock::Clock;
+use crate::domain::preferences::{UserPreferences, UserPreferencesRepository};
+use std::sync::Arc;
+
+/// A user's preferences, or the defaults when they never set any.
+pub struct GetPreferencesUseCase {
+    prefs: Arc<dyn UserPreferencesRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl GetPreferencesUseCase {
+    pub fn new(prefs: Arc<dyn UserPreferencesRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { prefs, clock }
+    }
+
+    pub async fn execute(&self, user_id: &str) -> Result<UserPreferences, String> {
+        Ok(self
+            .prefs
+            .find(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| UserPreferences::new(user_id.to_string(), self.clock.now())))
+    }
+}
+
+/// Turn the daily digest on at a UTC hour, or off (`None`). Returns the
+/// saved preferences, whose `next_digest_at` is when the first one goes out.
+pub struct SetDigestHourUseCase {
+    prefs: Arc<dyn UserPreferencesRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetDigestHourUseCase {
+    pub fn new(prefs: Arc<dyn UserPreferencesRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { prefs, clock }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        hour: Option<u32>,
+    ) -> Result<UserPreferences, String> {
+        let now = self.clock.now();
+        let mut prefs = self
+            .prefs
+            .find(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| UserPreferences::new(user_id.to_string(), now));
+        prefs
+            .set_digest_hour(hour, now)
+            .map_err(|e| e.to_string())?;
+        self.prefs.save(&prefs).await.map_err(|e| e.to_string())?;
+        Ok(prefs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::MockClock;
+    use crate::infra::memory::InMemoryBotRepository;
+
+    // 2024-01-05 07:30:00 UTC.
+    const NOW: i64 = 1_704_439_800;
+
+    #[tokio::test]
+    async fn set_then_turn_off_keeps_the_equity_baseline() {
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let clock = Arc::new(MockClock::new(NOW));
+        let set = SetDigestHourUseCase::new(repo.clone(), clock.clone());
+        let get = GetPreferencesUseCase::new(repo.clone(), clock);
+
+        assert_eq!(get.execute("1").await.unwrap().digest_hour, None);
+
+        let prefs = set.execute("1", Some(7)).await.unwrap();
+        // 07:00 has passed today, so the first digest is tomorrow's.
+        assert_eq!(prefs.next_digest_at, Some(NOW - 1_800 + 86_400));
+
+        repo.record_digest_equity("1", &[("a".to_string(), 10.0)].into())
+            .await
+            .unwrap();
+        let prefs = set.execute("1", None).await.unwrap();
+        assert_eq!(prefs.next_digest_at, None);
+        assert_eq!(get.execute("1").await.unwrap().digest_equity.len(), 1);
+
+        assert!(set.execute("1", Some(24)).await.is_err());
+    }
+}
@@ -0,0 +1,573 @@
+use crate::domain::account::ExchangeAccount;
+use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::clock::Clock;
+use crate::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+use crate::domain::notify::Notifier;
+use crate::domain::riskguard::{RiskGuard, RiskGuardRepository};
+use crate::domain::runtime::{BotRuntimeRepository, RuntimePhase};
+use crate::domain::schedule::ScheduleAction;
+use crate::telemetry::{CorrelationId, with_correlation_id};
+use crate::usecase::ScheduleActionRunner;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// Outcome of one guard run, for the Lambda's log.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GuardRun {
+    /// Armed guards whose bot's equity was read.
+    pub evaluated: usize,
+    pub tripped: usize,
+    /// Bots started again after a trip stopped them.
+    pub restarted: usize,
+    pub failed: usize,
+}
+
+/// Check every armed risk guard against its bot's account equity.
+///
+/// A breach is claimed first (conditional latch of the trip), so overlapping
+/// runs act on it once: both sides are switched to `graceful_stop`, a running
+/// bot is stopped, the event is recorded and the user notified. Passivbot
+/// reads the config only at launch, so the bot is started again on a later
+/// run once its task has stopped — a stop by the job itself, since the
+/// reconcile never restarts a user-initiated stop. The restart is marked
+/// (`restart_pending`) only once the job's stop went through, and any later
+/// stop (Stop, /panic, a schedule) clears it again, so the job never starts a
+/// bot the user stopped after the trip.
+pub struct EvaluateRiskGuardsUseCase {
+    guards: Arc<dyn RiskGuardRepository>,
+    bots: Arc<dyn BotRepository + Send + Sync>,
+    runtimes: Arc<dyn BotRuntimeRepository>,
+    account: Arc<dyn ExchangeAccount>,
+    runner: Arc<dyn ScheduleActionRunner>,
+    history: Arc<dyn BotHistoryRepository>,
+    notifier: Arc<dyn Notifier>,
+    clock: Arc<dyn Clock>,
+}
+
+impl EvaluateRiskGuardsUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        guards: Arc<dyn RiskGuardRepository>,
+        bots: Arc<dyn BotRepository + Send + Sync>,
+        runtimes: Arc<dyn BotRuntimeRepository>,
+        account: Arc<dyn ExchangeAccount>,
+        runner: Arc<dyn ScheduleActionRunner>,
+        history: Arc<dyn BotHistoryRepository>,
+        notifier: Arc<dyn Notifier>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            guards,
+            bots,
+            runtimes,
+            account,
+            runner,
+            history,
+            notifier,
+            clock,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<GuardRun, String> {
+        let guards = self.guards.find_all().await.map_err(|e| e.to_string())?;
+
+        let mut run = GuardRun::default();
+        for guard in guards {
+            if guard.restart_pending {
+                match self.finish_restart(&guard).await {
+                    Ok(true) => run.restarted += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!(bot_id = %guard.bot_id, error = %e, "guard: restart failed");
+                        run.failed += 1;
+                    }
+                }
+                continue;
+            }
+            if !guard.is_armed() {
+                continue;
+            }
+            let bot = match self.bots.find(&guard.user_id, &guard.bot_id).await {
+                // Nothing trades while the bot is turned off.
+                Ok(Some(bot)) if bot.enabled => bot,
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!(bot_id = %guard.bot_id, error = %e, "guard: bot unavailable");
+                    run.failed += 1;
+                    continue;
+                }
+            };
+            match self.evaluate(guard, &bot).await {
+                Ok(Some(tripped)) => {
+                    run.evaluated += 1;
+                    run.tripped += usize::from(tripped);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(bot_id = %bot.id, error = %e, "guard: evaluation failed");
+                    run.failed += 1;
+                }
+            }
+        }
+        Ok(run)
+    }
+
+    /// Read equity and record it. `Some(true)` when this run tripped the
+    /// guard, `None` when another run got there first.
+    async fn evaluate(&self, mut guard: RiskGuard, bot: &Bot) -> Result<Option<bool>, String> {
+        let equity = self.account.equity(bot).await.map_err(|e| e.to_string())?;
+        let now = self.clock.now();
+        let Some(reason) = guard.observe(equity, now) else {
+            self.guards
+                .record_observation(&guard)
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(Some(false));
+        };
+
+        let running = self
+            .runtimes
+            .find(&bot.user_id, &bot.id)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some_and(|r| r.phase != RuntimePhase::Stopped);
+        if !self
+            .guards
+            .claim_trip(&guard)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            return Ok(None);
+        }
+
+        // Acting on the breach is an action of its own, as a button press is.
+        let correlation_id = CorrelationId::new();
+        let span = tracing::info_span!(
+            "risk_guard",
+            correlation_id = %correlation_id,
+            user_id = %bot.user_id,
+            bot_id = %bot.id,
+        );
+        with_correlation_id(
+            correlation_id,
+            self.trip(&guard, bot, &reason, running).instrument(span),
+        )
+        .await?;
+        Ok(Some(true))
+    }
+
+    async fn trip(
+        &self,
+        guard: &RiskGuard,
+        bot: &Bot,
+        reason: &str,
+        running: bool,
+    ) -> Result<(), String> {
+        tracing::warn!(reason, "risk guard tripped");
+        let mut failures = Vec::new();
+        for side in ["long", "short"] {
+            let action = ScheduleAction::SetSide {
+                side: side.to_string(),
+                enabled: false,
+            };
+            if let Err(e) = self.runner.run(&bot.user_id, &bot.id, &action).await {
+                failures.push(format!("{side} side: {e}"));
+            }
+        }
+        let stopped = if running {
+            self.runner
+                .run(&bot.user_id, &bot.id, &ScheduleAction::Stop)
+                .await
+                .map(|_| ())
+        } else {
+            Ok(())
+        };
+        match stopped {
+            // Marked after the stop, which clears it like any other stop.
+            Ok(()) if running => {
+                if let Err(e) = self
+                    .guards
+                    .set_restart_pending(&bot.user_id, &bot.id, true)
+                    .await
+                {
+                    failures.push(format!("restart: {e}"));
+                }
+            }
+            Ok(()) => {}
+            // Nothing to start again if the stop never happened.
+            Err(e) => failures.push(format!("stop: {e}")),
+        }
+
+        let mut event = BotEvent::new(
+            &bot.user_id,
+            &bot.id,
+            BotEventKind::GuardTripped,
+            guard.tripped_at.unwrap_or_else(|| self.clock.now()),
+        );
+        event.detail = Some(reason.to_string());
+        if let Err(e) = self.history.append(&event).await {
+            tracing::warn!(error = %e, "failed to record guard event");
+        }
+
+        let mut text = format!(
+            "🛡️ Risk guard tripped on {}: {reason}.\n\
+             Both sides are set to graceful stop",
+            bot.name
+        );
+        text.push_str(if running && failures.is_empty() {
+            "; the bot is restarting to pick that up."
+        } else {
+            "."
+        });
+        if !failures.is_empty() {
+            text.push_str(&format!(
+                "\n⚠️ Not everything went through: {}",
+                failures.join("; ")
+            ));
+        }
+        text.push_str("\nRe-arm with /guard rearm once you have checked the bot.");
+        if let Err(e) = self.notifier.notify(&bot.user_id, &text).await {
+            tracing::warn!(error = %e, "failed to notify guard trip");
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("; "))
+        }
+    }
+
+    /// Start a bot the trip stopped, once its task is gone. `true` when it
+    /// was started (or someone else already did).
+    async fn finish_restart(&self, guard: &RiskGuard) -> Result<bool, String> {
+        let Some(bot) = self
+            .bots
+            .find(&guard.user_id, &guard.bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            // Deleted meanwhile: nothing left to start.
+            self.guards
+                .set_restart_pending(&guard.user_id, &guard.bot_id, false)
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(false);
+        };
+        let phase = self
+            .runtimes
+            .find(&guard.user_id, &guard.bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .map(|r| r.phase);
+        match phase {
+            Some(RuntimePhase::Stopping) => return Ok(false),
+            // A live task with the bot turned on was started by someone else;
+            // turned off, it is the one being stopped.
+            Some(RuntimePhase::Starting) | Some(RuntimePhase::Running) if !bot.enabled => {
+                return Ok(false);
+            }
+            Some(RuntimePhase::Starting) | Some(RuntimePhase::Running) => {}
+            Some(RuntimePhase::Stopped) | None => {
+                let result = self
+                    .runner
+                    .run(&guard.user_id, &guard.bot_id, &ScheduleAction::Start)
+                    .await?;
+                tracing::info!(bot_id = %guard.bot_id, result, "guard: restarted bot");
+            }
+        }
+        self.guards
+            .set_restart_pending(&guard.user_id, &guard.bot_id, false)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::MockClock;
+    use crate::domain::metrics::NoopMetrics;
+    use crate::domain::runtime::BotRuntime;
+    use crate::infra::memory::{InMemoryBotRepository, InMemoryTradingGateway, RecordingNotifier};
+    use crate::usecase::{
+        GuardLimit, RearmRiskGuardUseCase, SetRiskGuardLimitUseCase, StopBotUseCase, StopOutcome,
+        TaskController, TaskLiveness,
+    };
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    // 2024-01-05 00:00:00 UTC.
+    const MIDNIGHT: i64 = 1_704_412_800;
+
+    /// Records every action and plays the start/stop part against the
+    /// repository, as the real use cases would (minus ECS): a stop also calls
+    /// off a pending guard restart.
+    struct RecordingRunner {
+        repo: Arc<InMemoryBotRepository>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ScheduleActionRunner for RecordingRunner {
+        async fn run(
+            &self,
+            user_id: &str,
+            bot_id: &str,
+            action: &ScheduleAction,
+        ) -> Result<String, String> {
+            self.calls.lock().unwrap().push(action.to_string());
+            let mut bot = BotRepository::find(self.repo.as_ref(), user_id, bot_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .unwrap();
+            let runtime = match action {
+                ScheduleAction::Start => {
+                    BotRuntime::running(user_id.into(), bot_id.into(), "t2".into(), 2, 0)
+                }
+                ScheduleAction::Stop => {
+                    BotRuntime::stopping(user_id.into(), bot_id.into(), "t1".into(), 1, 0)
+                }
+                _ => return Ok(format!("did {action}")),
+            };
+            bot.enabled = *action == ScheduleAction::Start;
+            BotRepository::save(self.repo.as_ref(), &bot)
+                .await
+                .map_err(|e| e.to_string())?;
+            if *action == ScheduleAction::Stop {
+                self.repo
+                    .set_restart_pending(user_id, bot_id, false)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            self.repo
+                .record(&runtime)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(format!("did {action}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn breach_trips_once_then_restarts_the_bot_and_waits_for_rearm() {
+        let clock = Arc::new(MockClock::new(MIDNIGHT + 60));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let exchange = Arc::new(InMemoryTradingGateway::new());
+        let notifier = Arc::new(RecordingNotifier::new());
+        let runner = Arc::new(RecordingRunner {
+            repo: repo.clone(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let uc = EvaluateRiskGuardsUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            exchange.clone(),
+            runner.clone(),
+            repo.clone(),
+            notifier.clone(),
+            clock.clone(),
+        );
+
+        let mut bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 0);
+        bot.enabled = true;
+        BotRepository::save(repo.as_ref(), &bot).await.unwrap();
+        let running = BotRuntime::running("u".into(), "b".into(), "t1".into(), 1, 0);
+        repo.record(&running).await.unwrap();
+        SetRiskGuardLimitUseCase::new(repo.clone(), clock.clone())
+            .execute("u", "b", GuardLimit::DailyLoss, Some(5.0))
+            .await
+            .unwrap();
+
+        exchange.set_equity("b", 1_000.0);
+        let run = uc.execute().await.unwrap();
+        assert_eq!((run.evaluated, run.tripped), (1, 0));
+
+        clock.advance(60);
+        exchange.set_equity("b", 940.0);
+        let run = uc.execute().await.unwrap();
+        assert_eq!((run.evaluated, run.tripped, run.failed), (1, 1, 0));
+        assert_eq!(
+            *runner.calls.lock().unwrap(),
+            vec!["side:long:off", "side:short:off", "stop"]
+        );
+        let guard = RiskGuardRepository::find(repo.as_ref(), "u", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(guard.is_tripped() && guard.restart_pending);
+        let events = repo.history("u", "b");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, BotEventKind::GuardTripped);
+        assert!(
+            events[0]
+                .detail
+                .as_deref()
+                .unwrap()
+                .starts_with("daily loss 6.0%")
+        );
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].1.contains("restarting"), "{}", sent[0].1);
+
+        // Still stopping: wait. Once stopped, start it again.
+        clock.advance(60);
+        assert_eq!(uc.execute().await.unwrap(), GuardRun::default());
+        let stopped = BotRuntime::stopped("u".into(), "b".into(), 1, 0);
+        repo.record(&stopped).await.unwrap();
+        assert_eq!(uc.execute().await.unwrap().restarted, 1);
+        assert_eq!(runner.calls.lock().unwrap().last().unwrap(), "start");
+        assert!(
+            !RiskGuardRepository::find(repo.as_ref(), "u", "b")
+                .await
+                .unwrap()
+                .unwrap()
+                .restart_pending
+        );
+
+        // Latched: no more readings, no second trip, until re-armed.
+        exchange.set_equity("b", 800.0);
+        assert_eq!(uc.execute().await.unwrap(), GuardRun::default());
+        assert_eq!(notifier.sent().len(), 1);
+
+        let rearm = RearmRiskGuardUseCase::new(repo.clone(), clock.clone());
+        assert!(rearm.execute("u", "b").await.unwrap());
+        assert!(!rearm.execute("u", "b").await.unwrap());
+        let run = uc.execute().await.unwrap();
+        assert_eq!((run.evaluated, run.tripped), (1, 0));
+        let guard = RiskGuardRepository::find(repo.as_ref(), "u", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(guard.day_start_equity, Some(800.0));
+    }
+
+    /// No task of its own to stop: the guard's stop already ended it.
+    struct NoTasks;
+
+    #[async_trait]
+    impl TaskController for NoTasks {
+        async fn stop(&self, _: &str, _: &str, _: &str) -> anyhow::Result<()> {
+            unreachable!("the bot is already stopping")
+        }
+        async fn liveness(&self, _: &str, _: &str) -> anyhow::Result<TaskLiveness> {
+            Ok(TaskLiveness::Gone)
+        }
+    }
+
+    #[tokio::test]
+    async fn a_stop_after_the_trip_calls_off_the_restart() {
+        let clock = Arc::new(MockClock::new(MIDNIGHT + 60));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let exchange = Arc::new(InMemoryTradingGateway::new());
+        let runner = Arc::new(RecordingRunner {
+            repo: repo.clone(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let uc = EvaluateRiskGuardsUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            exchange.clone(),
+            runner.clone(),
+            repo.clone(),
+            Arc::new(RecordingNotifier::new()),
+            clock.clone(),
+        );
+        let mut bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 0);
+        bot.enabled = true;
+        BotRepository::save(repo.as_ref(), &bot).await.unwrap();
+        let running = BotRuntime::running("u".into(), "b".into(), "t1".into(), 1, 0);
+        repo.record(&running).await.unwrap();
+        SetRiskGuardLimitUseCase::new(repo.clone(), clock.clone())
+            .execute("u", "b", GuardLimit::Drawdown, Some(5.0))
+            .await
+            .unwrap();
+        exchange.set_equity("b", 1_000.0);
+        uc.execute().await.unwrap();
+        exchange.set_equity("b", 900.0);
+        assert_eq!(uc.execute().await.unwrap().tripped, 1);
+        assert!(
+            RiskGuardRepository::find(repo.as_ref(), "u", "b")
+                .await
+                .unwrap()
+                .unwrap()
+                .restart_pending
+        );
+
+        // The user presses Stop while the guard's stop winds the task down.
+        let stop = StopBotUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            Arc::new(NoTasks),
+            clock.clone(),
+            repo.clone(),
+            Arc::new(NoopMetrics),
+            "cluster".into(),
+        );
+        assert_eq!(
+            stop.execute("u", "b").await.unwrap(),
+            StopOutcome::AlreadyStopping
+        );
+        let stopped = BotRuntime::stopped("u".into(), "b".into(), 1, 0);
+        repo.record(&stopped).await.unwrap();
+
+        assert_eq!(uc.execute().await.unwrap(), GuardRun::default());
+        assert_eq!(runner.calls.lock().unwrap().last().unwrap(), "stop");
+        let bot = BotRepository::find(repo.as_ref(), "u", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!bot.enabled);
+        let guard = RiskGuardRepository::find(repo.as_ref(), "u", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(guard.is_tripped() && !guard.restart_pending);
+    }
+
+    #[tokio::test]
+    async fn stopped_or_unguarded_bots_are_left_alone() {
+        let clock = Arc::new(MockClock::new(MIDNIGHT));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let exchange = Arc::new(InMemoryTradingGateway::new());
+        let runner = Arc::new(RecordingRunner {
+            repo: repo.clone(),
+            calls: Mutex::new(Vec::new()),
+        });
+        let uc = EvaluateRiskGuardsUseCase::new(
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            exchange.clone(),
+            runner.clone(),
+            repo.clone(),
+            Arc::new(RecordingNotifier::new()),
+            clock.clone(),
+        );
+        let set = SetRiskGuardLimitUseCase::new(repo.clone(), clock.clone());
+
+        // "off" is turned off; "open" has its limit cleared again.
+        for (bot_id, enabled) in [("off", false), ("open", true)] {
+            let mut bot = Bot::create("u".into(), bot_id.into(), "ak".into(), "sk".into(), 0);
+            bot.enabled = enabled;
+            BotRepository::save(repo.as_ref(), &bot).await.unwrap();
+            exchange.set_equity(bot_id, 100.0);
+            set.execute("u", bot_id, GuardLimit::Drawdown, Some(10.0))
+                .await
+                .unwrap();
+        }
+        set.execute("u", "open", GuardLimit::Drawdown, None)
+            .await
+            .unwrap();
+
+        assert_eq!(uc.execute().await.unwrap(), GuardRun::default());
+        assert!(runner.calls.lock().unwrap().is_empty());
+        assert!(
+            set.execute("u", "open", GuardLimit::DailyLoss, Some(150.0))
+                .await
+                .is_err()
+        );
+    }
+}
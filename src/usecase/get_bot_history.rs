@@ -0,0 +1,27 @@
+use crate::domain::history::{BotEvent, BotHistoryRepository};
+use std::sync::Arc;
+
+/// How many lifecycle events the History view shows.
+pub const BOT_HISTORY_VIEW_LIMIT: usize = 15;
+
+/// A bot's most recent lifecycle events (starts, stops with their causes,
+/// restarts), newest first.
+pub struct GetBotHistoryUseCase {
+    history: Arc<dyn BotHistoryRepository>,
+}
+impl GetBotHistoryUseCase {
+    pub fn new(history: Arc<dyn BotHistoryRepository>) -> Self {
+        Self { history }
+    }
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        limit: usize,
+    ) -> Result<Vec<BotEvent>, String> {
+        self.history
+            .recent(user_id, bot_id, limit)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,314 @@
+use crate::domain::account::{ClosedPnl, ExchangeAccount, Fill};
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::trading::Position;
+use std::sync::Arc;
+
+/// How many of the latest fills the Performance view lists.
+pub const PERFORMANCE_RECENT_FILLS: usize = 10;
+
+const DAY: i64 = 24 * 60 * 60;
+/// How far back the recent fills are looked for.
+const RECENT_FILLS_WINDOW: i64 = 7 * DAY;
+
+/// Realized PnL over one period. A closed position counts as a win when it
+/// realised more than zero, fees included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlSummary {
+    pub realized_pnl: f64,
+    pub closed_trades: usize,
+    pub wins: usize,
+}
+
+impl PnlSummary {
+    fn add(&mut self, closed: &ClosedPnl) {
+        self.realized_pnl += closed.pnl;
+        self.closed_trades += 1;
+        if closed.pnl > 0.0 {
+            self.wins += 1;
+        }
+    }
+
+    /// Share of closed trades that were wins; `None` with nothing closed.
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.closed_trades > 0).then(|| self.wins as f64 / self.closed_trades as f64)
+    }
+}
+
+/// How a bot's exchange account is doing. Periods are rolling and end at
+/// `as_of`: the last 24 hours, 7 days and 30 days.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceReport {
+    /// Unix seconds.
+    pub as_of: i64,
+    pub day: PnlSummary,
+    pub week: PnlSummary,
+    pub month: PnlSummary,
+    pub positions: Vec<Position>,
+    /// Newest first, at most [`PERFORMANCE_RECENT_FILLS`].
+    pub recent_fills: Vec<Fill>,
+}
+
+impl PerformanceReport {
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions.iter().map(|p| p.unrealized_pnl).sum()
+    }
+}
+
+/// Reads a bot's closed PnL, open positions and latest fills from its
+/// exchange sub-account and aggregates them per period.
+pub struct GetPerformanceUseCase {
+    bots: Arc<dyn BotRepository>,
+    account: Arc<dyn ExchangeAccount>,
+    clock: Arc<dyn Clock>,
+}
+
+impl GetPerformanceUseCase {
+    pub fn new(
+        bots: Arc<dyn BotRepository>,
+        account: Arc<dyn ExchangeAccount>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            bots,
+            account,
+            clock,
+        }
+    }
+
+    /// `None` when the user has no bot `bot_id`.
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+    ) -> Result<Option<PerformanceReport>, String> {
+        let Some(bot) = self
+            .bots
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+
+        let now = self.clock.now();
+        let (closed, positions, recent_fills) = tokio::try_join!(
+            self.account.closed_pnl(&bot, now - 30 * DAY, now),
+            self.account.positions(&bot),
+            self.account.fills(
+                &bot,
+                now - RECENT_FILLS_WINDOW,
+                now,
+                PERFORMANCE_RECENT_FILLS
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut report = PerformanceReport {
+            as_of: now,
+            day: PnlSummary::default(),
+            week: PnlSummary::default(),
+            month: PnlSummary::default(),
+            positions,
+            recent_fills,
+        };
+        for c in &closed {
+            let age = now - c.closed_at;
+            if age <= DAY {
+                report.day.add(c);
+            }
+            if age <= 7 * DAY {
+                report.week.add(c);
+            }
+            if age <= 30 * DAY {
+                report.month.add(c);
+            }
+        }
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::OrderSide;
+    use crate::domain::bot::Bot;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use crate::domain::trading::PositionSide;
+    use crate::infra::memory::InMemoryBotRepository;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Fixed account history; records the windows it was asked for.
+    #[derive(Default)]
+    struct FakeAccount {
+        closed: Vec<ClosedPnl>,
+        fills: Vec<Fill>,
+        positions: Vec<Position>,
+        fail: bool,
+        asked: Mutex<Vec<(&'static str, i64, i64)>>,
+    }
+    #[async_trait]
+    impl ExchangeAccount for FakeAccount {
+        async fn closed_pnl(
+            &self,
+            _bot: &Bot,
+            since: i64,
+            until: i64,
+        ) -> Result<Vec<ClosedPnl>, DomainError> {
+            self.asked.lock().unwrap().push(("closed", since, until));
+            if self.fail {
+                return Err(DomainError::Exchange("retCode 10003: invalid key".into()));
+            }
+            Ok(self.closed.clone())
+        }
+        async fn fills(
+            &self,
+            _bot: &Bot,
+            since: i64,
+            until: i64,
+            limit: usize,
+        ) -> Result<Vec<Fill>, DomainError> {
+            self.asked.lock().unwrap().push(("fills", since, until));
+            Ok(self.fills.iter().take(limit).cloned().collect())
+        }
+        async fn positions(&self, _bot: &Bot) -> Result<Vec<Position>, DomainError> {
+            Ok(self.positions.clone())
+        }
+        async fn equity(&self, _bot: &Bot) -> Result<f64, DomainError> {
+            Ok(1000.0)
+        }
+    }
+
+    fn closed(pnl: f64, ago: i64) -> ClosedPnl {
+        ClosedPnl {
+            symbol: "BTCUSDT".into(),
+            side: PositionSide::Long,
+            qty: 0.01,
+            entry_price: 37000.0,
+            exit_price: 37000.0 + pnl * 100.0,
+            pnl,
+            closed_at: NOW - ago,
+        }
+    }
+
+    fn fill(ago: i64) -> Fill {
+        Fill {
+            symbol: "BTCUSDT".into(),
+            side: OrderSide::Buy,
+            price: 37000.0,
+            qty: 0.001,
+            fee: 0.02,
+            is_maker: true,
+            executed_at: NOW - ago,
+        }
+    }
+
+    fn position(symbol: &str, unrealized_pnl: f64) -> Position {
+        Position {
+            symbol: symbol.into(),
+            side: PositionSide::Long,
+            size: 0.01,
+            entry_price: 37000.0,
+            mark_price: 37500.0,
+            unrealized_pnl,
+            liq_price: None,
+            position_idx: 1,
+        }
+    }
+
+    async fn usecase(account: Arc<FakeAccount>) -> GetPerformanceUseCase {
+        let bots = Arc::new(InMemoryBotRepository::new());
+        let bot = Bot::create("u".into(), "bot-1".into(), "ak".into(), "sk".into(), 0);
+        bots.save(&bot).await.unwrap();
+        GetPerformanceUseCase::new(bots, account, Arc::new(MockClock::new(NOW)))
+    }
+
+    #[tokio::test]
+    async fn buckets_closed_pnl_into_rolling_periods() {
+        let account = Arc::new(FakeAccount {
+            closed: vec![
+                closed(10.0, 60 * 60),
+                closed(-4.0, 2 * 60 * 60),
+                closed(6.0, 3 * DAY),
+                closed(-1.0, 20 * DAY),
+            ],
+            positions: vec![position("BTCUSDT", 5.0), position("ETHUSDT", -1.5)],
+            ..Default::default()
+        });
+
+        let report = usecase(account.clone())
+            .await
+            .execute("u", "bot-1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            report.day,
+            PnlSummary {
+                realized_pnl: 6.0,
+                closed_trades: 2,
+                wins: 1
+            }
+        );
+        assert_eq!(report.day.win_rate(), Some(0.5));
+        assert_eq!(report.week.realized_pnl, 12.0);
+        assert_eq!(report.week.closed_trades, 3);
+        assert_eq!(report.month.realized_pnl, 11.0);
+        assert_eq!(report.month.wins, 2);
+        assert_eq!(report.unrealized_pnl(), 3.5);
+        assert_eq!(report.as_of, NOW);
+
+        let asked = account.asked.lock().unwrap().clone();
+        assert!(asked.contains(&("closed", NOW - 30 * DAY, NOW)));
+        assert!(asked.contains(&("fills", NOW - 7 * DAY, NOW)));
+    }
+
+    #[tokio::test]
+    async fn recent_fills_are_capped() {
+        let account = Arc::new(FakeAccount {
+            fills: (0..20).map(|i| fill(i * 60)).collect(),
+            ..Default::default()
+        });
+
+        let report = usecase(account)
+            .await
+            .execute("u", "bot-1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(report.recent_fills.len(), PERFORMANCE_RECENT_FILLS);
+        assert_eq!(report.day.win_rate(), None, "nothing closed yet");
+    }
+
+    #[tokio::test]
+    async fn unknown_bot_is_none() {
+        let account = Arc::new(FakeAccount::default());
+
+        assert_eq!(
+            usecase(account.clone()).await.execute("u", "nope").await,
+            Ok(None)
+        );
+        assert!(account.asked.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn exchange_errors_surface() {
+        let account = Arc::new(FakeAccount {
+            fail: true,
+            ..Default::default()
+        });
+
+        let err = usecase(account)
+            .await
+            .execute("u", "bot-1")
+            .await
+            .unwrap_err();
+        assert!(err.contains("invalid key"), "{err}");
+    }
+}
@@ -0,0 +1,115 @@
+use crate::domain::botconfig::{BotConfigRepository, RiskLevel};
+use crate::domain::configtemplate::ConfigTemplateRepository;
+use crate::domain::riskpreset::RiskPreset;
+use std::sync::Arc;
+
+/// Resolve the risk presets for a bot from the template its config was built
+/// from. The bot's own config is not used: once the user has changed the risk
+/// level it no longer reflects the template's baseline exposure.
+pub struct GetRiskPresetsUseCase {
+    bot_config_repository: Arc<dyn BotConfigRepository>,
+    template_repository: Arc<dyn ConfigTemplateRepository>,
+}
+
+impl GetRiskPresetsUseCase {
+    pub fn new(
+        bot_config_repository: Arc<dyn BotConfigRepository>,
+        template_repository: Arc<dyn ConfigTemplateRepository>,
+    ) -> Self {
+        Self {
+            bot_config_repository,
+            template_repository,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+    ) -> Result<Vec<(RiskPreset, RiskLevel)>, String> {
+        let config = self.bot_config_repository.get(user_id, bot_id).await?;
+        let template = self.template_repository.get(&config.template_name).await?;
+        Ok(RiskPreset::resolve_all(&template))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::botconfig::{BotConfig, BotType};
+    use crate::domain::configtemplate::ConfigTemplate;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    struct OneConfig;
+    #[async_trait]
+    impl BotConfigRepository for OneConfig {
+        async fn get(&self, user_id: &str, bot_id: &str) -> Result<BotConfig, String> {
+            Ok(BotConfig {
+                user_id: user_id.into(),
+                bot_id: bot_id.into(),
+                bot_type: BotType::Passivbot,
+                template_name: "grid".into(),
+                template_version: None,
+                // the user already tuned exposure away from the template
+                config_data: json!({
+                    "bot": {
+                        "long": {"total_wallet_exposure_limit": 9.0},
+                        "short": {"total_wallet_exposure_limit": 9.0}
+                    }
+                }),
+                created_at: 0,
+                updated_at: 0,
+            })
+        }
+        async fn save(&self, _config: &BotConfig) -> Result<(), String> {
+            Ok(())
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+        async fn exists(&self, _user_id: &str, _bot_id: &str) -> Result<bool, String> {
+            Ok(true)
+        }
+    }
+
+    struct Templates;
+    #[async_trait]
+    impl ConfigTemplateRepository for Templates {
+        async fn get(&self, template_name: &str) -> Result<ConfigTemplate, String> {
+            if template_name != "grid" {
+                return Err(format!("template {template_name} not found"));
+            }
+            Ok(ConfigTemplate {
+                name: "grid".into(),
+                description: None,
+                config_data: json!({
+                    "bot": {
+                        "long": {"total_wallet_exposure_limit": 2.0},
+                        "short": {"total_wallet_exposure_limit": 1.0}
+                    }
+                }),
+                version: None,
+            })
+        }
+        async fn list(&self) -> Result<Vec<String>, String> {
+            Ok(vec!["grid".into()])
+        }
+        async fn exists(&self, template_name: &str) -> Result<bool, String> {
+            Ok(template_name == "grid")
+        }
+        async fn save(&self, _template: &ConfigTemplate) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn presets_come_from_the_source_template() {
+        let uc = GetRiskPresetsUseCase::new(Arc::new(OneConfig), Arc::new(Templates));
+        let presets = uc.execute("u", "b").await.unwrap();
+        assert_eq!(
+            presets[1],
+            (RiskPreset::Balanced, RiskLevel::new(2.0, 1.0).unwrap())
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::domain::botconfig::BotConfig;
+use crate::domain::configtemplate::{ConfigTemplate, ConfigTemplateRepository};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Store a passivbot config as a predefined template, creating or replacing
+/// it. The config is checked the way `ApplyTemplateUseCase` will use it, so a
+/// template that could not be applied to a bot is refused up front.
+pub struct ImportTemplateUseCase {
+    template_repository: Arc<dyn ConfigTemplateRepository>,
+}
+
+impl ImportTemplateUseCase {
+    pub fn new(template_repository: Arc<dyn ConfigTemplateRepository>) -> Self {
+        Self {
+            template_repository,
+        }
+    }
+
+    /// Validate without saving. Returns whether a template of this name
+    /// already exists (and would be replaced).
+    pub async fn preview(&self, name: &str, config_data: &Value) -> Result<bool, String> {
+        let template = Self::template(name, config_data.clone())?;
+        // `from_template` needs a `live` object; the risk editor needs both
+        // exposure limits.
+        BotConfig::from_template(String::new(), String::new(), &template, 0)
+            .and_then(|probe| probe.risk_level())
+            .map_err(|e| e.to_string())?;
+        self.template_repository.exists(name).await
+    }
+
+    /// Validate and save. Returns whether an existing template was replaced.
+    pub async fn execute(&self, name: &str, config_data: Value) -> Result<bool, String> {
+        let replaced = self.preview(name, &config_data).await?;
+        self.template_repository
+            .save(&Self::template(name, config_data)?)
+            .await?;
+        Ok(replaced)
+    }
+
+    /// Names become S3 keys (`predefined/<name>.json`) and button labels.
+    fn template(name: &str, config_data: Value) -> Result<ConfigTemplate, String> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(format!(
+                "invalid template name {name:?}: use letters, digits, '_' and '-'"
+            ));
+        }
+        Ok(ConfigTemplate {
+            name: name.to_string(),
+            description: None,
+            config_data,
+            version: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Templates(Mutex<HashMap<String, ConfigTemplate>>);
+
+    #[async_trait]
+    impl ConfigTemplateRepository for Templates {
+        async fn get(&self, template_name: &str) -> Result<ConfigTemplate, String> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(template_name)
+                .cloned()
+                .ok_or_else(|| format!("template {template_name} not found"))
+        }
+        async fn list(&self) -> Result<Vec<String>, String> {
+            Ok(self.0.lock().unwrap().keys().cloned().collect())
+        }
+        async fn exists(&self, template_name: &str) -> Result<bool, String> {
+            Ok(self.0.lock().unwrap().contains_key(template_name))
+        }
+        async fn save(&self, template: &ConfigTemplate) -> Result<(), String> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(template.name.clone(), template.clone());
+            Ok(())
+        }
+    }
+
+    fn config() -> Value {
+        json!({
+            "live": { "user": "whoever" },
+            "bot": {
+                "long": { "total_wallet_exposure_limit": 1.0 },
+                "short": { "total_wallet_exposure_limit": 0.5 }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn imports_and_reports_replacement() {
+        let templates = Arc::new(Templates::default());
+        let usecase = ImportTemplateUseCase::new(templates.clone());
+
+        assert!(!usecase.execute("grid_v2", config()).await.unwrap());
+        assert!(usecase.preview("grid_v2", &config()).await.unwrap());
+        assert!(usecase.execute("grid_v2", config()).await.unwrap());
+        assert_eq!(
+            templates.get("grid_v2").await.unwrap().config_data,
+            config()
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_what_could_not_be_applied() {
+        let templates = Arc::new(Templates::default());
+        let usecase = ImportTemplateUseCase::new(templates.clone());
+
+        let no_live = json!({ "bot": config()["bot"].clone() });
+        assert!(usecase.execute("a", no_live).await.is_err());
+        let no_risk = json!({ "live": {} });
+        assert!(usecase.execute("a", no_risk).await.is_err());
+        assert!(usecase.execute("../etc", config()).await.is_err());
+        assert!(templates.list().await.unwrap().is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::bot::{Bot, BotPage, BotRepository};
 use std::sync::Arc;
 
 pub struct ListBotsUseCase {
@@ -17,4 +17,26 @@ impl ListBotsUseCase {
             .await
             .map_err(|e| e.to_string())
     }
+
+    /// The user's bot `bot_id`, `None` when they have no such bot.
+    pub async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<Bot>, String> {
+        self.bot_repository
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// One page of `page_size` bots in id order, resuming after `after` (the
+    /// previous page's `next_cursor`; `None` for the first page).
+    pub async fn page(
+        &self,
+        user_id: &str,
+        after: Option<&str>,
+        page_size: usize,
+    ) -> Result<BotPage, String> {
+        self.bot_repository
+            .find_page_by_user_id(user_id, after, page_size)
+            .await
+            .map_err(|e| e.to_string())
+    }
 }
@@ -0,0 +1,161 @@
+use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::runtime::{BotRuntime, BotRuntimeRepository};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// One page of the bot list, each bot paired with its OBSERVED runtime row
+/// (`None` when the bot never ran). `next_cursor` as in `BotPage`.
+#[derive(Debug, Clone)]
+pub struct BotListPage {
+    pub bots: Vec<(Bot, Option<BotRuntime>)>,
+    pub next_cursor: Option<String>,
+}
+
+/// The bot list with each bot's run state, for the "List" views. Reads the
+/// bots and all of the user's runtime rows with one query each, side by side,
+/// instead of one runtime read per bot.
+pub struct ListBotsWithRuntimeUseCase {
+    bots: Arc<dyn BotRepository>,
+    runtimes: Arc<dyn BotRuntimeRepository>,
+}
+
+impl ListBotsWithRuntimeUseCase {
+    pub fn new(bots: Arc<dyn BotRepository>, runtimes: Arc<dyn BotRuntimeRepository>) -> Self {
+        Self { bots, runtimes }
+    }
+
+    /// One page of `page_size` bots in id order, resuming after `after`.
+    pub async fn page(
+        &self,
+        user_id: &str,
+        after: Option<&str>,
+        page_size: usize,
+    ) -> Result<BotListPage, String> {
+        let (page, runtimes) = tokio::try_join!(
+            self.bots.find_page_by_user_id(user_id, after, page_size),
+            self.runtimes.find_all_for_user(user_id),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut by_bot: HashMap<String, BotRuntime> = runtimes
+            .into_iter()
+            .map(|r| (r.bot_id.clone(), r))
+            .collect();
+        Ok(BotListPage {
+            bots: page
+                .bots
+                .into_iter()
+                .map(|bot| {
+                    let runtime = by_bot.remove(&bot.id);
+                    (bot, runtime)
+                })
+                .collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::error::DomainError;
+    use crate::domain::runtime::RuntimePhase;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct Bots(Vec<Bot>);
+
+    #[async_trait]
+    impl BotRepository for Bots {
+        async fn find(&self, _user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+            Ok(self.0.iter().find(|b| b.id == bot_id).cloned())
+        }
+        async fn save(&self, _bot: &Bot) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|b| b.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Counts per-bot reads, which the list must not make.
+    struct Runtimes {
+        rows: Vec<BotRuntime>,
+        single_reads: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl BotRuntimeRepository for Runtimes {
+        async fn find(
+            &self,
+            user_id: &str,
+            bot_id: &str,
+        ) -> Result<Option<BotRuntime>, DomainError> {
+            *self.single_reads.lock().unwrap() += 1;
+            Ok(self
+                .rows
+                .iter()
+                .find(|r| r.user_id == user_id && r.bot_id == bot_id)
+                .cloned())
+        }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .rows
+                .iter()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn record(&self, _runtime: &BotRuntime) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    fn bot(user_id: &str, id: &str) -> Bot {
+        Bot::create(user_id.into(), id.into(), "ak".into(), "sk".into(), 0)
+    }
+
+    #[tokio::test]
+    async fn pairs_each_bot_with_its_own_runtime() {
+        let bots = Arc::new(Bots(vec![bot("u", "a"), bot("u", "b"), bot("u", "c")]));
+        let runtimes = Arc::new(Runtimes {
+            rows: vec![
+                BotRuntime::running("u".into(), "c".into(), "task-c".into(), 1, 10),
+                BotRuntime::stopped("u".into(), "a".into(), 2, 20),
+                // Another user's bot of the same id must not leak in.
+                BotRuntime::running("v".into(), "b".into(), "task-v".into(), 1, 10),
+            ],
+            single_reads: Mutex::new(0),
+        });
+        let usecase = ListBotsWithRuntimeUseCase::new(bots, runtimes.clone());
+
+        let page = usecase.page("u", None, 2).await.unwrap();
+        let phases: Vec<(&str, Option<RuntimePhase>)> = page
+            .bots
+            .iter()
+            .map(|(b, r)| (b.id.as_str(), r.as_ref().map(|r| r.phase.clone())))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![("a", Some(RuntimePhase::Stopped)), ("b", None)]
+        );
+        assert_eq!(page.next_cursor.as_deref(), Some("b"));
+
+        let page = usecase.page("u", Some("b"), 2).await.unwrap();
+        assert_eq!(page.bots.len(), 1);
+        assert_eq!(
+            page.bots[0].1.as_ref().unwrap().task_id.as_deref(),
+            Some("task-c")
+        );
+        assert_eq!(page.next_cursor, None);
+        assert_eq!(*runtimes.single_reads.lock().unwrap(), 0);
+    }
+}
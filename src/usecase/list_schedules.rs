@@ -0,0 +1,23 @@
+use crate::domain::schedule::{Schedule, ScheduleRepository};
+use std::sync::Arc;
+
+pub struct ListSchedulesUseCase {
+    schedules: Arc<dyn ScheduleRepository>,
+}
+
+impl ListSchedulesUseCase {
+    pub fn new(schedules: Arc<dyn ScheduleRepository>) -> Self {
+        Self { schedules }
+    }
+
+    /// The user's schedules, soonest first.
+    pub async fn execute(&self, user_id: &str) -> Result<Vec<Schedule>, String> {
+        let mut schedules = self
+            .schedules
+            .find_by_user(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        schedules.sort_by_key(|s| s.next_run_at);
+        Ok(schedules)
+    }
+}
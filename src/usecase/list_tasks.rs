@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_sdk_ecs::types::DesiredStatus;
+
+/// DescribeTasks accepts at most 100 task ids per call.
+const DESCRIBE_BATCH: usize = 100;
+
+/// A task ECS still intends to keep running, with the bot identity read back
+/// from the `USER_ID`/`BOT_ID` overrides `RunTaskUseCase` injects. Either id is
+/// `None` for a task launched outside the bot (or with its overrides stripped).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveTask {
+    pub task_id: String,
+    pub user_id: Option<String>,
+    pub bot_id: Option<String>,
+}
+
+/// Port for enumerating the live tasks of a cluster. Lets the sweeper depend
+/// on an abstraction (testable with a mock) rather than the concrete ECS client.
+#[async_trait]
+pub trait TaskInventory: Send + Sync {
+    /// Every task whose desired status is RUNNING. A task that has been asked
+    /// to stop is excluded even while its containers are still draining.
+    async fn list_running(&self, cluster_arn: &str) -> Result<Vec<LiveTask>>;
+}
+
+pub struct EcsTaskInventory {
+    ecs_client: aws_sdk_ecs::Client,
+}
+
+impl EcsTaskInventory {
+    pub fn new(client: aws_sdk_ecs::Client) -> Self {
+        Self { ecs_client: client }
+    }
+
+    async fn list_task_arns(&self, cluster_arn: &str) -> Result<Vec<String>> {
+        let mut arns = Vec::new();
+        let mut next_token = None;
+        loop {
+            let resp = self
+                .ecs_client
+                .list_tasks()
+                .cluster(cluster_arn)
+                .desired_status(DesiredStatus::Running)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .context("ecs list_tasks failed")?;
+            arns.extend(resp.task_arns().iter().cloned());
+            match resp.next_token() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => return Ok(arns),
+            }
+        }
+    }
+}
+
+fn task_id_from_arn(task_arn: &str) -> &str {
+    task_arn.rsplit('/').next().unwrap_or(task_arn)
+}
+
+#[async_trait]
+impl TaskInventory for EcsTaskInventory {
+    async fn list_running(&self, cluster_arn: &str) -> Result<Vec<LiveTask>> {
+        let arns = self.list_task_arns(cluster_arn).await?;
+        let mut tasks = Vec::with_capacity(arns.len());
+        for batch in arns.chunks(DESCRIBE_BATCH) {
+            let resp = self
+                .ecs_client
+                .describe_tasks()
+                .cluster(cluster_arn)
+                .set_tasks(Some(batch.to_vec()))
+                .send()
+                .await
+                .context("ecs describe_tasks failed")?;
+            for task in resp.tasks() {
+                let Some(arn) = task.task_arn() else {
+                    continue;
+                };
+                let mut live = LiveTask {
+                    task_id: task_id_from_arn(arn).to_string(),
+                    user_id: None,
+                    bot_id: None,
+                };
+                // Same rule as the state-change Lambda: a name-only sidecar
+                // override can sort ahead of the passivbot container, so scan
+                // every override for the ids.
+                let overrides = task.overrides().map(|o| o.container_overrides());
+                for env in overrides
+                    .unwrap_or_default()
+                    .iter()
+                    .flat_map(|co| co.environment())
+                {
+                    let value = env.value().map(str::to_string);
+                    match env.name().unwrap_or("") {
+                        "USER_ID" | "user_id" => live.user_id = value,
+                        "BOT_ID" | "bot_id" => live.bot_id = value,
+                        _ => {}
+                    }
+                }
+                tasks.push(live);
+            }
+        }
+        Ok(tasks)
+    }
+}
@@ -1,33 +1,100 @@
 mod add_bot;
+mod api_token;
 mod apply_template;
+mod approved_coins;
+mod bulk_bot_action;
+mod create_schedule;
+mod daily_digest;
 mod delete_bot;
+mod delete_schedule;
+mod digest_hour;
+mod evaluate_risk_guards;
 mod get_bot_config;
+mod get_bot_history;
 mod get_bot_runtime;
+mod get_performance;
+mod get_risk_presets;
+mod import_template;
 mod list_bots;
+mod list_bots_with_runtime;
+mod list_schedules;
+mod list_tasks;
 mod list_templates;
+mod panic;
+mod positions;
 mod reconcile_stopped_task;
 mod record_running_task;
+mod refresh_instruments;
+mod release_start_lock;
+mod risk_guard;
+mod run_due_schedules;
 mod run_task;
+mod set_exposure_ceiling;
+mod set_leverage_policy;
+mod set_resource_profile;
+mod set_restart_policy;
 mod set_strategy_side;
 mod start_bot;
 mod stop_bot;
 mod stop_task;
+mod sweep_runtimes;
 mod update_bot_config;
 mod update_risklevel;
 
 pub use add_bot::{AddBotUseCase, AddOutcome};
+pub use api_token::{AuthenticateApiTokenUseCase, IssueApiTokenUseCase, RevokeApiTokenUseCase};
 pub use apply_template::ApplyTemplateUseCase;
+pub use approved_coins::{
+    ApprovedCoins, CoinListing, GetApprovedCoinsUseCase, SetApprovedCoinsUseCase,
+};
+pub use bulk_bot_action::{
+    BULK_MAX_CONCURRENCY, BulkAction, BulkBotActionUseCase, BulkBotResult, BulkOutcome, BulkReport,
+};
+pub use create_schedule::CreateScheduleUseCase;
+pub use daily_digest::{
+    BotDigest, BuildDailyDigestUseCase, DailyDigest, DigestRun, SendDueDigestsUseCase,
+};
 pub use delete_bot::DeleteBotUseCase;
+pub use delete_schedule::DeleteScheduleUseCase;
+pub use digest_hour::{GetPreferencesUseCase, SetDigestHourUseCase};
+pub use evaluate_risk_guards::{EvaluateRiskGuardsUseCase, GuardRun};
 pub use get_bot_config::GetBotConfigUseCase;
+pub use get_bot_history::{BOT_HISTORY_VIEW_LIMIT, GetBotHistoryUseCase};
 pub use get_bot_runtime::GetBotRuntimeUseCase;
+pub use get_performance::{
+    GetPerformanceUseCase, PERFORMANCE_RECENT_FILLS, PerformanceReport, PnlSummary,
+};
+pub use get_risk_presets::GetRiskPresetsUseCase;
+pub use import_template::ImportTemplateUseCase;
 pub use list_bots::ListBotsUseCase;
+pub use list_bots_with_runtime::{BotListPage, ListBotsWithRuntimeUseCase};
+pub use list_schedules::ListSchedulesUseCase;
+pub use list_tasks::{EcsTaskInventory, LiveTask, TaskInventory};
 pub use list_templates::ListTemplatesUseCase;
-pub use reconcile_stopped_task::{ReconcileOutcome, ReconcileStoppedTaskUseCase, StopInfo};
+pub use panic::{PanicBotResult, PanicReport, PanicUseCase};
+pub use positions::{
+    CancelOrderOutcome, CancelOrderUseCase, ClosePositionOutcome, ClosePositionUseCase,
+    GetPositionsUseCase, PositionsReport,
+};
+pub use reconcile_stopped_task::{ReconcileOutcome, ReconcileStoppedTaskUseCase};
 pub use record_running_task::{RecordRunningOutcome, RecordRunningTaskUseCase};
+pub use refresh_instruments::RefreshInstrumentCatalogUseCase;
+pub use release_start_lock::{ReleaseLockOutcome, ReleaseStartLockUseCase};
+pub use risk_guard::{
+    GetRiskGuardUseCase, GuardLimit, RearmRiskGuardUseCase, SetRiskGuardLimitUseCase,
+};
+pub use run_due_schedules::{
+    RunDueSchedulesUseCase, ScheduleActionRunner, ScheduleRun, UseCaseActionRunner,
+};
 pub use run_task::{RunTaskUseCase, TaskRunner};
+pub use set_exposure_ceiling::SetExposureCeilingUseCase;
+pub use set_leverage_policy::SetLeveragePolicyUseCase;
+pub use set_resource_profile::SetResourceProfileUseCase;
+pub use set_restart_policy::SetRestartPolicyUseCase;
 pub use set_strategy_side::SetStrategySideUseCase;
-pub use start_bot::{StartBotUseCase, StartOutcome};
+pub use start_bot::{START_LOCK_STALE_AFTER_SECS, StartBotUseCase, StartOutcome};
 pub use stop_bot::{StopBotUseCase, StopOutcome};
-pub use stop_task::{EcsTaskController, TaskController};
+pub use stop_task::{EcsTaskController, TaskController, TaskLiveness};
+pub use sweep_runtimes::{SweepAction, SweepRuntimesUseCase, SweepSummary};
 pub use update_bot_config::UpdateBotConfigUseCase;
-pub use update_risklevel::UpdateRiskLevelUseCase;
+pub use update_risklevel::{RiskPreview, UpdateRiskLevelUseCase};
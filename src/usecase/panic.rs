@@ -0,0 +1,437 @@
+use crate::domain::bot::Bot;
+use crate::domain::botconfig::BotConfigRepository;
+use crate::domain::clock::Clock;
+use crate::domain::schedule::ScheduleAction;
+use crate::domain::trading::{ExchangeTradingGateway, Position};
+use crate::usecase::bulk_bot_action::run_bounded;
+use crate::usecase::{ListBotsUseCase, ScheduleActionRunner};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the positions are re-read after the closing orders went out,
+/// before a bot is reported as not flat.
+const SETTLE_CHECKS: usize = 3;
+const SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+/// What the kill switch did to one bot. Every step is attempted even when an
+/// earlier one failed: flattening matters more than a clean config write.
+#[derive(Debug, Clone)]
+pub struct PanicBotResult {
+    pub bot_id: String,
+    pub bot_name: String,
+    /// `forced_mode_long/short = panic` written to the bot config.
+    pub config: Result<(), String>,
+    /// Outcome of stopping the task (desired state off).
+    pub stop: Result<String, String>,
+    /// Number of closing orders placed after cancelling all open orders.
+    pub flatten: Result<usize, String>,
+    /// Positions still open at the end; `Err` when they could not be read.
+    pub remaining: Result<Vec<Position>, String>,
+}
+
+impl PanicBotResult {
+    /// `Some(true)` only when the exchange confirmed there are no positions.
+    pub fn is_flat(&self) -> Option<bool> {
+        self.remaining.as_ref().ok().map(|p| p.is_empty())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+    pub results: Vec<PanicBotResult>,
+}
+
+impl PanicReport {
+    pub fn all_flat(&self) -> bool {
+        self.results.iter().all(|r| r.is_flat() == Some(true))
+    }
+}
+
+/// Emergency kill switch over all of a user's bots. Per bot, in order:
+/// 1. force both sides to `panic` in the config, so any task that does get
+///    started (auto-restart, a racing Run) only flattens;
+/// 2. stop the task through the regular stop path (desired state off), so
+///    passivbot cannot re-enter while we close;
+/// 3. cancel every open order and market-close every position through the
+///    exchange port;
+/// 4. re-read the positions and report flat / not flat.
+///
+/// Closing directly instead of restarting passivbot in panic mode keeps the
+/// window between the decision and a flat book to a few API calls.
+pub struct PanicUseCase {
+    list_bots: Arc<ListBotsUseCase>,
+    bot_configs: Arc<dyn BotConfigRepository>,
+    runner: Arc<dyn ScheduleActionRunner>,
+    gateway: Arc<dyn ExchangeTradingGateway>,
+    clock: Arc<dyn Clock>,
+    max_concurrency: usize,
+}
+
+impl PanicUseCase {
+    pub fn new(
+        list_bots: Arc<ListBotsUseCase>,
+        bot_configs: Arc<dyn BotConfigRepository>,
+        runner: Arc<dyn ScheduleActionRunner>,
+        gateway: Arc<dyn ExchangeTradingGateway>,
+        clock: Arc<dyn Clock>,
+        max_concurrency: usize,
+    ) -> Self {
+        Self {
+            list_bots,
+            bot_configs,
+            runner,
+            gateway,
+            clock,
+            max_concurrency,
+        }
+    }
+
+    pub async fn execute(&self, user_id: &str) -> Result<PanicReport, String> {
+        let bots = self.list_bots.execute(user_id).await?;
+        let names: Vec<(String, String)> = bots
+            .iter()
+            .map(|b| (b.id.clone(), b.name.clone()))
+            .collect();
+
+        let results = run_bounded(bots, self.max_concurrency, |bot| {
+            let bot_configs = self.bot_configs.clone();
+            let runner = self.runner.clone();
+            let gateway = self.gateway.clone();
+            let now = self.clock.now();
+            async move {
+                panic_one(
+                    &bot,
+                    bot_configs.as_ref(),
+                    runner.as_ref(),
+                    gateway.as_ref(),
+                    now,
+                )
+                .await
+            }
+        })
+        .await;
+
+        let results = names
+            .into_iter()
+            .zip(results)
+            .map(|((bot_id, bot_name), result)| {
+                result.unwrap_or_else(|| {
+                    let failed = "internal error".to_string();
+                    PanicBotResult {
+                        bot_id,
+                        bot_name,
+                        config: Err(failed.clone()),
+                        stop: Err(failed.clone()),
+                        flatten: Err(failed.clone()),
+                        remaining: Err(failed),
+                    }
+                })
+            })
+            .collect();
+        Ok(PanicReport { results })
+    }
+}
+
+async fn panic_one(
+    bot: &Bot,
+    bot_configs: &dyn BotConfigRepository,
+    runner: &dyn ScheduleActionRunner,
+    gateway: &dyn ExchangeTradingGateway,
+    now: i64,
+) -> PanicBotResult {
+    let config = async {
+        let mut config = bot_configs.get(&bot.user_id, &bot.id).await?;
+        config.set_panic(now).map_err(|e| e.to_string())?;
+        bot_configs.save(&config).await
+    }
+    .await;
+
+    let stop = runner
+        .run(&bot.user_id, &bot.id, &ScheduleAction::Stop)
+        .await;
+
+    let flatten = async {
+        gateway
+            .cancel_all_orders(bot)
+            .await
+            .map_err(|e| e.to_string())?;
+        let positions = gateway.positions(bot).await.map_err(|e| e.to_string())?;
+        let mut errors = Vec::new();
+        for p in &positions {
+            if let Err(e) = gateway.close_position(bot, p).await {
+                errors.push(format!("{} {}: {e}", p.symbol, p.side.as_str()));
+            }
+        }
+        if errors.is_empty() {
+            Ok(positions.len())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+    .await;
+
+    // Market orders fill almost immediately; only wait for them when every
+    // close was accepted, otherwise the leftovers are already known.
+    let checks = if flatten.is_ok() { SETTLE_CHECKS } else { 1 };
+    let mut remaining = Err("not checked".to_string());
+    for attempt in 0..checks {
+        if attempt > 0 {
+            tokio::time::sleep(SETTLE_DELAY).await;
+        }
+        remaining = gateway.positions(bot).await.map_err(|e| e.to_string());
+        if matches!(&remaining, Ok(p) if p.is_empty()) {
+            break;
+        }
+    }
+
+    PanicBotResult {
+        bot_id: bot.id.clone(),
+        bot_name: bot.name.clone(),
+        config,
+        stop,
+        flatten,
+        remaining,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::BotRepository;
+    use crate::domain::botconfig::{BotConfig, BotType};
+    use crate::domain::error::DomainError;
+    use crate::domain::trading::{Order, PositionSide};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    struct FixedClock;
+    impl Clock for FixedClock {
+        fn now(&self) -> i64 {
+            1_700_000_000
+        }
+    }
+
+    struct FixedBots {
+        bots: Vec<Bot>,
+    }
+    #[async_trait]
+    impl BotRepository for FixedBots {
+        async fn find(&self, _user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+            Ok(self.bots.iter().find(|b| b.id == bot_id).cloned())
+        }
+        async fn save(&self, _bot: &Bot) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn find_by_user_id(&self, _user_id: &str) -> Result<Vec<Bot>, DomainError> {
+            Ok(self.bots.clone())
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Configs keyed by bot id; a bot without an entry has no config.
+    #[derive(Default)]
+    struct InMemoryConfigs {
+        configs: Mutex<HashMap<String, BotConfig>>,
+    }
+    #[async_trait]
+    impl BotConfigRepository for InMemoryConfigs {
+        async fn get(&self, _user_id: &str, bot_id: &str) -> Result<BotConfig, String> {
+            self.configs
+                .lock()
+                .unwrap()
+                .get(bot_id)
+                .cloned()
+                .ok_or_else(|| format!("no config for {bot_id}"))
+        }
+        async fn save(&self, config: &BotConfig) -> Result<(), String> {
+            self.configs
+                .lock()
+                .unwrap()
+                .insert(config.bot_id.clone(), config.clone());
+            Ok(())
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+        async fn exists(&self, _user_id: &str, bot_id: &str) -> Result<bool, String> {
+            Ok(self.configs.lock().unwrap().contains_key(bot_id))
+        }
+    }
+
+    #[derive(Default)]
+    struct StopRecorder {
+        stopped: Mutex<Vec<String>>,
+    }
+    #[async_trait]
+    impl ScheduleActionRunner for StopRecorder {
+        async fn run(
+            &self,
+            _user_id: &str,
+            bot_id: &str,
+            action: &ScheduleAction,
+        ) -> Result<String, String> {
+            assert_eq!(*action, ScheduleAction::Stop);
+            self.stopped.lock().unwrap().push(bot_id.to_string());
+            Ok("stopped".to_string())
+        }
+    }
+
+    /// Fake exchange: positions per bot id; closing removes the position,
+    /// except on symbols listed in `reject_close`.
+    #[derive(Default)]
+    struct FakeExchange {
+        positions: Mutex<HashMap<String, Vec<Position>>>,
+        reject_close: Vec<String>,
+        cancelled: Mutex<Vec<String>>,
+    }
+    #[async_trait]
+    impl ExchangeTradingGateway for FakeExchange {
+        async fn positions(&self, bot: &Bot) -> Result<Vec<Position>, DomainError> {
+            Ok(self
+                .positions
+                .lock()
+                .unwrap()
+                .get(&bot.id)
+                .cloned()
+                .unwrap_or_default())
+        }
+        async fn open_orders(&self, _bot: &Bot) -> Result<Vec<Order>, DomainError> {
+            Ok(Vec::new())
+        }
+        async fn cancel_order(&self, _bot: &Bot, _order: &Order) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn cancel_all_orders(&self, bot: &Bot) -> Result<(), DomainError> {
+            self.cancelled.lock().unwrap().push(bot.id.clone());
+            Ok(())
+        }
+        async fn close_position(&self, bot: &Bot, position: &Position) -> Result<(), DomainError> {
+            if self.reject_close.contains(&position.symbol) {
+                return Err(DomainError::Exchange("reduce-only rejected".into()));
+            }
+            if let Some(list) = self.positions.lock().unwrap().get_mut(&bot.id) {
+                list.retain(|p| p != position);
+            }
+            Ok(())
+        }
+    }
+
+    fn bot(id: &str) -> Bot {
+        Bot::create("u".into(), id.into(), "ak".into(), "sk".into(), 0)
+    }
+
+    fn config(bot_id: &str) -> BotConfig {
+        BotConfig {
+            user_id: "u".into(),
+            bot_id: bot_id.into(),
+            bot_type: BotType::Passivbot,
+            template_name: "t".into(),
+            template_version: None,
+            config_data: json!({ "live": { "forced_mode_long": "", "forced_mode_short": "" } }),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn position(symbol: &str, side: PositionSide) -> Position {
+        Position {
+            symbol: symbol.into(),
+            side,
+            size: 1.0,
+            entry_price: 10.0,
+            mark_price: 9.0,
+            unrealized_pnl: -1.0,
+            liq_price: None,
+            position_idx: match side {
+                PositionSide::Long => 1,
+                PositionSide::Short => 2,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn flattens_and_stops_every_bot() {
+        let configs = Arc::new(InMemoryConfigs::default());
+        for id in ["a", "b"] {
+            configs
+                .configs
+                .lock()
+                .unwrap()
+                .insert(id.into(), config(id));
+        }
+        let exchange = Arc::new(FakeExchange::default());
+        exchange.positions.lock().unwrap().insert(
+            "a".into(),
+            vec![
+                position("BTCUSDT", PositionSide::Long),
+                position("ETHUSDT", PositionSide::Short),
+            ],
+        );
+        let runner = Arc::new(StopRecorder::default());
+        let uc = PanicUseCase::new(
+            Arc::new(ListBotsUseCase::new(Arc::new(FixedBots {
+                bots: vec![bot("a"), bot("b")],
+            }))),
+            configs.clone(),
+            runner.clone(),
+            exchange.clone(),
+            Arc::new(FixedClock),
+            4,
+        );
+
+        let report = uc.execute("u").await.unwrap();
+
+        assert!(report.all_flat());
+        assert_eq!(report.results[0].flatten, Ok(2));
+        assert_eq!(report.results[1].flatten, Ok(0));
+        let mut stopped = runner.stopped.lock().unwrap().clone();
+        stopped.sort();
+        assert_eq!(stopped, ["a", "b"]);
+        assert_eq!(exchange.cancelled.lock().unwrap().len(), 2);
+        let saved = configs.configs.lock().unwrap();
+        assert_eq!(saved["a"].config_data["live"]["forced_mode_long"], "panic");
+        assert_eq!(saved["b"].config_data["live"]["forced_mode_short"], "panic");
+    }
+
+    #[tokio::test]
+    async fn reports_not_flat_and_still_acts_when_steps_fail() {
+        // no config for "a", and the exchange rejects closing its SOL position
+        let configs = Arc::new(InMemoryConfigs::default());
+        let exchange = Arc::new(FakeExchange {
+            reject_close: vec!["SOLUSDT".into()],
+            ..Default::default()
+        });
+        exchange.positions.lock().unwrap().insert(
+            "a".into(),
+            vec![
+                position("SOLUSDT", PositionSide::Long),
+                position("BTCUSDT", PositionSide::Long),
+            ],
+        );
+        let runner = Arc::new(StopRecorder::default());
+        let uc = PanicUseCase::new(
+            Arc::new(ListBotsUseCase::new(Arc::new(FixedBots {
+                bots: vec![bot("a")],
+            }))),
+            configs,
+            runner.clone(),
+            exchange,
+            Arc::new(FixedClock),
+            4,
+        );
+
+        let report = uc.execute("u").await.unwrap();
+        let r = &report.results[0];
+
+        assert!(r.config.is_err());
+        assert_eq!(r.stop.as_deref(), Ok("stopped"));
+        assert!(r.flatten.as_ref().unwrap_err().contains("SOLUSDT long"));
+        assert_eq!(r.is_flat(), Some(false));
+        assert_eq!(r.remaining.as_ref().unwrap()[0].symbol, "SOLUSDT");
+        assert!(!report.all_flat());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::clock::Clock;
+use crate::domain::trading::{ExchangeTradingGateway, Order, Position, PositionSide};
+use std::sync::Arc;
+
+/// What is open on a bot's exchange account right now.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionsReport {
+    /// Unix seconds.
+    pub as_of: i64,
+    pub positions: Vec<Position>,
+    pub orders: Vec<Order>,
+}
+
+/// Outcome of a manual close.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClosePositionOutcome {
+    /// The reduce-only market order for the position as it stood went out.
+    Closed(Position),
+    /// Nothing open on that symbol and side any more.
+    NotOpen,
+    BotNotFound,
+}
+
+/// Outcome of a manual order cancel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CancelOrderOutcome {
+    Cancelled(Order),
+    /// The order filled or was cancelled meanwhile.
+    NotOpen,
+    BotNotFound,
+}
+
+async fn find_bot(
+    bots: &dyn BotRepository,
+    user_id: &str,
+    bot_id: &str,
+) -> Result<Option<Bot>, String> {
+    bots.find(user_id, bot_id).await.map_err(|e| e.to_string())
+}
+
+/// Reads a bot's open positions and resting orders from its exchange
+/// sub-account.
+pub struct GetPositionsUseCase {
+    bots: Arc<dyn BotRepository>,
+    gateway: Arc<dyn ExchangeTradingGateway>,
+    clock: Arc<dyn Clock>,
+}
+
+impl GetPositionsUseCase {
+    pub fn new(
+        bots: Arc<dyn BotRepository>,
+        gateway: Arc<dyn ExchangeTradingGateway>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            bots,
+            gateway,
+            clock,
+        }
+    }
+
+    /// `None` when the user has no bot `bot_id`.
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+    ) -> Result<Option<PositionsReport>, String> {
+        let Some(bot) = find_bot(self.bots.as_ref(), user_id, bot_id).await? else {
+            return Ok(None);
+        };
+        let (positions, orders) =
+            tokio::try_join!(self.gateway.positions(&bot), self.gateway.open_orders(&bot))
+                .map_err(|e| e.to_string())?;
+        Ok(Some(PositionsReport {
+            as_of: self.clock.now(),
+            positions,
+            orders,
+        }))
+    }
+}
+
+/// Closes one of a bot's positions with a reduce-only market order. The
+/// position is read again first, so the order is sized to what is open now
+/// rather than to what the user saw when they tapped.
+///
+/// The passivbot task keeps running: unless its side is stopped it may open
+/// the position again.
+pub struct ClosePositionUseCase {
+    bots: Arc<dyn BotRepository>,
+    gateway: Arc<dyn ExchangeTradingGateway>,
+}
+
+impl ClosePositionUseCase {
+    pub fn new(bots: Arc<dyn BotRepository>, gateway: Arc<dyn ExchangeTradingGateway>) -> Self {
+        Self { bots, gateway }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        symbol: &str,
+        side: PositionSide,
+    ) -> Result<ClosePositionOutcome, String> {
+        let Some(bot) = find_bot(self.bots.as_ref(), user_id, bot_id).await? else {
+            return Ok(ClosePositionOutcome::BotNotFound);
+        };
+        let position = self
+            .gateway
+            .positions(&bot)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|p| p.symbol == symbol && p.side == side);
+        let Some(position) = position else {
+            return Ok(ClosePositionOutcome::NotOpen);
+        };
+        self.gateway
+            .close_position(&bot, &position)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(ClosePositionOutcome::Closed(position))
+    }
+}
+
+/// Cancels one of a bot's resting orders by its exchange id.
+pub struct CancelOrderUseCase {
+    bots: Arc<dyn BotRepository>,
+    gateway: Arc<dyn ExchangeTradingGateway>,
+}
+
+impl CancelOrderUseCase {
+    pub fn new(bots: Arc<dyn BotRepository>, gateway: Arc<dyn ExchangeTradingGateway>) -> Self {
+        Self { bots, gateway }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        order_id: &str,
+    ) -> Result<CancelOrderOutcome, String> {
+        let Some(bot) = find_bot(self.bots.as_ref(), user_id, bot_id).await? else {
+            return Ok(CancelOrderOutcome::BotNotFound);
+        };
+        // The cancel needs the symbol too; the id alone fits a callback.
+        let order = self
+            .gateway
+            .open_orders(&bot)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|o| o.order_id == order_id);
+        let Some(order) = order else {
+            return Ok(CancelOrderOutcome::NotOpen);
+        };
+        self.gateway
+            .cancel_order(&bot, &order)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(CancelOrderOutcome::Cancelled(order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::account::OrderSide;
+    use crate::domain::clock::MockClock;
+    use crate::infra::memory::{InMemoryBotRepository, InMemoryTradingGateway};
+
+    async fn setup() -> (Arc<InMemoryBotRepository>, Arc<InMemoryTradingGateway>) {
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let bot = Bot::create("u".into(), "grid-1".into(), "ak".into(), "sk".into(), 0);
+        BotRepository::save(repo.as_ref(), &bot).await.unwrap();
+        let exchange = Arc::new(InMemoryTradingGateway::new());
+        exchange.open(
+            "grid-1",
+            Position {
+                symbol: "BTCUSDT".into(),
+                side: PositionSide::Long,
+                size: 0.02,
+                entry_price: 37000.0,
+                mark_price: 37500.0,
+                unrealized_pnl: 10.0,
+                liq_price: Some(33500.0),
+                position_idx: 1,
+            },
+        );
+        exchange.place(
+            "grid-1",
+            Order {
+                order_id: "o-1".into(),
+                symbol: "BTCUSDT".into(),
+                side: OrderSide::Buy,
+                price: 36500.0,
+                qty: 0.01,
+                reduce_only: false,
+            },
+        );
+        (repo, exchange)
+    }
+
+    #[tokio::test]
+    async fn closing_reads_the_position_again_and_only_closes_it_once() {
+        let (repo, exchange) = setup().await;
+        let get = GetPositionsUseCase::new(
+            repo.clone(),
+            exchange.clone(),
+            Arc::new(MockClock::new(1_700_000_000)),
+        );
+        let close = ClosePositionUseCase::new(repo.clone(), exchange.clone());
+
+        let report = get.execute("u", "grid-1").await.unwrap().unwrap();
+        assert_eq!(report.positions.len(), 1);
+        assert_eq!(report.orders.len(), 1);
+
+        assert_eq!(
+            close
+                .execute("u", "grid-1", "BTCUSDT", PositionSide::Short)
+                .await
+                .unwrap(),
+            ClosePositionOutcome::NotOpen
+        );
+        let closed = close
+            .execute("u", "grid-1", "BTCUSDT", PositionSide::Long)
+            .await
+            .unwrap();
+        assert!(matches!(closed, ClosePositionOutcome::Closed(p) if p.size == 0.02));
+        assert_eq!(
+            close
+                .execute("u", "grid-1", "BTCUSDT", PositionSide::Long)
+                .await
+                .unwrap(),
+            ClosePositionOutcome::NotOpen
+        );
+        assert_eq!(
+            close
+                .execute("u", "other", "BTCUSDT", PositionSide::Long)
+                .await
+                .unwrap(),
+            ClosePositionOutcome::BotNotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelling_looks_the_order_up_by_id() {
+        let (repo, exchange) = setup().await;
+        let cancel = CancelOrderUseCase::new(repo, exchange.clone());
+
+        let outcome = cancel.execute("u", "grid-1", "o-1").await.unwrap();
+        assert!(matches!(outcome, CancelOrderOutcome::Cancelled(o) if o.symbol == "BTCUSDT"));
+        assert_eq!(
+            cancel.execute("u", "grid-1", "o-1").await.unwrap(),
+            CancelOrderOutcome::NotOpen
+        );
+    }
+}
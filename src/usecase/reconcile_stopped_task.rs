@@ -1,36 +1,47 @@
-use crate::domain::bot::BotRepository;
-use crate::domain::runtime::{BotRuntime, BotRuntimeRepository, StartClaim, StartLockRepository};
+use crate::domain::bot::{Bot, BotRepository};
+use crate::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+use crate::domain::metrics::{self, Metrics};
+use crate::domain::restart::{RESTART_STABLE_AFTER_SECS, StopCause, StopReport};
+use crate::domain::runtime::{
+    BotRuntime, BotRuntimeRepository, RuntimePhase, StartClaim, StartLockRepository,
+};
 use crate::usecase::run_task::TaskRunner;
 use crate::usecase::stop_task::TaskController;
 use anyhow::Result;
 use std::sync::Arc;
 
-/// Why a task stopped (parsed from the ECS event by the Lambda).
-pub struct StopInfo {
-    pub exit_code: i32,
-    pub stop_code: String,
-}
-impl StopInfo {
-    pub fn is_memory_related(&self) -> bool {
-        self.exit_code == 137 && !self.stop_code.contains("UserInitiated")
-    }
-}
-
 #[derive(Debug, PartialEq, Eq)]
 pub enum ReconcileOutcome {
-    Restarted { task_id: String },
-    SkippedNotEnabled, // user intent is OFF -> do NOT restart
-    SkippedNotMemoryRelated,
+    Restarted { task_id: String, attempt: u32 },
+    SkippedNotEnabled,                    // user intent is OFF -> do NOT restart
+    SkippedByPolicy,                      // the bot's restart policy does not cover this stop cause
+    SkippedMaxAttempts { attempts: u32 }, // crash loop: the policy's budget is spent
     SkippedSuperseded, // the stopped task is no longer current (e.g. duplicate STOPPED)
     BotNotFound,
 }
 
+impl ReconcileOutcome {
+    /// The `outcome` label of [`metrics::TASK_STOPPED_EVENTS`].
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Restarted { .. } => "restarted",
+            Self::SkippedNotEnabled => "skipped_not_enabled",
+            Self::SkippedByPolicy => "skipped_by_policy",
+            Self::SkippedMaxAttempts { .. } => "skipped_max_attempts",
+            Self::SkippedSuperseded => "skipped_superseded",
+            Self::BotNotFound => "bot_not_found",
+        }
+    }
+}
+
 pub struct ReconcileStoppedTaskUseCase {
     bots: Arc<dyn BotRepository>,
     runtimes: Arc<dyn BotRuntimeRepository>,
     locks: Arc<dyn StartLockRepository>,
     run_task: Arc<dyn TaskRunner>,
     stopper: Arc<dyn TaskController>,
+    history: Arc<dyn BotHistoryRepository>,
+    metrics: Arc<dyn Metrics>,
 }
 
 impl ReconcileStoppedTaskUseCase {
@@ -40,6 +51,8 @@ impl ReconcileStoppedTaskUseCase {
         locks: Arc<dyn StartLockRepository>,
         run_task: Arc<dyn TaskRunner>,
         stopper: Arc<dyn TaskController>,
+        history: Arc<dyn BotHistoryRepository>,
+        metrics: Arc<dyn Metrics>,
     ) -> Self {
         Self {
             bots,
@@ -47,6 +60,8 @@ impl ReconcileStoppedTaskUseCase {
             locks,
             run_task,
             stopper,
+            history,
+            metrics,
         }
     }
 
@@ -55,7 +70,15 @@ impl ReconcileStoppedTaskUseCase {
     /// repository's monotonic conditional write orders events consistently. `now`
     /// is fresh wall-clock seconds and stamps the start lock, so a just-claimed
     /// restart lock can never already look stale to a concurrent telebot start.
+    /// `stop` is the event's full report; its classified cause is matched
+    /// against the bot's restart policy. The stop and any restart are appended
+    /// to the bot's history, and counted by outcome and cause.
     #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(
+        name = "reconcile_stopped",
+        skip_all,
+        fields(user_id = %user_id, bot_id = %bot_id, task_id = %stopped_task_id)
+    )]
     pub async fn execute(
         &self,
         user_id: &str,
@@ -64,19 +87,192 @@ impl ReconcileStoppedTaskUseCase {
         cluster_arn: &str,
         td_arn: &str,
         container_name: &str,
-        stop: StopInfo,
+        stop: &StopReport,
+        observed_at: i64,
+        now: i64,
+    ) -> Result<ReconcileOutcome> {
+        let result = self
+            .reconcile(
+                user_id,
+                bot_id,
+                stopped_task_id,
+                cluster_arn,
+                td_arn,
+                container_name,
+                stop,
+                observed_at,
+                now,
+            )
+            .await;
+        let cause = stop.cause();
+        let label = result
+            .as_ref()
+            .map_or("error", ReconcileOutcome::metric_label);
+        self.metrics.increment(
+            metrics::TASK_STOPPED_EVENTS,
+            &[("outcome", label), ("cause", cause.kind())],
+        );
+        if let Ok(ReconcileOutcome::Restarted { .. }) = &result {
+            self.metrics
+                .increment(metrics::BOT_RESTARTS, &[("cause", cause.kind())]);
+        }
+        let outcome = result?;
+        self.append_history(
+            user_id,
+            bot_id,
+            stopped_task_id,
+            stop,
+            observed_at,
+            now,
+            &outcome,
+        )
+        .await;
+        Ok(outcome)
+    }
+
+    /// History for a reconciled stop: the stop itself with what was decided,
+    /// plus the replacement on a restart. A superseded (duplicate or late)
+    /// event or a deleted bot leaves no entry. Best-effort.
+    #[allow(clippy::too_many_arguments)]
+    async fn append_history(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        stopped_task_id: &str,
+        stop: &StopReport,
+        observed_at: i64,
+        now: i64,
+        outcome: &ReconcileOutcome,
+    ) {
+        let decision = match outcome {
+            ReconcileOutcome::Restarted { .. } => "restarting".to_string(),
+            ReconcileOutcome::SkippedNotEnabled => "not restarted: bot is off".to_string(),
+            ReconcileOutcome::SkippedByPolicy => "not restarted: restart policy".to_string(),
+            ReconcileOutcome::SkippedMaxAttempts { attempts } => {
+                format!("not restarted: gave up after {attempts} restarts")
+            }
+            ReconcileOutcome::SkippedSuperseded | ReconcileOutcome::BotNotFound => return,
+        };
+        let mut stopped = BotEvent::new(user_id, bot_id, BotEventKind::Stopped, observed_at);
+        stopped.task_id = Some(stopped_task_id.to_string());
+        stopped.detail = Some(format!("{}; {decision}", stop.summary()));
+        let mut events = vec![stopped];
+        if let ReconcileOutcome::Restarted { task_id, attempt } = outcome {
+            let mut restarted = BotEvent::new(user_id, bot_id, BotEventKind::Restarted, now);
+            restarted.task_id = Some(task_id.clone());
+            restarted.restart_attempt = Some(*attempt);
+            events.push(restarted);
+        }
+        for event in &events {
+            if let Err(e) = self.history.append(event).await {
+                tracing::warn!("failed to append history for bot {bot_id}: {e}");
+            }
+        }
+    }
+
+    /// Store the next larger resource profile after an OOM kill and note it in
+    /// the history. Best-effort: a failed write leaves the old size, and the
+    /// next OOM tries again. Already at the largest size, nothing changes; a
+    /// redelivered event for the same task, or a profile changed since the
+    /// bot was read, is not promoted again.
+    async fn promote_resources(
+        &self,
+        bot: &Bot,
+        stopped_task_id: &str,
+        observed_at: i64,
+        now: i64,
+    ) {
+        let Some(next) = bot.resource_profile.next() else {
+            return;
+        };
+        match self
+            .bots
+            .promote_resource_profile(
+                &bot.user_id,
+                &bot.id,
+                bot.resource_profile,
+                next,
+                stopped_task_id,
+                now,
+            )
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::info!(
+                    "resource profile for bot {} not promoted: already promoted after task {stopped_task_id} or changed since read",
+                    bot.id
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "failed to promote resource profile for bot {} to {next}: {e}",
+                    bot.id
+                );
+                return;
+            }
+        }
+        tracing::info!(
+            "promoted resource profile for bot {} from {} to {next} after out-of-memory stop",
+            bot.id,
+            bot.resource_profile
+        );
+        let mut event = BotEvent::new(&bot.user_id, &bot.id, BotEventKind::Resized, observed_at);
+        event.task_id = Some(stopped_task_id.to_string());
+        event.detail = Some(format!(
+            "{} → {next} ({}) after out of memory",
+            bot.resource_profile,
+            next.describe()
+        ));
+        if let Err(e) = self.history.append(&event).await {
+            tracing::warn!("failed to append history for bot {}: {e}", bot.id);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn reconcile(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        stopped_task_id: &str,
+        cluster_arn: &str,
+        td_arn: &str,
+        container_name: &str,
+        stop: &StopReport,
         observed_at: i64,
         now: i64,
     ) -> Result<ReconcileOutcome> {
-        // Compute prev_version up front: it does not depend on the bot, and we
-        // need it on the bot-not-found path to record a stopped runtime.
-        let prev_version = self
+        // Read the row up front: it does not depend on the bot, and we need its
+        // version on the bot-not-found path to record a stopped runtime.
+        let current = self
             .runtimes
             .find(user_id, bot_id)
             .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?
-            .map(|r| r.version)
-            .unwrap_or(0);
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        // The row tracks another task (e.g. this one was a duplicate the RUNNING
+        // path refused to adopt and stopped): recording stopped would hide the
+        // owner, which is still trading.
+        if let Some(owner) = current.as_ref().and_then(|r| r.task_id.as_deref())
+            && owner != stopped_task_id
+        {
+            return Ok(ReconcileOutcome::SkippedSuperseded);
+        }
+        let prev_version = current.as_ref().map(|r| r.version).unwrap_or(0);
+        // Consecutive restart number this one would be. A task that ran stably
+        // before it stopped starts a fresh budget, so one crash a week never
+        // accumulates into a tripped limit.
+        let attempt = match current {
+            Some(r)
+                if r.phase == RuntimePhase::Running
+                    && observed_at - r.observed_at >= RESTART_STABLE_AFTER_SECS =>
+            {
+                1
+            }
+            Some(r) => r.restart_attempts.saturating_add(1),
+            None => 1,
+        };
 
         let bot = match self
             .bots
@@ -107,6 +303,13 @@ impl ReconcileStoppedTaskUseCase {
             }
         };
 
+        // Out of memory: move the bot up a size whether or not it restarts, so
+        // the next launch (automatic or manual) does not hit the same limit.
+        if stop.cause() == StopCause::OutOfMemory {
+            self.promote_resources(&bot, stopped_task_id, observed_at, now)
+                .await;
+        }
+
         // Desired state OFF (user manually stopped) -> reflect stopped, never restart. THIS is the rule the old Lambda was missing.
         if !bot.enabled {
             self.runtimes
@@ -120,7 +323,17 @@ impl ReconcileStoppedTaskUseCase {
                 .map_err(|e| anyhow::anyhow!(e.to_string()))?;
             return Ok(ReconcileOutcome::SkippedNotEnabled);
         }
-        if !stop.is_memory_related() {
+        let policy = &bot.restart_policy;
+        let skipped = if !policy.restarts_on(&stop.cause()) {
+            Some(ReconcileOutcome::SkippedByPolicy)
+        } else if !policy.within_budget(attempt) {
+            Some(ReconcileOutcome::SkippedMaxAttempts {
+                attempts: attempt - 1,
+            })
+        } else {
+            None
+        };
+        if let Some(outcome) = skipped {
             self.runtimes
                 .record(&BotRuntime::stopped(
                     user_id.to_string(),
@@ -130,7 +343,7 @@ impl ReconcileStoppedTaskUseCase {
                 ))
                 .await
                 .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-            return Ok(ReconcileOutcome::SkippedNotMemoryRelated);
+            return Ok(outcome);
         }
 
         // Claim the restart through the same exclusive lock the telebot uses,
@@ -142,7 +355,7 @@ impl ReconcileStoppedTaskUseCase {
         // abandoned one and reclaim it.
         match self
             .locks
-            .try_acquire_restart(user_id, bot_id, stopped_task_id, now)
+            .try_acquire_restart(user_id, bot_id, stopped_task_id, attempt, now)
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))?
         {
@@ -156,9 +369,16 @@ impl ReconcileStoppedTaskUseCase {
         // disabled (or deleted) the bot between the read above and the claim. A
         // read *failure* rolls the lock back and propagates Err so the restart is
         // retried, never silently downgraded to "disabled".
-        let still_enabled = match self.bots.find_consistent(user_id, bot_id).await {
-            Ok(Some(b)) => b.enabled,
-            Ok(None) => false,
+        // The same read supplies the size to launch at, including a promotion
+        // written above.
+        let resources = match self.bots.find_consistent(user_id, bot_id).await {
+            Ok(Some(b)) if b.enabled => b.resource_profile,
+            Ok(_) => {
+                if let Err(e) = self.locks.release_start(user_id, bot_id, now).await {
+                    tracing::warn!("failed to release start lock for bot {bot_id}: {e}");
+                }
+                return Ok(ReconcileOutcome::SkippedNotEnabled);
+            }
             Err(e) => {
                 if let Err(re) = self.locks.release_start(user_id, bot_id, now).await {
                     tracing::warn!("failed to release start lock for bot {bot_id}: {re}");
@@ -166,18 +386,19 @@ impl ReconcileStoppedTaskUseCase {
                 return Err(anyhow::anyhow!(e.to_string()));
             }
         };
-        if !still_enabled {
-            if let Err(e) = self.locks.release_start(user_id, bot_id, now).await {
-                tracing::warn!("failed to release start lock for bot {bot_id}: {e}");
-            }
-            return Ok(ReconcileOutcome::SkippedNotEnabled);
-        }
 
         // Lock held (row is `starting`): launch, then attach. On failure release
         // the lock back to stopped so the bot can be started again.
         let task_id = match self
             .run_task
-            .run(user_id, bot_id, cluster_arn, td_arn, container_name)
+            .run(
+                user_id,
+                bot_id,
+                cluster_arn,
+                td_arn,
+                container_name,
+                resources,
+            )
             .await
         {
             Ok(id) => id,
@@ -234,49 +455,37 @@ impl ReconcileStoppedTaskUseCase {
             }
             return Ok(ReconcileOutcome::SkippedNotEnabled);
         }
-        Ok(ReconcileOutcome::Restarted { task_id })
+        Ok(ReconcileOutcome::Restarted { task_id, attempt })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::runtime::RuntimePhase;
+    use crate::domain::metrics::{NoopMetrics, RecordingMetrics};
+    use crate::domain::resources::ResourceProfile;
+    use crate::domain::restart::{ContainerExit, RestartPolicy};
     use crate::usecase::run_task::RunTaskUseCase;
 
     // Fixed event time used by the behaviour tests below.
     const EVENT_AT: i64 = 1_700_000_000;
 
-    #[test]
-    fn memory_related_when_137_and_not_user_initiated() {
-        let stop = StopInfo {
-            exit_code: 137,
-            stop_code: "TaskFailedToStart".to_string(),
-        };
-        assert!(stop.is_memory_related());
-    }
-
-    #[test]
-    fn not_memory_related_when_137_but_user_initiated() {
-        let stop = StopInfo {
-            exit_code: 137,
-            stop_code: "UserInitiated".to_string(),
-        };
-        assert!(!stop.is_memory_related());
-    }
-
-    #[test]
-    fn not_memory_related_when_other_exit_code() {
-        let stop = StopInfo {
-            exit_code: 0,
-            stop_code: "TaskFailedToStart".to_string(),
-        };
-        assert!(!stop.is_memory_related());
+    /// A single-container STOPPED report with the given stop code and exit.
+    fn stop_report(stop_code: &str, exit_code: i32) -> StopReport {
+        StopReport {
+            stop_code: Some(stop_code.to_string()),
+            stopped_reason: None,
+            main_container: "container".to_string(),
+            containers: vec![ContainerExit {
+                name: "container".to_string(),
+                exit_code: Some(exit_code),
+                reason: None,
+            }],
+        }
     }
 
     // --- Use-case behaviour tests with in-memory mock repositories ---
 
-    use crate::domain::bot::Bot;
     use crate::domain::error::DomainError;
     use crate::domain::exchange::Exchange;
     use async_trait::async_trait;
@@ -352,6 +561,16 @@ mod tests {
                 .get(&(user_id.to_string(), bot_id.to_string()))
                 .cloned())
         }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .runtimes
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
         async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
             self.runtimes.lock().unwrap().insert(
                 (runtime.user_id.clone(), runtime.bot_id.clone()),
@@ -366,6 +585,7 @@ mod tests {
     struct MockLock {
         restart_claim: StartClaim,
         restart_calls: Mutex<usize>,
+        last_attempt: Mutex<Option<u32>>,
         attached: Mutex<Option<String>>,
         released: Mutex<usize>,
     }
@@ -374,6 +594,7 @@ mod tests {
             Self {
                 restart_claim,
                 restart_calls: Mutex::new(0),
+                last_attempt: Mutex::new(None),
                 attached: Mutex::new(None),
                 released: Mutex::new(0),
             }
@@ -395,9 +616,11 @@ mod tests {
             _u: &str,
             _b: &str,
             _stopped: &str,
+            attempt: u32,
             _now: i64,
         ) -> Result<StartClaim, DomainError> {
             *self.restart_calls.lock().unwrap() += 1;
+            *self.last_attempt.lock().unwrap() = Some(attempt);
             Ok(self.restart_claim.clone())
         }
         async fn attach_started_task(
@@ -415,6 +638,35 @@ mod tests {
         }
     }
 
+    /// In-memory BotHistoryRepository recording every appended event.
+    #[derive(Default)]
+    struct InMemoryHistory {
+        events: Mutex<Vec<BotEvent>>,
+    }
+    #[async_trait]
+    impl BotHistoryRepository for InMemoryHistory {
+        async fn append(&self, event: &BotEvent) -> Result<(), DomainError> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+        async fn recent(
+            &self,
+            _user_id: &str,
+            _bot_id: &str,
+            limit: usize,
+        ) -> Result<Vec<BotEvent>, DomainError> {
+            Ok(self
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
     /// In-memory TaskController recording StopTask calls; liveness is unused here.
     #[derive(Default)]
     struct MockStopper {
@@ -472,6 +724,8 @@ mod tests {
             locks,
             run_task,
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let outcome = uc
@@ -482,10 +736,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -501,7 +752,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn enabled_bot_non_memory_stop_skips_without_restart() {
+    async fn enabled_bot_stop_outside_policy_skips_without_restart() {
         let bots = Arc::new(InMemoryBots::with(enabled_bot(true)));
         let runtimes = Arc::new(InMemoryRuntimes::default());
         let run_task = Arc::new(RunTaskUseCase::new(dummy_ecs_client()));
@@ -512,9 +763,11 @@ mod tests {
             locks,
             run_task,
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
-        // exit 0 => not memory related.
+        // A clean exit is outside the default on-oom policy.
         let outcome = uc
             .execute(
                 "user-1",
@@ -523,18 +776,15 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 0,
-                    stop_code: "EssentialContainerExited".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 0),
                 EVENT_AT,
                 EVENT_AT,
             )
             .await
             .unwrap();
-        assert_eq!(outcome, ReconcileOutcome::SkippedNotMemoryRelated);
+        assert_eq!(outcome, ReconcileOutcome::SkippedByPolicy);
 
-        // 137 but UserInitiated => also not memory related.
+        // A user-initiated stop is never restarted, whatever the policy.
         let outcome2 = uc
             .execute(
                 "user-1",
@@ -543,21 +793,160 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "UserInitiated".to_string(),
-                },
+                &stop_report("UserInitiated", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
             .await
             .unwrap();
-        assert_eq!(outcome2, ReconcileOutcome::SkippedNotMemoryRelated);
+        assert_eq!(outcome2, ReconcileOutcome::SkippedByPolicy);
 
         let rt = runtimes.find("user-1", "bot-1").await.unwrap().unwrap();
         assert_eq!(rt.phase, RuntimePhase::Stopped);
     }
 
+    #[tokio::test]
+    async fn crash_loop_stops_restarting_once_the_budget_is_spent() {
+        let mut bot = enabled_bot(true);
+        bot.set_restart_policy(RestartPolicy::parse("on-failure:3").unwrap(), 2);
+        let bots = Arc::new(InMemoryBots::with(bot));
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        let mut row = BotRuntime::running(
+            "user-1".into(),
+            "bot-1".into(),
+            "stopped-task".into(),
+            4,
+            EVENT_AT - 30,
+        );
+        row.restart_attempts = 3;
+        runtimes.record(&row).await.unwrap();
+        let locks = Arc::new(MockLock::new(StartClaim::Acquired));
+        let uc = ReconcileStoppedTaskUseCase::new(
+            bots,
+            runtimes.clone(),
+            locks.clone(),
+            Arc::new(RunTaskUseCase::new(dummy_ecs_client())),
+            Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
+        );
+
+        let outcome = uc
+            .execute(
+                "user-1",
+                "bot-1",
+                "stopped-task",
+                "cluster",
+                "td",
+                "container",
+                &stop_report("EssentialContainerExited", 1),
+                EVENT_AT,
+                EVENT_AT,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            ReconcileOutcome::SkippedMaxAttempts { attempts: 3 }
+        );
+        assert_eq!(*locks.restart_calls.lock().unwrap(), 0);
+        let rt = runtimes.find("user-1", "bot-1").await.unwrap().unwrap();
+        assert_eq!(rt.phase, RuntimePhase::Stopped);
+    }
+
+    #[tokio::test]
+    async fn stable_run_resets_the_restart_budget() {
+        let mut bot = enabled_bot(true);
+        bot.set_restart_policy(RestartPolicy::parse("on-failure:3").unwrap(), 2);
+        let bots = Arc::new(InMemoryBots::with(bot));
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        let mut row = BotRuntime::running(
+            "user-1".into(),
+            "bot-1".into(),
+            "stopped-task".into(),
+            4,
+            EVENT_AT - RESTART_STABLE_AFTER_SECS,
+        );
+        row.restart_attempts = 3;
+        runtimes.record(&row).await.unwrap();
+        // A rejected claim ends the run before any launch; we only need the attempt.
+        let locks = Arc::new(MockLock::new(StartClaim::AlreadyStarting));
+        let uc = ReconcileStoppedTaskUseCase::new(
+            bots,
+            runtimes,
+            locks.clone(),
+            Arc::new(RunTaskUseCase::new(dummy_ecs_client())),
+            Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
+        );
+
+        uc.execute(
+            "user-1",
+            "bot-1",
+            "stopped-task",
+            "cluster",
+            "td",
+            "container",
+            &stop_report("EssentialContainerExited", 1),
+            EVENT_AT,
+            EVENT_AT,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*locks.last_attempt.lock().unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn stopped_duplicate_does_not_clobber_the_owners_row() {
+        let bots = Arc::new(InMemoryBots::with(enabled_bot(true)));
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        runtimes
+            .record(&BotRuntime::running(
+                "user-1".into(),
+                "bot-1".into(),
+                "owner-task".into(),
+                2,
+                EVENT_AT - 60,
+            ))
+            .await
+            .unwrap();
+        let locks = Arc::new(MockLock::new(StartClaim::Acquired));
+        let uc = ReconcileStoppedTaskUseCase::new(
+            bots,
+            runtimes.clone(),
+            locks.clone(),
+            Arc::new(RunTaskUseCase::new(dummy_ecs_client())),
+            Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
+        );
+
+        // The duplicate the RUNNING path stopped reports STOPPED (UserInitiated).
+        let outcome = uc
+            .execute(
+                "user-1",
+                "bot-1",
+                "duplicate-task",
+                "cluster",
+                "td",
+                "container",
+                &stop_report("UserInitiated", 137),
+                EVENT_AT,
+                EVENT_AT,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, ReconcileOutcome::SkippedSuperseded);
+        let rt = runtimes.find("user-1", "bot-1").await.unwrap().unwrap();
+        assert_eq!(rt.phase, RuntimePhase::Running);
+        assert_eq!(rt.task_id.as_deref(), Some("owner-task"));
+        assert_eq!(*locks.restart_calls.lock().unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn missing_bot_returns_bot_not_found() {
         let bots = Arc::new(InMemoryBots::default());
@@ -570,6 +959,8 @@ mod tests {
             locks,
             run_task,
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let outcome = uc
@@ -580,10 +971,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -600,12 +988,14 @@ mod tests {
     struct MockTaskRunner {
         task_id: String,
         calls: Mutex<usize>,
+        resources: Mutex<Option<ResourceProfile>>,
     }
     impl MockTaskRunner {
         fn new(task_id: &str) -> Self {
             Self {
                 task_id: task_id.to_string(),
                 calls: Mutex::new(0),
+                resources: Mutex::new(None),
             }
         }
         fn call_count(&self) -> usize {
@@ -621,8 +1011,10 @@ mod tests {
             _cluster_arn: &str,
             _td_arn: &str,
             _container_name: &str,
+            resources: ResourceProfile,
         ) -> Result<String> {
             *self.calls.lock().unwrap() += 1;
+            *self.resources.lock().unwrap() = Some(resources);
             Ok(self.task_id.clone())
         }
     }
@@ -633,12 +1025,16 @@ mod tests {
         let runtimes = Arc::new(InMemoryRuntimes::default());
         let runner = Arc::new(MockTaskRunner::new("task-xyz"));
         let locks = Arc::new(MockLock::new(StartClaim::Acquired));
+        let history = Arc::new(InMemoryHistory::default());
+        let metrics = Arc::new(RecordingMetrics::default());
         let uc = ReconcileStoppedTaskUseCase::new(
-            bots,
+            bots.clone(),
             runtimes.clone(),
             locks.clone(),
             runner.clone(),
             Arc::new(MockStopper::default()),
+            history.clone(),
+            metrics.clone(),
         );
 
         // OOM stop: exit 137, not UserInitiated.
@@ -650,10 +1046,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -663,7 +1056,8 @@ mod tests {
         assert_eq!(
             outcome,
             ReconcileOutcome::Restarted {
-                task_id: "task-xyz".to_string()
+                task_id: "task-xyz".to_string(),
+                attempt: 1,
             }
         );
         assert_eq!(
@@ -677,6 +1071,106 @@ mod tests {
             Some("task-xyz".to_string()),
             "new task id attached to the lock"
         );
+
+        // The OOM promoted the bot a size before the replacement launched.
+        assert_eq!(
+            bots.find("user-1", "bot-1")
+                .await
+                .unwrap()
+                .unwrap()
+                .resource_profile,
+            ResourceProfile::Medium
+        );
+        assert_eq!(
+            *runner.resources.lock().unwrap(),
+            Some(ResourceProfile::Medium),
+            "replacement launched at the promoted size"
+        );
+
+        let events = history.events.lock().unwrap().clone();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, BotEventKind::Resized);
+        assert_eq!(
+            events[0].detail.as_deref(),
+            Some("small → medium (256 CPU units, 800 MiB) after out of memory")
+        );
+        assert_eq!(events[1].kind, BotEventKind::Stopped);
+        assert_eq!(events[1].task_id.as_deref(), Some("old-task"));
+        assert_eq!(
+            events[1].detail.as_deref(),
+            Some("out of memory, exit 137; restarting")
+        );
+        assert_eq!(events[2].kind, BotEventKind::Restarted);
+        assert_eq!(events[2].task_id.as_deref(), Some("task-xyz"));
+        assert_eq!(events[2].restart_attempt, Some(1));
+
+        assert_eq!(
+            metrics.count(
+                metrics::TASK_STOPPED_EVENTS,
+                &[("outcome", "restarted"), ("cause", "out_of_memory")]
+            ),
+            1
+        );
+        assert_eq!(
+            metrics.count(metrics::BOT_RESTARTS, &[("cause", "out_of_memory")]),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn oom_at_the_largest_size_and_other_failures_keep_the_profile() {
+        for (profile, stop) in [
+            (
+                ResourceProfile::Large,
+                stop_report("EssentialContainerExited", 137),
+            ),
+            (
+                ResourceProfile::Small,
+                stop_report("EssentialContainerExited", 1),
+            ),
+        ] {
+            let mut bot = enabled_bot(true);
+            bot.set_resource_profile(profile, 1);
+            bot.set_restart_policy(RestartPolicy::parse("on-failure").unwrap(), 1);
+            let bots = Arc::new(InMemoryBots::with(bot));
+            let runner = Arc::new(MockTaskRunner::new("task-xyz"));
+            let history = Arc::new(InMemoryHistory::default());
+            let uc = ReconcileStoppedTaskUseCase::new(
+                bots.clone(),
+                Arc::new(InMemoryRuntimes::default()),
+                Arc::new(MockLock::new(StartClaim::Acquired)),
+                runner.clone(),
+                Arc::new(MockStopper::default()),
+                history.clone(),
+                Arc::new(NoopMetrics),
+            );
+
+            uc.execute(
+                "user-1",
+                "bot-1",
+                "old-task",
+                "cluster",
+                "td",
+                "container",
+                &stop,
+                EVENT_AT,
+                EVENT_AT,
+            )
+            .await
+            .unwrap();
+
+            let stored = bots.find("user-1", "bot-1").await.unwrap().unwrap();
+            assert_eq!(stored.resource_profile, profile);
+            assert_eq!(*runner.resources.lock().unwrap(), Some(profile));
+            assert!(
+                history
+                    .events
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .all(|e| e.kind != BotEventKind::Resized)
+            );
+        }
     }
 
     #[tokio::test]
@@ -692,6 +1186,8 @@ mod tests {
             locks.clone(),
             runner.clone(),
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let outcome = uc
@@ -702,10 +1198,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -755,6 +1248,8 @@ mod tests {
             locks.clone(),
             runner.clone(),
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let outcome = uc
@@ -765,10 +1260,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -833,6 +1325,8 @@ mod tests {
             locks.clone(),
             runner.clone(),
             stopper.clone(),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let outcome = uc
@@ -843,10 +1337,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -900,6 +1391,8 @@ mod tests {
             locks,
             runner.clone(),
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let result = uc
@@ -910,10 +1403,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
@@ -967,6 +1457,8 @@ mod tests {
             locks.clone(),
             runner.clone(),
             Arc::new(MockStopper::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
         );
 
         let result = uc
@@ -977,10 +1469,7 @@ mod tests {
                 "cluster",
                 "td",
                 "container",
-                StopInfo {
-                    exit_code: 137,
-                    stop_code: "TaskFailedToStart".to_string(),
-                },
+                &stop_report("EssentialContainerExited", 137),
                 EVENT_AT,
                 EVENT_AT,
             )
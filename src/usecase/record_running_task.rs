@@ -1,35 +1,131 @@
+use crate::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+use crate::domain::metrics::{self, Metrics};
 use crate::domain::runtime::{BotRuntime, BotRuntimeRepository, RuntimePhase};
+use crate::usecase::stop_task::{TaskController, TaskLiveness};
 use anyhow::Result;
 use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum RecordRunningOutcome {
-    Recorded { version: i64 },
+    Recorded {
+        version: i64,
+    },
     SkippedStale,
+    /// Another live task already owns the row (e.g. a duplicate launched by hand
+    /// from the console). The row keeps tracking the owner; the newcomer was
+    /// asked to stop unless `newcomer_stopped` is false, in which case two tasks
+    /// are trading and an operator must step in.
+    Conflict {
+        owner_task_id: String,
+        newcomer_stopped: bool,
+    },
+}
+
+impl RecordRunningOutcome {
+    /// The `outcome` label of [`metrics::TASK_RUNNING_EVENTS`].
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Recorded { .. } => "recorded",
+            Self::SkippedStale => "skipped_stale",
+            Self::Conflict { .. } => "conflict",
+        }
+    }
 }
 
 /// Records observed-running state for a bot whose ECS task reached RUNNING,
 /// driven by the ECS Task State Change Lambda.
 ///
 /// This is an observation, not a restart: it sets phase + task_id, preserves the
-/// version/restart counter, and never starts or stops a task. `observed_at` is the
+/// version/restart counter, and never starts a task. `observed_at` is the
 /// EventBridge event time; an event no newer than the last observation is dropped
 /// (a STOPPED wins an equal-second tie) so a late/reordered RUNNING cannot flip a
 /// stopped bot back to running.
+///
+/// Every launch this system makes goes through the start lock, which clears or
+/// attaches the row's `task_id` before the task can report RUNNING. A RUNNING for
+/// a different task while the row's task is still alive is therefore a task
+/// started outside the telebot; adopting it would leave the original trading
+/// untracked, so the newcomer is stopped instead.
 pub struct RecordRunningTaskUseCase {
     runtimes: Arc<dyn BotRuntimeRepository>,
+    controller: Arc<dyn TaskController>,
+    history: Arc<dyn BotHistoryRepository>,
+    metrics: Arc<dyn Metrics>,
 }
 
 impl RecordRunningTaskUseCase {
-    pub fn new(runtimes: Arc<dyn BotRuntimeRepository>) -> Self {
-        Self { runtimes }
+    pub fn new(
+        runtimes: Arc<dyn BotRuntimeRepository>,
+        controller: Arc<dyn TaskController>,
+        history: Arc<dyn BotHistoryRepository>,
+        metrics: Arc<dyn Metrics>,
+    ) -> Self {
+        Self {
+            runtimes,
+            controller,
+            history,
+            metrics,
+        }
     }
 
+    /// Record the observation and append it to the bot's history (a stale
+    /// event leaves no entry). The history append is best-effort. Every event
+    /// is counted by outcome.
+    #[tracing::instrument(
+        name = "record_running",
+        skip_all,
+        fields(user_id = %user_id, bot_id = %bot_id, task_id = %task_id)
+    )]
     pub async fn execute(
         &self,
         user_id: &str,
         bot_id: &str,
         task_id: &str,
+        cluster_arn: &str,
+        observed_at: i64,
+    ) -> Result<RecordRunningOutcome> {
+        let result = self
+            .record(user_id, bot_id, task_id, cluster_arn, observed_at)
+            .await;
+        let label = result
+            .as_ref()
+            .map_or("error", RecordRunningOutcome::metric_label);
+        self.metrics
+            .increment(metrics::TASK_RUNNING_EVENTS, &[("outcome", label)]);
+        let outcome = result?;
+        let (kind, detail) = match &outcome {
+            RecordRunningOutcome::Recorded { .. } => (BotEventKind::Running, None),
+            RecordRunningOutcome::SkippedStale => return Ok(outcome),
+            RecordRunningOutcome::Conflict {
+                owner_task_id,
+                newcomer_stopped,
+            } => (
+                BotEventKind::DuplicateRefused,
+                Some(format!(
+                    "duplicate of running task {owner_task_id}; {}",
+                    if *newcomer_stopped {
+                        "stopped"
+                    } else {
+                        "could NOT be stopped"
+                    }
+                )),
+            ),
+        };
+        let mut event = BotEvent::new(user_id, bot_id, kind, observed_at);
+        event.task_id = Some(task_id.to_string());
+        event.detail = detail;
+        if let Err(e) = self.history.append(&event).await {
+            tracing::warn!("failed to append history for bot {bot_id}: {e}");
+        }
+        Ok(outcome)
+    }
+
+    async fn record(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        task_id: &str,
+        cluster_arn: &str,
         observed_at: i64,
     ) -> Result<RecordRunningOutcome> {
         let existing = self
@@ -55,20 +151,67 @@ impl RecordRunningTaskUseCase {
             }
         }
 
-        // Preserve the restart/generation counter; observing a running task is not a restart.
-        let version = existing.map(|r| r.version).unwrap_or(0).max(1);
+        // Ownership guard. An owner that is gone only lost its STOPPED event, so
+        // the newcomer may take over; a liveness failure propagates so the event
+        // is redelivered rather than adopted blind.
+        let owner = existing
+            .as_ref()
+            .filter(|r| r.phase != RuntimePhase::Stopped)
+            .and_then(|r| r.task_id.as_deref())
+            .filter(|owner| *owner != task_id);
+        if let Some(owner) = owner
+            && self.controller.liveness(cluster_arn, owner).await? == TaskLiveness::Alive
+        {
+            let newcomer_stopped = match self
+                .controller
+                .stop(
+                    cluster_arn,
+                    task_id,
+                    &format!("stopped: duplicate of running task {owner}"),
+                )
+                .await
+            {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::error!(
+                        "failed to stop duplicate task {task_id} of bot {bot_id} (owner {owner}): {e:#}"
+                    );
+                    false
+                }
+            };
+            return Ok(RecordRunningOutcome::Conflict {
+                owner_task_id: owner.to_string(),
+                newcomer_stopped,
+            });
+        }
+
+        // Preserve the restart/generation counter and the consecutive-restart
+        // count; observing a running task is not a restart.
+        let version = existing.as_ref().map(|r| r.version).unwrap_or(0).max(1);
+        let mut runtime = BotRuntime::running(
+            user_id.to_string(),
+            bot_id.to_string(),
+            task_id.to_string(),
+            version,
+            observed_at,
+        );
+        runtime.restart_attempts = existing.as_ref().map_or(0, |r| r.restart_attempts);
 
         self.runtimes
-            .record(&BotRuntime::running(
-                user_id.to_string(),
-                bot_id.to_string(),
-                task_id.to_string(),
-                version,
-                observed_at,
-            ))
+            .record(&runtime)
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
+        // A `starting` row was stamped when its launch claimed the start lock,
+        // so this is how long the launch took to come up.
+        if let Some(prev) = existing.filter(|r| r.phase == RuntimePhase::Starting) {
+            self.metrics.observe(
+                metrics::TIME_TO_RUNNING_SECONDS,
+                &[],
+                (observed_at - prev.observed_at).max(0) as f64,
+            );
+        }
+
         Ok(RecordRunningOutcome::Recorded { version })
     }
 }
@@ -77,11 +220,78 @@ impl RecordRunningTaskUseCase {
 mod tests {
     use super::*;
     use crate::domain::error::DomainError;
+    use crate::domain::metrics::{NoopMetrics, RecordingMetrics};
     use crate::domain::runtime::RuntimePhase;
     use async_trait::async_trait;
     use std::collections::HashMap;
     use std::sync::Mutex;
 
+    /// TaskController whose listed tasks are alive; records StopTask calls.
+    #[derive(Default)]
+    struct MockController {
+        alive: Vec<String>,
+        stops: Mutex<Vec<String>>,
+    }
+    impl MockController {
+        fn alive(task_id: &str) -> Self {
+            Self {
+                alive: vec![task_id.to_string()],
+                ..Self::default()
+            }
+        }
+    }
+    #[async_trait]
+    impl TaskController for MockController {
+        async fn stop(&self, _cluster_arn: &str, task_id: &str, _reason: &str) -> Result<()> {
+            self.stops.lock().unwrap().push(task_id.to_string());
+            Ok(())
+        }
+        async fn liveness(&self, _cluster_arn: &str, task_id: &str) -> Result<TaskLiveness> {
+            Ok(if self.alive.iter().any(|t| t == task_id) {
+                TaskLiveness::Alive
+            } else {
+                TaskLiveness::Gone
+            })
+        }
+    }
+
+    fn use_case(runtimes: Arc<InMemoryRuntimes>) -> RecordRunningTaskUseCase {
+        RecordRunningTaskUseCase::new(
+            runtimes,
+            Arc::new(MockController::default()),
+            Arc::new(InMemoryHistory::default()),
+            Arc::new(NoopMetrics),
+        )
+    }
+
+    #[derive(Default)]
+    struct InMemoryHistory {
+        events: Mutex<Vec<BotEvent>>,
+    }
+    #[async_trait]
+    impl BotHistoryRepository for InMemoryHistory {
+        async fn append(&self, event: &BotEvent) -> Result<(), DomainError> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+        async fn recent(
+            &self,
+            _user_id: &str,
+            _bot_id: &str,
+            limit: usize,
+        ) -> Result<Vec<BotEvent>, DomainError> {
+            Ok(self
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
     #[derive(Default)]
     struct InMemoryRuntimes {
         runtimes: Mutex<HashMap<(String, String), BotRuntime>>,
@@ -100,6 +310,16 @@ mod tests {
                 .get(&(user_id.to_string(), bot_id.to_string()))
                 .cloned())
         }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .runtimes
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
         async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
             self.runtimes.lock().unwrap().insert(
                 (runtime.user_id.clone(), runtime.bot_id.clone()),
@@ -112,9 +332,12 @@ mod tests {
     #[tokio::test]
     async fn records_running_for_new_bot_at_version_one() {
         let runtimes = Arc::new(InMemoryRuntimes::default());
-        let uc = RecordRunningTaskUseCase::new(runtimes.clone());
+        let uc = use_case(runtimes.clone());
 
-        let outcome = uc.execute("u", "b", "task-1", 1_700_000_000).await.unwrap();
+        let outcome = uc
+            .execute("u", "b", "task-1", "cluster", 1_700_000_000)
+            .await
+            .unwrap();
         assert_eq!(outcome, RecordRunningOutcome::Recorded { version: 1 });
 
         let rt = runtimes.find("u", "b").await.unwrap().unwrap();
@@ -137,10 +360,10 @@ mod tests {
             ))
             .await
             .unwrap();
-        let uc = RecordRunningTaskUseCase::new(runtimes.clone());
+        let uc = use_case(runtimes.clone());
 
         let outcome = uc
-            .execute("u", "b", "task-new", 1_700_000_000)
+            .execute("u", "b", "task-new", "cluster", 1_700_000_000)
             .await
             .unwrap();
         assert_eq!(outcome, RecordRunningOutcome::Recorded { version: 7 });
@@ -153,6 +376,42 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn measures_time_from_start_lock_to_running() {
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        runtimes
+            .record(&BotRuntime {
+                user_id: "u".into(),
+                bot_id: "b".into(),
+                task_id: Some("task-1".into()),
+                phase: RuntimePhase::Starting,
+                version: 1,
+                observed_at: 1_700_000_000,
+                restart_attempts: 0,
+            })
+            .await
+            .unwrap();
+        let metrics = Arc::new(RecordingMetrics::default());
+        let uc = RecordRunningTaskUseCase::new(
+            runtimes,
+            Arc::new(MockController::alive("task-1")),
+            Arc::new(InMemoryHistory::default()),
+            metrics.clone(),
+        );
+
+        uc.execute("u", "b", "task-1", "cluster", 1_700_000_042)
+            .await
+            .unwrap();
+        assert_eq!(
+            metrics.observations(metrics::TIME_TO_RUNNING_SECONDS),
+            vec![42.0]
+        );
+        assert_eq!(
+            metrics.count(metrics::TASK_RUNNING_EVENTS, &[("outcome", "recorded")]),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn skips_stale_event_older_than_last_observation() {
         let runtimes = Arc::new(InMemoryRuntimes::default());
@@ -161,10 +420,13 @@ mod tests {
             .record(&BotRuntime::stopped("u".into(), "b".into(), 3, 2000))
             .await
             .unwrap();
-        let uc = RecordRunningTaskUseCase::new(runtimes.clone());
+        let uc = use_case(runtimes.clone());
 
         // A reordered RUNNING event timestamped t=1000 must not resurrect it.
-        let outcome = uc.execute("u", "b", "task-stale", 1000).await.unwrap();
+        let outcome = uc
+            .execute("u", "b", "task-stale", "cluster", 1000)
+            .await
+            .unwrap();
         assert_eq!(outcome, RecordRunningOutcome::SkippedStale);
 
         let rt = runtimes.find("u", "b").await.unwrap().unwrap();
@@ -189,10 +451,13 @@ mod tests {
             ))
             .await
             .unwrap();
-        let uc = RecordRunningTaskUseCase::new(runtimes.clone());
+        let uc = use_case(runtimes.clone());
 
         // A reordered RUNNING on the same second must not flip a stopping task back.
-        let outcome = uc.execute("u", "b", "task-tie", 2000).await.unwrap();
+        let outcome = uc
+            .execute("u", "b", "task-tie", "cluster", 2000)
+            .await
+            .unwrap();
         assert_eq!(outcome, RecordRunningOutcome::SkippedStale);
 
         let rt = runtimes.find("u", "b").await.unwrap().unwrap();
@@ -207,13 +472,65 @@ mod tests {
             .record(&BotRuntime::stopped("u".into(), "b".into(), 3, 2000))
             .await
             .unwrap();
-        let uc = RecordRunningTaskUseCase::new(runtimes.clone());
+        let uc = use_case(runtimes.clone());
 
         // A RUNNING event on the SAME second must not resurrect the stop (tie -> stopped wins).
-        let outcome = uc.execute("u", "b", "task-tie", 2000).await.unwrap();
+        let outcome = uc
+            .execute("u", "b", "task-tie", "cluster", 2000)
+            .await
+            .unwrap();
         assert_eq!(outcome, RecordRunningOutcome::SkippedStale);
 
         let rt = runtimes.find("u", "b").await.unwrap().unwrap();
         assert_eq!(rt.phase, RuntimePhase::Stopped);
     }
+
+    #[tokio::test]
+    async fn refuses_duplicate_while_owner_is_alive_and_stops_it() {
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        runtimes
+            .record(&BotRuntime::running(
+                "u".into(),
+                "b".into(),
+                "task-owner".into(),
+                2,
+                1_699_000_000,
+            ))
+            .await
+            .unwrap();
+        let controller = Arc::new(MockController::alive("task-owner"));
+        let history = Arc::new(InMemoryHistory::default());
+        let uc = RecordRunningTaskUseCase::new(
+            runtimes.clone(),
+            controller.clone(),
+            history.clone(),
+            Arc::new(NoopMetrics),
+        );
+
+        let outcome = uc
+            .execute("u", "b", "task-manual", "cluster", 1_700_000_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            outcome,
+            RecordRunningOutcome::Conflict {
+                owner_task_id: "task-owner".into(),
+                newcomer_stopped: true,
+            }
+        );
+        assert_eq!(*controller.stops.lock().unwrap(), vec!["task-manual"]);
+
+        let rt = runtimes.find("u", "b").await.unwrap().unwrap();
+        assert_eq!(
+            rt.task_id.as_deref(),
+            Some("task-owner"),
+            "the row must keep tracking the original task"
+        );
+        assert_eq!(rt.observed_at, 1_699_000_000);
+
+        let events = history.events.lock().unwrap().clone();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, BotEventKind::DuplicateRefused);
+        assert_eq!(events[0].task_id.as_deref(), Some("task-manual"));
+    }
 }
@@ -0,0 +1,159 @@
+use crate::domain::clock::Clock;
+use crate::domain::exchange::Exchange;
+use crate::domain::instrument::{InstrumentCatalog, InstrumentCatalogRepository, InstrumentSource};
+use std::sync::Arc;
+
+/// How old (seconds) a cached catalog may get before it is no longer trusted:
+/// four missed refreshes of the 6-hourly Lambda.
+pub(crate) const CATALOG_MAX_AGE_SECS: i64 = 24 * 60 * 60;
+
+/// The cached instrument catalog, or `None` when there is none yet, it cannot
+/// be read, or it is older than [`CATALOG_MAX_AGE_SECS`] at `now`. Callers
+/// then skip the instrument checks rather than block the user on a cache
+/// outage or judge coins by delistings long past; the exchange still has the
+/// last word.
+pub(crate) async fn current_catalog(
+    catalogs: &dyn InstrumentCatalogRepository,
+    now: i64,
+) -> Option<InstrumentCatalog> {
+    match catalogs.load().await {
+        Ok(Some(catalog)) if now - catalog.fetched_at > CATALOG_MAX_AGE_SECS => {
+            tracing::warn!(
+                fetched_at = catalog.fetched_at,
+                "instrument catalog is stale, skipping instrument checks"
+            );
+            None
+        }
+        Ok(catalog) => catalog,
+        Err(e) => {
+            tracing::warn!("instrument catalog unavailable, skipping instrument checks: {e}");
+            None
+        }
+    }
+}
+
+/// Read the exchange's instrument list and replace the cached catalog with
+/// it. An empty list is treated as a failed read and leaves the cache alone,
+/// so one bad answer cannot make every coin look unlisted.
+pub struct RefreshInstrumentCatalogUseCase {
+    source: Arc<dyn InstrumentSource>,
+    catalogs: Arc<dyn InstrumentCatalogRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RefreshInstrumentCatalogUseCase {
+    pub fn new(
+        source: Arc<dyn InstrumentSource>,
+        catalogs: Arc<dyn InstrumentCatalogRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            source,
+            catalogs,
+            clock,
+        }
+    }
+
+    /// Returns how many instruments were stored.
+    pub async fn execute(&self) -> Result<usize, String> {
+        let instruments = self.source.instruments().await.map_err(|e| e.to_string())?;
+        if instruments.is_empty() {
+            return Err("the exchange listed no instruments; keeping the cached catalog".into());
+        }
+        let catalog = InstrumentCatalog {
+            exchange: Exchange::Bybit,
+            fetched_at: self.clock.now(),
+            instruments,
+        };
+        self.catalogs
+            .save(&catalog)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(catalog.instruments.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use crate::domain::instrument::Instrument;
+    use crate::infra::memory::InMemoryInstrumentCatalog;
+    use async_trait::async_trait;
+
+    struct FixedSource(Vec<Instrument>);
+    #[async_trait]
+    impl InstrumentSource for FixedSource {
+        async fn instruments(&self) -> Result<Vec<Instrument>, DomainError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn a_refresh_replaces_the_catalog_but_an_empty_answer_does_not() {
+        let catalogs = Arc::new(InMemoryInstrumentCatalog::new());
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let btc = Instrument {
+            symbol: "BTCUSDT".into(),
+            base_coin: "BTC".into(),
+            status: "Trading".into(),
+            min_qty: 0.001,
+            tick_size: 0.1,
+            max_leverage: 100.0,
+        };
+
+        let refresh = RefreshInstrumentCatalogUseCase::new(
+            Arc::new(FixedSource(vec![btc.clone()])),
+            catalogs.clone(),
+            clock.clone(),
+        );
+        assert_eq!(refresh.execute().await.unwrap(), 1);
+
+        let empty = RefreshInstrumentCatalogUseCase::new(
+            Arc::new(FixedSource(Vec::new())),
+            catalogs.clone(),
+            clock.clone(),
+        );
+        assert!(empty.execute().await.is_err());
+        let cached = current_catalog(catalogs.as_ref(), clock.now())
+            .await
+            .unwrap();
+        assert_eq!(cached.instruments, vec![btc]);
+        assert_eq!(cached.fetched_at, 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn a_catalog_past_its_max_age_is_not_trusted() {
+        let catalogs = Arc::new(InMemoryInstrumentCatalog::new());
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        RefreshInstrumentCatalogUseCase::new(
+            Arc::new(FixedSource(vec![Instrument {
+                symbol: "BTCUSDT".into(),
+                base_coin: "BTC".into(),
+                status: "Trading".into(),
+                min_qty: 0.001,
+                tick_size: 0.1,
+                max_leverage: 100.0,
+            }])),
+            catalogs.clone(),
+            clock.clone(),
+        )
+        .execute()
+        .await
+        .unwrap();
+
+        clock.advance(CATALOG_MAX_AGE_SECS);
+        assert!(
+            current_catalog(catalogs.as_ref(), clock.now())
+                .await
+                .is_some()
+        );
+        clock.advance(1);
+        assert!(
+            current_catalog(catalogs.as_ref(), clock.now())
+                .await
+                .is_none()
+        );
+    }
+}
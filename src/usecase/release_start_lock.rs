@@ -0,0 +1,241 @@
+use crate::domain::clock::Clock;
+use crate::domain::runtime::{BotRuntimeRepository, RuntimePhase, StartLockRepository};
+use crate::usecase::stop_task::{TaskController, TaskLiveness};
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReleaseLockOutcome {
+    /// The `starting` lock was rolled back to `stopped`.
+    Released,
+    /// `preview` only: the lock is held and no live task backs it.
+    Releasable,
+    /// There is no lock to release: the row is absent (`None`) or not
+    /// `starting`.
+    NotLocked(Option<RuntimePhase>),
+    /// The lock's task is alive; the RUNNING event will settle the row, and
+    /// releasing now would let a second task launch.
+    TaskAlive { task_id: String },
+}
+
+/// Operator override for a stuck start lock: a `starting` row whose launch
+/// died before it could release it. `StartBotUseCase` reclaims such a lock by
+/// itself after `START_LOCK_STALE_AFTER_SECS`; this releases it now, with the
+/// same guard — never while the lock's task is alive.
+pub struct ReleaseStartLockUseCase {
+    runtimes: Arc<dyn BotRuntimeRepository>,
+    locks: Arc<dyn StartLockRepository>,
+    controller: Arc<dyn TaskController>,
+    clock: Arc<dyn Clock>,
+    cluster_arn: String,
+}
+
+impl ReleaseStartLockUseCase {
+    pub fn new(
+        runtimes: Arc<dyn BotRuntimeRepository>,
+        locks: Arc<dyn StartLockRepository>,
+        controller: Arc<dyn TaskController>,
+        clock: Arc<dyn Clock>,
+        cluster_arn: String,
+    ) -> Self {
+        Self {
+            runtimes,
+            locks,
+            controller,
+            clock,
+            cluster_arn,
+        }
+    }
+
+    /// What `execute` would do, without writing.
+    pub async fn preview(&self, user_id: &str, bot_id: &str) -> Result<ReleaseLockOutcome, String> {
+        let runtime = self
+            .runtimes
+            .find_consistent(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let runtime = match runtime {
+            Some(r) if r.phase == RuntimePhase::Starting => r,
+            other => return Ok(ReleaseLockOutcome::NotLocked(other.map(|r| r.phase))),
+        };
+        if let Some(task_id) = runtime.task_id.filter(|t| !t.is_empty()) {
+            let liveness = self
+                .controller
+                .liveness(&self.cluster_arn, &task_id)
+                .await
+                .map_err(|e| format!("could not confirm task {task_id} is gone: {e}"))?;
+            if liveness == TaskLiveness::Alive {
+                return Ok(ReleaseLockOutcome::TaskAlive { task_id });
+            }
+        }
+        Ok(ReleaseLockOutcome::Releasable)
+    }
+
+    pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<ReleaseLockOutcome, String> {
+        match self.preview(user_id, bot_id).await? {
+            ReleaseLockOutcome::Releasable => {
+                // Conditional on the row still being `starting`: a RUNNING
+                // event landing in between wins.
+                self.locks
+                    .release_start(user_id, bot_id, self.clock.now())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(ReleaseLockOutcome::Released)
+            }
+            other => Ok(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use crate::domain::runtime::{BotRuntime, StartClaim};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// One bot's runtime row; `release_start` applies the same condition as
+    /// the table.
+    struct Row(Mutex<Option<BotRuntime>>);
+
+    #[async_trait]
+    impl BotRuntimeRepository for Row {
+        async fn find(&self, _u: &str, _b: &str) -> Result<Option<BotRuntime>, DomainError> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+        async fn find_all_for_user(&self, _u: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self.0.lock().unwrap().clone().into_iter().collect())
+        }
+        async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
+            *self.0.lock().unwrap() = Some(runtime.clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartLockRepository for Row {
+        async fn try_acquire_start(
+            &self,
+            _u: &str,
+            _b: &str,
+            _now: i64,
+            _stale_after: i64,
+        ) -> Result<StartClaim, DomainError> {
+            unreachable!()
+        }
+        async fn try_acquire_restart(
+            &self,
+            _u: &str,
+            _b: &str,
+            _t: &str,
+            _attempt: u32,
+            _now: i64,
+        ) -> Result<StartClaim, DomainError> {
+            unreachable!()
+        }
+        async fn attach_started_task(
+            &self,
+            _u: &str,
+            _b: &str,
+            _t: &str,
+        ) -> Result<(), DomainError> {
+            unreachable!()
+        }
+        async fn release_start(&self, _u: &str, _b: &str, now: i64) -> Result<(), DomainError> {
+            if let Some(r) = self.0.lock().unwrap().as_mut()
+                && r.phase == RuntimePhase::Starting
+            {
+                r.phase = RuntimePhase::Stopped;
+                r.task_id = None;
+                r.observed_at = now;
+            }
+            Ok(())
+        }
+    }
+
+    struct Controller(TaskLiveness);
+
+    #[async_trait]
+    impl TaskController for Controller {
+        async fn stop(&self, _c: &str, _t: &str, _r: &str) -> Result<()> {
+            Ok(())
+        }
+        async fn liveness(&self, _c: &str, _t: &str) -> Result<TaskLiveness> {
+            Ok(self.0)
+        }
+    }
+
+    fn starting(task_id: Option<&str>) -> BotRuntime {
+        BotRuntime {
+            user_id: "u".into(),
+            bot_id: "b".into(),
+            task_id: task_id.map(str::to_string),
+            phase: RuntimePhase::Starting,
+            version: 1,
+            observed_at: 500,
+            restart_attempts: 0,
+        }
+    }
+
+    fn usecase(row: &Arc<Row>, liveness: TaskLiveness) -> ReleaseStartLockUseCase {
+        ReleaseStartLockUseCase::new(
+            row.clone(),
+            row.clone(),
+            Arc::new(Controller(liveness)),
+            Arc::new(MockClock::new(1_000)),
+            "cluster".to_string(),
+        )
+    }
+
+    fn phase(row: &Row) -> Option<RuntimePhase> {
+        row.0.lock().unwrap().as_ref().map(|r| r.phase.clone())
+    }
+
+    #[tokio::test]
+    async fn releases_a_lock_without_a_live_task() {
+        let row = Arc::new(Row(Mutex::new(None)));
+        let usecase = usecase(&row, TaskLiveness::Gone);
+        assert_eq!(
+            usecase.execute("u", "b").await.unwrap(),
+            ReleaseLockOutcome::NotLocked(None)
+        );
+
+        *row.0.lock().unwrap() = Some(starting(Some("task-1")));
+        assert_eq!(
+            usecase.preview("u", "b").await.unwrap(),
+            ReleaseLockOutcome::Releasable
+        );
+        assert_eq!(phase(&row), Some(RuntimePhase::Starting));
+        assert_eq!(
+            usecase.execute("u", "b").await.unwrap(),
+            ReleaseLockOutcome::Released
+        );
+        assert_eq!(phase(&row), Some(RuntimePhase::Stopped));
+        assert_eq!(
+            usecase.execute("u", "b").await.unwrap(),
+            ReleaseLockOutcome::NotLocked(Some(RuntimePhase::Stopped))
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_a_lock_whose_task_is_alive() {
+        let row = Arc::new(Row(Mutex::new(Some(starting(Some("task-1"))))));
+        let usecase = usecase(&row, TaskLiveness::Alive);
+        assert_eq!(
+            usecase.execute("u", "b").await.unwrap(),
+            ReleaseLockOutcome::TaskAlive {
+                task_id: "task-1".into()
+            }
+        );
+        assert_eq!(phase(&row), Some(RuntimePhase::Starting));
+
+        // A lock with no task attached never launched one.
+        *row.0.lock().unwrap() = Some(starting(None));
+        assert_eq!(
+            usecase.execute("u", "b").await.unwrap(),
+            ReleaseLockOutcome::Released
+        );
+    }
+}
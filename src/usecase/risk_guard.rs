@@ -0,0 +1,100 @@
+use crate::domain::clock::Clock;
+use crate::domain::riskguard::{RiskGuard, RiskGuardRepository};
+use std::sync::Arc;
+
+/// Which of a guard's limits to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardLimit {
+    DailyLoss,
+    Drawdown,
+}
+
+/// A bot's risk guard, or an unset one when the user never configured it.
+pub struct GetRiskGuardUseCase {
+    guards: Arc<dyn RiskGuardRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl GetRiskGuardUseCase {
+    pub fn new(guards: Arc<dyn RiskGuardRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { guards, clock }
+    }
+
+    pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<RiskGuard, String> {
+        Ok(self
+            .guards
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| {
+                RiskGuard::new(user_id.to_string(), bot_id.to_string(), self.clock.now())
+            }))
+    }
+}
+
+/// Set one of a bot's guard limits in percent, or clear it (`None`). Returns
+/// the guard as stored.
+pub struct SetRiskGuardLimitUseCase {
+    guards: Arc<dyn RiskGuardRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetRiskGuardLimitUseCase {
+    pub fn new(guards: Arc<dyn RiskGuardRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { guards, clock }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        limit: GuardLimit,
+        pct: Option<f64>,
+    ) -> Result<RiskGuard, String> {
+        let now = self.clock.now();
+        let mut guard = self
+            .guards
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| RiskGuard::new(user_id.to_string(), bot_id.to_string(), now));
+        match limit {
+            GuardLimit::DailyLoss => guard.set_max_daily_loss(pct, now),
+            GuardLimit::Drawdown => guard.set_max_drawdown(pct, now),
+        }
+        .map_err(|e| e.to_string())?;
+        self.guards
+            .save_limits(&guard)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(guard)
+    }
+}
+
+/// Re-arm a tripped guard. `false` when it was not tripped. The bot's sides
+/// stay in `graceful_stop` until the user turns them back on.
+pub struct RearmRiskGuardUseCase {
+    guards: Arc<dyn RiskGuardRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RearmRiskGuardUseCase {
+    pub fn new(guards: Arc<dyn RiskGuardRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self { guards, clock }
+    }
+
+    pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<bool, String> {
+        let Some(mut guard) = self
+            .guards
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(RiskGuard::is_tripped)
+        else {
+            return Ok(false);
+        };
+        guard.rearm(self.clock.now());
+        self.guards.rearm(&guard).await.map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
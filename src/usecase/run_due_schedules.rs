@@ -0,0 +1,485 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::schedule::{Schedule, ScheduleAction, ScheduleRepository};
+use crate::telemetry::{CorrelationId, with_correlation_id};
+use crate::usecase::{
+    SetStrategySideUseCase, StartBotUseCase, StartOutcome, StopBotUseCase, StopOutcome,
+    UpdateRiskLevelUseCase,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// Port for carrying out a schedule's action on a bot. Returns a short
+/// human-readable summary for the schedule's `last_result`.
+#[async_trait]
+pub trait ScheduleActionRunner: Send + Sync {
+    async fn run(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        action: &ScheduleAction,
+    ) -> Result<String, String>;
+}
+
+/// Production runner: each action goes through the same use case the Telegram
+/// buttons use, so a scheduled start/stop takes the start lock and flips
+/// desired state exactly like a manual one.
+pub struct UseCaseActionRunner {
+    start_bot: Arc<StartBotUseCase>,
+    stop_bot: Arc<StopBotUseCase>,
+    update_risk_level: Arc<UpdateRiskLevelUseCase>,
+    set_strategy_side: Arc<SetStrategySideUseCase>,
+}
+
+impl UseCaseActionRunner {
+    pub fn new(
+        start_bot: Arc<StartBotUseCase>,
+        stop_bot: Arc<StopBotUseCase>,
+        update_risk_level: Arc<UpdateRiskLevelUseCase>,
+        set_strategy_side: Arc<SetStrategySideUseCase>,
+    ) -> Self {
+        Self {
+            start_bot,
+            stop_bot,
+            update_risk_level,
+            set_strategy_side,
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduleActionRunner for UseCaseActionRunner {
+    async fn run(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        action: &ScheduleAction,
+    ) -> Result<String, String> {
+        match action {
+            ScheduleAction::Start => Ok(match self.start_bot.execute(user_id, bot_id).await? {
+                StartOutcome::Started { task_id } => format!("started task {task_id}"),
+                StartOutcome::AlreadyRunning => "already running".to_string(),
+                StartOutcome::AlreadyStarting => "already starting".to_string(),
+                StartOutcome::Stopping => "previous task still stopping; not started".to_string(),
+                StartOutcome::BotNotFound => return Err(format!("bot {bot_id} not found")),
+                StartOutcome::Rejected(problems) => return Err(format!("not started: {problems}")),
+            }),
+            ScheduleAction::Stop => Ok(match self.stop_bot.execute(user_id, bot_id).await? {
+                StopOutcome::Stopped { task_id } => format!("stopped task {task_id}"),
+                StopOutcome::NotRunning => "not running".to_string(),
+                StopOutcome::StartInProgress => {
+                    "start in progress; desired state set off".to_string()
+                }
+                StopOutcome::AlreadyStopping => "already stopping".to_string(),
+                StopOutcome::BotNotFound => return Err(format!("bot {bot_id} not found")),
+            }),
+            ScheduleAction::SetRisk { long, short } => {
+                let leverage = self
+                    .update_risk_level
+                    .execute(user_id, bot_id, *long, *short)
+                    .await?;
+                Ok(format!(
+                    "risk set to {long}/{short}, leverage {:.1}x",
+                    leverage.long
+                ))
+            }
+            ScheduleAction::SetSide { side, enabled } => {
+                let now_enabled = self
+                    .set_strategy_side
+                    .execute(user_id, bot_id, side, *enabled)
+                    .await?;
+                Ok(format!(
+                    "{side} side {}",
+                    if now_enabled { "enabled" } else { "disabled" }
+                ))
+            }
+        }
+    }
+}
+
+/// Outcome of one firing, for the runner's log.
+#[derive(Debug)]
+pub struct ScheduleRun {
+    pub schedule: Schedule,
+    pub result: Result<String, String>,
+}
+
+/// Fire every due schedule once. Each firing is claimed first (conditional
+/// advance of `next_run_at`), so overlapping invocations never fire a slot
+/// twice; a failed action is recorded on the schedule and does not stop the
+/// others. A schedule whose bot no longer exists is deleted instead of fired.
+pub struct RunDueSchedulesUseCase {
+    schedules: Arc<dyn ScheduleRepository>,
+    bots: Arc<dyn BotRepository>,
+    runner: Arc<dyn ScheduleActionRunner>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RunDueSchedulesUseCase {
+    pub fn new(
+        schedules: Arc<dyn ScheduleRepository>,
+        bots: Arc<dyn BotRepository>,
+        runner: Arc<dyn ScheduleActionRunner>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            schedules,
+            bots,
+            runner,
+            clock,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<Vec<ScheduleRun>, String> {
+        let now = self.clock.now();
+        let due = self
+            .schedules
+            .find_due(now)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut runs = Vec::new();
+        for mut schedule in due {
+            // `find_due` may come from an eventually-consistent read.
+            if !schedule.is_due(now) {
+                continue;
+            }
+            // Left behind by a bot delete: it would only fail every time it
+            // comes due. A failed read fires as usual and the action reports it.
+            if let Ok(None) = self.bots.find(&schedule.user_id, &schedule.bot_id).await {
+                match self.schedules.delete(&schedule.user_id, &schedule.id).await {
+                    Ok(_) => tracing::info!(
+                        schedule_id = %schedule.id,
+                        bot_id = %schedule.bot_id,
+                        "deleted schedule of a deleted bot"
+                    ),
+                    Err(e) => tracing::warn!(
+                        schedule_id = %schedule.id,
+                        error = %e,
+                        "failed to delete schedule of a deleted bot"
+                    ),
+                }
+                continue;
+            }
+            let expected = schedule.next_run_at;
+            schedule.advance(now);
+            match self.schedules.claim_run(&schedule, expected).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!(schedule_id = %schedule.id, error = %e, "failed to claim schedule");
+                    continue;
+                }
+            }
+
+            // Each firing is an action of its own, as a button press would be.
+            let correlation_id = CorrelationId::new();
+            let span = tracing::info_span!(
+                "schedule",
+                correlation_id = %correlation_id,
+                schedule_id = %schedule.id,
+                user_id = %schedule.user_id,
+                bot_id = %schedule.bot_id,
+            );
+            let result = with_correlation_id(
+                correlation_id,
+                self.runner
+                    .run(&schedule.user_id, &schedule.bot_id, &schedule.action)
+                    .instrument(span),
+            )
+            .await;
+            let summary = match &result {
+                Ok(s) => s.clone(),
+                Err(e) => format!("failed: {e}"),
+            };
+            if let Err(e) = self
+                .schedules
+                .record_result(&schedule.user_id, &schedule.id, &summary)
+                .await
+            {
+                tracing::warn!(schedule_id = %schedule.id, error = %e, "failed to record schedule result");
+            }
+            schedule.last_result = Some(summary);
+            runs.push(ScheduleRun { schedule, result });
+        }
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use crate::domain::schedule::CronSchedule;
+    use crate::infra::memory::InMemoryBotRepository;
+    use std::sync::Mutex;
+
+    // 2024-01-05 00:00:00 UTC, a Friday.
+    const FRI: i64 = 1_704_412_800;
+
+    /// Mock ScheduleRepository with the same claim semantics as DynamoDB: the
+    /// advance only lands if `next_run_at` still matches.
+    #[derive(Default)]
+    struct InMemorySchedules {
+        rows: Mutex<Vec<Schedule>>,
+    }
+    #[async_trait]
+    impl ScheduleRepository for InMemorySchedules {
+        async fn save(&self, schedule: &Schedule) -> Result<(), DomainError> {
+            let mut rows = self.rows.lock().unwrap();
+            rows.retain(|s| s.id != schedule.id);
+            rows.push(schedule.clone());
+            Ok(())
+        }
+        async fn find_by_user(&self, user_id: &str) -> Result<Vec<Schedule>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| s.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn delete(&self, _user_id: &str, schedule_id: &str) -> Result<bool, DomainError> {
+            let mut rows = self.rows.lock().unwrap();
+            let before = rows.len();
+            rows.retain(|s| s.id != schedule_id);
+            Ok(rows.len() != before)
+        }
+        async fn find_due(&self, now: i64) -> Result<Vec<Schedule>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| s.is_due(now))
+                .cloned()
+                .collect())
+        }
+        async fn claim_run(
+            &self,
+            schedule: &Schedule,
+            expected_next_run_at: i64,
+        ) -> Result<bool, DomainError> {
+            let mut rows = self.rows.lock().unwrap();
+            match rows
+                .iter_mut()
+                .find(|s| s.id == schedule.id && s.next_run_at == expected_next_run_at)
+            {
+                Some(row) => {
+                    row.next_run_at = schedule.next_run_at;
+                    row.last_run_at = schedule.last_run_at;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+        async fn record_result(
+            &self,
+            _user_id: &str,
+            schedule_id: &str,
+            result: &str,
+        ) -> Result<(), DomainError> {
+            if let Some(row) = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|s| s.id == schedule_id)
+            {
+                row.last_result = Some(result.to_string());
+            }
+            Ok(())
+        }
+    }
+
+    /// Records every action it is asked to run; fails for bot `broken`.
+    #[derive(Default)]
+    struct RecordingRunner {
+        calls: Mutex<Vec<(String, String)>>,
+    }
+    #[async_trait]
+    impl ScheduleActionRunner for RecordingRunner {
+        async fn run(
+            &self,
+            _user_id: &str,
+            bot_id: &str,
+            action: &ScheduleAction,
+        ) -> Result<String, String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((bot_id.to_string(), action.to_string()));
+            if bot_id == "broken" {
+                return Err("exchange unavailable".to_string());
+            }
+            Ok(format!("did {action}"))
+        }
+    }
+
+    fn schedule(id: &str, bot_id: &str, cron: &str, action: ScheduleAction) -> Schedule {
+        Schedule::create(
+            id.into(),
+            "u".into(),
+            bot_id.into(),
+            CronSchedule::parse(cron).unwrap(),
+            action,
+            FRI,
+        )
+        .unwrap()
+    }
+
+    async fn setup(
+        rows: Vec<Schedule>,
+    ) -> (
+        Arc<InMemorySchedules>,
+        Arc<RecordingRunner>,
+        Arc<MockClock>,
+        RunDueSchedulesUseCase,
+    ) {
+        // Every scheduled bot exists, except `gone`.
+        let bots = Arc::new(InMemoryBotRepository::new());
+        for s in rows.iter().filter(|s| s.bot_id != "gone") {
+            let bot = Bot::create(
+                s.user_id.clone(),
+                s.bot_id.clone(),
+                "ak".into(),
+                "sk".into(),
+                0,
+            );
+            BotRepository::save(bots.as_ref(), &bot).await.unwrap();
+        }
+        let repo = Arc::new(InMemorySchedules {
+            rows: Mutex::new(rows),
+        });
+        let runner = Arc::new(RecordingRunner::default());
+        let clock = Arc::new(MockClock::new(FRI));
+        let uc = RunDueSchedulesUseCase::new(repo.clone(), bots, runner.clone(), clock.clone());
+        (repo, runner, clock, uc)
+    }
+
+    #[tokio::test]
+    async fn fires_only_when_due_and_once_per_slot() {
+        // weekend pause: Friday 22:00 stop, Monday 06:00 start
+        let (repo, runner, clock, uc) = setup(vec![
+            schedule("stop", "b1", "0 22 * * 5", ScheduleAction::Stop),
+            schedule("start", "b1", "0 6 * * 1", ScheduleAction::Start),
+        ])
+        .await;
+
+        clock.set(FRI + 21 * 3_600 + 59 * 60);
+        assert!(uc.execute().await.unwrap().is_empty());
+
+        clock.set(FRI + 22 * 3_600);
+        let runs = uc.execute().await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].schedule.id, "stop");
+        assert_eq!(runs[0].result.as_deref(), Ok("did stop"));
+
+        // a second invocation in the same minute finds nothing due
+        clock.advance(30);
+        assert!(uc.execute().await.unwrap().is_empty());
+
+        let stop = repo.rows.lock().unwrap()[0].clone();
+        assert_eq!(stop.next_run_at, FRI + 7 * 86_400 + 22 * 3_600);
+        assert_eq!(stop.last_run_at, Some(FRI + 22 * 3_600));
+        assert_eq!(stop.last_result.as_deref(), Some("did stop"));
+
+        clock.set(FRI + 3 * 86_400 + 6 * 3_600);
+        let runs = uc.execute().await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].schedule.id, "start");
+
+        assert_eq!(
+            *runner.calls.lock().unwrap(),
+            vec![
+                ("b1".to_string(), "stop".to_string()),
+                ("b1".to_string(), "start".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn lost_claim_does_not_fire() {
+        let (repo, runner, clock, uc) =
+            setup(vec![schedule("s", "b1", "0 * * * *", ScheduleAction::Stop)]).await;
+        clock.set(FRI + 3_600);
+
+        // another runner advanced the slot between our read and our claim
+        let mut stale = repo.rows.lock().unwrap()[0].clone();
+        stale.advance(FRI + 3_600);
+        assert!(repo.claim_run(&stale, FRI + 3_600).await.unwrap());
+
+        assert!(uc.execute().await.unwrap().is_empty());
+        assert!(runner.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_action_is_recorded_and_others_still_fire() {
+        let (repo, runner, clock, uc) = setup(vec![
+            schedule(
+                "a",
+                "broken",
+                "0 12 * * *",
+                ScheduleAction::SetRisk {
+                    long: 0.5,
+                    short: 0.0,
+                },
+            ),
+            schedule(
+                "b",
+                "b2",
+                "0 12 * * *",
+                ScheduleAction::SetSide {
+                    side: "long".into(),
+                    enabled: false,
+                },
+            ),
+        ])
+        .await;
+        clock.set(FRI + 12 * 3_600 + 10);
+
+        let runs = uc.execute().await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runner.calls.lock().unwrap().len(), 2);
+
+        let rows = repo.rows.lock().unwrap();
+        assert_eq!(
+            rows[0].last_result.as_deref(),
+            Some("failed: exchange unavailable")
+        );
+        assert_eq!(rows[1].last_result.as_deref(), Some("did side:long:off"));
+        // the failed one still moves on to tomorrow's slot
+        assert_eq!(rows[0].next_run_at, FRI + 86_400 + 12 * 3_600);
+    }
+
+    #[tokio::test]
+    async fn a_schedule_of_a_deleted_bot_is_deleted_not_fired() {
+        let (repo, runner, clock, uc) = setup(vec![
+            schedule("orphan", "gone", "0 12 * * *", ScheduleAction::Start),
+            schedule("live", "b1", "0 12 * * *", ScheduleAction::Start),
+        ])
+        .await;
+        clock.set(FRI + 12 * 3_600);
+
+        let runs = uc.execute().await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].schedule.id, "live");
+        assert_eq!(
+            *runner.calls.lock().unwrap(),
+            vec![("b1".to_string(), "start".to_string())]
+        );
+        let ids: Vec<String> = repo
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| s.id.clone())
+            .collect();
+        assert_eq!(ids, vec!["live".to_string()]);
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
-use aws_sdk_ecs::types::{ContainerOverride, KeyValuePair, PropagateTags, TaskOverride};
+use aws_sdk_ecs::types::{ContainerOverride, KeyValuePair, PropagateTags, Tag, TaskOverride};
+
+use crate::domain::resources::ResourceProfile;
+use crate::telemetry::{CORRELATION_ID_ENV, CORRELATION_ID_TAG, correlation_id};
 
 /// Port for starting an ECS task. Lets the reconcile use case depend on an
 /// abstraction (testable with a mock) rather than the concrete RunTaskUseCase.
+/// `resources` sizes the task, overriding the task definition's CPU/memory.
 #[async_trait]
 pub trait TaskRunner: Send + Sync {
     async fn run(
@@ -13,9 +17,46 @@ pub trait TaskRunner: Send + Sync {
         cluster_arn: &str,
         td_arn: &str,
         container_name: &str,
+        resources: ResourceProfile,
     ) -> Result<String>;
 }
 
+/// Size the task and pass the bot to the passivbot container. Task-level
+/// cpu/memory drive placement and the cgroup limit. The container only gets
+/// a soft reservation below the task memory: a hard container limit equal to
+/// the task's would leave nothing for sidecars and fail the launch.
+fn task_overrides(
+    user_id: &str,
+    bot_id: &str,
+    container_name: &str,
+    correlation_id: &str,
+    resources: ResourceProfile,
+) -> TaskOverride {
+    TaskOverride::builder()
+        .cpu(resources.cpu().to_string())
+        .memory(resources.memory_mib().to_string())
+        .container_overrides(
+            ContainerOverride::builder()
+                .name(container_name)
+                .environment(
+                    KeyValuePair::builder()
+                        .name("USER_ID")
+                        .value(user_id)
+                        .build(),
+                )
+                .environment(KeyValuePair::builder().name("BOT_ID").value(bot_id).build())
+                .environment(
+                    KeyValuePair::builder()
+                        .name(CORRELATION_ID_ENV)
+                        .value(correlation_id)
+                        .build(),
+                )
+                .memory_reservation(resources.container_reservation_mib() as i32)
+                .build(),
+        )
+        .build()
+}
+
 pub struct RunTaskUseCase {
     ecs_client: aws_sdk_ecs::Client,
 }
@@ -25,6 +66,11 @@ impl RunTaskUseCase {
         Self { ecs_client: client }
     }
 
+    #[tracing::instrument(
+        name = "ecs_run_task",
+        skip_all,
+        fields(user_id = %user_id, bot_id = %bot_id, task_id = tracing::field::Empty)
+    )]
     pub async fn execute(
         &self,
         user_id: &str,
@@ -32,21 +78,19 @@ impl RunTaskUseCase {
         cluster_arn: &str,
         td_arn: &str,
         container_name: &str,
+        resources: ResourceProfile,
     ) -> Result<String> {
-        let overrides = TaskOverride::builder()
-            .container_overrides(
-                ContainerOverride::builder()
-                    .name(container_name)
-                    .environment(
-                        KeyValuePair::builder()
-                            .name("USER_ID")
-                            .value(user_id)
-                            .build(),
-                    )
-                    .environment(KeyValuePair::builder().name("BOT_ID").value(bot_id).build())
-                    .build(),
-            )
-            .build();
+        // The task carries the action's correlation id so the Lambda's lines
+        // for its RUNNING / STOPPED events join the ones that launched it. A
+        // launch outside any action still gets its own.
+        let correlation_id = correlation_id().unwrap_or_default();
+        let overrides = task_overrides(
+            user_id,
+            bot_id,
+            container_name,
+            correlation_id.as_str(),
+            resources,
+        );
 
         let resp = self
             .ecs_client
@@ -56,6 +100,12 @@ impl RunTaskUseCase {
             .overrides(overrides)
             .enable_ecs_managed_tags(true)
             .propagate_tags(PropagateTags::TaskDefinition)
+            .tags(
+                Tag::builder()
+                    .key(CORRELATION_ID_TAG)
+                    .value(correlation_id.as_str())
+                    .build(),
+            )
             .send()
             .await
             .context("ecs run_task failed")?;
@@ -63,9 +113,10 @@ impl RunTaskUseCase {
         let started = resp.tasks().len();
         let failed = resp.failures().len();
         tracing::info!(
-            "ecs run_task done: started_tasks={}, failures={}",
+            "ecs run_task done: started_tasks={}, failures={}, resources={}",
             started,
-            failed
+            failed,
+            resources
         );
 
         if failed > 0 {
@@ -84,6 +135,7 @@ impl RunTaskUseCase {
             .next()
             .ok_or_else(|| anyhow!("failed to parse task id from taskArn"))?
             .to_string();
+        tracing::Span::current().record("task_id", task_id.as_str());
 
         Ok(task_id)
     }
@@ -98,8 +150,39 @@ impl TaskRunner for RunTaskUseCase {
         cluster_arn: &str,
         td_arn: &str,
         container_name: &str,
+        resources: ResourceProfile,
     ) -> Result<String> {
-        self.execute(user_id, bot_id, cluster_arn, td_arn, container_name)
-            .await
+        self.execute(
+            user_id,
+            bot_id,
+            cluster_arn,
+            td_arn,
+            container_name,
+            resources,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_container_reserves_less_than_the_task_and_sets_no_hard_limit() {
+        let custom = ResourceProfile::Custom {
+            cpu: 128,
+            memory_mib: 128,
+        };
+        for resources in [ResourceProfile::Small, ResourceProfile::Large, custom] {
+            let overrides = task_overrides("u", "b", "passivbot", "cid", resources);
+            let task_memory: i32 = overrides.memory().unwrap().parse().unwrap();
+            assert_eq!(task_memory as u32, resources.memory_mib());
+            let container = &overrides.container_overrides()[0];
+            assert_eq!(container.name(), Some("passivbot"));
+            assert_eq!(container.memory(), None, "{resources}");
+            let reservation = container.memory_reservation().unwrap();
+            assert!(reservation > 0 && reservation < task_memory, "{resources}");
+        }
     }
 }
@@ -0,0 +1,39 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use std::sync::Arc;
+
+/// Set or clear the leveraged-exposure ceiling the risk dialogue warns
+/// against. Persisted on the bot row; nothing running is affected.
+pub struct SetExposureCeilingUseCase {
+    bot_repository: Arc<dyn BotRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetExposureCeilingUseCase {
+    pub fn new(bot_repository: Arc<dyn BotRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            bot_repository,
+            clock,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        ceiling: Option<f64>,
+    ) -> Result<(), String> {
+        let mut bot = self
+            .bot_repository
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("bot {bot_id} not found"))?;
+        bot.set_exposure_ceiling(ceiling, self.clock.now())
+            .map_err(|e| e.to_string())?;
+        self.bot_repository
+            .save(&bot)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
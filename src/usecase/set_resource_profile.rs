@@ -0,0 +1,95 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::resources::ResourceProfile;
+use std::sync::Arc;
+
+/// Select the CPU/memory a bot's task is launched with, persisted on the bot
+/// row. The running task keeps its size: the profile applies from the next
+/// launch, manual or automatic.
+pub struct SetResourceProfileUseCase {
+    bot_repository: Arc<dyn BotRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetResourceProfileUseCase {
+    pub fn new(bot_repository: Arc<dyn BotRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            bot_repository,
+            clock,
+        }
+    }
+
+    /// Parse `profile` (`small`/`medium`/`large` or `<cpu>:<memory>`), store
+    /// it on the bot, and return the stored selection.
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        profile: &str,
+    ) -> Result<ResourceProfile, String> {
+        let profile = ResourceProfile::parse(profile).map_err(|e| e.to_string())?;
+        let mut bot = self
+            .bot_repository
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("bot {bot_id} not found"))?;
+        bot.set_resource_profile(profile, self.clock.now());
+        self.bot_repository
+            .save(&bot)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::clock::MockClock;
+    use crate::infra::memory::InMemoryBotRepository;
+
+    /// Bot `b` of user `u`, and the use case over it at a fixed time.
+    async fn setup() -> (Arc<InMemoryBotRepository>, SetResourceProfileUseCase) {
+        let bots = Arc::new(InMemoryBotRepository::new());
+        let bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 0);
+        bots.save(&bot).await.unwrap();
+        let uc =
+            SetResourceProfileUseCase::new(bots.clone(), Arc::new(MockClock::new(1_700_000_000)));
+        (bots, uc)
+    }
+
+    async fn stored_bot(bots: &InMemoryBotRepository) -> Bot {
+        bots.find("u", "b").await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn stores_parsed_profile_on_bot() {
+        let (bots, uc) = setup().await;
+
+        let stored = uc.execute("u", "b", "512:2048").await.unwrap();
+        assert_eq!(
+            stored,
+            ResourceProfile::Custom {
+                cpu: 512,
+                memory_mib: 2048
+            }
+        );
+
+        let saved = stored_bot(&bots).await;
+        assert_eq!(saved.resource_profile, stored);
+        assert_eq!(saved.updated_at, 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn invalid_profile_is_rejected_without_saving() {
+        let (bots, uc) = setup().await;
+
+        assert!(uc.execute("u", "b", "256:99999").await.is_err());
+        assert_eq!(
+            stored_bot(&bots).await.resource_profile,
+            ResourceProfile::default()
+        );
+    }
+}
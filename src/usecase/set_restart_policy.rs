@@ -0,0 +1,91 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::restart::RestartPolicy;
+use std::sync::Arc;
+
+/// Select when a bot's task is automatically restarted after it stops,
+/// persisted on the bot row. The running task is untouched: the policy is read
+/// when its next STOPPED event is reconciled.
+pub struct SetRestartPolicyUseCase {
+    bot_repository: Arc<dyn BotRepository>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SetRestartPolicyUseCase {
+    pub fn new(bot_repository: Arc<dyn BotRepository>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            bot_repository,
+            clock,
+        }
+    }
+
+    /// Parse `policy` (the compact `on-failure:5` form), store it on the bot,
+    /// and return the stored selection.
+    pub async fn execute(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        policy: &str,
+    ) -> Result<RestartPolicy, String> {
+        let policy = RestartPolicy::parse(policy).map_err(|e| e.to_string())?;
+        let mut bot = self
+            .bot_repository
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("bot {bot_id} not found"))?;
+        bot.set_restart_policy(policy, self.clock.now());
+        self.bot_repository
+            .save(&bot)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::clock::MockClock;
+    use crate::domain::restart::RestartMode;
+    use crate::infra::memory::InMemoryBotRepository;
+
+    /// Bot `b` of user `u`, and the use case over it at a fixed time.
+    async fn setup() -> (Arc<InMemoryBotRepository>, SetRestartPolicyUseCase) {
+        let bots = Arc::new(InMemoryBotRepository::new());
+        let bot = Bot::create("u".into(), "b".into(), "ak".into(), "sk".into(), 0);
+        bots.save(&bot).await.unwrap();
+        let uc =
+            SetRestartPolicyUseCase::new(bots.clone(), Arc::new(MockClock::new(1_700_000_000)));
+        (bots, uc)
+    }
+
+    async fn stored_bot(bots: &InMemoryBotRepository) -> Bot {
+        bots.find("u", "b").await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn stores_parsed_policy_on_bot() {
+        let (bots, uc) = setup().await;
+
+        let stored = uc.execute("u", "b", "on-failure:5").await.unwrap();
+        assert_eq!(stored.mode, RestartMode::OnFailure);
+        assert_eq!(stored.max_attempts, Some(5));
+
+        let saved = stored_bot(&bots).await;
+        assert_eq!(saved.restart_policy, stored);
+        assert_eq!(saved.updated_at, 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn invalid_policy_is_rejected_without_saving() {
+        let (bots, uc) = setup().await;
+
+        assert!(uc.execute("u", "b", "on-failure:0").await.is_err());
+        assert_eq!(
+            stored_bot(&bots).await.restart_policy,
+            RestartPolicy::default()
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use crate::domain::bot::BotRepository;
+use crate::domain::botconfig::BotConfigRepository;
 use crate::domain::clock::Clock;
+use crate::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+use crate::domain::instrument::InstrumentCatalogRepository;
+use crate::domain::metrics::{self, Metrics};
 use crate::domain::runtime::{BotRuntimeRepository, RuntimePhase, StartClaim, StartLockRepository};
+use crate::usecase::refresh_instruments::current_catalog;
 use crate::usecase::run_task::TaskRunner;
 use crate::usecase::stop_task::{TaskController, TaskLiveness};
 use std::sync::Arc;
@@ -24,11 +29,30 @@ pub enum StartOutcome {
     /// should retry once it settles. Launching now is refused to never double-run.
     Stopping,
     BotNotFound,
+    /// The config names a coin the exchange does not list or trade, or a
+    /// leverage above a coin's maximum; nothing was launched.
+    Rejected(String),
+}
+
+impl StartOutcome {
+    /// The `outcome` label of [`metrics::BOT_STARTS`].
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Started { .. } => "started",
+            Self::AlreadyRunning => "already_running",
+            Self::AlreadyStarting => "already_starting",
+            Self::Stopping => "stopping",
+            Self::BotNotFound => "bot_not_found",
+            Self::Rejected(_) => "rejected",
+        }
+    }
 }
 
 /// Turns the user's "Run bot" intent into a single ECS task launch.
 ///
 /// Order is deliberate and money-critical:
+/// 0. check the config against the cached instrument catalog (skipped when
+///    there is none) so an impossible config never becomes a crash-looping task,
 /// 1. flip desired state ON (so the reconcile Lambda will keep it up),
 /// 2. claim the exclusive start lock (CAS) — only the winner launches,
 /// 3. launch, then record the task id so a stop during startup can find it.
@@ -39,6 +63,10 @@ pub struct StartBotUseCase {
     runner: Arc<dyn TaskRunner>,
     controller: Arc<dyn TaskController>,
     clock: Arc<dyn Clock>,
+    history: Arc<dyn BotHistoryRepository>,
+    metrics: Arc<dyn Metrics>,
+    configs: Arc<dyn BotConfigRepository>,
+    instruments: Arc<dyn InstrumentCatalogRepository>,
     cluster_arn: String,
     td_arn: String,
     container_name: String,
@@ -53,6 +81,10 @@ impl StartBotUseCase {
         runner: Arc<dyn TaskRunner>,
         controller: Arc<dyn TaskController>,
         clock: Arc<dyn Clock>,
+        history: Arc<dyn BotHistoryRepository>,
+        metrics: Arc<dyn Metrics>,
+        configs: Arc<dyn BotConfigRepository>,
+        instruments: Arc<dyn InstrumentCatalogRepository>,
         cluster_arn: String,
         td_arn: String,
         container_name: String,
@@ -64,13 +96,41 @@ impl StartBotUseCase {
             runner,
             controller,
             clock,
+            history,
+            metrics,
+            configs,
+            instruments,
             cluster_arn,
             td_arn,
             container_name,
         }
     }
 
+    /// Start the bot; a launch is appended to the bot's history (best-effort)
+    /// and every attempt is counted by outcome.
+    #[tracing::instrument(
+        name = "start_bot",
+        skip_all,
+        fields(user_id = %user_id, bot_id = %bot_id, task_id = tracing::field::Empty)
+    )]
     pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<StartOutcome, String> {
+        let result = self.start(user_id, bot_id).await;
+        let label = result.as_ref().map_or("error", StartOutcome::metric_label);
+        self.metrics
+            .increment(metrics::BOT_STARTS, &[("outcome", label)]);
+        let outcome = result?;
+        if let StartOutcome::Started { task_id } = &outcome {
+            tracing::Span::current().record("task_id", task_id.as_str());
+            let mut event = BotEvent::new(user_id, bot_id, BotEventKind::Started, self.clock.now());
+            event.task_id = Some(task_id.clone());
+            if let Err(e) = self.history.append(&event).await {
+                tracing::warn!("failed to append history for bot {bot_id}: {e}");
+            }
+        }
+        Ok(outcome)
+    }
+
+    async fn start(&self, user_id: &str, bot_id: &str) -> Result<StartOutcome, String> {
         let mut bot = match self
             .bots
             .find(user_id, bot_id)
@@ -80,6 +140,9 @@ impl StartBotUseCase {
             Some(b) => b,
             None => return Ok(StartOutcome::BotNotFound),
         };
+        if let Some(problems) = self.preflight(user_id, bot_id).await {
+            return Ok(StartOutcome::Rejected(problems));
+        }
 
         // Desired state ON first: intent is recorded even if the launch fails,
         // and auto-restart keys off it.
@@ -140,6 +203,7 @@ impl StartBotUseCase {
                 &self.cluster_arn,
                 &self.td_arn,
                 &self.container_name,
+                bot.resource_profile,
             )
             .await
         {
@@ -159,6 +223,24 @@ impl StartBotUseCase {
             }
         }
     }
+
+    /// Why the exchange would refuse this bot's config, if it would. A missing
+    /// catalog or an unreadable config is not a reason to block the launch;
+    /// the latter is the container's to report.
+    async fn preflight(&self, user_id: &str, bot_id: &str) -> Option<String> {
+        let catalog = current_catalog(self.instruments.as_ref(), self.clock.now()).await?;
+        let config = match self.configs.get(user_id, bot_id).await {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("skipping the instrument check for bot {bot_id}: {e}");
+                return None;
+            }
+        };
+        config
+            .check_instruments(&catalog)
+            .err()
+            .map(|e| e.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -167,7 +249,10 @@ mod tests {
     use crate::domain::bot::Bot;
     use crate::domain::error::DomainError;
     use crate::domain::exchange::Exchange;
+    use crate::domain::metrics::{NoopMetrics, RecordingMetrics};
+    use crate::domain::resources::ResourceProfile;
     use crate::domain::runtime::BotRuntime;
+    use crate::infra::memory::{InMemoryBotConfigRepository, InMemoryInstrumentCatalog};
     use anyhow::{Result, anyhow};
     use async_trait::async_trait;
     use std::collections::HashMap;
@@ -175,6 +260,23 @@ mod tests {
 
     const NOW: i64 = 1_700_000_000;
 
+    /// History is covered by the reconcile tests; here appends are dropped.
+    struct NoHistory;
+    #[async_trait]
+    impl BotHistoryRepository for NoHistory {
+        async fn append(&self, _event: &BotEvent) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn recent(
+            &self,
+            _user_id: &str,
+            _bot_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<BotEvent>, DomainError> {
+            Ok(Vec::new())
+        }
+    }
+
     struct FixedClock;
     impl Clock for FixedClock {
         fn now(&self) -> i64 {
@@ -260,6 +362,16 @@ mod tests {
                 .get(&(user_id.to_string(), bot_id.to_string()))
                 .cloned())
         }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .runtimes
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
         async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
             self.runtimes.lock().unwrap().insert(
                 (runtime.user_id.clone(), runtime.bot_id.clone()),
@@ -304,6 +416,7 @@ mod tests {
             _u: &str,
             _b: &str,
             _stopped: &str,
+            _attempt: u32,
             _now: i64,
         ) -> Result<StartClaim, DomainError> {
             Ok(StartClaim::Acquired)
@@ -323,32 +436,41 @@ mod tests {
         }
     }
 
-    /// Mock TaskRunner that either returns a fixed id or fails, counting calls.
+    /// Mock TaskRunner that either returns a fixed id or fails, recording the
+    /// profile of every launch.
     struct MockRunner {
         result: std::result::Result<String, String>,
-        calls: Mutex<usize>,
+        launched: Mutex<Vec<ResourceProfile>>,
     }
     impl MockRunner {
         fn ok(task_id: &str) -> Self {
             Self {
                 result: Ok(task_id.to_string()),
-                calls: Mutex::new(0),
+                launched: Mutex::new(Vec::new()),
             }
         }
         fn fail(msg: &str) -> Self {
             Self {
                 result: Err(msg.to_string()),
-                calls: Mutex::new(0),
+                launched: Mutex::new(Vec::new()),
             }
         }
         fn call_count(&self) -> usize {
-            *self.calls.lock().unwrap()
+            self.launched.lock().unwrap().len()
         }
     }
     #[async_trait]
     impl TaskRunner for MockRunner {
-        async fn run(&self, _u: &str, _b: &str, _c: &str, _t: &str, _n: &str) -> Result<String> {
-            *self.calls.lock().unwrap() += 1;
+        async fn run(
+            &self,
+            _u: &str,
+            _b: &str,
+            _c: &str,
+            _t: &str,
+            _n: &str,
+            resources: ResourceProfile,
+        ) -> Result<String> {
+            self.launched.lock().unwrap().push(resources);
             self.result.clone().map_err(|e| anyhow!(e))
         }
     }
@@ -399,6 +521,7 @@ mod tests {
             phase: RuntimePhase::Starting,
             version: 1,
             observed_at: NOW - START_LOCK_STALE_AFTER_SECS - 1,
+            restart_attempts: 0,
         }
     }
 
@@ -416,6 +539,10 @@ mod tests {
             runner,
             controller,
             Arc::new(FixedClock),
+            Arc::new(NoHistory),
+            Arc::new(NoopMetrics),
+            Arc::new(InMemoryBotConfigRepository::new()),
+            Arc::new(InMemoryInstrumentCatalog::new()),
             "cluster".to_string(),
             "td".to_string(),
             "container".to_string(),
@@ -457,6 +584,27 @@ mod tests {
         assert_eq!(*locks.released.lock().unwrap(), 0);
     }
 
+    #[tokio::test]
+    async fn launches_with_the_bots_resource_profile() {
+        let mut large = bot(false);
+        large.set_resource_profile(ResourceProfile::Large, 1);
+        let bots = Arc::new(InMemoryBots::with(large));
+        let runner = Arc::new(MockRunner::ok("task-xyz"));
+        let uc = use_case(
+            bots,
+            Arc::new(InMemoryRuntimes::default()),
+            Arc::new(MockLock::new(StartClaim::Acquired)),
+            runner.clone(),
+            Arc::new(MockController::new(TaskLiveness::Gone)),
+        );
+
+        uc.execute("user-1", "bot-1").await.unwrap();
+        assert_eq!(
+            *runner.launched.lock().unwrap(),
+            vec![ResourceProfile::Large]
+        );
+    }
+
     #[tokio::test]
     async fn already_running_does_not_launch_but_still_enables() {
         let bots = Arc::new(InMemoryBots::with(bot(false)));
@@ -537,6 +685,113 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn counts_each_attempt_by_outcome() {
+        let metrics = Arc::new(RecordingMetrics::default());
+        let runner = Arc::new(MockRunner::fail("ecs run_task failed"));
+        let uc = StartBotUseCase::new(
+            Arc::new(InMemoryBots::with(bot(false))),
+            Arc::new(InMemoryRuntimes::default()),
+            Arc::new(MockLock::new(StartClaim::Acquired)),
+            runner,
+            Arc::new(MockController::new(TaskLiveness::Gone)),
+            Arc::new(FixedClock),
+            Arc::new(NoHistory),
+            metrics.clone(),
+            Arc::new(InMemoryBotConfigRepository::new()),
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            "cluster".to_string(),
+            "td".to_string(),
+            "container".to_string(),
+        );
+
+        uc.execute("user-1", "bot-1").await.unwrap_err();
+        uc.execute("user-1", "ghost").await.unwrap();
+        assert_eq!(
+            metrics.count(metrics::BOT_STARTS, &[("outcome", "error")]),
+            1
+        );
+        assert_eq!(
+            metrics.count(metrics::BOT_STARTS, &[("outcome", "bot_not_found")]),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn a_config_the_exchange_cannot_trade_is_rejected_before_anything_changes() {
+        use crate::domain::botconfig::{BotConfig, BotType};
+        use crate::domain::instrument::{Instrument, InstrumentCatalog};
+
+        let bots = Arc::new(InMemoryBots::with(bot(false)));
+        let locks = Arc::new(MockLock::new(StartClaim::Acquired));
+        let runner = Arc::new(MockRunner::ok("task-xyz"));
+        let metrics = Arc::new(RecordingMetrics::default());
+        let configs = Arc::new(InMemoryBotConfigRepository::new());
+        configs
+            .save(&BotConfig {
+                user_id: "user-1".into(),
+                bot_id: "bot-1".into(),
+                bot_type: BotType::Passivbot,
+                template_name: "t".into(),
+                template_version: None,
+                config_data: serde_json::json!({
+                    "live": { "leverage": 20.0, "approved_coins": { "long": ["BTC"], "short": ["GONE"] } }
+                }),
+                created_at: 0,
+                updated_at: 0,
+            })
+            .await
+            .unwrap();
+        let instruments = Arc::new(InMemoryInstrumentCatalog::new());
+        instruments
+            .save(&InstrumentCatalog {
+                exchange: Exchange::Bybit,
+                fetched_at: NOW,
+                instruments: vec![Instrument {
+                    symbol: "BTCUSDT".into(),
+                    base_coin: "BTC".into(),
+                    status: "Trading".into(),
+                    min_qty: 0.001,
+                    tick_size: 0.1,
+                    max_leverage: 10.0,
+                }],
+            })
+            .await
+            .unwrap();
+        let uc = StartBotUseCase::new(
+            bots.clone(),
+            Arc::new(InMemoryRuntimes::default()),
+            locks.clone(),
+            runner.clone(),
+            Arc::new(MockController::new(TaskLiveness::Gone)),
+            Arc::new(FixedClock),
+            Arc::new(NoHistory),
+            metrics.clone(),
+            configs,
+            instruments,
+            "cluster".to_string(),
+            "td".to_string(),
+            "container".to_string(),
+        );
+
+        let out = uc.execute("user-1", "bot-1").await.unwrap();
+        assert_eq!(
+            out,
+            StartOutcome::Rejected(
+                "invalid config: GONE is not listed on bybit; \
+                 leverage 20x exceeds the 10x maximum of BTCUSDT"
+                    .to_string()
+            )
+        );
+        assert_eq!(runner.call_count(), 0);
+        assert_eq!(*locks.acquire_calls.lock().unwrap(), 0);
+        assert!(!bots.get("user-1", "bot-1").unwrap().enabled);
+        assert_eq!(
+            metrics.count(metrics::BOT_STARTS, &[("outcome", "rejected")]),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn missing_bot_returns_not_found_without_claiming() {
         let bots = Arc::new(InMemoryBots::default());
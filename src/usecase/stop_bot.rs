@@ -1,5 +1,8 @@
 use crate::domain::bot::BotRepository;
 use crate::domain::clock::Clock;
+use crate::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+use crate::domain::metrics::{self, Metrics};
+use crate::domain::riskguard::RiskGuardRepository;
 use crate::domain::runtime::{BotRuntime, BotRuntimeRepository, RuntimePhase};
 use crate::usecase::stop_task::{TaskController, TaskLiveness};
 use std::sync::Arc;
@@ -20,38 +23,98 @@ pub enum StopOutcome {
     BotNotFound,
 }
 
+impl StopOutcome {
+    /// The `outcome` label of [`metrics::BOT_STOPS`].
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Stopped { .. } => "stopped",
+            Self::NotRunning => "not_running",
+            Self::StartInProgress => "start_in_progress",
+            Self::AlreadyStopping => "already_stopping",
+            Self::BotNotFound => "bot_not_found",
+        }
+    }
+}
+
 /// Turns the user's "Stop bot" intent into an ECS StopTask.
 ///
 /// Desired state is flipped OFF first so that the STOPPED event from our own
 /// StopTask (and any racing observation) is reconciled as user-initiated and
 /// never auto-restarted. The task to stop is located by the task id recorded on
 /// the runtime row, read strongly-consistently so a just-started task is seen.
+/// A restart the risk guard has pending is called off too, so the job cannot
+/// undo the user's stop.
 pub struct StopBotUseCase {
     bots: Arc<dyn BotRepository>,
     runtimes: Arc<dyn BotRuntimeRepository>,
+    guards: Arc<dyn RiskGuardRepository>,
     stopper: Arc<dyn TaskController>,
     clock: Arc<dyn Clock>,
+    history: Arc<dyn BotHistoryRepository>,
+    metrics: Arc<dyn Metrics>,
     cluster_arn: String,
 }
 
 impl StopBotUseCase {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         bots: Arc<dyn BotRepository>,
         runtimes: Arc<dyn BotRuntimeRepository>,
+        guards: Arc<dyn RiskGuardRepository>,
         stopper: Arc<dyn TaskController>,
         clock: Arc<dyn Clock>,
+        history: Arc<dyn BotHistoryRepository>,
+        metrics: Arc<dyn Metrics>,
         cluster_arn: String,
     ) -> Self {
         Self {
             bots,
             runtimes,
+            guards,
             stopper,
             clock,
+            history,
+            metrics,
             cluster_arn,
         }
     }
 
+    /// Stop the bot; a stop that reached (or will reach) a task is appended to
+    /// the bot's history (best-effort); every attempt is counted by outcome.
+    #[tracing::instrument(
+        name = "stop_bot",
+        skip_all,
+        fields(user_id = %user_id, bot_id = %bot_id, task_id = tracing::field::Empty)
+    )]
     pub async fn execute(&self, user_id: &str, bot_id: &str) -> Result<StopOutcome, String> {
+        let result = self.stop(user_id, bot_id).await;
+        let label = result.as_ref().map_or("error", StopOutcome::metric_label);
+        self.metrics
+            .increment(metrics::BOT_STOPS, &[("outcome", label)]);
+        let outcome = result?;
+        let (task_id, detail) = match &outcome {
+            StopOutcome::Stopped { task_id } => (Some(task_id.clone()), None),
+            StopOutcome::StartInProgress => (None, Some("during startup".to_string())),
+            _ => return Ok(outcome),
+        };
+        if let Some(task_id) = &task_id {
+            tracing::Span::current().record("task_id", task_id.as_str());
+        }
+        let mut event = BotEvent::new(
+            user_id,
+            bot_id,
+            BotEventKind::StopRequested,
+            self.clock.now(),
+        );
+        event.task_id = task_id;
+        event.detail = detail;
+        if let Err(e) = self.history.append(&event).await {
+            tracing::warn!("failed to append history for bot {bot_id}: {e}");
+        }
+        Ok(outcome)
+    }
+
+    async fn stop(&self, user_id: &str, bot_id: &str) -> Result<StopOutcome, String> {
         let mut bot = match self
             .bots
             .find(user_id, bot_id)
@@ -67,6 +130,12 @@ impl StopBotUseCase {
         let now = self.clock.now();
         bot.disable(now);
         self.bots.save(&bot).await.map_err(|e| e.to_string())?;
+        // The guard's own stop comes through here as well; it marks the
+        // restart only after this returns.
+        self.guards
+            .set_restart_pending(user_id, bot_id, false)
+            .await
+            .map_err(|e| e.to_string())?;
 
         let runtime = self
             .runtimes
@@ -150,12 +219,31 @@ mod tests {
     use crate::domain::bot::Bot;
     use crate::domain::error::DomainError;
     use crate::domain::exchange::Exchange;
+    use crate::domain::metrics::NoopMetrics;
     use crate::domain::runtime::BotRuntime;
+    use crate::infra::memory::InMemoryBotRepository;
     use anyhow::Result;
     use async_trait::async_trait;
     use std::collections::HashMap;
     use std::sync::Mutex;
 
+    /// History is covered by the reconcile tests; here appends are dropped.
+    struct NoHistory;
+    #[async_trait]
+    impl BotHistoryRepository for NoHistory {
+        async fn append(&self, _event: &BotEvent) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn recent(
+            &self,
+            _user_id: &str,
+            _bot_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<BotEvent>, DomainError> {
+            Ok(Vec::new())
+        }
+    }
+
     struct FixedClock;
     impl Clock for FixedClock {
         fn now(&self) -> i64 {
@@ -241,6 +329,16 @@ mod tests {
                 .get(&(user_id.to_string(), bot_id.to_string()))
                 .cloned())
         }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .runtimes
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
         async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
             self.runtimes.lock().unwrap().insert(
                 (runtime.user_id.clone(), runtime.bot_id.clone()),
@@ -294,6 +392,7 @@ mod tests {
             phase: RuntimePhase::Starting,
             version: 1,
             observed_at: 1_699_999_000,
+            restart_attempts: 0,
         }
     }
 
@@ -305,8 +404,11 @@ mod tests {
         StopBotUseCase::new(
             bots,
             runtimes,
+            Arc::new(InMemoryBotRepository::new()),
             stopper,
             Arc::new(FixedClock),
+            Arc::new(NoHistory),
+            Arc::new(NoopMetrics),
             "cluster".to_string(),
         )
     }
@@ -0,0 +1,1053 @@
+use crate::domain::bot::BotRepository;
+use crate::domain::clock::Clock;
+use crate::domain::restart::RestartMode;
+use crate::domain::runtime::{
+    BotRuntime, BotRuntimeRepository, RuntimePhase, RuntimeScanRepository, StartClaim,
+    StartLockRepository,
+};
+use crate::usecase::list_tasks::{LiveTask, TaskInventory};
+use crate::usecase::run_task::TaskRunner;
+use crate::usecase::start_bot::START_LOCK_STALE_AFTER_SECS;
+use crate::usecase::stop_task::{TaskController, TaskLiveness};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+type BotKey = (String, String);
+
+/// One repair (or unrepairable finding) made by a sweep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SweepAction {
+    /// The row pointed at a task that is gone and the bot is not to be kept up.
+    RecordedStopped {
+        user_id: String,
+        bot_id: String,
+        task_id: Option<String>,
+    },
+    /// A live task the row did not know about (lost RUNNING event) was adopted.
+    RecordedRunning {
+        user_id: String,
+        bot_id: String,
+        task_id: String,
+    },
+    /// An abandoned `starting` lock of a disabled bot was released.
+    ReleasedStaleStart { user_id: String, bot_id: String },
+    /// The task of an enabled bot vanished; a replacement was launched.
+    Restarted {
+        user_id: String,
+        bot_id: String,
+        lost_task_id: Option<String>,
+        task_id: String,
+    },
+    /// A live task of a disabled or deleted bot was stopped.
+    StoppedOrphan {
+        user_id: String,
+        bot_id: String,
+        task_id: String,
+        reason: String,
+    },
+    /// A live task without USER_ID/BOT_ID overrides; left alone.
+    Unidentified { task_id: String },
+    /// The bot has a live task other than the one its row records; left for
+    /// an operator since either could be the one trading.
+    Conflict {
+        user_id: String,
+        bot_id: String,
+        recorded_task_id: Option<String>,
+        live_task_id: String,
+    },
+    Failed {
+        user_id: String,
+        bot_id: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for SweepAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepAction::RecordedStopped {
+                user_id,
+                bot_id,
+                task_id,
+            } => write!(
+                f,
+                "{user_id}/{bot_id}: recorded stopped (task {} is gone)",
+                task_id.as_deref().unwrap_or("-")
+            ),
+            SweepAction::RecordedRunning {
+                user_id,
+                bot_id,
+                task_id,
+            } => write!(f, "{user_id}/{bot_id}: recorded running task {task_id}"),
+            SweepAction::ReleasedStaleStart { user_id, bot_id } => {
+                write!(f, "{user_id}/{bot_id}: released stale start lock")
+            }
+            SweepAction::Restarted {
+                user_id,
+                bot_id,
+                lost_task_id,
+                task_id,
+            } => write!(
+                f,
+                "{user_id}/{bot_id}: task {} vanished, started {task_id}",
+                lost_task_id.as_deref().unwrap_or("-")
+            ),
+            SweepAction::StoppedOrphan {
+                user_id,
+                bot_id,
+                task_id,
+                reason,
+            } => write!(
+                f,
+                "{user_id}/{bot_id}: stopped orphan task {task_id} ({reason})"
+            ),
+            SweepAction::Unidentified { task_id } => {
+                write!(f, "task {task_id}: no USER_ID/BOT_ID overrides")
+            }
+            SweepAction::Conflict {
+                user_id,
+                bot_id,
+                recorded_task_id,
+                live_task_id,
+            } => write!(
+                f,
+                "{user_id}/{bot_id}: live task {live_task_id} but row records {}",
+                recorded_task_id.as_deref().unwrap_or("none")
+            ),
+            SweepAction::Failed {
+                user_id,
+                bot_id,
+                error,
+            } => write!(f, "{user_id}/{bot_id}: failed: {error}"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SweepSummary {
+    pub rows_checked: usize,
+    pub live_tasks: usize,
+    pub actions: Vec<SweepAction>,
+}
+
+impl SweepSummary {
+    pub fn failed(&self) -> usize {
+        self.actions
+            .iter()
+            .filter(|a| matches!(a, SweepAction::Failed { .. }))
+            .count()
+    }
+}
+
+/// Anti-entropy pass over the observed-state model. The runtime rows are only
+/// as good as the ECS events that reach `task_state_change_handler`; a lost
+/// RUNNING or STOPPED leaves a row wrong until something notices. This compares
+/// every row with what ECS actually runs and repairs the difference through the
+/// same conditional writes and start lock as the event path:
+///
+/// 1. per row: a `running`/`stopping` task that is gone is settled (or, for an
+///    enabled bot, replaced); a stale `starting` lock is resolved to the task
+///    it launched, relaunched, or released;
+/// 2. per live task: one whose bot is disabled or deleted is stopped, an
+///    unrecorded one of an enabled bot is adopted.
+///
+/// Every write re-reads the row first and skips if it moved since the scan, so
+/// a sweep racing the telebot or the Lambda never overwrites fresher state.
+pub struct SweepRuntimesUseCase {
+    scan: Arc<dyn RuntimeScanRepository>,
+    bots: Arc<dyn BotRepository>,
+    runtimes: Arc<dyn BotRuntimeRepository>,
+    locks: Arc<dyn StartLockRepository>,
+    inventory: Arc<dyn TaskInventory>,
+    runner: Arc<dyn TaskRunner>,
+    controller: Arc<dyn TaskController>,
+    clock: Arc<dyn Clock>,
+    cluster_arn: String,
+    td_arn: String,
+    container_name: String,
+}
+
+impl SweepRuntimesUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        scan: Arc<dyn RuntimeScanRepository>,
+        bots: Arc<dyn BotRepository>,
+        runtimes: Arc<dyn BotRuntimeRepository>,
+        locks: Arc<dyn StartLockRepository>,
+        inventory: Arc<dyn TaskInventory>,
+        runner: Arc<dyn TaskRunner>,
+        controller: Arc<dyn TaskController>,
+        clock: Arc<dyn Clock>,
+        cluster_arn: String,
+        td_arn: String,
+        container_name: String,
+    ) -> Self {
+        Self {
+            scan,
+            bots,
+            runtimes,
+            locks,
+            inventory,
+            runner,
+            controller,
+            clock,
+            cluster_arn,
+            td_arn,
+            container_name,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<SweepSummary, String> {
+        // Rows before tasks: a task launched between the two reads then shows
+        // up live, instead of a row naming a task the listing predates.
+        let scanned = self
+            .scan
+            .scan_all()
+            .await
+            .map_err(|e| format!("Failed to scan runtimes: {e}"))?;
+        let live = self
+            .inventory
+            .list_running(&self.cluster_arn)
+            .await
+            .map_err(|e| format!("Failed to list ECS tasks: {e:#}"))?;
+        let now = self.clock.now();
+
+        let mut summary = SweepSummary {
+            rows_checked: scanned.len(),
+            live_tasks: live.len(),
+            actions: Vec::new(),
+        };
+        // Tracks the sweep's own writes so pass 2 does not flag a task pass 1
+        // just adopted or launched.
+        let mut rows: HashMap<BotKey, BotRuntime> = scanned
+            .iter()
+            .map(|r| ((r.user_id.clone(), r.bot_id.clone()), r.clone()))
+            .collect();
+
+        for row in &scanned {
+            match self.sweep_row(row, &live, &mut rows, now).await {
+                Ok(Some(action)) => summary.actions.push(action),
+                Ok(None) => {}
+                Err(error) => summary.actions.push(SweepAction::Failed {
+                    user_id: row.user_id.clone(),
+                    bot_id: row.bot_id.clone(),
+                    error,
+                }),
+            }
+        }
+
+        for task in &live {
+            let (Some(user_id), Some(bot_id)) = (task.user_id.as_deref(), task.bot_id.as_deref())
+            else {
+                summary.actions.push(SweepAction::Unidentified {
+                    task_id: task.task_id.clone(),
+                });
+                continue;
+            };
+            match self
+                .sweep_live_task(task, user_id, bot_id, &live, &mut rows, now)
+                .await
+            {
+                Ok(Some(action)) => summary.actions.push(action),
+                Ok(None) => {}
+                Err(error) => summary.actions.push(SweepAction::Failed {
+                    user_id: user_id.to_string(),
+                    bot_id: bot_id.to_string(),
+                    error,
+                }),
+            }
+        }
+        Ok(summary)
+    }
+
+    async fn sweep_row(
+        &self,
+        row: &BotRuntime,
+        live: &[LiveTask],
+        rows: &mut HashMap<BotKey, BotRuntime>,
+        now: i64,
+    ) -> Result<Option<SweepAction>, String> {
+        match row.phase {
+            RuntimePhase::Stopped => Ok(None),
+            RuntimePhase::Running => {
+                let Some(task_id) = row.task_id.as_deref() else {
+                    return Ok(None);
+                };
+                if self.is_alive(live, task_id).await? {
+                    return Ok(None);
+                }
+                self.replace_lost_task(row, Some(task_id), live, rows, now)
+                    .await
+            }
+            RuntimePhase::Starting => {
+                if now - row.observed_at < START_LOCK_STALE_AFTER_SECS {
+                    return Ok(None);
+                }
+                if let Some(task_id) = row.task_id.as_deref()
+                    && self.is_alive(live, task_id).await?
+                {
+                    // The launch worked; only its RUNNING event was lost.
+                    return self
+                        .adopt(&row.user_id, &row.bot_id, task_id, rows, now)
+                        .await;
+                }
+                self.replace_lost_task(row, None, live, rows, now).await
+            }
+            RuntimePhase::Stopping => {
+                let Some(task_id) = row.task_id.as_deref() else {
+                    return Ok(None);
+                };
+                if self.is_alive(live, task_id).await? {
+                    return Ok(None);
+                }
+                self.settle_stopped(row, rows).await
+            }
+        }
+    }
+
+    async fn sweep_live_task(
+        &self,
+        task: &LiveTask,
+        user_id: &str,
+        bot_id: &str,
+        live: &[LiveTask],
+        rows: &mut HashMap<BotKey, BotRuntime>,
+        now: i64,
+    ) -> Result<Option<SweepAction>, String> {
+        let bot = self
+            .bots
+            .find_consistent(user_id, bot_id)
+            .await
+            .map_err(|e| format!("Failed to read bot: {e}"))?;
+        let orphan_reason = match &bot {
+            None => Some("bot deleted"),
+            Some(b) if !b.enabled => Some("bot disabled"),
+            Some(_) => None,
+        };
+        if let Some(reason) = orphan_reason {
+            self.controller
+                .stop(
+                    &self.cluster_arn,
+                    &task.task_id,
+                    &format!("anti-entropy: {reason}"),
+                )
+                .await
+                .map_err(|e| format!("Failed to stop task {}: {e:#}", task.task_id))?;
+            return Ok(Some(SweepAction::StoppedOrphan {
+                user_id: user_id.to_string(),
+                bot_id: bot_id.to_string(),
+                task_id: task.task_id.clone(),
+                reason: reason.to_string(),
+            }));
+        }
+
+        let row = rows.get(&(user_id.to_string(), bot_id.to_string()));
+        if row.and_then(|r| r.task_id.as_deref()) == Some(task.task_id.as_str()) {
+            return Ok(None);
+        }
+        let unrecorded = row.is_none_or(|r| r.phase == RuntimePhase::Stopped);
+        if unrecorded && live_for(live, user_id, bot_id).len() == 1 {
+            return self.adopt(user_id, bot_id, &task.task_id, rows, now).await;
+        }
+        if row.is_some_and(|r| r.phase == RuntimePhase::Starting) {
+            // A fresh launch is in flight; stale ones were settled in pass 1.
+            return Ok(None);
+        }
+        Ok(Some(SweepAction::Conflict {
+            user_id: user_id.to_string(),
+            bot_id: bot_id.to_string(),
+            recorded_task_id: row.and_then(|r| r.task_id.clone()),
+            live_task_id: task.task_id.clone(),
+        }))
+    }
+
+    /// The row's task is gone. Keep an enabled bot up — by adopting a live
+    /// task it already has, else by relaunching under the start lock — and
+    /// settle everyone else's row.
+    async fn replace_lost_task(
+        &self,
+        row: &BotRuntime,
+        lost_task_id: Option<&str>,
+        live: &[LiveTask],
+        rows: &mut HashMap<BotKey, BotRuntime>,
+        now: i64,
+    ) -> Result<Option<SweepAction>, String> {
+        let (user_id, bot_id) = (row.user_id.as_str(), row.bot_id.as_str());
+        let bot = self
+            .bots
+            .find_consistent(user_id, bot_id)
+            .await
+            .map_err(|e| format!("Failed to read bot: {e}"))?;
+        // A lost task's stop cause is unknown, so only the policy's mode and
+        // budget apply: `never` and a spent crash-loop budget settle the row.
+        let attempt = row.restart_attempts.saturating_add(1);
+        let keep_up = bot.is_some_and(|b| {
+            b.enabled
+                && (lost_task_id.is_none()
+                    || (b.restart_policy.mode != RestartMode::Never
+                        && b.restart_policy.within_budget(attempt)))
+        });
+        if !keep_up {
+            return match row.phase {
+                RuntimePhase::Starting => self.release_stale_start(row, rows, now).await,
+                _ => self.settle_stopped(row, rows).await,
+            };
+        }
+
+        match live_for(live, user_id, bot_id).as_slice() {
+            [] => {}
+            // A replacement already runs unrecorded; launching another would
+            // double-trade.
+            [only] => return self.adopt(user_id, bot_id, &only.task_id, rows, now).await,
+            // Several: pass 2 reports the conflict.
+            _ => return Ok(None),
+        }
+
+        // Same locks as the event path: a restart is keyed on the lost task so
+        // a racing STOPPED reconcile cannot also relaunch, and a stale start is
+        // reclaimed only while it is still stale.
+        let claim = match lost_task_id {
+            Some(task_id) => {
+                self.locks
+                    .try_acquire_restart(user_id, bot_id, task_id, attempt, now)
+                    .await
+            }
+            None => {
+                self.locks
+                    .try_acquire_start(user_id, bot_id, now, START_LOCK_STALE_AFTER_SECS)
+                    .await
+            }
+        }
+        .map_err(|e| format!("Failed to claim start lock: {e}"))?;
+        if claim != StartClaim::Acquired {
+            return Ok(None);
+        }
+
+        let Some(task_id) = self.launch_claimed(user_id, bot_id, now).await? else {
+            return Ok(Some(SweepAction::RecordedStopped {
+                user_id: user_id.to_string(),
+                bot_id: bot_id.to_string(),
+                task_id: row.task_id.clone(),
+            }));
+        };
+        rows.insert(
+            (user_id.to_string(), bot_id.to_string()),
+            BotRuntime {
+                task_id: Some(task_id.clone()),
+                phase: RuntimePhase::Starting,
+                observed_at: now,
+                ..row.clone()
+            },
+        );
+        Ok(Some(SweepAction::Restarted {
+            user_id: user_id.to_string(),
+            bot_id: bot_id.to_string(),
+            lost_task_id: row.task_id.clone(),
+            task_id,
+        }))
+    }
+
+    /// Launch under a held start lock, with the same desired-state re-checks
+    /// as the STOPPED reconcile. `None` means the bot was disabled meanwhile
+    /// and nothing is left running.
+    async fn launch_claimed(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        now: i64,
+    ) -> Result<Option<String>, String> {
+        // The re-read also supplies the size to launch at, so a promotion the
+        // reconcile Lambda just wrote is honoured.
+        let resources = match self.bots.find_consistent(user_id, bot_id).await {
+            Ok(Some(bot)) if bot.enabled => bot.resource_profile,
+            Ok(_) => {
+                self.release(user_id, bot_id, now).await;
+                return Ok(None);
+            }
+            Err(e) => {
+                self.release(user_id, bot_id, now).await;
+                return Err(format!("Failed to re-read bot: {e}"));
+            }
+        };
+
+        let task_id = match self
+            .runner
+            .run(
+                user_id,
+                bot_id,
+                &self.cluster_arn,
+                &self.td_arn,
+                &self.container_name,
+                resources,
+            )
+            .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                self.release(user_id, bot_id, now).await;
+                return Err(format!("Failed to start task: {e:#}"));
+            }
+        };
+        self.locks
+            .attach_started_task(user_id, bot_id, &task_id)
+            .await
+            .map_err(|e| format!("Failed to attach task {task_id}: {e}"))?;
+
+        // A disable that landed during the launch saw no task id to stop.
+        let still_enabled = self
+            .bots
+            .find_consistent(user_id, bot_id)
+            .await
+            .map(|bot| bot.is_some_and(|b| b.enabled))
+            .unwrap_or(false);
+        if !still_enabled {
+            if let Err(e) = self
+                .controller
+                .stop(
+                    &self.cluster_arn,
+                    &task_id,
+                    "stopped: bot disabled during restart",
+                )
+                .await
+            {
+                tracing::warn!("failed to stop replacement task {task_id} for bot {bot_id}: {e}");
+            }
+            return Ok(None);
+        }
+        Ok(Some(task_id))
+    }
+
+    async fn release(&self, user_id: &str, bot_id: &str, now: i64) {
+        if let Err(e) = self.locks.release_start(user_id, bot_id, now).await {
+            tracing::warn!("failed to release start lock for bot {bot_id}: {e}");
+        }
+    }
+
+    /// Record a live task as the bot's running task. Stamped one second past
+    /// the row so it beats only the row as read; any newer observation —
+    /// including this task's own STOPPED — still wins.
+    async fn adopt(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        task_id: &str,
+        rows: &mut HashMap<BotKey, BotRuntime>,
+        now: i64,
+    ) -> Result<Option<SweepAction>, String> {
+        let key = (user_id.to_string(), bot_id.to_string());
+        let fresh = self.reread(user_id, bot_id).await?;
+        if !same_row(fresh.as_ref(), rows.get(&key)) {
+            return Ok(None);
+        }
+        let (version, observed_at, restart_attempts) = fresh.map_or((1, now, 0), |r| {
+            (r.version.max(1), r.observed_at + 1, r.restart_attempts)
+        });
+        let mut runtime = BotRuntime::running(
+            user_id.to_string(),
+            bot_id.to_string(),
+            task_id.to_string(),
+            version,
+            observed_at,
+        );
+        runtime.restart_attempts = restart_attempts;
+        self.runtimes
+            .record(&runtime)
+            .await
+            .map_err(|e| format!("Failed to record running: {e}"))?;
+        rows.insert(key, runtime);
+        Ok(Some(SweepAction::RecordedRunning {
+            user_id: user_id.to_string(),
+            bot_id: bot_id.to_string(),
+            task_id: task_id.to_string(),
+        }))
+    }
+
+    /// Record `stopped` stamped with the row's own time: the monotonic write
+    /// then only lands while the row is still the one the sweep read.
+    async fn settle_stopped(
+        &self,
+        row: &BotRuntime,
+        rows: &mut HashMap<BotKey, BotRuntime>,
+    ) -> Result<Option<SweepAction>, String> {
+        let fresh = self.reread(&row.user_id, &row.bot_id).await?;
+        if !same_row(fresh.as_ref(), Some(row)) {
+            return Ok(None);
+        }
+        let runtime = BotRuntime::stopped(
+            row.user_id.clone(),
+            row.bot_id.clone(),
+            row.version,
+            row.observed_at,
+        );
+        self.runtimes
+            .record(&runtime)
+            .await
+            .map_err(|e| format!("Failed to record stopped: {e}"))?;
+        rows.insert((row.user_id.clone(), row.bot_id.clone()), runtime);
+        Ok(Some(SweepAction::RecordedStopped {
+            user_id: row.user_id.clone(),
+            bot_id: row.bot_id.clone(),
+            task_id: row.task_id.clone(),
+        }))
+    }
+
+    async fn release_stale_start(
+        &self,
+        row: &BotRuntime,
+        rows: &mut HashMap<BotKey, BotRuntime>,
+        now: i64,
+    ) -> Result<Option<SweepAction>, String> {
+        let fresh = self.reread(&row.user_id, &row.bot_id).await?;
+        if !same_row(fresh.as_ref(), Some(row)) {
+            return Ok(None);
+        }
+        self.locks
+            .release_start(&row.user_id, &row.bot_id, now)
+            .await
+            .map_err(|e| format!("Failed to release start lock: {e}"))?;
+        rows.insert(
+            (row.user_id.clone(), row.bot_id.clone()),
+            BotRuntime::stopped(row.user_id.clone(), row.bot_id.clone(), row.version, now),
+        );
+        Ok(Some(SweepAction::ReleasedStaleStart {
+            user_id: row.user_id.clone(),
+            bot_id: row.bot_id.clone(),
+        }))
+    }
+
+    async fn reread(&self, user_id: &str, bot_id: &str) -> Result<Option<BotRuntime>, String> {
+        self.runtimes
+            .find_consistent(user_id, bot_id)
+            .await
+            .map_err(|e| format!("Failed to re-read runtime: {e}"))
+    }
+
+    /// Listed tasks are alive. An unlisted one may still be draining after a
+    /// StopTask (its desired status is already STOPPED), so ask ECS directly
+    /// before treating it as gone.
+    async fn is_alive(&self, live: &[LiveTask], task_id: &str) -> Result<bool, String> {
+        if live.iter().any(|t| t.task_id == task_id) {
+            return Ok(true);
+        }
+        let liveness = self
+            .controller
+            .liveness(&self.cluster_arn, task_id)
+            .await
+            .map_err(|e| format!("Failed to check task {task_id}: {e:#}"))?;
+        Ok(liveness == TaskLiveness::Alive)
+    }
+}
+
+fn live_for<'a>(live: &'a [LiveTask], user_id: &str, bot_id: &str) -> Vec<&'a LiveTask> {
+    live.iter()
+        .filter(|t| t.user_id.as_deref() == Some(user_id) && t.bot_id.as_deref() == Some(bot_id))
+        .collect()
+}
+
+fn same_row(a: Option<&BotRuntime>, b: Option<&BotRuntime>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            a.phase == b.phase
+                && a.task_id == b.task_id
+                && a.version == b.version
+                && a.observed_at == b.observed_at
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bot::Bot;
+    use crate::domain::clock::MockClock;
+    use crate::domain::error::DomainError;
+    use crate::domain::exchange::Exchange;
+    use crate::domain::resources::ResourceProfile;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[derive(Default)]
+    struct InMemoryBots {
+        bots: Mutex<HashMap<BotKey, Bot>>,
+    }
+    #[async_trait]
+    impl BotRepository for InMemoryBots {
+        async fn find(&self, user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+            Ok(self
+                .bots
+                .lock()
+                .unwrap()
+                .get(&(user_id.to_string(), bot_id.to_string()))
+                .cloned())
+        }
+        async fn save(&self, bot: &Bot) -> Result<(), DomainError> {
+            self.bots
+                .lock()
+                .unwrap()
+                .insert((bot.user_id.clone(), bot.id.clone()), bot.clone());
+            Ok(())
+        }
+        async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<Bot>, DomainError> {
+            Ok(self
+                .bots
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|b| b.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn delete(&self, user_id: &str, bot_id: &str) -> Result<(), String> {
+            self.bots
+                .lock()
+                .unwrap()
+                .remove(&(user_id.to_string(), bot_id.to_string()));
+            Ok(())
+        }
+    }
+
+    /// Runtime rows serving both the scan and the per-row port; `record`
+    /// overwrites unconditionally (the CAS is integration-tested).
+    #[derive(Default)]
+    struct InMemoryRuntimes {
+        rows: Mutex<HashMap<BotKey, BotRuntime>>,
+    }
+    impl InMemoryRuntimes {
+        fn get(&self, bot_id: &str) -> Option<BotRuntime> {
+            self.rows
+                .lock()
+                .unwrap()
+                .get(&("user-1".to_string(), bot_id.to_string()))
+                .cloned()
+        }
+    }
+    #[async_trait]
+    impl BotRuntimeRepository for InMemoryRuntimes {
+        async fn find(
+            &self,
+            user_id: &str,
+            bot_id: &str,
+        ) -> Result<Option<BotRuntime>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .get(&(user_id.to_string(), bot_id.to_string()))
+                .cloned())
+        }
+        async fn find_all_for_user(&self, user_id: &str) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self
+                .rows
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.user_id == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn record(&self, runtime: &BotRuntime) -> Result<(), DomainError> {
+            self.rows.lock().unwrap().insert(
+                (runtime.user_id.clone(), runtime.bot_id.clone()),
+                runtime.clone(),
+            );
+            Ok(())
+        }
+    }
+    #[async_trait]
+    impl RuntimeScanRepository for InMemoryRuntimes {
+        async fn scan_all(&self) -> Result<Vec<BotRuntime>, DomainError> {
+            Ok(self.rows.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    /// Grants every claim and records the lifecycle calls.
+    #[derive(Default)]
+    struct MockLock {
+        attached: Mutex<Vec<String>>,
+        released: Mutex<usize>,
+    }
+    #[async_trait]
+    impl StartLockRepository for MockLock {
+        async fn try_acquire_start(
+            &self,
+            _u: &str,
+            _b: &str,
+            _now: i64,
+            _stale: i64,
+        ) -> Result<StartClaim, DomainError> {
+            Ok(StartClaim::Acquired)
+        }
+        async fn try_acquire_restart(
+            &self,
+            _u: &str,
+            _b: &str,
+            _stopped: &str,
+            _attempt: u32,
+            _now: i64,
+        ) -> Result<StartClaim, DomainError> {
+            Ok(StartClaim::Acquired)
+        }
+        async fn attach_started_task(
+            &self,
+            _u: &str,
+            _b: &str,
+            task_id: &str,
+        ) -> Result<(), DomainError> {
+            self.attached.lock().unwrap().push(task_id.to_string());
+            Ok(())
+        }
+        async fn release_start(&self, _u: &str, _b: &str, _now: i64) -> Result<(), DomainError> {
+            *self.released.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    struct MockInventory(Vec<LiveTask>);
+    #[async_trait]
+    impl TaskInventory for MockInventory {
+        async fn list_running(&self, _cluster_arn: &str) -> anyhow::Result<Vec<LiveTask>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockRunner {
+        launched: Mutex<Vec<String>>,
+    }
+    #[async_trait]
+    impl TaskRunner for MockRunner {
+        async fn run(
+            &self,
+            _user_id: &str,
+            bot_id: &str,
+            _cluster_arn: &str,
+            _td_arn: &str,
+            _container_name: &str,
+            _resources: ResourceProfile,
+        ) -> anyhow::Result<String> {
+            self.launched.lock().unwrap().push(bot_id.to_string());
+            Ok(format!("new-{bot_id}"))
+        }
+    }
+
+    /// Every task not listed is gone.
+    #[derive(Default)]
+    struct MockController {
+        stops: Mutex<Vec<String>>,
+    }
+    #[async_trait]
+    impl TaskController for MockController {
+        async fn stop(
+            &self,
+            _cluster_arn: &str,
+            task_id: &str,
+            _reason: &str,
+        ) -> anyhow::Result<()> {
+            self.stops.lock().unwrap().push(task_id.to_string());
+            Ok(())
+        }
+        async fn liveness(
+            &self,
+            _cluster_arn: &str,
+            _task_id: &str,
+        ) -> anyhow::Result<TaskLiveness> {
+            Ok(TaskLiveness::Gone)
+        }
+    }
+
+    fn bot(id: &str, enabled: bool) -> Bot {
+        Bot::new(
+            id.to_string(),
+            "user-1".to_string(),
+            Exchange::Bybit,
+            id.to_string(),
+            "ak".to_string(),
+            "sk".to_string(),
+            enabled,
+            1,
+            1,
+        )
+    }
+
+    fn live(task_id: &str, bot_id: Option<&str>) -> LiveTask {
+        LiveTask {
+            task_id: task_id.to_string(),
+            user_id: bot_id.map(|_| "user-1".to_string()),
+            bot_id: bot_id.map(str::to_string),
+        }
+    }
+
+    struct Fixture {
+        runtimes: Arc<InMemoryRuntimes>,
+        locks: Arc<MockLock>,
+        runner: Arc<MockRunner>,
+        controller: Arc<MockController>,
+        uc: SweepRuntimesUseCase,
+    }
+
+    fn fixture(bots: Vec<Bot>, rows: Vec<BotRuntime>, tasks: Vec<LiveTask>) -> Fixture {
+        let repo = Arc::new(InMemoryBots::default());
+        for b in bots {
+            repo.bots
+                .lock()
+                .unwrap()
+                .insert((b.user_id.clone(), b.id.clone()), b);
+        }
+        let runtimes = Arc::new(InMemoryRuntimes::default());
+        for r in rows {
+            runtimes
+                .rows
+                .lock()
+                .unwrap()
+                .insert((r.user_id.clone(), r.bot_id.clone()), r);
+        }
+        let locks = Arc::new(MockLock::default());
+        let runner = Arc::new(MockRunner::default());
+        let controller = Arc::new(MockController::default());
+        let uc = SweepRuntimesUseCase::new(
+            runtimes.clone(),
+            repo,
+            runtimes.clone(),
+            locks.clone(),
+            Arc::new(MockInventory(tasks)),
+            runner.clone(),
+            controller.clone(),
+            Arc::new(MockClock::new(NOW)),
+            "cluster".to_string(),
+            "td".to_string(),
+            "container".to_string(),
+        );
+        Fixture {
+            runtimes,
+            locks,
+            runner,
+            controller,
+            uc,
+        }
+    }
+
+    fn running(bot_id: &str, task_id: &str) -> BotRuntime {
+        BotRuntime::running(
+            "user-1".into(),
+            bot_id.into(),
+            task_id.into(),
+            2,
+            NOW - 3600,
+        )
+    }
+
+    #[tokio::test]
+    async fn vanished_task_is_replaced_for_enabled_and_settled_for_disabled_bot() {
+        let f = fixture(
+            vec![bot("on", true), bot("off", false)],
+            vec![running("on", "lost-on"), running("off", "lost-off")],
+            vec![],
+        );
+
+        let summary = f.uc.execute().await.unwrap();
+
+        assert_eq!(summary.rows_checked, 2);
+        assert_eq!(summary.failed(), 0);
+        assert!(summary.actions.contains(&SweepAction::Restarted {
+            user_id: "user-1".into(),
+            bot_id: "on".into(),
+            lost_task_id: Some("lost-on".into()),
+            task_id: "new-on".into(),
+        }));
+        assert_eq!(*f.runner.launched.lock().unwrap(), vec!["on".to_string()]);
+        assert_eq!(
+            *f.locks.attached.lock().unwrap(),
+            vec!["new-on".to_string()]
+        );
+
+        let off = f.runtimes.get("off").unwrap();
+        assert_eq!(off.phase, RuntimePhase::Stopped);
+        assert_eq!(off.task_id, None);
+        // Stamped with the row's own time so the write only lands on that row.
+        assert_eq!(off.observed_at, NOW - 3600);
+    }
+
+    #[tokio::test]
+    async fn lost_running_event_is_adopted_instead_of_relaunching() {
+        let stale_start = BotRuntime {
+            phase: RuntimePhase::Starting,
+            task_id: Some("t-start".into()),
+            ..running("starting", "t-start")
+        };
+        let f = fixture(
+            vec![bot("starting", true), bot("unrecorded", true)],
+            vec![
+                stale_start,
+                BotRuntime::stopped("user-1".into(), "unrecorded".into(), 4, NOW - 60),
+            ],
+            vec![
+                live("t-start", Some("starting")),
+                live("t-unrecorded", Some("unrecorded")),
+            ],
+        );
+
+        let summary = f.uc.execute().await.unwrap();
+
+        assert_eq!(summary.actions.len(), 2, "{:?}", summary.actions);
+        assert!(f.runner.launched.lock().unwrap().is_empty());
+        let started = f.runtimes.get("starting").unwrap();
+        assert_eq!(started.phase, RuntimePhase::Running);
+        assert_eq!(started.task_id.as_deref(), Some("t-start"));
+        assert_eq!(started.observed_at, NOW - 3600 + 1);
+        let adopted = f.runtimes.get("unrecorded").unwrap();
+        assert_eq!(adopted.phase, RuntimePhase::Running);
+        assert_eq!(adopted.task_id.as_deref(), Some("t-unrecorded"));
+        assert_eq!(adopted.version, 4);
+    }
+
+    #[tokio::test]
+    async fn orphans_of_disabled_or_deleted_bots_are_stopped() {
+        let f = fixture(
+            vec![bot("off", false)],
+            vec![running("off", "t-off")],
+            vec![
+                live("t-off", Some("off")),
+                live("t-gone", Some("deleted")),
+                live("t-foreign", None),
+            ],
+        );
+
+        let summary = f.uc.execute().await.unwrap();
+
+        let mut stops = f.controller.stops.lock().unwrap().clone();
+        stops.sort();
+        assert_eq!(stops, vec!["t-gone".to_string(), "t-off".to_string()]);
+        assert!(summary.actions.contains(&SweepAction::Unidentified {
+            task_id: "t-foreign".into()
+        }));
+        // The row is settled by the STOPPED event the StopTask produces.
+        assert_eq!(f.runtimes.get("off").unwrap().phase, RuntimePhase::Running);
+        assert_eq!(*f.locks.released.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn fresh_start_lock_and_consistent_rows_are_left_alone() {
+        let fresh_start = BotRuntime {
+            phase: RuntimePhase::Starting,
+            task_id: None,
+            observed_at: NOW - 5,
+            ..running("starting", "-")
+        };
+        let f = fixture(
+            vec![bot("starting", true), bot("ok", true)],
+            vec![fresh_start, running("ok", "t-ok")],
+            vec![live("t-ok", Some("ok"))],
+        );
+
+        let summary = f.uc.execute().await.unwrap();
+
+        assert!(summary.actions.is_empty(), "{:?}", summary.actions);
+        assert!(f.runner.launched.lock().unwrap().is_empty());
+        assert!(f.controller.stops.lock().unwrap().is_empty());
+    }
+}
@@ -1,51 +1,138 @@
 use crate::domain::RiskLevel;
-use crate::domain::botconfig::BotConfigRepository;
+use crate::domain::bot::BotRepository;
+use crate::domain::botconfig::{BotConfig, BotConfigRepository, Leverage};
 use crate::domain::clock::Clock;
+use crate::domain::instrument::InstrumentCatalogRepository;
+use crate::domain::leverage::{InstrumentCap, LeveragePolicy};
+use crate::usecase::refresh_instruments::current_catalog;
 use std::sync::Arc;
 
+/// What saving a risk level would produce, for the confirmation step: the
+/// validated risk, the leverage the bot's policy derives, and how the leveraged
+/// exposure compares with the bot's exposure ceiling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskPreview {
+    pub risk: RiskLevel,
+    pub leverage: Leverage,
+    pub leveraged_exposure: f64,
+    pub ceiling: Option<f64>,
+}
+
+impl RiskPreview {
+    /// Whether the leveraged exposure is above the bot's ceiling. Only a
+    /// warning: the user may still confirm.
+    pub fn exceeds_ceiling(&self) -> bool {
+        self.ceiling.is_some_and(|c| self.leveraged_exposure > c)
+    }
+}
+
 pub struct UpdateRiskLevelUseCase {
+    bot_repository: Arc<dyn BotRepository>,
     bot_config_repository: Arc<dyn BotConfigRepository>,
+    instruments: Arc<dyn InstrumentCatalogRepository>,
     clock: Arc<dyn Clock>,
 }
 
 impl UpdateRiskLevelUseCase {
-    pub fn new(bot_config_repository: Arc<dyn BotConfigRepository>, clock: Arc<dyn Clock>) -> Self {
+    pub fn new(
+        bot_repository: Arc<dyn BotRepository>,
+        bot_config_repository: Arc<dyn BotConfigRepository>,
+        instruments: Arc<dyn InstrumentCatalogRepository>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         Self {
+            bot_repository,
             bot_config_repository,
+            instruments,
             clock,
         }
     }
 
+    /// Apply the risk level with the bot's leverage policy and persist it.
+    /// Returns the derived leverage that was saved.
     pub async fn execute(
         &self,
         user_id: &str,
         bot_id: &str,
         risk_long: f64,
         risk_short: f64,
-    ) -> Result<(), String> {
-        // 1. Get existing bot config
+    ) -> Result<Leverage, String> {
+        let (bot_config, preview) = self.apply(user_id, bot_id, risk_long, risk_short).await?;
+        self.bot_config_repository.save(&bot_config).await?;
+        Ok(preview.leverage)
+    }
+
+    /// What `execute` would save for this risk level, WITHOUT saving — for the
+    /// confirmation step. Fails exactly when `execute` would.
+    pub async fn preview(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        risk_long: f64,
+        risk_short: f64,
+    ) -> Result<RiskPreview, String> {
+        self.apply(user_id, bot_id, risk_long, risk_short)
+            .await
+            .map(|(_, preview)| preview)
+    }
+
+    async fn apply(
+        &self,
+        user_id: &str,
+        bot_id: &str,
+        risk_long: f64,
+        risk_short: f64,
+    ) -> Result<(BotConfig, RiskPreview), String> {
+        let bot = self
+            .bot_repository
+            .find(user_id, bot_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("bot {bot_id} not found"))?;
         let mut bot_config = self.bot_config_repository.get(user_id, bot_id).await?;
 
-        // 2. Create RiskLevel value object (validated on construction)
+        // RiskLevel is validated on construction; the policy and the leverage
+        // derivation both live in the domain. The derived leverage is capped at
+        // the lowest max leverage among the bot's coins, when the catalog has
+        // them, so no risk level derives a leverage the exchange refuses.
         let risk = RiskLevel::new(risk_long, risk_short).map_err(|e| e.to_string())?;
-
-        // 3. Apply risk level: sets risk, derives leverage, bumps updated_at.
-        // The leverage policy lives in the domain now.
-        bot_config
-            .apply_risk_level(&risk, self.clock.now())
+        let mut policy: Box<dyn LeveragePolicy> = bot.leverage_policy.policy();
+        if let Some(max_leverage) = self.coin_max_leverage(&bot_config).await {
+            policy = Box::new(InstrumentCap {
+                base: policy,
+                max_leverage,
+            });
+        }
+        let leverage = bot_config
+            .apply_risk_level(&risk, policy.as_ref(), self.clock.now())
             .map_err(|e| e.to_string())?;
 
-        // 4. Save updated config
-        self.bot_config_repository.save(&bot_config).await?;
+        let preview = RiskPreview {
+            leveraged_exposure: risk.leveraged_exposure(&leverage),
+            risk,
+            leverage,
+            ceiling: bot.exposure_ceiling,
+        };
+        Ok((bot_config, preview))
+    }
 
-        Ok(())
+    async fn coin_max_leverage(&self, bot_config: &BotConfig) -> Option<f64> {
+        let coins = bot_config.coins().ok()?.all_coins();
+        let catalog = current_catalog(self.instruments.as_ref(), self.clock.now()).await?;
+        catalog.tightest_leverage(&coins).map(|i| i.max_leverage)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::botconfig::{BotConfig, BotType};
+    use crate::domain::bot::Bot;
+    use crate::domain::botconfig::BotType;
+    use crate::domain::error::DomainError;
+    use crate::domain::exchange::Exchange;
+    use crate::domain::instrument::{Instrument, InstrumentCatalog};
+    use crate::domain::leverage::LeveragePolicyKind;
+    use crate::infra::memory::InMemoryInstrumentCatalog;
     use async_trait::async_trait;
     use serde_json::json;
     use std::sync::Mutex;
@@ -79,6 +166,38 @@ mod tests {
         }
     }
 
+    /// Mock BotRepository holding a single bot, so the test controls its
+    /// leverage policy.
+    struct SingleBot {
+        bot: Bot,
+    }
+    #[async_trait]
+    impl BotRepository for SingleBot {
+        async fn find(&self, _user_id: &str, bot_id: &str) -> Result<Option<Bot>, DomainError> {
+            Ok(Some(self.bot.clone()).filter(|b| b.id == bot_id))
+        }
+        async fn save(&self, _bot: &Bot) -> Result<(), DomainError> {
+            Ok(())
+        }
+        async fn find_by_user_id(&self, _user_id: &str) -> Result<Vec<Bot>, DomainError> {
+            Ok(vec![self.bot.clone()])
+        }
+        async fn delete(&self, _user_id: &str, _bot_id: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn bots(policy: LeveragePolicyKind) -> Arc<SingleBot> {
+        bots_with_ceiling(policy, None)
+    }
+
+    fn bots_with_ceiling(policy: LeveragePolicyKind, ceiling: Option<f64>) -> Arc<SingleBot> {
+        let mut bot = Bot::create("user-1".into(), "bot-1".into(), "ak".into(), "sk".into(), 0);
+        bot.set_leverage_policy(policy, 0);
+        bot.set_exposure_ceiling(ceiling, 0).unwrap();
+        Arc::new(SingleBot { bot })
+    }
+
     fn sample_config() -> BotConfig {
         BotConfig {
             user_id: "user-1".into(),
@@ -103,10 +222,16 @@ mod tests {
         let repo = Arc::new(InMemoryConfig {
             config: Mutex::new(sample_config()),
         });
-        let uc = UpdateRiskLevelUseCase::new(repo.clone(), Arc::new(FixedClock));
+        let uc = UpdateRiskLevelUseCase::new(
+            bots(LeveragePolicyKind::default()),
+            repo.clone(),
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            Arc::new(FixedClock),
+        );
 
-        // long=3, short=5 => leverage = max(3,5)+1 = 6
-        uc.execute("user-1", "bot-1", 3.0, 5.0).await.unwrap();
+        // long=3, short=5 => default policy: leverage = max(3,5)+1 = 6
+        let derived = uc.execute("user-1", "bot-1", 3.0, 5.0).await.unwrap();
+        assert_eq!(derived.long, 6.0);
 
         let saved = repo.config.lock().unwrap().clone();
         let risk = saved.risk_level().unwrap();
@@ -123,9 +248,117 @@ mod tests {
         let repo = Arc::new(InMemoryConfig {
             config: Mutex::new(sample_config()),
         });
-        let uc = UpdateRiskLevelUseCase::new(repo, Arc::new(FixedClock));
+        let uc = UpdateRiskLevelUseCase::new(
+            bots(LeveragePolicyKind::default()),
+            repo,
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            Arc::new(FixedClock),
+        );
         // 11.0 is above the [0,10] range.
         let err = uc.execute("user-1", "bot-1", 11.0, 5.0).await.unwrap_err();
         assert!(!err.is_empty());
     }
+
+    #[tokio::test]
+    async fn bot_policy_drives_derivation_and_preview_does_not_save() {
+        let repo = Arc::new(InMemoryConfig {
+            config: Mutex::new(sample_config()),
+        });
+        let policy = LeveragePolicyKind::parse("cap:8:multiplier:2").unwrap();
+        let uc = UpdateRiskLevelUseCase::new(
+            bots(policy),
+            repo.clone(),
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            Arc::new(FixedClock),
+        );
+
+        // max(3,5) * 2 = 10, capped at 8
+        let previewed = uc.preview("user-1", "bot-1", 3.0, 5.0).await.unwrap();
+        assert_eq!(previewed.leverage.long, 8.0);
+        assert_eq!(
+            repo.config.lock().unwrap().leverage().unwrap().long,
+            2.0,
+            "preview must not persist"
+        );
+
+        let saved = uc.execute("user-1", "bot-1", 3.0, 5.0).await.unwrap();
+        assert_eq!(saved, previewed.leverage);
+        assert_eq!(repo.config.lock().unwrap().leverage().unwrap().long, 8.0);
+    }
+
+    #[tokio::test]
+    async fn preview_flags_exposure_above_ceiling() {
+        let repo = Arc::new(InMemoryConfig {
+            config: Mutex::new(sample_config()),
+        });
+        let uc = UpdateRiskLevelUseCase::new(
+            bots_with_ceiling(LeveragePolicyKind::default(), Some(20.0)),
+            repo,
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            Arc::new(FixedClock),
+        );
+
+        // (2 + 1) * (2 + 1) = 9 stays under the ceiling
+        let calm = uc.preview("user-1", "bot-1", 2.0, 1.0).await.unwrap();
+        assert_eq!(calm.leveraged_exposure, 9.0);
+        assert!(!calm.exceeds_ceiling());
+
+        // (4 + 3) * (4 + 1) = 35 is above it
+        let hot = uc.preview("user-1", "bot-1", 4.0, 3.0).await.unwrap();
+        assert_eq!(hot.leveraged_exposure, 35.0);
+        assert!(hot.exceeds_ceiling());
+    }
+
+    #[tokio::test]
+    async fn leverage_is_capped_by_the_tightest_coin() {
+        let mut config = sample_config();
+        config.config_data["live"]["approved_coins"] =
+            json!({ "long": ["BTC", "PEPE"], "short": [] });
+        let repo = Arc::new(InMemoryConfig {
+            config: Mutex::new(config),
+        });
+        let instruments = Arc::new(InMemoryInstrumentCatalog::new());
+        instruments
+            .save(&InstrumentCatalog {
+                exchange: Exchange::Bybit,
+                fetched_at: 1_700_000_000,
+                instruments: [("BTC", 100.0), ("1000PEPE", 5.0)]
+                    .map(|(coin, max_leverage)| Instrument {
+                        symbol: format!("{coin}USDT"),
+                        base_coin: coin.into(),
+                        status: "Trading".into(),
+                        min_qty: 1.0,
+                        tick_size: 0.0001,
+                        max_leverage,
+                    })
+                    .into(),
+            })
+            .await
+            .unwrap();
+        let uc = UpdateRiskLevelUseCase::new(
+            bots(LeveragePolicyKind::default()),
+            repo.clone(),
+            instruments,
+            Arc::new(FixedClock),
+        );
+
+        // max(3,5)+1 = 6, above 1000PEPEUSDT's 5x
+        let derived = uc.execute("user-1", "bot-1", 3.0, 5.0).await.unwrap();
+        assert_eq!(derived.long, 5.0);
+        assert_eq!(repo.config.lock().unwrap().leverage().unwrap().short, 5.0);
+    }
+
+    #[tokio::test]
+    async fn missing_bot_is_rejected() {
+        let repo = Arc::new(InMemoryConfig {
+            config: Mutex::new(sample_config()),
+        });
+        let uc = UpdateRiskLevelUseCase::new(
+            bots(LeveragePolicyKind::default()),
+            repo,
+            Arc::new(InMemoryInstrumentCatalog::new()),
+            Arc::new(FixedClock),
+        );
+        assert!(uc.execute("user-1", "ghost", 1.0, 1.0).await.is_err());
+    }
 }
@@ -162,6 +162,137 @@ module "lambda_task_state_change_handler" {
   dynamodb_table_arn          = module.dynamodb.bots_table_arn
 }
 
+module "lambda_schedule_runner" {
+  source = "../../modules/lambda/schedule_runner"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  environment_variables = {
+    ENV = var.env
+    # Like the task-state-change handler, config comes only from env. Scheduled
+    # risk/side actions also rewrite the bot's S3 config object.
+    APP__DYNAMODB__REGION     = var.region
+    APP__DYNAMODB__TABLE_NAME = module.dynamodb.bots_table_name
+    APP__S3__REGION           = var.region
+    APP__S3__ENDPOINT_URL     = "https://s3.${var.region}.amazonaws.com"
+    APP__S3__BUCKET_NAME      = module.s3_bucket.bucket_name
+  }
+
+  ecs_region                  = var.region
+  ecs_cluster_arn             = module.ecs.cluster_arn
+  td_passivbot_arn            = module.passivbot_task.task_definition_arn
+  passivbot_container_name    = var.passivbot_container_name
+  lambda_code_bucket          = module.lambda_code_bucket.bucket_name
+  ecs_task_execution_role_arn = module.task_base.task_execution_role_arn
+  ecs_task_role_arn           = module.task_base.task_role_arn
+  dynamodb_table_arn          = module.dynamodb.bots_table_arn
+  s3_bucket_name              = module.s3_bucket.bucket_name
+}
+
+module "lambda_runtime_sweeper" {
+  source = "../../modules/lambda/runtime_sweeper"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  environment_variables = {
+    ENV = var.env
+    # Same env-only config as the task-state-change handler.
+    APP__DYNAMODB__REGION     = var.region
+    APP__DYNAMODB__TABLE_NAME = module.dynamodb.bots_table_name
+  }
+
+  ecs_region                  = var.region
+  ecs_cluster_arn             = module.ecs.cluster_arn
+  td_passivbot_arn            = module.passivbot_task.task_definition_arn
+  passivbot_container_name    = var.passivbot_container_name
+  lambda_code_bucket          = module.lambda_code_bucket.bucket_name
+  ecs_task_execution_role_arn = module.task_base.task_execution_role_arn
+  ecs_task_role_arn           = module.task_base.task_role_arn
+  dynamodb_table_arn          = module.dynamodb.bots_table_arn
+}
+
+module "lambda_daily_digest" {
+  source = "../../modules/lambda/daily_digest"
+
+  project     = var.project
+  env         = var.env
+  region      = var.region
+  common_tags = var.common_tags
+
+  environment_variables = {
+    ENV = var.env
+    # Env-only config like the other Lambdas; drift reads the S3 config objects.
+    APP__DYNAMODB__REGION     = var.region
+    APP__DYNAMODB__TABLE_NAME = module.dynamodb.bots_table_name
+    APP__S3__REGION           = var.region
+    APP__S3__ENDPOINT_URL     = "https://s3.${var.region}.amazonaws.com"
+    APP__S3__BUCKET_NAME      = module.s3_bucket.bucket_name
+  }
+
+  lambda_code_bucket             = module.lambda_code_bucket.bucket_name
+  dynamodb_table_arn             = module.dynamodb.bots_table_arn
+  s3_bucket_name                 = module.s3_bucket.bucket_name
+  telegram_token_param_name      = aws_ssm_parameter.telebot_token.name
+  telegram_token_param_arn       = aws_ssm_parameter.telebot_token.arn
+  parameters_extension_layer_arn = var.parameters_extension_layer_arn
+}
+
+module "lambda_risk_guard" {
+  source = "../../modules/lambda/risk_guard"
+
+  project     = var.project
+  env         = var.env
+  region      = var.region
+  common_tags = var.common_tags
+
+  environment_variables = {
+    ENV = var.env
+    # Env-only config like the other Lambdas; a trip rewrites the bot's S3
+    # config object and restarts its ECS task.
+    APP__DYNAMODB__REGION     = var.region
+    APP__DYNAMODB__TABLE_NAME = module.dynamodb.bots_table_name
+    APP__S3__REGION           = var.region
+    APP__S3__ENDPOINT_URL     = "https://s3.${var.region}.amazonaws.com"
+    APP__S3__BUCKET_NAME      = module.s3_bucket.bucket_name
+  }
+
+  ecs_region                     = var.region
+  ecs_cluster_arn                = module.ecs.cluster_arn
+  td_passivbot_arn               = module.passivbot_task.task_definition_arn
+  passivbot_container_name       = var.passivbot_container_name
+  lambda_code_bucket             = module.lambda_code_bucket.bucket_name
+  ecs_task_execution_role_arn    = module.task_base.task_execution_role_arn
+  ecs_task_role_arn              = module.task_base.task_role_arn
+  dynamodb_table_arn             = module.dynamodb.bots_table_arn
+  s3_bucket_name                 = module.s3_bucket.bucket_name
+  telegram_token_param_name      = aws_ssm_parameter.telebot_token.name
+  telegram_token_param_arn       = aws_ssm_parameter.telebot_token.arn
+  parameters_extension_layer_arn = var.parameters_extension_layer_arn
+}
+
+module "lambda_instrument_refresh" {
+  source = "../../modules/lambda/instrument_refresh"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  environment_variables = {
+    ENV = var.env
+    # The catalog lands next to the configs it is checked against.
+    APP__S3__REGION       = var.region
+    APP__S3__ENDPOINT_URL = "https://s3.${var.region}.amazonaws.com"
+    APP__S3__BUCKET_NAME  = module.s3_bucket.bucket_name
+  }
+
+  lambda_code_bucket = module.lambda_code_bucket.bucket_name
+  s3_bucket_name     = module.s3_bucket.bucket_name
+}
+
 module "lambda_code_bucket" {
   source = "../../modules/lambda/s3"
 
@@ -162,6 +162,14 @@ resource "aws_iam_role_policy" "telebot_app" {
           ArnEquals = { "ecs:cluster" = local.ecs_cluster_arn }
         }
       },
+      {
+        # RunTask tags each task with the launching action's correlation_id.
+        Sid       = "TagTasksOnLaunch"
+        Effect    = "Allow"
+        Action    = "ecs:TagResource"
+        Resource  = "*"
+        Condition = { StringEquals = { "ecs:CreateAction" = "RunTask" } }
+      },
       {
         Sid      = "EcsDescribe"
         Effect   = "Allow"
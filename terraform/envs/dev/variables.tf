@@ -117,4 +117,8 @@ variable "github_oidc_provider_arn" {
   description = "Existing GitHub OIDC provider ARN. Empty string = create the provider here."
   type        = string
   default     = ""
-}
\ No newline at end of file
+}
+variable "parameters_extension_layer_arn" {
+  description = "AWS Parameters and Secrets Lambda Extension layer (x86_64) for var.region; the daily-digest and risk-guard Lambdas read the Telegram token through it"
+  type        = string
+}
@@ -15,6 +15,13 @@ resource "aws_dynamodb_table" "bots" {
     type = "S"
   }
 
+  # Bot lifecycle event rows (sk = event#...) carry expires_at; other rows
+  # do not and are never expired.
+  ttl {
+    attribute_name = "expires_at"
+    enabled        = true
+  }
+
   server_side_encryption {
     enabled = true
   }
@@ -46,6 +46,12 @@ variable "environment_variables" {
   default = {}
 }
 
+variable "layers" {
+  type        = list(string)
+  default     = []
+  description = "Lambda layer ARNs (e.g. the AWS Parameters and Secrets extension)"
+}
+
 variable "code_s3_bucket" {
   type        = string
   default     = ""
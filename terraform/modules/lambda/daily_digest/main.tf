@@ -0,0 +1,117 @@
+// terraform/modules/lambda/daily_digest/main.tf
+module "base" {
+  source = "../base"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  function_name  = "daily-digest"
+  bootstrap_path = "${path.root}/../../../target/lambda/daily_digest/bootstrap"
+  architecture   = "x86_64"
+  code_s3_bucket = var.lambda_code_bucket
+  # One exchange call per bot plus a Telegram send per user.
+  timeout_seconds = 60
+
+  # The token is read at cold start through the Parameters and Secrets
+  # extension, so it never lands in the function configuration or TF state.
+  layers = [var.parameters_extension_layer_arn]
+
+  environment_variables = merge(
+    var.environment_variables,
+    {
+      APP__TELEGRAM__TOKEN_PARAM = var.telegram_token_param_name
+    }
+  )
+}
+
+# DynamoDB: find due preference rows (Scan), claim them (conditional
+# UpdateItem), and read the bot, runtime and history rows the digest reports.
+resource "aws_iam_role_policy" "dynamodb" {
+  name = "${var.project}-${var.env}-daily-digest-dynamodb"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "BotsTableRW"
+        Effect = "Allow"
+        Action = [
+          "dynamodb:GetItem",
+          "dynamodb:UpdateItem",
+          "dynamodb:Query",
+          "dynamodb:Scan"
+        ]
+        Resource = var.dynamodb_table_arn
+      }
+    ]
+  })
+}
+
+# S3: config drift compares the bot's config object with its running task.
+resource "aws_iam_role_policy" "s3" {
+  name = "${var.project}-${var.env}-daily-digest-s3"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "BotConfigsRead"
+        Effect   = "Allow"
+        Action   = ["s3:GetObject"]
+        Resource = "arn:aws:s3:::${var.s3_bucket_name}/*"
+      }
+    ]
+  })
+}
+
+# SSM: the telebot's token, decrypted with the SSM-managed key.
+resource "aws_iam_role_policy" "token" {
+  name = "${var.project}-${var.env}-daily-digest-token"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "ReadToken"
+        Effect   = "Allow"
+        Action   = ["ssm:GetParameter"]
+        Resource = var.telegram_token_param_arn
+      },
+      {
+        Sid      = "DecryptToken"
+        Effect   = "Allow"
+        Action   = ["kms:Decrypt"]
+        Resource = "*"
+        Condition = {
+          StringEquals = { "kms:ViaService" = "ssm.${var.region}.amazonaws.com" }
+        }
+      }
+    ]
+  })
+}
+
+resource "aws_cloudwatch_event_rule" "digest_tick" {
+  name                = "${var.project}-${var.env}-daily-digest-tick"
+  description         = "Trigger daily-digest at the top of every hour to send the digests due then"
+  schedule_expression = "cron(0 * * * ? *)"
+
+  tags = var.common_tags
+}
+
+resource "aws_cloudwatch_event_target" "digest_tick_to_lambda" {
+  rule      = aws_cloudwatch_event_rule.digest_tick.name
+  target_id = "daily-digest"
+  arn       = module.base.function_arn
+}
+
+resource "aws_lambda_permission" "allow_eventbridge_invoke" {
+  statement_id  = "AllowExecutionFromEventBridgeDigestTick"
+  action        = "lambda:InvokeFunction"
+  function_name = module.base.function_name
+  principal     = "events.amazonaws.com"
+  source_arn    = aws_cloudwatch_event_rule.digest_tick.arn
+}
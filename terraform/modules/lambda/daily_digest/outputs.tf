@@ -0,0 +1,10 @@
+# terraform/modules/lambda/daily_digest/outputs.tf
+output "function_name" {
+  description = "daily-digest lambda function name"
+  value       = module.base.function_name
+}
+
+output "function_arn" {
+  description = "daily-digest lambda function arn"
+  value       = module.base.function_arn
+}
@@ -0,0 +1,56 @@
+// terraform/modules/lambda/daily_digest/variables.tf
+variable "project" {
+  type        = string
+  description = "Project name"
+}
+
+variable "env" {
+  type        = string
+  description = "Environment name"
+}
+
+variable "region" {
+  type        = string
+  description = "AWS region (the SSM parameter's KMS condition)"
+}
+
+variable "common_tags" {
+  type        = map(string)
+  default     = {}
+  description = "Common tags"
+}
+
+variable "environment_variables" {
+  type    = map(string)
+  default = {}
+}
+
+variable "lambda_code_bucket" {
+  type        = string
+  description = "S3 bucket to store lambda zip for deployment"
+}
+
+variable "dynamodb_table_arn" {
+  type        = string
+  description = "DynamoDB bots table ARN (bot, runtime, history and preference rows)"
+}
+
+variable "s3_bucket_name" {
+  type        = string
+  description = "S3 bucket holding the per-bot config objects"
+}
+
+variable "telegram_token_param_name" {
+  type        = string
+  description = "SSM SecureString parameter holding the telebot's TELOXIDE_TOKEN"
+}
+
+variable "telegram_token_param_arn" {
+  type        = string
+  description = "ARN of the same parameter, for the read policy"
+}
+
+variable "parameters_extension_layer_arn" {
+  type        = string
+  description = "AWS Parameters and Secrets Lambda Extension layer ARN for the region (x86_64)"
+}
@@ -0,0 +1,56 @@
+// terraform/modules/lambda/instrument_refresh/main.tf
+module "base" {
+  source = "../base"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  function_name  = "instrument-refresh"
+  bootstrap_path = "${path.root}/../../../target/lambda/instrument_refresh/bootstrap"
+  architecture   = "x86_64"
+  code_s3_bucket = var.lambda_code_bucket
+
+  environment_variables = var.environment_variables
+}
+
+# S3: the catalog is one object per exchange under instruments/; the exchange
+# endpoint it is read from is public.
+resource "aws_iam_role_policy" "s3" {
+  name = "${var.project}-${var.env}-instrument-refresh-s3"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "InstrumentCatalogWrite"
+        Effect   = "Allow"
+        Action   = ["s3:PutObject"]
+        Resource = "arn:aws:s3:::${var.s3_bucket_name}/instruments/*"
+      }
+    ]
+  })
+}
+
+resource "aws_cloudwatch_event_rule" "refresh_tick" {
+  name                = "${var.project}-${var.env}-instrument-refresh-tick"
+  description         = "Trigger instrument-refresh to cache the exchange's instrument list"
+  schedule_expression = "rate(6 hours)"
+
+  tags = var.common_tags
+}
+
+resource "aws_cloudwatch_event_target" "refresh_tick_to_lambda" {
+  rule      = aws_cloudwatch_event_rule.refresh_tick.name
+  target_id = "instrument-refresh"
+  arn       = module.base.function_arn
+}
+
+resource "aws_lambda_permission" "allow_eventbridge_invoke" {
+  statement_id  = "AllowExecutionFromEventBridgeRefreshTick"
+  action        = "lambda:InvokeFunction"
+  function_name = module.base.function_name
+  principal     = "events.amazonaws.com"
+  source_arn    = aws_cloudwatch_event_rule.refresh_tick.arn
+}
@@ -0,0 +1,10 @@
+# terraform/modules/lambda/instrument_refresh/outputs.tf
+output "function_name" {
+  description = "instrument-refresh lambda function name"
+  value       = module.base.function_name
+}
+
+output "function_arn" {
+  description = "instrument-refresh lambda function arn"
+  value       = module.base.function_arn
+}
@@ -0,0 +1,31 @@
+// terraform/modules/lambda/instrument_refresh/variables.tf
+variable "project" {
+  type        = string
+  description = "Project name"
+}
+
+variable "env" {
+  type        = string
+  description = "Environment name"
+}
+
+variable "common_tags" {
+  type        = map(string)
+  default     = {}
+  description = "Common tags"
+}
+
+variable "environment_variables" {
+  type    = map(string)
+  default = {}
+}
+
+variable "lambda_code_bucket" {
+  type        = string
+  description = "S3 bucket to store lambda zip for deployment"
+}
+
+variable "s3_bucket_name" {
+  type        = string
+  description = "S3 bucket the instrument catalog is cached in (the config bucket)"
+}
@@ -0,0 +1,177 @@
+// terraform/modules/lambda/risk_guard/main.tf
+module "base" {
+  source = "../base"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  function_name  = "risk-guard"
+  bootstrap_path = "${path.root}/../../../target/lambda/risk_guard/bootstrap"
+  architecture   = "x86_64"
+  code_s3_bucket = var.lambda_code_bucket
+  # One exchange call per armed guard, plus the actions of any that trip.
+  timeout_seconds = 60
+
+  # The token is read at cold start through the Parameters and Secrets
+  # extension, so it never lands in the function configuration or TF state.
+  layers = [var.parameters_extension_layer_arn]
+
+  environment_variables = merge(
+    var.environment_variables,
+    {
+      APP__ECS__REGION                      = var.ecs_region
+      APP__ECS__CLUSTER_ARN                 = var.ecs_cluster_arn
+      APP__ECS__TD_PASSIVBOT_ARN            = var.td_passivbot_arn
+      APP__ECS__TD_PASSIVBOT_CONTAINER_NAME = var.passivbot_container_name
+      APP__TELEGRAM__TOKEN_PARAM            = var.telegram_token_param_name
+    }
+  )
+}
+
+# The controlled restart goes through the same StopTask/RunTask path as the
+# telebot.
+resource "aws_iam_role_policy" "ecs_run_task" {
+  name = "${var.project}-${var.env}-risk-guard-ecs-run-task"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "EcsRunStopTask"
+        Effect = "Allow"
+        Action = [
+          "ecs:RunTask",
+          "ecs:StopTask",
+          "ecs:DescribeTasks",
+          "ecs:DescribeTaskDefinition",
+          "ecs:DescribeClusters"
+        ]
+        Resource = "*"
+      },
+      {
+        Sid    = "TagTasksOnLaunch"
+        Effect = "Allow"
+        Action = [
+          "ecs:TagResource"
+        ]
+        Resource = "*"
+        # RunTask tags each task with the launching action's correlation_id.
+        Condition = {
+          StringEquals = {
+            "ecs:CreateAction" = "RunTask"
+          }
+        }
+      },
+      {
+        Sid    = "PassEcsTaskRoles"
+        Effect = "Allow"
+        Action = [
+          "iam:PassRole"
+        ]
+        Resource = [
+          var.ecs_task_execution_role_arn,
+          var.ecs_task_role_arn
+        ]
+        Condition = {
+          StringEquals = {
+            "iam:PassedToService" = "ecs-tasks.amazonaws.com"
+          }
+        }
+      }
+    ]
+  })
+}
+
+# DynamoDB: read every guard row (Scan), latch trips (conditional UpdateItem),
+# and read/write the bot, runtime and history rows the stop/start touch.
+resource "aws_iam_role_policy" "dynamodb" {
+  name = "${var.project}-${var.env}-risk-guard-dynamodb"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "BotsTableRW"
+        Effect = "Allow"
+        Action = [
+          "dynamodb:GetItem",
+          "dynamodb:PutItem",
+          "dynamodb:UpdateItem",
+          "dynamodb:Query",
+          "dynamodb:Scan"
+        ]
+        Resource = var.dynamodb_table_arn
+      }
+    ]
+  })
+}
+
+# S3: a trip switches both sides to graceful_stop in the bot's config object.
+resource "aws_iam_role_policy" "s3" {
+  name = "${var.project}-${var.env}-risk-guard-s3"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "BotConfigsRW"
+        Effect   = "Allow"
+        Action   = ["s3:GetObject", "s3:PutObject"]
+        Resource = "arn:aws:s3:::${var.s3_bucket_name}/*"
+      }
+    ]
+  })
+}
+
+# SSM: the telebot's token, decrypted with the SSM-managed key.
+resource "aws_iam_role_policy" "token" {
+  name = "${var.project}-${var.env}-risk-guard-token"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "ReadToken"
+        Effect   = "Allow"
+        Action   = ["ssm:GetParameter"]
+        Resource = var.telegram_token_param_arn
+      },
+      {
+        Sid      = "DecryptToken"
+        Effect   = "Allow"
+        Action   = ["kms:Decrypt"]
+        Resource = "*"
+        Condition = {
+          StringEquals = { "kms:ViaService" = "ssm.${var.region}.amazonaws.com" }
+        }
+      }
+    ]
+  })
+}
+
+resource "aws_cloudwatch_event_rule" "guard_tick" {
+  name                = "${var.project}-${var.env}-risk-guard-tick"
+  description         = "Trigger risk-guard every minute to check each armed guard against equity"
+  schedule_expression = "rate(1 minute)"
+
+  tags = var.common_tags
+}
+
+resource "aws_cloudwatch_event_target" "guard_tick_to_lambda" {
+  rule      = aws_cloudwatch_event_rule.guard_tick.name
+  target_id = "risk-guard"
+  arn       = module.base.function_arn
+}
+
+resource "aws_lambda_permission" "allow_eventbridge_invoke" {
+  statement_id  = "AllowExecutionFromEventBridgeGuardTick"
+  action        = "lambda:InvokeFunction"
+  function_name = module.base.function_name
+  principal     = "events.amazonaws.com"
+  source_arn    = aws_cloudwatch_event_rule.guard_tick.arn
+}
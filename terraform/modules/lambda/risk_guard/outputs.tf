@@ -0,0 +1,10 @@
+# terraform/modules/lambda/risk_guard/outputs.tf
+output "function_name" {
+  description = "risk-guard lambda function name"
+  value       = module.base.function_name
+}
+
+output "function_arn" {
+  description = "risk-guard lambda function arn"
+  value       = module.base.function_arn
+}
@@ -0,0 +1,87 @@
+// terraform/modules/lambda/risk_guard/variables.tf
+variable "project" {
+  type        = string
+  description = "Project name"
+}
+
+variable "env" {
+  type        = string
+  description = "Environment name"
+}
+
+variable "region" {
+  type        = string
+  description = "AWS region (the SSM parameter's KMS condition)"
+}
+
+variable "common_tags" {
+  type        = map(string)
+  default     = {}
+  description = "Common tags"
+}
+
+variable "environment_variables" {
+  type    = map(string)
+  default = {}
+}
+
+variable "ecs_cluster_arn" {
+  type        = string
+  description = "ECS cluster ARN the guarded bots run in"
+}
+
+variable "ecs_region" {
+  type        = string
+  description = "ECS region for AWS SDK client"
+}
+
+variable "td_passivbot_arn" {
+  type        = string
+  description = "Task definition ARN for the passivbot family"
+}
+
+variable "passivbot_container_name" {
+  description = "Container name for the passivbot task (must match the RunTask override)"
+  type        = string
+  default     = "passivbot-container"
+}
+
+variable "lambda_code_bucket" {
+  type        = string
+  description = "S3 bucket to store lambda zip for deployment"
+}
+
+variable "ecs_task_execution_role_arn" {
+  type        = string
+  description = "ECS task execution role ARN referenced by the task definition (executionRoleArn)"
+}
+
+variable "ecs_task_role_arn" {
+  type        = string
+  description = "ECS task role ARN referenced by the task definition (taskRoleArn)"
+}
+
+variable "dynamodb_table_arn" {
+  type        = string
+  description = "DynamoDB bots table ARN (bot, runtime, guard and history rows)"
+}
+
+variable "s3_bucket_name" {
+  type        = string
+  description = "S3 bucket holding the per-bot config objects"
+}
+
+variable "telegram_token_param_name" {
+  type        = string
+  description = "SSM SecureString parameter holding the telebot's TELOXIDE_TOKEN"
+}
+
+variable "telegram_token_param_arn" {
+  type        = string
+  description = "ARN of the same parameter, for the read policy"
+}
+
+variable "parameters_extension_layer_arn" {
+  type        = string
+  description = "AWS Parameters and Secrets Lambda Extension layer ARN for the region (x86_64)"
+}
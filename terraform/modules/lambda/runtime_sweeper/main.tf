@@ -0,0 +1,124 @@
+// terraform/modules/lambda/runtime_sweeper/main.tf
+module "base" {
+  source = "../base"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  function_name  = "runtime-sweeper"
+  bootstrap_path = "${path.root}/../../../target/lambda/runtime_sweeper/bootstrap"
+  architecture   = "x86_64"
+  code_s3_bucket = var.lambda_code_bucket
+
+  environment_variables = merge(
+    var.environment_variables,
+    {
+      APP__ECS__REGION                      = var.ecs_region
+      APP__ECS__CLUSTER_ARN                 = var.ecs_cluster_arn
+      APP__ECS__TD_PASSIVBOT_ARN            = var.td_passivbot_arn
+      APP__ECS__TD_PASSIVBOT_CONTAINER_NAME = var.passivbot_container_name
+    }
+  )
+}
+
+# List/describe the cluster's tasks, relaunch vanished ones and stop orphans.
+resource "aws_iam_role_policy" "ecs_run_task" {
+  name = "${var.project}-${var.env}-runtime-sweeper-ecs-run-task"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "EcsListRunStopTask"
+        Effect = "Allow"
+        Action = [
+          "ecs:ListTasks",
+          "ecs:DescribeTasks",
+          "ecs:RunTask",
+          "ecs:StopTask",
+          "ecs:DescribeTaskDefinition",
+          "ecs:DescribeClusters"
+        ]
+        Resource = "*"
+      },
+      {
+        Sid    = "TagTasksOnLaunch"
+        Effect = "Allow"
+        Action = [
+          "ecs:TagResource"
+        ]
+        Resource = "*"
+        # RunTask tags each task with the launching action's correlation_id.
+        Condition = {
+          StringEquals = {
+            "ecs:CreateAction" = "RunTask"
+          }
+        }
+      },
+      {
+        Sid    = "PassEcsTaskRoles"
+        Effect = "Allow"
+        Action = [
+          "iam:PassRole"
+        ]
+        Resource = [
+          var.ecs_task_execution_role_arn,
+          var.ecs_task_role_arn
+        ]
+        Condition = {
+          StringEquals = {
+            "iam:PassedToService" = "ecs-tasks.amazonaws.com"
+          }
+        }
+      }
+    ]
+  })
+}
+
+# DynamoDB: Scan every runtime row, read bots, and repair rows through the
+# conditional PutItem/UpdateItem writes.
+resource "aws_iam_role_policy" "dynamodb" {
+  name = "${var.project}-${var.env}-runtime-sweeper-dynamodb"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "BotsTableRW"
+        Effect = "Allow"
+        Action = [
+          "dynamodb:GetItem",
+          "dynamodb:PutItem",
+          "dynamodb:UpdateItem",
+          "dynamodb:Scan"
+        ]
+        Resource = var.dynamodb_table_arn
+      }
+    ]
+  })
+}
+
+resource "aws_cloudwatch_event_rule" "sweep_tick" {
+  name                = "${var.project}-${var.env}-runtime-sweeper-tick"
+  description         = "Trigger runtime-sweeper to repair runtime rows left wrong by lost ECS events"
+  schedule_expression = "rate(5 minutes)"
+
+  tags = var.common_tags
+}
+
+resource "aws_cloudwatch_event_target" "sweep_tick_to_lambda" {
+  rule      = aws_cloudwatch_event_rule.sweep_tick.name
+  target_id = "runtime-sweeper"
+  arn       = module.base.function_arn
+}
+
+resource "aws_lambda_permission" "allow_eventbridge_invoke" {
+  statement_id  = "AllowExecutionFromEventBridgeSweepTick"
+  action        = "lambda:InvokeFunction"
+  function_name = module.base.function_name
+  principal     = "events.amazonaws.com"
+  source_arn    = aws_cloudwatch_event_rule.sweep_tick.arn
+}
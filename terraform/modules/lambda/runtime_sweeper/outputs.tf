@@ -0,0 +1,10 @@
+# terraform/modules/lambda/runtime_sweeper/outputs.tf
+output "function_name" {
+  description = "runtime-sweeper lambda function name"
+  value       = module.base.function_name
+}
+
+output "function_arn" {
+  description = "runtime-sweeper lambda function arn"
+  value       = module.base.function_arn
+}
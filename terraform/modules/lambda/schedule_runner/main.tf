@@ -0,0 +1,142 @@
+// terraform/modules/lambda/schedule_runner/main.tf
+module "base" {
+  source = "../base"
+
+  project     = var.project
+  env         = var.env
+  common_tags = var.common_tags
+
+  function_name  = "schedule-runner"
+  bootstrap_path = "${path.root}/../../../target/lambda/schedule_runner/bootstrap"
+  architecture   = "x86_64"
+  code_s3_bucket = var.lambda_code_bucket
+
+  environment_variables = merge(
+    var.environment_variables,
+    {
+      APP__ECS__REGION                      = var.ecs_region
+      APP__ECS__CLUSTER_ARN                 = var.ecs_cluster_arn
+      APP__ECS__TD_PASSIVBOT_ARN            = var.td_passivbot_arn
+      APP__ECS__TD_PASSIVBOT_CONTAINER_NAME = var.passivbot_container_name
+    }
+  )
+}
+
+# Scheduled start/stop go through the same RunTask/StopTask path as the telebot.
+resource "aws_iam_role_policy" "ecs_run_task" {
+  name = "${var.project}-${var.env}-schedule-runner-ecs-run-task"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "EcsRunStopTask"
+        Effect = "Allow"
+        Action = [
+          "ecs:RunTask",
+          "ecs:StopTask",
+          "ecs:DescribeTasks",
+          "ecs:DescribeTaskDefinition",
+          "ecs:DescribeClusters"
+        ]
+        Resource = "*"
+      },
+      {
+        Sid    = "TagTasksOnLaunch"
+        Effect = "Allow"
+        Action = [
+          "ecs:TagResource"
+        ]
+        Resource = "*"
+        # RunTask tags each task with the launching action's correlation_id.
+        Condition = {
+          StringEquals = {
+            "ecs:CreateAction" = "RunTask"
+          }
+        }
+      },
+      {
+        Sid    = "PassEcsTaskRoles"
+        Effect = "Allow"
+        Action = [
+          "iam:PassRole"
+        ]
+        Resource = [
+          var.ecs_task_execution_role_arn,
+          var.ecs_task_role_arn
+        ]
+        Condition = {
+          StringEquals = {
+            "iam:PassedToService" = "ecs-tasks.amazonaws.com"
+          }
+        }
+      }
+    ]
+  })
+}
+
+# DynamoDB: find due schedule rows (Scan), claim them (conditional UpdateItem),
+# and read/write the bot + runtime rows the start/stop use cases touch.
+resource "aws_iam_role_policy" "dynamodb" {
+  name = "${var.project}-${var.env}-schedule-runner-dynamodb"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid    = "BotsTableRW"
+        Effect = "Allow"
+        Action = [
+          "dynamodb:GetItem",
+          "dynamodb:PutItem",
+          "dynamodb:UpdateItem",
+          "dynamodb:Query",
+          "dynamodb:Scan"
+        ]
+        Resource = var.dynamodb_table_arn
+      }
+    ]
+  })
+}
+
+# S3: risk / side actions rewrite the bot's config object.
+resource "aws_iam_role_policy" "s3" {
+  name = "${var.project}-${var.env}-schedule-runner-s3"
+  role = module.base.role_name
+
+  policy = jsonencode({
+    Version = "2012-10-17"
+    Statement = [
+      {
+        Sid      = "BotConfigsRW"
+        Effect   = "Allow"
+        Action   = ["s3:GetObject", "s3:PutObject"]
+        Resource = "arn:aws:s3:::${var.s3_bucket_name}/*"
+      }
+    ]
+  })
+}
+
+resource "aws_cloudwatch_event_rule" "schedule_tick" {
+  name                = "${var.project}-${var.env}-schedule-runner-tick"
+  description         = "Trigger schedule-runner every minute to fire due scheduled actions"
+  schedule_expression = "rate(1 minute)"
+
+  tags = var.common_tags
+}
+
+resource "aws_cloudwatch_event_target" "schedule_tick_to_lambda" {
+  rule      = aws_cloudwatch_event_rule.schedule_tick.name
+  target_id = "schedule-runner"
+  arn       = module.base.function_arn
+}
+
+resource "aws_lambda_permission" "allow_eventbridge_invoke" {
+  statement_id  = "AllowExecutionFromEventBridgeScheduleTick"
+  action        = "lambda:InvokeFunction"
+  function_name = module.base.function_name
+  principal     = "events.amazonaws.com"
+  source_arn    = aws_cloudwatch_event_rule.schedule_tick.arn
+}
@@ -0,0 +1,10 @@
+# terraform/modules/lambda/schedule_runner/outputs.tf
+output "function_name" {
+  description = "schedule-runner lambda function name"
+  value       = module.base.function_name
+}
+
+output "function_arn" {
+  description = "schedule-runner lambda function arn"
+  value       = module.base.function_arn
+}
@@ -41,6 +41,20 @@ resource "aws_iam_role_policy" "ecs_run_task" {
         ]
         Resource = "*"
       },
+      {
+        Sid    = "TagTasksOnLaunch"
+        Effect = "Allow"
+        Action = [
+          "ecs:TagResource"
+        ]
+        Resource = "*"
+        # RunTask tags each task with the launching action's correlation_id.
+        Condition = {
+          StringEquals = {
+            "ecs:CreateAction" = "RunTask"
+          }
+        }
+      },
       {
         Sid    = "PassEcsTaskRoles"
         Effect = "Allow"
@@ -20,9 +20,11 @@ use aws_sdk_dynamodb::types::{
 };
 
 use pbtb_rust::domain::bot::{Bot, BotRepository};
+use pbtb_rust::domain::resources::ResourceProfile;
 use pbtb_rust::domain::runtime::{
     BotRuntime, BotRuntimeRepository, RuntimePhase, StartClaim, StartLockRepository,
 };
+use pbtb_rust::domain::schedule::{CronSchedule, Schedule, ScheduleAction};
 use pbtb_rust::infra::botrepository::DynamoBotRepository;
 
 use testcontainers::core::{IntoContainerPort, WaitFor};
@@ -283,6 +285,106 @@ async fn dynamo_bot_repository_roundtrip() {
     assert!(after_delete.is_none(), "bot should be gone after delete");
 }
 
+/// The reconcile Lambda's OOM promotion writes only the profile: a concurrent
+/// desired-state change survives, and a deleted bot is not resurrected.
+#[tokio::test]
+async fn resource_profile_update_touches_only_the_profile() {
+    let Some((_container, client)) = start_dynamodb().await else {
+        return; // Docker unavailable: skip gracefully.
+    };
+    let repo = DynamoBotRepository::new(client, TABLE_NAME.to_string());
+
+    let mut bot = Bot::create(
+        "user-1".to_string(),
+        "sized-bot".to_string(),
+        "ak".to_string(),
+        "sk".to_string(),
+        1_700_000_000,
+    );
+    bot.set_resource_profile(
+        ResourceProfile::Custom {
+            cpu: 256,
+            memory_mib: 600,
+        },
+        1_700_000_000,
+    );
+    repo.save(&bot).await.expect("save should succeed");
+    let found = BotRepository::find(&repo, "user-1", "sized-bot")
+        .await
+        .expect("find should not error")
+        .expect("bot should be found");
+    assert_eq!(found.resource_profile, bot.resource_profile);
+
+    // The user enables the bot after the Lambda read it, then the Lambda promotes.
+    let mut enabled = found.clone();
+    enabled.enable(1_700_000_100);
+    repo.save(&enabled)
+        .await
+        .expect("enable save should succeed");
+    let custom = bot.resource_profile;
+    let promoted = repo
+        .promote_resource_profile(
+            "user-1",
+            "sized-bot",
+            custom,
+            ResourceProfile::Medium,
+            "task-1",
+            1_700_000_200,
+        )
+        .await
+        .expect("profile update should succeed");
+    assert!(promoted);
+
+    let after = BotRepository::find(&repo, "user-1", "sized-bot")
+        .await
+        .expect("find should not error")
+        .expect("bot should be found");
+    assert_eq!(after.resource_profile, ResourceProfile::Medium);
+    assert!(after.enabled, "the concurrent enable must survive");
+    assert_eq!(after.updated_at, 1_700_000_200);
+
+    // A redelivered STOPPED event for the same task promotes nothing more,
+    // and neither does a promotion from a profile that is no longer stored.
+    for (from, task) in [(ResourceProfile::Medium, "task-1"), (custom, "task-2")] {
+        let promoted = repo
+            .promote_resource_profile(
+                "user-1",
+                "sized-bot",
+                from,
+                ResourceProfile::Large,
+                task,
+                1_700_000_300,
+            )
+            .await
+            .expect("a refused promotion is not an error");
+        assert!(!promoted, "{from} after {task}");
+    }
+    let after = BotRepository::find(&repo, "user-1", "sized-bot")
+        .await
+        .expect("find should not error")
+        .expect("bot should be found");
+    assert_eq!(after.resource_profile, ResourceProfile::Medium);
+
+    let promoted = repo
+        .promote_resource_profile(
+            "user-1",
+            "ghost-bot",
+            ResourceProfile::Small,
+            ResourceProfile::Medium,
+            "task-3",
+            1_700_000_400,
+        )
+        .await
+        .expect("a missing bot is a no-op");
+    assert!(!promoted);
+    assert!(
+        BotRepository::find(&repo, "user-1", "ghost-bot")
+            .await
+            .expect("find should not error")
+            .is_none()
+    );
+}
+
 /// The money-critical exclusive-start lock: exercises the atomic CAS, the
 /// starting->running transition via the observed-write path, AlreadyRunning /
 /// AlreadyStarting reporting, release, and stale-lock recovery against real
@@ -398,7 +500,7 @@ async fn start_lock_restart_is_idempotent_per_stopped_task() {
 
     // First STOPPED(task-1): claim succeeds, row -> starting, version bumped, id cleared.
     assert_eq!(
-        repo.try_acquire_restart(u, b, "task-1", 1100)
+        repo.try_acquire_restart(u, b, "task-1", 1, 1100)
             .await
             .unwrap(),
         StartClaim::Acquired
@@ -413,11 +515,15 @@ async fn start_lock_restart_is_idempotent_per_stopped_task() {
         "task id cleared while the replacement launches"
     );
     assert_eq!(rt.version, 6, "restart counter bumped");
+    assert_eq!(
+        rt.restart_attempts, 1,
+        "attempt recorded for the restart policy"
+    );
 
     // Duplicate STOPPED(task-1): the id is already cleared, so the claim is refused.
     // The restart path only distinguishes Acquired from "nothing to restart".
     assert_ne!(
-        repo.try_acquire_restart(u, b, "task-1", 1101)
+        repo.try_acquire_restart(u, b, "task-1", 1, 1101)
             .await
             .unwrap(),
         StartClaim::Acquired
@@ -433,7 +539,7 @@ async fn start_lock_restart_is_idempotent_per_stopped_task() {
 
     // A late STOPPED(task-1) after task-2 is running is rejected — task-1 is no longer current.
     assert_ne!(
-        repo.try_acquire_restart(u, b, "task-1", 1300)
+        repo.try_acquire_restart(u, b, "task-1", 1, 1300)
             .await
             .unwrap(),
         StartClaim::Acquired
@@ -451,7 +557,7 @@ async fn start_lock_restart_is_idempotent_per_stopped_task() {
 
     // STOPPED(task-2) (the current task) is honoured.
     assert_eq!(
-        repo.try_acquire_restart(u, b, "task-2", 1400)
+        repo.try_acquire_restart(u, b, "task-2", 1, 1400)
             .await
             .unwrap(),
         StartClaim::Acquired
@@ -502,7 +608,7 @@ async fn stopping_write_is_identity_guarded() {
     .await
     .unwrap();
     assert_eq!(
-        repo.try_acquire_restart(u, b, "task-1", 1300)
+        repo.try_acquire_restart(u, b, "task-1", 1, 1300)
             .await
             .unwrap(),
         StartClaim::Acquired
@@ -606,3 +712,256 @@ async fn terminal_stopped_settles_future_stamped_stopping() {
     );
     assert_eq!(still.task_id.as_deref(), Some("task-2"));
 }
+
+/// Schedule rows share the user's partition with bot rows: they round-trip on
+/// their own, stay out of `find_by_user_id`, and a firing slot can be claimed
+/// only once.
+#[tokio::test]
+async fn schedule_rows_roundtrip_and_claim_once() {
+    // Scoped here: its save/delete would clash with BotRepository's elsewhere.
+    use pbtb_rust::domain::schedule::ScheduleRepository;
+
+    let Some((_container, client)) = start_dynamodb().await else {
+        return; // Docker unavailable: skip gracefully.
+    };
+    let repo = DynamoBotRepository::new(client, TABLE_NAME.to_string());
+    let u = "user-sched";
+
+    let bot = Bot::create(u.into(), "sched-bot".into(), "ak".into(), "sk".into(), 0);
+    BotRepository::save(&repo, &bot).await.unwrap();
+
+    // Hourly stop, created at t=0 -> first slot at 3600.
+    let schedule = Schedule::create(
+        "abc123".into(),
+        u.into(),
+        "sched-bot".into(),
+        CronSchedule::parse("0 * * * *").unwrap(),
+        ScheduleAction::parse("side:short:off").unwrap(),
+        0,
+    )
+    .unwrap();
+    ScheduleRepository::save(&repo, &schedule).await.unwrap();
+
+    let listed = repo.find_by_user(u).await.unwrap();
+    assert_eq!(listed, vec![schedule.clone()]);
+    assert_eq!(
+        repo.find_by_user_id(u).await.unwrap().len(),
+        1,
+        "schedule rows are not bots"
+    );
+
+    assert!(repo.find_due(3_599).await.unwrap().is_empty());
+    let due = repo.find_due(3_600).await.unwrap();
+    assert_eq!(due.len(), 1);
+
+    // Two runners claim the same slot: only the first wins.
+    let mut advanced = due[0].clone();
+    advanced.advance(3_600);
+    assert!(repo.claim_run(&advanced, 3_600).await.unwrap());
+    assert!(!repo.claim_run(&advanced, 3_600).await.unwrap());
+
+    repo.record_result(u, "abc123", "short side disabled")
+        .await
+        .unwrap();
+    let stored = &repo.find_by_user(u).await.unwrap()[0];
+    assert_eq!(stored.next_run_at, 7_200);
+    assert_eq!(stored.last_run_at, Some(3_600));
+    assert_eq!(stored.last_result.as_deref(), Some("short side disabled"));
+
+    // Deleting reports presence; a result for a deleted schedule is a no-op.
+    assert!(
+        ScheduleRepository::delete(&repo, u, "abc123")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !ScheduleRepository::delete(&repo, u, "abc123")
+            .await
+            .unwrap()
+    );
+    repo.record_result(u, "abc123", "late").await.unwrap();
+    assert!(repo.find_by_user(u).await.unwrap().is_empty());
+}
+
+/// The preferences row round-trips, stays out of the bot list, is found by
+/// the digest scan once due, and its digest slot can be claimed only once.
+#[tokio::test]
+async fn preferences_row_roundtrip_and_claim_digest_once() {
+    use pbtb_rust::domain::preferences::{UserPreferences, UserPreferencesRepository};
+
+    let Some((_container, client)) = start_dynamodb().await else {
+        return; // Docker unavailable: skip gracefully.
+    };
+    let repo = DynamoBotRepository::new(client, TABLE_NAME.to_string());
+    let u = "user-prefs";
+
+    let bot = Bot::create(u.into(), "prefs-bot".into(), "ak".into(), "sk".into(), 0);
+    BotRepository::save(&repo, &bot).await.unwrap();
+    assert_eq!(
+        UserPreferencesRepository::find(&repo, u).await.unwrap(),
+        None
+    );
+
+    // Digest at 07:00 UTC, set at t=0 -> first slot at 25200.
+    let mut prefs = UserPreferences::new(u.into(), 0);
+    prefs.set_digest_hour(Some(7), 0).unwrap();
+    UserPreferencesRepository::save(&repo, &prefs)
+        .await
+        .unwrap();
+    assert_eq!(
+        UserPreferencesRepository::find(&repo, u).await.unwrap(),
+        Some(prefs.clone())
+    );
+    assert_eq!(
+        repo.find_by_user_id(u).await.unwrap().len(),
+        1,
+        "the preferences row is not a bot"
+    );
+
+    assert!(repo.find_due_digests(25_199).await.unwrap().is_empty());
+    let due = repo.find_due_digests(25_200).await.unwrap();
+    assert_eq!(due, vec![prefs.clone()]);
+
+    // Two runs claim the same slot: only the first wins.
+    let mut advanced = due[0].clone();
+    advanced.advance_digest(25_200);
+    assert!(repo.claim_digest(&advanced, 25_200).await.unwrap());
+    assert!(!repo.claim_digest(&advanced, 25_200).await.unwrap());
+
+    let equity = std::collections::BTreeMap::from([("prefs-bot".to_string(), 1523.75)]);
+    repo.record_digest_equity(u, &equity).await.unwrap();
+    let stored = UserPreferencesRepository::find(&repo, u)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.next_digest_at, Some(25_200 + 86_400));
+    assert_eq!(stored.digest_equity, equity);
+
+    // Turning the digest off drops the slot, so the scan no longer finds it.
+    let mut off = stored.clone();
+    off.set_digest_hour(None, 30_000).unwrap();
+    UserPreferencesRepository::save(&repo, &off).await.unwrap();
+    assert!(
+        repo.find_due_digests(i64::MAX / 2)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+/// Event rows come back newest first, per bot, bounded by the limit; a
+/// redelivered event overwrites its entry; and they stay out of the bot list.
+#[tokio::test]
+async fn bot_history_is_newest_first_and_deduplicated() {
+    use pbtb_rust::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+
+    let Some((_container, client)) = start_dynamodb().await else {
+        return; // Docker unavailable: skip gracefully.
+    };
+    let repo = DynamoBotRepository::new(client, TABLE_NAME.to_string());
+    let u = "user-history";
+
+    let bot = Bot::create(u.into(), "hist-bot".into(), "ak".into(), "sk".into(), 0);
+    BotRepository::save(&repo, &bot).await.unwrap();
+
+    let mut started = BotEvent::new(u, "hist-bot", BotEventKind::Started, 1_000);
+    started.task_id = Some("task-1".into());
+    let mut stopped = BotEvent::new(u, "hist-bot", BotEventKind::Stopped, 2_000);
+    stopped.task_id = Some("task-1".into());
+    stopped.detail = Some("out of memory, exit 137; restarting".into());
+    let mut restarted = BotEvent::new(u, "hist-bot", BotEventKind::Restarted, 2_001);
+    restarted.task_id = Some("task-2".into());
+    restarted.restart_attempt = Some(1);
+    // Another bot's event under the same user must not leak into the history.
+    let other = BotEvent::new(u, "hist-bot-2", BotEventKind::Started, 3_000);
+    for event in [&started, &stopped, &stopped, &restarted, &other] {
+        repo.append(event).await.unwrap();
+    }
+
+    let recent = repo.recent(u, "hist-bot", 10).await.unwrap();
+    assert_eq!(
+        recent,
+        vec![restarted.clone(), stopped.clone(), started.clone()]
+    );
+    assert_eq!(
+        repo.recent(u, "hist-bot", 2).await.unwrap(),
+        vec![restarted, stopped]
+    );
+    assert_eq!(
+        repo.find_by_user_id(u).await.unwrap().len(),
+        1,
+        "event rows are not bots"
+    );
+}
+
+/// Bot pages resume from the cursor and skip the runtime, schedule and event
+/// rows interleaved with bot rows in the user's partition.
+#[tokio::test]
+async fn bot_pages_follow_the_cursor_past_other_row_kinds() {
+    use pbtb_rust::domain::history::{BotEvent, BotEventKind, BotHistoryRepository};
+    use pbtb_rust::domain::schedule::ScheduleRepository;
+
+    let Some((_container, client)) = start_dynamodb().await else {
+        return; // Docker unavailable: skip gracefully.
+    };
+    let repo = DynamoBotRepository::new(client, TABLE_NAME.to_string());
+    let u = "user-pages";
+
+    // Ids that sort on both sides of the `ecs_task_metadata#`, `event#` and
+    // `schedule#` prefixes.
+    let ids = ["alpha", "echo", "foxtrot", "romeo", "tango"];
+    for id in ids {
+        let bot = Bot::create(u.into(), id.into(), "ak".into(), "sk".into(), 0);
+        BotRepository::save(&repo, &bot).await.unwrap();
+        BotRuntimeRepository::record(&repo, &BotRuntime::stopped(u.into(), id.into(), 1, 0))
+            .await
+            .unwrap();
+        repo.append(&BotEvent::new(u, id, BotEventKind::Started, 0))
+            .await
+            .unwrap();
+    }
+    let schedule = Schedule::create(
+        "page-sched".into(),
+        u.into(),
+        "alpha".into(),
+        CronSchedule::parse("0 * * * *").unwrap(),
+        ScheduleAction::parse("stop").unwrap(),
+        0,
+    )
+    .unwrap();
+    ScheduleRepository::save(&repo, &schedule).await.unwrap();
+
+    let mut seen = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let page = repo
+            .find_page_by_user_id(u, after.as_deref(), 2)
+            .await
+            .unwrap();
+        assert!(page.bots.len() <= 2);
+        seen.extend(page.bots.into_iter().map(|b| b.id));
+        match page.next_cursor {
+            Some(cursor) => after = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(seen, ids);
+    assert_eq!(repo.find_by_user_id(u).await.unwrap().len(), ids.len());
+    // The runtime rows of the same partition come back in one listing too.
+    let mut runtime_ids: Vec<String> = BotRuntimeRepository::find_all_for_user(&repo, u)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.bot_id)
+        .collect();
+    runtime_ids.sort();
+    assert_eq!(runtime_ids, ids);
+
+    // A full last page ends the listing instead of pointing at an empty one.
+    let page = repo
+        .find_page_by_user_id(u, Some("foxtrot"), 2)
+        .await
+        .unwrap();
+    assert_eq!(page.bots.len(), 2);
+    assert_eq!(page.next_cursor, None);
+}
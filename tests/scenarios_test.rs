@@ -0,0 +1,380 @@
+//! End-to-end scenarios across the telebot and Lambda use cases, wired to the
+//! in-memory adapters of the `testing` feature instead of DynamoDB, S3 and
+//! ECS. Each test plays the role of every process in turn: the Telegram
+//! handlers (add, apply template, Run / Stop), the ECS event Lambda (RUNNING /
+//! STOPPED), and the runtime sweeper.
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use pbtb_rust::domain::bot::BotRepository;
+use pbtb_rust::domain::botconfig::BotConfigRepository;
+use pbtb_rust::domain::clock::{Clock, MockClock};
+use pbtb_rust::domain::configtemplate::ConfigTemplate;
+use pbtb_rust::domain::history::BotEventKind;
+use pbtb_rust::domain::metrics::NoopMetrics;
+use pbtb_rust::domain::resources::ResourceProfile;
+use pbtb_rust::domain::restart::RestartPolicy;
+use pbtb_rust::domain::runtime::{BotRuntime, BotRuntimeRepository, RuntimePhase};
+use pbtb_rust::infra::memory::{
+    FakeTaskBackend, FakeTaskStatus, InMemoryApiKeyRepository, InMemoryBotConfigRepository,
+    InMemoryBotRepository, InMemoryInstrumentCatalog, InMemoryTemplateRepository,
+};
+use pbtb_rust::usecase::{
+    AddBotUseCase, AddOutcome, ApplyTemplateUseCase, ReconcileOutcome, ReconcileStoppedTaskUseCase,
+    RecordRunningOutcome, RecordRunningTaskUseCase, START_LOCK_STALE_AFTER_SECS, StartBotUseCase,
+    StartOutcome, StopBotUseCase, StopOutcome, SweepAction, SweepRuntimesUseCase,
+};
+
+const USER: &str = "1001";
+const CLUSTER: &str = "arn:aws:ecs:ap-northeast-1:000000000000:cluster/test";
+const TD: &str = "arn:aws:ecs:ap-northeast-1:000000000000:task-definition/passivbot:1";
+const CONTAINER: &str = "passivbot";
+
+/// Every process of the system over one set of in-memory adapters.
+struct World {
+    clock: Arc<MockClock>,
+    repo: Arc<InMemoryBotRepository>,
+    configs: Arc<InMemoryBotConfigRepository>,
+    api_keys: Arc<InMemoryApiKeyRepository>,
+    backend: Arc<FakeTaskBackend>,
+    add_bot: AddBotUseCase,
+    apply_template: ApplyTemplateUseCase,
+    start_bot: StartBotUseCase,
+    stop_bot: StopBotUseCase,
+    record_running: RecordRunningTaskUseCase,
+    reconcile: ReconcileStoppedTaskUseCase,
+    sweep: SweepRuntimesUseCase,
+}
+
+impl World {
+    fn new() -> Self {
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let repo = Arc::new(InMemoryBotRepository::new());
+        let configs = Arc::new(InMemoryBotConfigRepository::new());
+        let api_keys = Arc::new(InMemoryApiKeyRepository::new());
+        let templates = Arc::new(InMemoryTemplateRepository::new(vec![ConfigTemplate {
+            name: "btc_grid".to_string(),
+            description: None,
+            config_data: json!({
+                "live": { "user": "template" },
+                "bot": {
+                    "long": { "total_wallet_exposure_limit": 1.0 },
+                    "short": { "total_wallet_exposure_limit": 0.0 }
+                }
+            }),
+            version: Some("1".to_string()),
+        }]));
+        let backend = Arc::new(FakeTaskBackend::new());
+        Self {
+            add_bot: AddBotUseCase::new(repo.clone(), api_keys.clone(), clock.clone()),
+            apply_template: ApplyTemplateUseCase::new(templates, configs.clone(), clock.clone()),
+            start_bot: StartBotUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                backend.clone(),
+                backend.clone(),
+                clock.clone(),
+                repo.clone(),
+                Arc::new(NoopMetrics),
+                configs.clone(),
+                Arc::new(InMemoryInstrumentCatalog::new()),
+                CLUSTER.to_string(),
+                TD.to_string(),
+                CONTAINER.to_string(),
+            ),
+            stop_bot: StopBotUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                backend.clone(),
+                clock.clone(),
+                repo.clone(),
+                Arc::new(NoopMetrics),
+                CLUSTER.to_string(),
+            ),
+            record_running: RecordRunningTaskUseCase::new(
+                repo.clone(),
+                backend.clone(),
+                repo.clone(),
+                Arc::new(NoopMetrics),
+            ),
+            reconcile: ReconcileStoppedTaskUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                backend.clone(),
+                backend.clone(),
+                repo.clone(),
+                Arc::new(NoopMetrics),
+            ),
+            sweep: SweepRuntimesUseCase::new(
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                backend.clone(),
+                backend.clone(),
+                backend.clone(),
+                clock.clone(),
+                CLUSTER.to_string(),
+                TD.to_string(),
+                CONTAINER.to_string(),
+            ),
+            clock,
+            repo,
+            configs,
+            api_keys,
+            backend,
+        }
+    }
+
+    /// Add a bot and apply the template, as the Telegram add flow does.
+    async fn add_configured_bot(&self, name: &str) -> String {
+        let outcome = self
+            .add_bot
+            .execute(USER, name.to_string(), "key".into(), "secret".into())
+            .await
+            .unwrap();
+        let AddOutcome::Added(bot) = outcome else {
+            panic!("bot {name} already existed");
+        };
+        self.apply_template
+            .execute(USER, &bot.id, "btc_grid")
+            .await
+            .unwrap();
+        bot.id
+    }
+
+    /// The ECS RUNNING event for `task_id`, one second from now.
+    async fn running(&self, bot_id: &str, task_id: &str) -> RecordRunningOutcome {
+        self.clock.advance(1);
+        self.record_running
+            .execute(USER, bot_id, task_id, CLUSTER, self.clock.now())
+            .await
+            .unwrap()
+    }
+
+    /// End `task_id` with `exit_code` and deliver its STOPPED event.
+    async fn exits(&self, bot_id: &str, task_id: &str, exit_code: i32) -> ReconcileOutcome {
+        self.clock.advance(1);
+        let report = self.backend.exit(task_id, exit_code).unwrap();
+        self.stopped(bot_id, task_id, &report).await
+    }
+
+    async fn stopped(
+        &self,
+        bot_id: &str,
+        task_id: &str,
+        report: &pbtb_rust::domain::restart::StopReport,
+    ) -> ReconcileOutcome {
+        let now = self.clock.now();
+        self.reconcile
+            .execute(
+                USER, bot_id, task_id, CLUSTER, TD, CONTAINER, report, now, now,
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn runtime(&self, bot_id: &str) -> BotRuntime {
+        BotRuntimeRepository::find(self.repo.as_ref(), USER, bot_id)
+            .await
+            .unwrap()
+            .expect("runtime row")
+    }
+
+    fn history(&self, bot_id: &str) -> Vec<BotEventKind> {
+        self.repo
+            .history(USER, bot_id)
+            .into_iter()
+            .map(|e| e.kind)
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn start_run_oom_restart_and_stop() {
+    let world = World::new();
+    let bot_id = world.add_configured_bot("grid-1").await;
+    assert!(world.configs.exists(USER, &bot_id).await.unwrap());
+    assert!(world.api_keys.get(USER, &bot_id).is_some());
+
+    // Run button: desired ON, lock claimed, task launched and attached.
+    let StartOutcome::Started { task_id: first } =
+        world.start_bot.execute(USER, &bot_id).await.unwrap()
+    else {
+        panic!("expected the bot to start");
+    };
+    let runtime = world.runtime(&bot_id).await;
+    assert_eq!(runtime.phase, RuntimePhase::Starting);
+    assert_eq!(runtime.task_id.as_deref(), Some(first.as_str()));
+    // A second press while the launch is in flight is refused.
+    assert_eq!(
+        world.start_bot.execute(USER, &bot_id).await.unwrap(),
+        StartOutcome::AlreadyStarting
+    );
+
+    assert!(matches!(
+        world.running(&bot_id, &first).await,
+        RecordRunningOutcome::Recorded { .. }
+    ));
+    assert_eq!(world.runtime(&bot_id).await.phase, RuntimePhase::Running);
+
+    // The task is OOM-killed: the default policy restarts it one size up.
+    let ReconcileOutcome::Restarted {
+        task_id: second,
+        attempt: 1,
+    } = world.exits(&bot_id, &first, 137).await
+    else {
+        panic!("expected an automatic restart");
+    };
+    let bot = BotRepository::find(world.repo.as_ref(), USER, &bot_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(bot.resource_profile, ResourceProfile::Medium);
+    assert_eq!(
+        world.backend.task(&second).unwrap().resources,
+        ResourceProfile::Medium
+    );
+    world.running(&bot_id, &second).await;
+    let runtime = world.runtime(&bot_id).await;
+    assert_eq!(runtime.phase, RuntimePhase::Running);
+    assert_eq!(runtime.task_id.as_deref(), Some(second.as_str()));
+    assert_eq!(runtime.restart_attempts, 1);
+
+    // Stop button: desired OFF, the task is asked to stop and the row shows it
+    // winding down until the STOPPED event settles it without a restart.
+    world.clock.advance(1);
+    assert_eq!(
+        world.stop_bot.execute(USER, &bot_id).await.unwrap(),
+        StopOutcome::Stopped {
+            task_id: second.clone()
+        }
+    );
+    assert_eq!(world.runtime(&bot_id).await.phase, RuntimePhase::Stopping);
+    assert_eq!(
+        world.backend.task(&second).unwrap().status,
+        FakeTaskStatus::StopRequested
+    );
+    assert_eq!(
+        world.exits(&bot_id, &second, 0).await,
+        ReconcileOutcome::SkippedNotEnabled
+    );
+    assert_eq!(world.runtime(&bot_id).await.phase, RuntimePhase::Stopped);
+    assert!(world.backend.alive_tasks(USER, &bot_id).is_empty());
+
+    // Events of the same second sort by kind, as the history sort key does.
+    assert_eq!(
+        world.history(&bot_id),
+        vec![
+            BotEventKind::Started,
+            BotEventKind::Running,
+            BotEventKind::Resized,
+            BotEventKind::Restarted,
+            BotEventKind::Stopped,
+            BotEventKind::Running,
+            BotEventKind::StopRequested,
+            BotEventKind::Stopped,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn a_redelivered_stopped_event_restarts_once() {
+    let world = World::new();
+    let bot_id = world.add_configured_bot("grid-1").await;
+    let StartOutcome::Started { task_id } = world.start_bot.execute(USER, &bot_id).await.unwrap()
+    else {
+        panic!("expected the bot to start");
+    };
+    world.running(&bot_id, &task_id).await;
+
+    world.clock.advance(1);
+    let report = world.backend.exit(&task_id, 137).unwrap();
+    assert!(matches!(
+        world.stopped(&bot_id, &task_id, &report).await,
+        ReconcileOutcome::Restarted { attempt: 1, .. }
+    ));
+    assert_eq!(
+        world.stopped(&bot_id, &task_id, &report).await,
+        ReconcileOutcome::SkippedSuperseded
+    );
+    assert_eq!(world.backend.tasks().len(), 2);
+    assert_eq!(world.backend.alive_tasks(USER, &bot_id).len(), 1);
+}
+
+#[tokio::test]
+async fn a_redelivered_oom_stopped_event_promotes_once() {
+    let world = World::new();
+    let bot_id = world.add_configured_bot("grid-1").await;
+    // Not restarted, so the redelivery is not caught as superseded.
+    let mut bot = BotRepository::find(world.repo.as_ref(), USER, &bot_id)
+        .await
+        .unwrap()
+        .unwrap();
+    bot.set_restart_policy(RestartPolicy::parse("never").unwrap(), world.clock.now());
+    world.repo.save(&bot).await.unwrap();
+    let StartOutcome::Started { task_id } = world.start_bot.execute(USER, &bot_id).await.unwrap()
+    else {
+        panic!("expected the bot to start");
+    };
+    world.running(&bot_id, &task_id).await;
+
+    world.clock.advance(1);
+    let report = world.backend.exit(&task_id, 137).unwrap();
+    for _ in 0..2 {
+        assert_eq!(
+            world.stopped(&bot_id, &task_id, &report).await,
+            ReconcileOutcome::SkippedByPolicy
+        );
+    }
+
+    let bot = BotRepository::find(world.repo.as_ref(), USER, &bot_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(bot.resource_profile, ResourceProfile::Medium);
+    assert_eq!(
+        world
+            .history(&bot_id)
+            .iter()
+            .filter(|k| **k == BotEventKind::Resized)
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn the_sweeper_adopts_a_task_whose_running_event_was_lost() {
+    let world = World::new();
+    let bot_id = world.add_configured_bot("grid-1").await;
+    let StartOutcome::Started { task_id } = world.start_bot.execute(USER, &bot_id).await.unwrap()
+    else {
+        panic!("expected the bot to start");
+    };
+
+    // No RUNNING event arrives; the lock goes stale while the task trades.
+    world.clock.advance(START_LOCK_STALE_AFTER_SECS + 1);
+    // A Run press now must not launch a second task next to the live one.
+    assert_eq!(
+        world.start_bot.execute(USER, &bot_id).await.unwrap(),
+        StartOutcome::AlreadyRunning
+    );
+    let summary = world.sweep.execute().await.unwrap();
+    assert!(
+        summary
+            .actions
+            .iter()
+            .any(|a| matches!(a, SweepAction::RecordedRunning { .. })),
+        "{:?}",
+        summary.actions
+    );
+    let runtime = world.runtime(&bot_id).await;
+    assert_eq!(runtime.phase, RuntimePhase::Running);
+    assert_eq!(runtime.task_id.as_deref(), Some(task_id.as_str()));
+    assert_eq!(world.backend.tasks().len(), 1);
+}